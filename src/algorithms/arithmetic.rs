@@ -1,10 +1,15 @@
 //! Quantum Arithmetic Circuits
 //!
 //! This module provides quantum circuits for arithmetic operations:
-//! - Quantum adders (Draper QFT-based, Cuccaro ripple-carry)
+//! - Quantum adders (Draper QFT-based, Cuccaro ripple-carry, log-depth
+//!   carry-lookahead) and cost-aware selection
+//! - `_with_endianness` adder variants for either register bit order
 //! - Quantum multipliers
-//! - Modular arithmetic (essential for Shor's algorithm)
+//! - Modular arithmetic (essential for Shor's algorithm), including an exact
+//!   controlled modular multiplier and the controlled a^(2^j) mod N stages of
+//!   order finding
 //! - Comparators
+//! - Controlled increment and decrement (modular ±1 counters)
 //!
 //! ## 🎯 Why is this used?
 //! Quantum arithmetic is the fundamental engine for algorithms that require high-level 
@@ -19,6 +24,10 @@
 //!   Toffoli (CCX) and CNOT gates to propagate carries through a register.
 //! - **Modular Arithmetic**: Uses conditional subtraction and sign-bit checking to implement 
 //!   $|x\rangle \rightarrow |x+y \pmod N\rangle$, which is core to modular exponentiation.
+//! - **Permutation Multiplier**: `modular_multiplier` treats x ↦ a·x mod N as a
+//!   permutation of basis states, splits it into cycles and each cycle into
+//!   transpositions, and swaps each pair along a Gray-code path of fully
+//!   controlled X gates. Exact, but the gate count grows with N.
 //!
 //! ## 📍 Where to apply this?
 //! - **Shor's Algorithm**: For modular exponentiation $a^x \pmod N$.
@@ -28,13 +37,16 @@
 //! ## 📊 Code Behavior
 //! - **Complexity**: Draper adders require $O(n^2)$ gates due to QFT, but use 0 ancilla. 
 //!   Ripple-carry adders are $O(n)$ gates but may require ancilla management.
+//!   The carry-lookahead adder trades about 2n ancillas for $O(\log n)$ depth.
 //! - **Memory**: In-place operations are prioritized to minimize qubit footprint.
 //! - **Side Effects**: Some multipliers require ancilla qubits which must be uncomputed 
 //!   to avoid entanglement-driven errors in larger algorithms.
 
 use std::f64::consts::PI;
-use crate::gates::core::Gate;
-use crate::algorithms::qft::{qft, inverse_qft};
+use crate::gates::core::{Endianness, Gate};
+use crate::gates::decomposition::mcx_gate;
+use crate::algorithms::qft::{qft_no_swap, inverse_qft_no_swap};
+use crate::analysis::circuit_analysis::{circuit_cost, CostWeights};
 
 // ============================================================================
 // DRAPER QFT ADDER
@@ -46,6 +58,9 @@ use crate::algorithms::qft::{qft, inverse_qft};
 /// |b⟩ → |b + a mod 2^n⟩
 /// 
 /// This implementation works in Fourier space for efficient addition.
+/// `b_qubits[0]` is the most significant bit of b. The swap-free QFT leaves
+/// the Fourier digits bit-reversed, so the phases are applied to `b_qubits`
+/// in reverse order instead of swapping.
 /// 
 /// # Arguments
 /// * `a` - Classical value to add
@@ -55,10 +70,10 @@ pub fn draper_adder_classical(a: u64, b_qubits: &[usize]) -> Vec<Gate> {
     let mut gates = Vec::new();
     
     // Transform to Fourier basis
-    gates.extend(qft(n, Some(b_qubits)));
+    gates.extend(qft_no_swap(n, Some(b_qubits)));
     
     // Apply phase rotations based on classical value a
-    for (i, &qubit) in b_qubits.iter().enumerate() {
+    for (i, &qubit) in b_qubits.iter().rev().enumerate() {
         for j in 0..=i {
             if (a >> j) & 1 == 1 {
                 let angle = PI / (1 << (i - j)) as f64;
@@ -68,7 +83,7 @@ pub fn draper_adder_classical(a: u64, b_qubits: &[usize]) -> Vec<Gate> {
     }
     
     // Transform back from Fourier basis
-    gates.extend(inverse_qft(n, Some(b_qubits)));
+    gates.extend(inverse_qft_no_swap(n, Some(b_qubits)));
     
     gates
 }
@@ -77,11 +92,22 @@ pub fn draper_adder_classical(a: u64, b_qubits: &[usize]) -> Vec<Gate> {
 /// 
 /// Adds quantum register |a⟩ to quantum register |b⟩:
 /// |a⟩|b⟩ → |a⟩|a + b mod 2^n⟩
+///
+/// **Mixed bit order:** `a_qubits[0]` is the least significant bit of a but
+/// `b_qubits[0]` the most significant bit of b, so neither `Endianness`
+/// describes both registers. Use [`draper_adder_with_endianness`], which
+/// reads both registers the same way.
 /// 
 /// # Arguments
 /// * `a_qubits` - Qubits storing |a⟩ (unchanged)
 /// * `b_qubits` - Qubits storing |b⟩ (becomes |a + b⟩)
+#[deprecated(note = "reads `a` LSB first but `b` MSB first; use `draper_adder_with_endianness`")]
 pub fn draper_adder(a_qubits: &[usize], b_qubits: &[usize]) -> Vec<Gate> {
+    draper_adder_mixed(a_qubits, b_qubits)
+}
+
+/// Draper adder with `a` least significant bit first and `b` most significant bit first
+fn draper_adder_mixed(a_qubits: &[usize], b_qubits: &[usize]) -> Vec<Gate> {
     let n_a = a_qubits.len();
     let n_b = b_qubits.len();
     let _n = n_a.max(n_b);
@@ -89,10 +115,10 @@ pub fn draper_adder(a_qubits: &[usize], b_qubits: &[usize]) -> Vec<Gate> {
     let mut gates = Vec::new();
     
     // Transform b to Fourier basis
-    gates.extend(qft(n_b, Some(b_qubits)));
+    gates.extend(qft_no_swap(n_b, Some(b_qubits)));
     
     // Apply controlled phase rotations
-    for (i, &b_qubit) in b_qubits.iter().enumerate() {
+    for (i, &b_qubit) in b_qubits.iter().rev().enumerate() {
         for (j, &a_qubit) in a_qubits.iter().enumerate() {
             if j <= i {
                 let angle = PI / (1 << (i - j)) as f64;
@@ -102,11 +128,19 @@ pub fn draper_adder(a_qubits: &[usize], b_qubits: &[usize]) -> Vec<Gate> {
     }
     
     // Transform back from Fourier basis
-    gates.extend(inverse_qft(n_b, Some(b_qubits)));
+    gates.extend(inverse_qft_no_swap(n_b, Some(b_qubits)));
     
     gates
 }
 
+/// Draper adder |a⟩|b⟩ → |a⟩|a + b mod 2ⁿ⟩ with both registers in the given bit order
+///
+/// Unlike the deprecated [`draper_adder`], `a` and `b` follow `endianness`
+/// alike; `Endianness::default()` puts the least significant bit first.
+pub fn draper_adder_with_endianness(a_qubits: &[usize], b_qubits: &[usize], endianness: Endianness) -> Vec<Gate> {
+    draper_adder_mixed(&endianness.lsb_first(a_qubits), &endianness.msb_first(b_qubits))
+}
+
 /// QFT-based subtractor
 /// 
 /// |a⟩|b⟩ → |a⟩|b - a mod 2^n⟩
@@ -117,10 +151,10 @@ pub fn draper_subtractor(a_qubits: &[usize], b_qubits: &[usize]) -> Vec<Gate> {
     let mut gates = Vec::new();
     
     // Transform b to Fourier basis
-    gates.extend(qft(n_b, Some(b_qubits)));
+    gates.extend(qft_no_swap(n_b, Some(b_qubits)));
     
     // Apply controlled phase rotations with negative phases
-    for (i, &b_qubit) in b_qubits.iter().enumerate() {
+    for (i, &b_qubit) in b_qubits.iter().rev().enumerate() {
         for (j, &a_qubit) in a_qubits.iter().enumerate() {
             if j <= i {
                 let angle = -PI / (1 << (i - j)) as f64;
@@ -130,7 +164,7 @@ pub fn draper_subtractor(a_qubits: &[usize], b_qubits: &[usize]) -> Vec<Gate> {
     }
     
     // Transform back from Fourier basis
-    gates.extend(inverse_qft(n_b, Some(b_qubits)));
+    gates.extend(inverse_qft_no_swap(n_b, Some(b_qubits)));
     
     gates
 }
@@ -208,6 +242,156 @@ pub fn cuccaro_adder(
     gates
 }
 
+/// [`cuccaro_adder`] with both registers in the given bit order
+///
+/// `cuccaro_adder` itself is little-endian.
+pub fn cuccaro_adder_with_endianness(
+    a_qubits: &[usize],
+    b_qubits: &[usize],
+    carry_in: Option<usize>,
+    carry_out: Option<usize>,
+    endianness: Endianness,
+) -> Vec<Gate> {
+    cuccaro_adder(&endianness.lsb_first(a_qubits), &endianness.lsb_first(b_qubits), carry_in, carry_out)
+}
+
+// ============================================================================
+// CARRY-LOOKAHEAD ADDER
+// ============================================================================
+
+/// Number of `ancilla` qubits `carry_lookahead_adder` needs for n-bit operands
+///
+/// (n + 1) sum qubits plus n − w(n) − ⌊log₂ n⌋ propagate qubits, where w(n)
+/// is the number of ones in the binary expansion of n.
+pub fn carry_lookahead_ancillas(n: usize) -> usize {
+    if n == 0 {
+        return 0;
+    }
+    let log_n = n.ilog2() as usize;
+    (n + 1) + n - n.count_ones() as usize - log_n
+}
+
+/// Log-depth out-of-place adder |a⟩|b⟩|0⟩ → |a⟩|b⟩|a + b⟩
+///
+/// Draper, Kutin, Rains and Svore's carry-lookahead adder (2004). Carries are
+/// combined along a binary prefix tree, so Toffoli depth is O(log n) rather
+/// than the O(n) of `cuccaro_adder`.
+///
+/// # Arguments
+/// * `a`, `b` - Operand registers, least significant bit first, restored
+/// * `ancilla` - Clean qubits, at least `carry_lookahead_ancillas(n)` =
+///   2n + 1 − w(n) − ⌊log₂ n⌋ of them. `ancilla[0..=n]` receives the (n+1)-bit
+///   sum, least significant bit first (`ancilla[n]` is the carry out); the
+///   remaining n − w(n) − ⌊log₂ n⌋ hold intermediate propagate bits and are
+///   returned to |0⟩
+pub fn carry_lookahead_adder(a: &[usize], b: &[usize], ancilla: &[usize]) -> Vec<Gate> {
+    assert_eq!(a.len(), b.len(), "Input registers must have same size");
+    let n = a.len();
+    assert!(ancilla.len() >= carry_lookahead_ancillas(n),
+        "Carry-lookahead adder on {} bits needs {} ancillas", n, carry_lookahead_ancillas(n));
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let z = &ancilla[..=n];
+    let log_n = n.ilog2() as usize;
+
+    // P_t[m] = p[2ᵗm, 2ᵗ(m+1)] lives in b for t = 0, then in the tree for
+    // 1 ≤ m < ⌊n/2ᵗ⌋
+    let mut offsets = vec![0; log_n.max(1)];
+    let mut next = n + 1;
+    for (t, offset) in offsets.iter_mut().enumerate().skip(1) {
+        *offset = next;
+        next += (n >> t) - 1;
+    }
+    let p = |t: usize, m: usize| if t == 0 { b[m] } else { ancilla[offsets[t] + m - 1] };
+
+    let p_rounds = |gates: &mut Vec<Gate>, ts: &mut dyn Iterator<Item = usize>| {
+        for t in ts {
+            for m in 1..(n >> t) {
+                gates.push(Gate::CCX(p(t - 1, 2 * m), p(t - 1, 2 * m + 1), p(t, m)));
+            }
+        }
+    };
+
+    let mut gates = Vec::new();
+
+    // Generate g[i, i+1] = aᵢbᵢ into z_{i+1}, then propagate p[i, i+1] = aᵢ ⊕ bᵢ into b
+    for i in 0..n {
+        gates.push(Gate::CCX(a[i], b[i], z[i + 1]));
+    }
+    for i in 0..n {
+        gates.push(Gate::CX(a[i], b[i]));
+    }
+
+    // P-rounds: propagate bits of aligned blocks of width 2ᵗ
+    p_rounds(&mut gates, &mut (1..log_n));
+
+    // G-rounds: z at each block end holds the carry generated inside the block
+    for t in 1..=log_n {
+        for m in 0..(n >> t) {
+            let end = (m << t) + (1 << t);
+            gates.push(Gate::CCX(z[end - (1 << (t - 1))], p(t - 1, 2 * m + 1), z[end]));
+        }
+    }
+
+    // C-rounds: fill in the carries between block ends, t = ⌊log₂(2n/3)⌋ … 1
+    let top = (1..).take_while(|&t| 3 << t <= 2 * n).last().unwrap_or(0);
+    for t in (1..=top).rev() {
+        for m in 1..=((n - (1 << (t - 1))) >> t) {
+            let start = m << t;
+            gates.push(Gate::CCX(z[start], p(t - 1, 2 * m), z[start + (1 << (t - 1))]));
+        }
+    }
+
+    // Undo the P-rounds, leaving z_i = c_i
+    p_rounds(&mut gates, &mut (1..log_n).rev());
+
+    // sᵢ = pᵢ ⊕ cᵢ, then restore b
+    for i in 0..n {
+        gates.push(Gate::CX(b[i], z[i]));
+    }
+    for i in 0..n {
+        gates.push(Gate::CX(a[i], b[i]));
+    }
+
+    gates
+}
+
+// ============================================================================
+// COST-AWARE ADDER SELECTION
+// ============================================================================
+
+/// |a⟩|b⟩ → |a⟩|a + b mod 2ⁿ⟩ with whichever adder is cheaper under `weights`
+///
+/// Both registers are least significant bit first. Candidates:
+/// - Draper (QFT): no ancilla, O(n²) controlled phases, all arbitrary-angle
+///   rotations once lowered.
+/// - Cuccaro (ripple carry): 2n Toffolis and O(n) CX, but needs one clean
+///   carry qubit, taken from `ancilla[0]` and returned to |0⟩.
+///
+/// Each is scored with `circuit_cost`, plus `weights.ancilla` for the carry
+/// qubit; the cheaper wins, and Draper is used when no ancilla is supplied.
+/// Rotation-heavy (fault-tolerant) weights therefore pick Cuccaro, while an
+/// expensive ancilla picks Draper.
+/// Under `CostWeights::noise_aware` the score is the estimated infidelity,
+/// so the choice follows whichever gate types the device runs worst.
+pub fn best_adder(a: &[usize], b: &[usize], ancilla: &[usize], weights: &CostWeights) -> Vec<Gate> {
+    assert_eq!(a.len(), b.len(), "Input registers must have same size");
+
+    let draper = draper_adder_with_endianness(a, b, Endianness::Little);
+    let Some(&carry) = ancilla.first() else {
+        return draper;
+    };
+
+    let cuccaro = cuccaro_adder(a, b, Some(carry), None);
+    if circuit_cost(&cuccaro, weights) + weights.ancilla < circuit_cost(&draper, weights) {
+        cuccaro
+    } else {
+        draper
+    }
+}
+
 // ============================================================================
 // VBE (VEDRAL-BARENCO-EKERT) ADDER
 // ============================================================================
@@ -316,8 +500,8 @@ pub fn quantum_equals(
     
     // b is now 0..0 iff a == b original
     // Use multi-controlled NOT to set flag
-    for i in 0..n {
-        gates.push(Gate::X(b_qubits[i])); // Flip so 0..0 becomes 1..1
+    for &b in &b_qubits[..n] {
+        gates.push(Gate::X(b)); // Flip so 0..0 becomes 1..1
     }
     
     // Multi-controlled X on flag
@@ -325,8 +509,8 @@ pub fn quantum_equals(
     gates.push(Gate::MCX(controls.clone(), flag));
     
     // Uncompute
-    for i in 0..n {
-        gates.push(Gate::X(b_qubits[i]));
+    for &b in &b_qubits[..n] {
+        gates.push(Gate::X(b));
     }
     for i in 0..n {
         gates.push(Gate::CX(a_qubits[i], b_qubits[i]));
@@ -352,7 +536,7 @@ pub fn modular_adder(
     let mut gates = Vec::new();
     
     // Step 1: Add a to b
-    gates.extend(draper_adder(a_qubits, b_qubits));
+    gates.extend(draper_adder_mixed(a_qubits, b_qubits));
     
     // Step 2: Subtract N from b (we need to check if result >= N)
     gates.extend(draper_adder_classical(n_value.wrapping_neg(), b_qubits));
@@ -370,7 +554,7 @@ pub fn modular_adder(
     }
     
     // Uncompute by adding a again and checking overflow
-    gates.extend(draper_adder(a_qubits, b_qubits));
+    gates.extend(draper_adder_mixed(a_qubits, b_qubits));
     
     gates
 }
@@ -391,7 +575,7 @@ pub fn controlled_modular_multiply(
     // Use repeated doubling: a*x = Σ a*2^i*x[i]
     let mut power_of_a = a;
     
-    for (_i, &x_bit) in x_qubits.iter().enumerate() {
+    for &x_bit in x_qubits.iter() {
         // Controlled addition of power_of_a if x[i] = 1
         // c-ADD(power_of_a, result) controlled by x[i] and main control
         
@@ -415,6 +599,71 @@ pub fn controlled_modular_multiply(
     gates
 }
 
+/// Exact |x⟩ → |a·x mod N⟩ on `x_qubits` (LSB first), applied only when all `controls` are 1
+///
+/// States with x ≥ N are left alone, so the map is a permutation of the
+/// whole register; `a` must be coprime to N and N ≤ 2ⁿ. Each transposition
+/// of two basis states costs 2d − 1 fully controlled X gates for Hamming
+/// distance d, so this suits the small moduli of simulations and tests.
+pub fn modular_multiplier(a: u64, n: u64, x_qubits: &[usize], controls: &[usize]) -> Vec<Gate> {
+    let width = x_qubits.len();
+    assert!(n >= 1 && n <= 1u64 << width, "Modulus {} does not fit in {} qubits", n, width);
+    assert_eq!(gcd(a % n, n), 1, "{} is not invertible mod {}", a, n);
+
+    let image = |x: u64| if x < n { (a % n) * x % n } else { x };
+    let mut seen = vec![false; n as usize];
+    let mut gates = Vec::new();
+    for start in 0..n {
+        if seen[start as usize] {
+            continue;
+        }
+        let mut cycle = vec![start];
+        seen[start as usize] = true;
+        let mut x = image(start);
+        while x != start {
+            seen[x as usize] = true;
+            cycle.push(x);
+            x = image(x);
+        }
+        // (c₀ → c₁ → … → c_k−1 → c₀) is swap(c_k−2, c_k−1) first, swap(c₀, c₁) last
+        for pair in cycle.windows(2).rev() {
+            gates.extend(transpose_basis_states(pair[0], pair[1], x_qubits, controls));
+        }
+    }
+    gates
+}
+
+/// |u⟩ ↔ |v⟩ on `qubits`, via the Gray-code path that flips their differing bits
+fn transpose_basis_states(u: u64, v: u64, qubits: &[usize], controls: &[usize]) -> Vec<Gate> {
+    let mut path = vec![u];
+    for b in (0..qubits.len()).filter(|b| (u ^ v) >> b & 1 == 1) {
+        path.push(path[path.len() - 1] ^ 1 << b);
+    }
+    // Walk u to v, then walk the displaced v back along the same edges
+    let steps: Vec<(u64, u64)> = path.windows(2).map(|w| (w[0], w[1])).collect();
+    let mut gates = Vec::new();
+    for &(from, to) in steps.iter().chain(steps.iter().rev().skip(1)) {
+        let bit = (from ^ to).trailing_zeros() as usize;
+        let others: Vec<usize> = (0..qubits.len()).filter(|&i| i != bit).collect();
+        let flips: Vec<Gate> = others.iter().filter(|&&i| from >> i & 1 == 0).map(|&i| Gate::X(qubits[i])).collect();
+        let mut wires: Vec<usize> = controls.to_vec();
+        wires.extend(others.iter().map(|&i| qubits[i]));
+        gates.extend(flips.iter().cloned());
+        gates.push(match wires[..] {
+            [] => Gate::X(qubits[bit]),
+            [c] => Gate::CX(c, qubits[bit]),
+            [c1, c2] => Gate::CCX(c1, c2, qubits[bit]),
+            _ => Gate::MCX(wires, qubits[bit]),
+        });
+        gates.extend(flips);
+    }
+    gates
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
 /// Modular exponentiation: |x⟩|0⟩ → |x⟩|a^x mod N⟩
 /// 
 /// Key subroutine for Shor's algorithm.
@@ -433,7 +682,7 @@ pub fn modular_exponentiation(
     // Square-and-multiply algorithm
     let mut power = a;
     
-    for (_i, &x_bit) in x_qubits.iter().enumerate() {
+    for &x_bit in x_qubits.iter() {
         // Controlled multiplication by power
         gates.extend(controlled_modular_multiply(
             x_bit,
@@ -451,6 +700,74 @@ pub fn modular_exponentiation(
     gates
 }
 
+/// Controlled-U^(2^j) for every counting qubit j, with U|x⟩ = |a·x mod N⟩
+///
+/// `control_qubits[j]` multiplies `x_qubits` (LSB first) by a^(2^j) mod N,
+/// with the power computed classically by repeated squaring, so each stage
+/// is one `modular_multiplier` instead of 2^j copies of U. This is the
+/// controlled-powers stage of QPE for order finding. The exact permutation
+/// multiplier needs no workspace, so `_ancilla` is accepted for signature
+/// compatibility with ancilla-based multipliers and left untouched.
+pub fn controlled_modular_exp(
+    control_qubits: &[usize],
+    x_qubits: &[usize],
+    a: u64,
+    n: u64,
+    _ancilla: &[usize],
+) -> Vec<Gate> {
+    let mut gates = Vec::new();
+    let mut power = a % n;
+    for &control in control_qubits {
+        gates.extend(modular_multiplier(power, n, x_qubits, &[control]));
+        power = power * power % n;
+    }
+    gates
+}
+
+// ============================================================================
+// CONTROLLED INCREMENT / DECREMENT
+// ============================================================================
+
+/// |c⟩|x⟩ → |c⟩|x + c mod 2ⁿ⟩, with `register[0]` the least significant bit
+///
+/// Arithmetic is modulo 2ⁿ with no carry-out qubit: 2ⁿ − 1 + 1 wraps to 0.
+/// Bit i flips when the control and all lower bits are 1. The gates run
+/// highest bit first, so each one still reads the lower bits before the
+/// carry reaches them. Uses one CX, one CCX and n − 2 MCX gates with
+/// 3..n+1 controls.
+pub fn controlled_increment(control: usize, register: &[usize]) -> Vec<Gate> {
+    multi_controlled_increment(&[control], register)
+}
+
+/// |c⟩|x⟩ → |c⟩|x − c mod 2ⁿ⟩: the increment's gates in reverse
+///
+/// Each gate is self-inverse, so this is exactly the adjoint of
+/// `controlled_increment`: 0 wraps to 2ⁿ − 1, with no borrow qubit. Bits
+/// flip lowest first, and bit i flips when every lower bit has just turned
+/// from 0 to 1, which is exactly when a borrow reaches it.
+pub fn controlled_decrement(control: usize, register: &[usize]) -> Vec<Gate> {
+    multi_controlled_decrement(&[control], register)
+}
+
+/// |x⟩ → |x + 1 mod 2ⁿ⟩ when every control is 1 (always with no controls)
+pub(crate) fn multi_controlled_increment(controls: &[usize], register: &[usize]) -> Vec<Gate> {
+    (0..register.len())
+        .rev()
+        .map(|i| {
+            let mut all = controls.to_vec();
+            all.extend(&register[..i]);
+            mcx_gate(&all, register[i])
+        })
+        .collect()
+}
+
+/// |x⟩ → |x − 1 mod 2ⁿ⟩ when every control is 1
+pub(crate) fn multi_controlled_decrement(controls: &[usize], register: &[usize]) -> Vec<Gate> {
+    let mut gates = multi_controlled_increment(controls, register);
+    gates.reverse();
+    gates
+}
+
 // ============================================================================
 // QUANTUM MULTIPLIER
 // ============================================================================
@@ -500,6 +817,107 @@ mod tests {
         assert!(!circuit.is_empty());
     }
 
+    #[test]
+    fn test_draper_adder_classical_adds() {
+        use crate::simulator::{basis_state, simulate_from, states_close};
+
+        // b_qubits[0] is the MSB; the swap-free QFT inserts no SWAPs
+        let b = [0, 1, 2];
+        let circuit = draper_adder_classical(3, &b);
+        assert!(!circuit.iter().any(|g| matches!(g, Gate::SWAP(_, _))));
+
+        let encode = |v: usize| (0..3).filter(|&i| v >> (2 - i) & 1 == 1).map(|i| 1 << b[i]).sum();
+        for x in 0..8 {
+            let state = simulate_from(&circuit, &basis_state(3, encode(x))).unwrap();
+            assert!(states_close(&state, &basis_state(3, encode((x + 3) % 8)), 1e-10), "x = {}", x);
+        }
+    }
+
+    #[test]
+    fn test_modular_multiplier_permutes_residues() {
+        use crate::simulator::{basis_state, simulate_from, states_close};
+
+        // Multiply by 7 mod 15 on qubits [4, 1, 2, 0], controlled by qubit 3
+        let x_qubits = [4, 1, 2, 0];
+        let circuit = modular_multiplier(7, 15, &x_qubits, &[3]);
+        let encode = |x: usize| (0..4).fold(0, |acc, i| acc | (x >> i & 1) << x_qubits[i]);
+        for x in 0..16 {
+            let product = if x < 15 { 7 * x % 15 } else { x };
+            for (control, expected) in [(0, x), (1 << 3, product)] {
+                let state = simulate_from(&circuit, &basis_state(5, control | encode(x))).unwrap();
+                assert!(states_close(&state, &basis_state(5, control | encode(expected)), 1e-12), "x = {}", x);
+            }
+        }
+        assert!(modular_multiplier(1, 15, &x_qubits, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_controlled_modular_exp_order_finding() {
+        use crate::algorithms::qft::inverse_qft;
+        use crate::simulator::{probabilities, simulate};
+
+        // Counting qubits 5, 0, 6 (LSB first), work register 1..5 starting at |1⟩
+        let controls = [5, 0, 6];
+        let x_qubits = [1, 2, 3, 4];
+        for (a, order) in [(7, 4), (4, 2), (11, 2)] {
+            let mut circuit = vec![Gate::X(x_qubits[0])];
+            circuit.extend(controls.iter().map(|&c| Gate::H(c)));
+            circuit.extend(controlled_modular_exp(&controls, &x_qubits, a, 15, &[]));
+            circuit.extend(inverse_qft(3, Some(&[6, 0, 5])));
+
+            let probs = probabilities(&simulate(&circuit, 7).unwrap());
+            let mut counting = [0.0; 8];
+            for (index, p) in probs.iter().enumerate() {
+                let m = (0..3).fold(0, |acc, i| acc | (index >> controls[i] & 1) << i);
+                counting[m] += p;
+            }
+            // m / 8 = s / r for s ∈ 0..r, each with probability 1/r
+            for (m, p) in counting.iter().enumerate() {
+                let expected = if m * order % 8 == 0 { 1.0 / order as f64 } else { 0.0 };
+                assert!((p - expected).abs() < 1e-9, "a = {}, m = {}: {}", a, m, p);
+            }
+        }
+    }
+
+    #[test]
+    fn test_controlled_increment_and_decrement() {
+        use crate::simulator::{basis_state, simulate_from, states_close};
+
+        // Exhaustive on 3- and 4-qubit registers laid out out of order
+        for (control, register) in [(3, vec![0, 1, 2]), (1, vec![4, 0, 3, 2])] {
+            let n = register.len();
+            let width = n + 1;
+            let size = 1 << n;
+            let inc = controlled_increment(control, &register);
+            let dec = controlled_decrement(control, &register);
+            let index = |c: usize, x: usize| {
+                (0..n).map(|i| ((x >> i) & 1) << register[i]).sum::<usize>() | c << control
+            };
+            for c in 0..2 {
+                for x in 0..size {
+                    let input = basis_state(width, index(c, x));
+                    let up = simulate_from(&inc, &input).unwrap();
+                    assert!(states_close(&up, &basis_state(width, index(c, (x + c) % size)), 1e-12), "{} + {}", x, c);
+                    let down = simulate_from(&dec, &input).unwrap();
+                    let expected = basis_state(width, index(c, (x + size - c) % size));
+                    assert!(states_close(&down, &expected, 1e-12), "{} - {}", x, c);
+                    assert!(states_close(&simulate_from(&dec, &up).unwrap(), &input, 1e-12));
+                }
+            }
+        }
+
+        // The shared kernel with two controls fires only when both are set
+        let inc = multi_controlled_increment(&[3, 4], &[0, 1, 2]);
+        for controls in 0..4 {
+            for x in 0..8 {
+                let on = controls == 3;
+                let state = simulate_from(&inc, &basis_state(5, controls << 3 | x)).unwrap();
+                let expected = basis_state(5, (controls << 3) | ((x + on as usize) % 8));
+                assert!(states_close(&state, &expected, 1e-12), "Controls {:02b}, x = {}", controls, x);
+            }
+        }
+    }
+
     #[test]
     fn test_cuccaro_adder() {
         let circuit = cuccaro_adder(&[0, 1], &[2, 3], Some(4), Some(5));
@@ -510,6 +928,128 @@ mod tests {
         assert!(ccx_count > 0);
     }
 
+    #[test]
+    fn test_adders_with_endianness() {
+        use crate::simulator::{basis_state, probabilities, simulate_from};
+
+        // 3 + 5 = 8 on 4-bit registers a = [0..4], b = [4..8], carry ancilla 8
+        let (a, b) = ([0, 1, 2, 3], [4, 5, 6, 7]);
+        for endianness in [Endianness::Little, Endianness::Big] {
+            let encode = |reg: &[usize], v: usize| {
+                endianness.lsb_first(reg).iter().enumerate().map(|(i, &q)| ((v >> i) & 1) << q).sum::<usize>()
+            };
+            let input = encode(&a, 3) | encode(&b, 5);
+            let output = encode(&a, 3) | encode(&b, 8);
+            for circuit in [
+                draper_adder_with_endianness(&a, &b, endianness),
+                cuccaro_adder_with_endianness(&a, &b, Some(8), None, endianness),
+            ] {
+                let probs = probabilities(&simulate_from(&circuit, &basis_state(9, input)).unwrap());
+                assert!((probs[output] - 1.0).abs() < 1e-9, "{:?}", endianness);
+            }
+        }
+    }
+
+    #[test]
+    fn test_carry_lookahead_adder() {
+        use crate::analysis::circuit_analysis::circuit_depth;
+        use crate::simulator::{basis_state, probabilities, simulate_from};
+
+        // Reversible classical circuit, so track one basis state as a bitmask
+        let run = |circuit: &[Gate], mut bits: u64| {
+            for gate in circuit {
+                match *gate {
+                    Gate::CX(c, t) => bits ^= (bits >> c & 1) << t,
+                    Gate::CCX(c1, c2, t) => bits ^= (bits >> c1 & bits >> c2 & 1) << t,
+                    _ => panic!("Unexpected gate {:?}", gate),
+                }
+            }
+            bits
+        };
+        let encode = |reg: &[usize], v: u64| (0..reg.len()).map(|i| (v >> i & 1) << reg[i]).sum::<u64>();
+
+        for n in 1..=6 {
+            let a: Vec<usize> = (0..n).collect();
+            let b: Vec<usize> = (n..2 * n).collect();
+            let ancilla: Vec<usize> = (2 * n..2 * n + carry_lookahead_ancillas(n)).collect();
+            let circuit = carry_lookahead_adder(&a, &b, &ancilla);
+            let used = circuit.iter().flat_map(|g| g.qubits()).max().unwrap();
+            assert_eq!(used, *ancilla.last().unwrap(), "n = {} uses every ancilla", n);
+            for x in 0..1u64 << n {
+                for y in 0..1u64 << n {
+                    let input = encode(&a, x) | encode(&b, y);
+                    assert_eq!(run(&circuit, input), input | encode(&ancilla[..=n], x + y), "{} + {} on {} bits", x, y, n);
+                }
+            }
+        }
+        assert_eq!(carry_lookahead_ancillas(16), 16 + 1 + 16 - 1 - 4);
+
+        // Interleaved registers through the state-vector simulator
+        let (a, b) = ([0, 2, 4, 6], [1, 3, 5, 7]);
+        let ancilla: Vec<usize> = (8..8 + carry_lookahead_ancillas(4)).collect();
+        let circuit = carry_lookahead_adder(&a, &b, &ancilla);
+        let width = 8 + ancilla.len();
+        for (x, y) in [(15, 15), (9, 6), (0, 13), (7, 8)] {
+            let input = (encode(&a, x) | encode(&b, y)) as usize;
+            let output = input | encode(&ancilla[..5], x + y) as usize;
+            let probs = probabilities(&simulate_from(&circuit, &basis_state(width, input)).unwrap());
+            assert!((probs[output] - 1.0).abs() < 1e-9, "{} + {}", x, y);
+        }
+
+        // Logarithmic against linear depth
+        let n = 32;
+        let a: Vec<usize> = (0..n).collect();
+        let b: Vec<usize> = (n..2 * n).collect();
+        let ancilla: Vec<usize> = (2 * n..2 * n + carry_lookahead_ancillas(n)).collect();
+        let lookahead = circuit_depth(&carry_lookahead_adder(&a, &b, &ancilla));
+        let ripple = circuit_depth(&cuccaro_adder(&a, &b, Some(ancilla[0]), Some(ancilla[1])));
+        assert!(lookahead * 4 < ripple, "Depth {} vs {}", lookahead, ripple);
+    }
+
+    #[test]
+    fn test_best_adder_follows_weights() {
+        use crate::simulator::{basis_state, probabilities, simulate_from};
+
+        let (a, b, ancilla) = ([0, 1, 2], [3, 4, 5], [6]);
+        let is_ripple = |gates: &[Gate]| gates.iter().any(|g| matches!(g, Gate::CCX(..)));
+
+        // Rotations priced as T-heavy synthesis: ripple carry wins
+        let ft = best_adder(&a, &b, &ancilla, &CostWeights::fault_tolerant());
+        assert!(is_ripple(&ft));
+
+        // Prohibitive ancilla, or none at all: the QFT adder
+        let weights = CostWeights { ancilla: 1e12, ..CostWeights::fault_tolerant() };
+        let qft = best_adder(&a, &b, &ancilla, &weights);
+        assert!(!is_ripple(&qft));
+        assert!(!is_ripple(&best_adder(&a, &b, &[], &CostWeights::fault_tolerant())));
+
+        // Both choices add with the same bit order
+        let encode = |reg: &[usize], v: usize| (0..3).filter(|&i| v >> i & 1 == 1).map(|i| 1 << reg[i]).sum::<usize>();
+        for circuit in [ft, qft] {
+            for (x, y) in [(3, 6), (5, 5), (7, 1), (0, 4)] {
+                let probs = probabilities(&simulate_from(&circuit, &basis_state(7, encode(&a, x) | encode(&b, y))).unwrap());
+                assert!((probs[encode(&a, x) | encode(&b, (x + y) % 8)] - 1.0).abs() < 1e-9, "{} + {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_best_adder_follows_error_rates() {
+        use crate::analysis::circuit_analysis::ErrorRates;
+        use crate::gates::core::GateKind;
+
+        let (a, b, ancilla) = ([0, 1, 2], [3, 4, 5], [6]);
+        let is_ripple = |gates: &[Gate]| gates.iter().any(|g| matches!(g, Gate::CCX(..)));
+
+        // Native controlled phases: the QFT adder has far fewer two-qubit gates
+        let rates = ErrorRates::new(1e-4, 1e-3, 0.0);
+        assert!(!is_ripple(&best_adder(&a, &b, &ancilla, &CostWeights::noise_aware(rates.clone()))));
+
+        // Poorly calibrated CP gates make the Toffoli ladder the safer choice
+        let noisy_cp = CostWeights::noise_aware(rates.with_kind(GateKind::CP, 0.05));
+        assert!(is_ripple(&best_adder(&a, &b, &ancilla, &noisy_cp)));
+    }
+
     #[test]
     fn test_quantum_equals() {
         let circuit = quantum_equals(&[0, 1], &[2, 3], 4);
@@ -31,9 +31,11 @@
 //! - **Interaction**: Requires a "Black Box" for Hamiltonian simulation (e^(iAt)) 
 //!   and state preparation for the vector $b$.
 //! - **Side Effects**: Post-selection on the ancilla qubit is required to collapse 
-//!    the system into the solution state.
+//!   the system into the solution state.
 
 use crate::gates::core::Gate;
+use crate::gates::circuit::inverse_circuit;
+use crate::gates::decomposition::control_circuit;
 use crate::algorithms::qft;
 
 // ============================================================================
@@ -65,9 +67,7 @@ pub fn hhl_circuit(
     for (i, &cq) in clock_qubits.iter().enumerate() {
         let power = 1 << i;
         for _ in 0..power {
-            for g in &matrix_evolution {
-                gates.extend(make_controlled(cq, g));
-            }
+            gates.extend(control_circuit(cq, &matrix_evolution));
         }
     }
     
@@ -86,13 +86,11 @@ pub fn hhl_circuit(
     // 3. Uncompute QPE
     gates.extend(qft::qft(clock_qubits.len(), Some(clock_qubits))); // Forward QFT as inverse of inverse
     
+    let inverse_evolution = inverse_circuit(&matrix_evolution);
     for (i, &cq) in clock_qubits.iter().rev().enumerate() {
         let power = 1 << (clock_qubits.len() - 1 - i);
         for _ in 0..power {
-            for g in &matrix_evolution {
-                // In reality, this should be the inverse evolution e^(-iAt)
-                gates.extend(make_controlled(cq, &g.inverse()));
-            }
+            gates.extend(control_circuit(cq, &inverse_evolution));
         }
     }
     
@@ -103,20 +101,6 @@ pub fn hhl_circuit(
     gates
 }
 
-fn make_controlled(control: usize, gate: &Gate) -> Vec<Gate> {
-    match gate {
-        Gate::X(t) => vec![Gate::CX(control, *t)],
-        Gate::Y(t) => vec![Gate::CY(control, *t)],
-        Gate::Z(t) => vec![Gate::CZ(control, *t)],
-        Gate::RZ(t, theta) => vec![Gate::CRZ(control, *t, *theta)],
-        Gate::H(t) => {
-            // H = RY(pi/2) Z
-            vec![Gate::CRY(control, *t, std::f64::consts::FRAC_PI_2), Gate::CZ(control, *t)]
-        },
-        _ => vec![gate.clone()], // Fallback (should decompose in real implementation)
-    }
-}
-
 // ============================================================================
 // QUANTUM SINGULAR VALUE TRANSFORMATION (QSVT)
 // ============================================================================
@@ -79,10 +79,10 @@ impl QuantumEchoes {
         
         // Apply X to each qubit (bit flip all)
         let mut new_state = vec![0.0; size];
-        for i in 0..size {
+        for (i, &amp) in state.iter().enumerate() {
             // XOR with all 1s flips all bits
             let flipped = i ^ ((1 << n) - 1);
-            new_state[flipped] = state[i];
+            new_state[flipped] = amp;
         }
         
         state.copy_from_slice(&new_state);
@@ -183,11 +183,11 @@ impl BernsteinVaziraniState {
         
         let mut new_amplitudes = vec![0.0; size];
         
-        for y in 0..size {
+        for (y, out) in new_amplitudes.iter_mut().enumerate() {
             for x in 0..size {
                 let dot = (x & y).count_ones();
                 let sign = if dot % 2 == 0 { 1.0 } else { -1.0 };
-                new_amplitudes[y] += sign * self.amplitudes[x] * factor;
+                *out += sign * self.amplitudes[x] * factor;
             }
         }
         
@@ -194,12 +194,12 @@ impl DeutschJozsaState {
         // We implement it using the fast Walsh-Hadamard transform (FWHT)
         let mut new_amplitudes = vec![0.0; size];
         
-        for x in 0..size {
+        for (x, out) in new_amplitudes.iter_mut().enumerate() {
             for y in 0..size {
                 // (-1)^(x·y) where x·y is bitwise AND popcount
                 let dot_product = (x & y).count_ones();
                 let sign = if dot_product % 2 == 0 { 1.0 } else { -1.0 };
-                new_amplitudes[x] += sign * self.amplitudes[y] * factor;
+                *out += sign * self.amplitudes[y] * factor;
             }
         }
         
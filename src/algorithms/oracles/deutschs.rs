@@ -529,7 +529,7 @@ mod tests {
 
     #[test]
     fn test_trace_execution() {
-        let (final_state, result, traces) = 
+        let (_final_state, result, traces) = 
             deutsch_algorithm_with_trace(&DeutschOracle::BalancedIdentity);
         
         assert_eq!(result, DeutschResult::Balanced);
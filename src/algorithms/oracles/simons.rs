@@ -166,7 +166,7 @@ impl SimonsState {
 
     /// Applies Hadamard to the first n qubits only
     pub fn apply_hadamard_first_register(&mut self) {
-        let size_n = 1 << self.n;
+        let size_n: usize = 1 << self.n;
         let size_2n = 1 << (2 * self.n);
         let factor = 1.0 / (size_n as f64).sqrt();
         
@@ -178,8 +178,8 @@ impl SimonsState {
                     let old_idx = old_x + y * size_n;
                     let new_idx = new_x + y * size_n;
                     
-                    let dot = ((old_x & new_x) as usize).count_ones();
-                    let sign = if dot % 2 == 0 { 1.0 } else { -1.0 };
+                    let dot = (old_x & new_x).count_ones();
+                    let sign = if dot.is_multiple_of(2) { 1.0 } else { -1.0 };
                     
                     new_amplitudes[new_idx] += sign * self.amplitudes[old_idx] * factor;
                 }
@@ -216,10 +216,10 @@ impl SimonsState {
         let size_n = 1 << self.n;
         let mut probs = vec![0.0; size_n];
         
-        for x in 0..size_n {
+        for (x, p) in probs.iter_mut().enumerate() {
             for y in 0..(1 << self.n) {
                 let idx = x + y * size_n;
-                probs[x] += self.amplitudes[idx].powi(2);
+                *p += self.amplitudes[idx].powi(2);
             }
         }
         
@@ -280,13 +280,7 @@ fn are_linearly_independent(constraints: &[u64], n: usize) -> bool {
     
     for col in 0..n {
         // Find pivot
-        let mut pivot_row = None;
-        for row in rank..matrix.len() {
-            if (matrix[row] >> col) & 1 == 1 {
-                pivot_row = Some(row);
-                break;
-            }
-        }
+        let pivot_row = (rank..matrix.len()).find(|&row| (matrix[row] >> col) & 1 == 1);
         
         if let Some(pr) = pivot_row {
             // Swap with current rank row
@@ -324,13 +318,7 @@ fn solve_linear_system(constraints: &[u64], n: usize) -> u64 {
     
     for col in 0..n {
         // Find pivot
-        let mut pivot_row = None;
-        for row in current_row..matrix.len() {
-            if (matrix[row] >> col) & 1 == 1 {
-                pivot_row = Some(row);
-                break;
-            }
-        }
+        let pivot_row = (current_row..matrix.len()).find(|&row| (matrix[row] >> col) & 1 == 1);
         
         if let Some(pr) = pivot_row {
             matrix.swap(current_row, pr);
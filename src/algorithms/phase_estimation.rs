@@ -184,15 +184,15 @@ pub fn quantum_phase_estimation(
     
     // Build probability distribution
     let mut distribution = vec![0.0; size];
-    for m in 0..size {
+    for (m, p) in distribution.iter_mut().enumerate() {
         // Probability from interference pattern
         let delta = (m as f64 - theta_scaled).abs();
         if delta < 0.5 {
-            distribution[m] = max_overlap.powi(2);
+            *p = max_overlap.powi(2);
         } else {
             // Sinc-squared falloff
             let x = PI * delta;
-            distribution[m] = max_overlap.powi(2) * (x.sin() / x).powi(2) * 0.1;
+            *p = max_overlap.powi(2) * (x.sin() / x).powi(2) * 0.1;
         }
     }
     
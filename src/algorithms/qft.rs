@@ -3,8 +3,11 @@
 //! This module provides:
 //! - Standard QFT circuit synthesis
 //! - Inverse QFT
+//! - Swap-free QFT / inverse QFT for Fourier-basis arithmetic
+//! - `_with_endianness` variants for registers stored least significant bit first
 //! - Approximate QFT (reduced depth)
-//! - Quantum Phase Estimation (QPE)
+//! - Quantum Phase Estimation (QPE) and the order-finding circuit of Shor's algorithm
+//! - Grover phase oracles for marked bitstrings
 //!
 //! ## 🎯 Why is this used?
 //! The QFT is one of the most critical subroutines in quantum computing. It is the quantum 
@@ -34,12 +37,18 @@
 //!     - Standard QFT: $O(n^2)$ gates for $n$ qubits.
 //!     - Approximate QFT: $O(n \log n)$ gates, significantly improving scalability.
 //! - **Interaction**: Standard implementation includes a final sequence of SWAP gates 
-//!   to correct qubit ordering unless specified otherwise.
+//!   to correct qubit ordering; `qft_no_swap` / `inverse_qft_no_swap` omit them.
+//! - **Bit Ordering**: With swaps, input and output both read `qubits[0]` as the
+//!   most significant bit. Without swaps, the input is read MSB-first and the
+//!   output LSB-first (bit-reversed).
 //! - **Precision**: Controlled rotations use high-precision $2\pi/2^k$ calculations 
 //!   to minimize phase accumulation errors.
 
 use std::f64::consts::PI;
-use crate::gates::core::Gate;
+use crate::algorithms::arithmetic::controlled_modular_exp;
+use crate::gates::circuit::{inverse_circuit, reverse_register};
+use crate::gates::core::{Endianness, Gate};
+use crate::gates::decomposition::mcz;
 
 // ============================================================================
 // QUANTUM FOURIER TRANSFORM (QFT)
@@ -50,7 +59,12 @@ use crate::gates::core::Gate;
 /// QFT transforms computational basis states to Fourier basis:
 /// |j⟩ → (1/√N) Σₖ e^(2πijk/N) |k⟩
 /// 
-/// The circuit consists of Hadamard gates and controlled phase rotations.
+/// The circuit consists of Hadamard gates and controlled phase rotations,
+/// followed by the bit-reversal SWAPs.
+///
+/// **Bit order:** j and k both read `qubits[0]` as the most significant bit,
+/// i.e. `Endianness::Big`, the textbook convention but not the crate default
+/// `Endianness::Little`. Use [`qft_with_endianness`] to pick the order.
 /// 
 /// # Arguments
 /// * `n` - Number of qubits
@@ -62,6 +76,35 @@ pub fn qft(n: usize, qubits: Option<&[usize]>) -> Vec<Gate> {
     let q: Vec<usize> = qubits.map(|qs| qs.to_vec())
         .unwrap_or_else(|| (0..n).collect());
     
+    let mut gates = qft_no_swap(n, Some(&q));
+    
+    // Swap qubits to reverse order (for standard QFT convention)
+    gates.extend(reverse_register(&q));
+    
+    gates
+}
+
+/// [`qft`] on `qubits` read in the given bit order, input and output alike
+///
+/// `Endianness::Big` is plain `qft`; `Endianness::Little` reads
+/// `qubits[0]` as the least significant bit of both j and k.
+pub fn qft_with_endianness(n: usize, qubits: Option<&[usize]>, endianness: Endianness) -> Vec<Gate> {
+    let q: Vec<usize> = qubits.map(|qs| qs.to_vec())
+        .unwrap_or_else(|| (0..n).collect());
+    qft(n, Some(&endianness.msb_first(&q)))
+}
+
+/// QFT without the final bit-reversal SWAPs
+/// 
+/// |j⟩ → (1/√N) Σₖ e^(2πijk/N) |k⟩ with j read `qubits[0]`-first (MSB) and
+/// k read `qubits[0]`-last (LSB): qubit `qubits[i]` ends in
+/// (|0⟩ + e^(2πij/2^(n-i))|1⟩)/√2. Use this when the next stage only applies
+/// phases in the Fourier basis (e.g. Draper addition) and the swaps would
+/// cancel anyway.
+pub fn qft_no_swap(n: usize, qubits: Option<&[usize]>) -> Vec<Gate> {
+    let q: Vec<usize> = qubits.map(|qs| qs.to_vec())
+        .unwrap_or_else(|| (0..n).collect());
+    
     assert_eq!(q.len(), n, "Qubit list length must match n");
     
     let mut gates = Vec::new();
@@ -78,30 +121,45 @@ pub fn qft(n: usize, qubits: Option<&[usize]>) -> Vec<Gate> {
         }
     }
     
-    // Swap qubits to reverse order (for standard QFT convention)
-    for i in 0..(n / 2) {
-        gates.push(Gate::SWAP(q[i], q[n - 1 - i]));
-    }
-    
     gates
 }
 
 /// Generate the inverse QFT circuit
 /// 
 /// The inverse QFT transforms Fourier basis back to computational basis.
-/// It's the adjoint of the QFT circuit.
+/// It's the adjoint of `qft`, with the same bit ordering: `qubits[0]` is the
+/// most significant bit (`Endianness::Big`, not the crate default).
 pub fn inverse_qft(n: usize, qubits: Option<&[usize]>) -> Vec<Gate> {
     let q: Vec<usize> = qubits.map(|qs| qs.to_vec())
         .unwrap_or_else(|| (0..n).collect());
     
     assert_eq!(q.len(), n, "Qubit list length must match n");
     
-    let mut gates = Vec::new();
-    
     // Reverse the swaps first
-    for i in 0..(n / 2) {
-        gates.push(Gate::SWAP(q[i], q[n - 1 - i]));
-    }
+    let mut gates = reverse_register(&q);
+    gates.extend(inverse_qft_no_swap(n, Some(&q)));
+    
+    gates
+}
+
+/// [`inverse_qft`] with the bit order of [`qft_with_endianness`]
+pub fn inverse_qft_with_endianness(n: usize, qubits: Option<&[usize]>, endianness: Endianness) -> Vec<Gate> {
+    let q: Vec<usize> = qubits.map(|qs| qs.to_vec())
+        .unwrap_or_else(|| (0..n).collect());
+    inverse_qft(n, Some(&endianness.msb_first(&q)))
+}
+
+/// Inverse QFT without the leading bit-reversal SWAPs
+/// 
+/// The adjoint of `qft_no_swap`: expects the Fourier index `qubits[0]`-last
+/// and returns the computational index `qubits[0]`-first.
+pub fn inverse_qft_no_swap(n: usize, qubits: Option<&[usize]>) -> Vec<Gate> {
+    let q: Vec<usize> = qubits.map(|qs| qs.to_vec())
+        .unwrap_or_else(|| (0..n).collect());
+    
+    assert_eq!(q.len(), n, "Qubit list length must match n");
+    
+    let mut gates = Vec::new();
     
     // Apply inverse rotations in reverse order
     for i in (0..n).rev() {
@@ -148,9 +206,7 @@ pub fn approximate_qft(n: usize, approximation_degree: usize, qubits: Option<&[u
     }
     
     // Swap qubits
-    for i in 0..(n / 2) {
-        gates.push(Gate::SWAP(q[i], q[n - 1 - i]));
-    }
+    gates.extend(reverse_register(&q));
     
     gates
 }
@@ -189,7 +245,7 @@ pub fn qpe_circuit(
     
     // Step 2: Apply controlled unitary powers
     // Control qubit k applies U^(2^k) to the unitary register
-    for (_k, cu_gates) in controlled_powers.iter().enumerate() {
+    for cu_gates in controlled_powers.iter() {
         gates.extend(cu_gates.clone());
     }
     
@@ -199,6 +255,35 @@ pub fn qpe_circuit(
     gates
 }
 
+/// Order finding for a modulo N: QPE of U|y⟩ = |a·y mod N⟩ started on |1⟩
+///
+/// The counting register is qubits `0..counting_qubits` and `work_qubits`
+/// (LSB first) hold y; there must be at least ⌈log₂ N⌉ of them, and at least
+/// one, or the call panics. Counting qubit j controls
+/// U^(2^j), one stage of `controlled_modular_exp`. Measuring the counting
+/// register as a little-endian integer m gives m / 2^t ≈ s/r for the order
+/// r of a and a uniformly random s.
+pub fn order_finding(a: u64, n: u64, counting_qubits: usize, work_qubits: &[usize]) -> Vec<Gate> {
+    assert!(
+        work_qubits.iter().all(|&q| q >= counting_qubits),
+        "Work qubits must not overlap the counting register 0..{}", counting_qubits
+    );
+    let needed = (u64::BITS - n.saturating_sub(1).leading_zeros()).max(1) as usize;
+    assert!(
+        work_qubits.len() >= needed,
+        "Work register has {} qubits but residues mod {} need {}", work_qubits.len(), n, needed
+    );
+    // Reversed so the inverse QFT reads qubit t − 1 as the most significant bit
+    let precision: Vec<usize> = (0..counting_qubits).rev().collect();
+    let counting: Vec<usize> = (0..counting_qubits).collect();
+
+    let mut gates = vec![Gate::X(work_qubits[0])];
+    gates.extend(counting.iter().map(|&q| Gate::H(q)));
+    gates.extend(controlled_modular_exp(&counting, work_qubits, a, n, &[]));
+    gates.extend(inverse_qft(counting_qubits, Some(&precision)));
+    gates
+}
+
 /// Helper function to create controlled-U circuits for common unitaries
 /// This creates c-U^(2^k) by repeated application or efficient synthesis
 pub fn create_controlled_power(
@@ -243,20 +328,9 @@ pub fn grover_diffuser(n: usize, qubits: Option<&[usize]>) -> Vec<Gate> {
         gates.push(Gate::X(qubit));
     }
     
-    // Apply multi-controlled Z (or H-MCX-H on last qubit)
-    if n == 1 {
-        gates.push(Gate::Z(q[0]));
-    } else if n == 2 {
-        gates.push(Gate::CZ(q[0], q[1]));
-    } else {
-        // Multi-controlled Z: MCZ on all qubits
-        // Implemented as H on target, MCX, H on target
-        let controls: Vec<usize> = q[..n-1].to_vec();
-        let target = q[n-1];
-        
-        gates.push(Gate::H(target));
-        gates.push(Gate::MCX(controls, target));
-        gates.push(Gate::H(target));
+    // Apply multi-controlled Z (H-MCX-H on the last qubit)
+    if let Some((&target, controls)) = q.split_last() {
+        gates.extend(mcz(controls, target));
     }
     
     // Apply X to all qubits
@@ -348,10 +422,7 @@ pub fn amplitude_amplification(
     gates.extend(state_prep.iter().cloned());
     
     // Compute inverse of state preparation
-    let state_prep_inv: Vec<Gate> = state_prep.iter()
-        .rev()
-        .map(|g| g.inverse())
-        .collect();
+    let state_prep_inv = inverse_circuit(state_prep);
     
     for _ in 0..num_iterations {
         // Apply oracle: S_χ (marks good states with -1)
@@ -373,16 +444,8 @@ pub fn amplitude_amplification(
             gates.push(Gate::X(q));
         }
         
-        if qubits.len() == 1 {
-            gates.push(Gate::Z(qubits[0]));
-        } else if qubits.len() == 2 {
-            gates.push(Gate::CZ(qubits[0], qubits[1]));
-        } else if qubits.len() >= 3 {
-            let controls: Vec<usize> = qubits[..qubits.len()-1].to_vec();
-            let target = qubits[qubits.len()-1];
-            gates.push(Gate::H(target));
-            gates.push(Gate::MCX(controls, target));
-            gates.push(Gate::H(target));
+        if let Some((&target, controls)) = qubits.split_last() {
+            gates.extend(mcz(controls, target));
         }
         
         for &q in &qubits {
@@ -399,43 +462,49 @@ pub fn amplitude_amplification(
 // ORACLE CONSTRUCTION
 // ============================================================================
 
+/// Phase oracle flipping the sign of the single basis state |target⟩
+///
+/// Bit i of `target` is the value of `qubits[i]` (LSB first). Qubits whose
+/// bit is 0 are sandwiched in X so that an all-ones multi-controlled Z fires
+/// exactly on |target⟩.
+pub fn phase_oracle_for_bitstring(target: usize, qubits: &[usize]) -> Vec<Gate> {
+    let n = qubits.len();
+    assert!(n > 0, "Oracle needs at least one qubit");
+    assert!(n >= usize::BITS as usize || target >> n == 0, "Target {} does not fit in {} qubits", target, n);
+
+    let flips: Vec<Gate> = (0..n)
+        .filter(|i| (target >> i) & 1 == 0)
+        .map(|i| Gate::X(qubits[i]))
+        .collect();
+
+    let mut gates = flips.clone();
+    gates.push(match qubits {
+        [q] => Gate::Z(*q),
+        [a, b] => Gate::CZ(*a, *b),
+        _ => Gate::MCZ(qubits[..n - 1].to_vec(), qubits[n - 1]),
+    });
+    gates.extend(flips);
+    gates
+}
+
+/// Phase oracle flipping the sign of every basis state in `marked`
+///
+/// One `phase_oracle_for_bitstring` per index; the marks must be distinct,
+/// since a repeated index would flip back.
+pub fn phase_oracle_for_indices(marked: &[usize], qubits: &[usize]) -> Vec<Gate> {
+    for (i, m) in marked.iter().enumerate() {
+        assert!(!marked[..i].contains(m), "Index {} is marked twice", m);
+    }
+    marked.iter().flat_map(|&m| phase_oracle_for_bitstring(m, qubits)).collect()
+}
+
 /// Create a phase oracle that marks a specific computational basis state
 /// 
 /// Applies phase -1 to |target_state⟩, identity to others.
 pub fn create_phase_oracle(n: usize, target_state: usize, qubits: Option<&[usize]>) -> Vec<Gate> {
-    let q: Vec<usize> = qubits.map(|qs| qs.to_vec())
+    let q: Vec<usize> = qubits.map(|qs| qs[..n].to_vec())
         .unwrap_or_else(|| (0..n).collect());
-    
-    let mut gates = Vec::new();
-    
-    // Apply X gates to flip bits that are 0 in target
-    for i in 0..n {
-        if (target_state >> i) & 1 == 0 {
-            gates.push(Gate::X(q[i]));
-        }
-    }
-    
-    // Apply multi-controlled Z
-    if n == 1 {
-        gates.push(Gate::Z(q[0]));
-    } else if n == 2 {
-        gates.push(Gate::CZ(q[0], q[1]));
-    } else {
-        let controls: Vec<usize> = q[..n-1].to_vec();
-        let target = q[n-1];
-        gates.push(Gate::H(target));
-        gates.push(Gate::MCX(controls.clone(), target));
-        gates.push(Gate::H(target));
-    }
-    
-    // Undo X gates
-    for i in 0..n {
-        if (target_state >> i) & 1 == 0 {
-            gates.push(Gate::X(q[i]));
-        }
-    }
-    
-    gates
+    phase_oracle_for_bitstring(target_state, &q)
 }
 
 /// Create an oracle that marks multiple states
@@ -475,6 +544,87 @@ mod tests {
         assert_eq!(qft_gates.len(), iqft_gates.len());
     }
 
+    #[test]
+    fn test_qft_then_inverse_is_identity() {
+        use crate::simulator::{basis_state, simulate_from, states_close};
+
+        let q = [2, 0, 3, 1];
+        for (forward, inverse) in [
+            (qft(4, Some(&q)), inverse_qft(4, Some(&q))),
+            (qft_no_swap(4, Some(&q)), inverse_qft_no_swap(4, Some(&q))),
+        ] {
+            let circuit: Vec<Gate> = forward.into_iter().chain(inverse).collect();
+            for input in 0..16 {
+                let state = simulate_from(&circuit, &basis_state(4, input)).unwrap();
+                assert!(states_close(&state, &basis_state(4, input), 1e-10), "Input {}", input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_qft_with_endianness() {
+        use crate::gates::core::Complex;
+        use crate::simulator::{basis_state, simulate_from, states_close};
+
+        let q = [1, 3, 0, 2];
+        for endianness in [Endianness::Little, Endianness::Big] {
+            let lsb = endianness.lsb_first(&q);
+            let index = |v: usize| (0..4).map(|i| ((v >> i) & 1) << lsb[i]).sum::<usize>();
+            let forward = qft_with_endianness(4, Some(&q), endianness);
+            let state = simulate_from(&forward, &basis_state(4, index(5))).unwrap();
+            for k in 0..16 {
+                let expected = Complex::from_polar(0.25, 2.0 * PI * 5.0 * k as f64 / 16.0);
+                assert!((state[index(k)] - expected).norm() < 1e-10, "{:?}, k = {}", endianness, k);
+            }
+            let back = simulate_from(&inverse_qft_with_endianness(4, Some(&q), endianness), &state).unwrap();
+            assert!(states_close(&back, &basis_state(4, index(5)), 1e-10));
+        }
+        assert_eq!(qft_with_endianness(4, Some(&q), Endianness::Big), qft(4, Some(&q)));
+    }
+
+    #[test]
+    fn test_qft_no_swap_bit_order() {
+        use crate::gates::core::Complex;
+        use crate::simulator::{basis_state, simulate_from};
+
+        // j = 3 read MSB-first on [0, 1, 2] is basis index 0b110
+        let state = simulate_from(&qft_no_swap(3, None), &basis_state(3, 0b110)).unwrap();
+        for (k, amp) in state.iter().enumerate() {
+            // Output index k read LSB-first is the plain basis index
+            let expected = Complex::from_polar(1.0 / 8f64.sqrt(), 2.0 * PI * 3.0 * k as f64 / 8.0);
+            assert!((*amp - expected).norm() < 1e-10, "k = {}", k);
+        }
+    }
+
+    #[test]
+    fn test_order_finding_mod_15() {
+        use crate::simulator::{probabilities, simulate};
+
+        // 7 has order 4 mod 15: with 3 counting qubits, m ∈ {0, 2, 4, 6} each with 1/4
+        let circuit = order_finding(7, 15, 3, &[3, 4, 5, 6]);
+        let probs = probabilities(&simulate(&circuit, 7).unwrap());
+        let mut counting = [0.0; 8];
+        for (index, p) in probs.iter().enumerate() {
+            counting[index & 0b111] += p;
+        }
+        for (m, p) in counting.iter().enumerate() {
+            let expected = if m % 2 == 0 { 0.25 } else { 0.0 };
+            assert!((p - expected).abs() < 1e-9, "m = {}: {}", m, p);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "residues mod 15 need 4")]
+    fn test_order_finding_rejects_small_work_register() {
+        order_finding(7, 15, 3, &[3, 4, 5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Work register has 0 qubits")]
+    fn test_order_finding_rejects_empty_work_register() {
+        order_finding(2, 3, 2, &[]);
+    }
+
     #[test]
     fn test_grover_optimal_iterations() {
         // For 1 marked out of 4 (2 qubits), optimal is about 1 iteration
@@ -483,7 +633,7 @@ mod tests {
         
         // For 1 marked out of 256 (8 qubits), optimal is about 12 iterations
         let iters = optimal_grover_iterations(8, 1);
-        assert!(iters >= 10 && iters <= 15);
+        assert!((10..=15).contains(&iters));
     }
 
     #[test]
@@ -493,4 +643,30 @@ mod tests {
         // Should have CZ (no X gates since target is all 1s)
         assert!(oracle.iter().any(|g| matches!(g, Gate::CZ(_, _))));
     }
+
+    #[test]
+    fn test_phase_oracles_flip_only_marked_states() {
+        use crate::simulator::{basis_state, probabilities, simulate, simulate_from, states_close};
+
+        // Register on non-contiguous qubits 3, 0, 2; qubit 1 is a spectator
+        let qubits = [3, 0, 2];
+        let index_of = |value: usize| (0..3).fold(0, |acc, i| acc | ((value >> i) & 1) << qubits[i]);
+        for (oracle, marked) in [
+            (phase_oracle_for_bitstring(0b101, &qubits), vec![0b101]),
+            (phase_oracle_for_bitstring(0b000, &qubits), vec![0b000]),
+            (phase_oracle_for_indices(&[1, 6, 7], &qubits), vec![1, 6, 7]),
+        ] {
+            for value in 0..8 {
+                let input = basis_state(4, index_of(value) | 0b10);
+                let sign = if marked.contains(&value) { -1.0 } else { 1.0 };
+                let expected: Vec<_> = input.iter().map(|a| *a * sign).collect();
+                assert!(states_close(&simulate_from(&oracle, &input).unwrap(), &expected, 1e-12), "Value {:03b}", value);
+            }
+        }
+
+        // One amplification round finds 1 of 4 with certainty
+        let prep = [Gate::H(0), Gate::H(1)];
+        let circuit = amplitude_amplification(&prep, &phase_oracle_for_bitstring(2, &[0, 1]), 1);
+        assert!((probabilities(&simulate(&circuit, 2).unwrap())[2] - 1.0).abs() < 1e-10);
+    }
 }
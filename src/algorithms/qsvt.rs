@@ -27,6 +27,8 @@
 //!     [  ·    ·  ]
 //! ```
 //! where (⟨0|⊗I) U (|0⟩⊗I) = A/α for some normalization α.
+//! `block_encode_sparse` builds U as a gate circuit for a Pauli-sum
+//! Hamiltonian, with α the 1-norm of its coefficients.
 //!
 //! ### QSVT Circuit
 //!
@@ -57,6 +59,9 @@
 //! - Low & Chuang (2017). "Optimal Hamiltonian simulation by quantum signal processing"
 
 use std::f64::consts::PI;
+use crate::gates::circuit::inverse_circuit;
+use crate::gates::core::{Gate, PauliOp};
+use crate::variational::observable::Observable;
 
 /// Represents a block encoding of a matrix
 ///
@@ -187,10 +192,8 @@ impl BlockEncoding {
         let mut result = vec![0.0; dim];
         
         // Matrix-vector multiplication (blocked version)
-        for i in 0..dim {
-            for j in 0..dim {
-                result[i] += self.matrix[i * dim + j] * state[j];
-            }
+        for (row, out) in self.matrix.chunks(dim).zip(result.iter_mut()) {
+            *out = row.iter().zip(state).map(|(a, x)| a * x).sum();
         }
         
         result
@@ -240,7 +243,7 @@ impl QSPPhases {
         
         QSPPhases {
             phases,
-            even_parity: degree % 2 == 0,
+            even_parity: degree.is_multiple_of(2),
         }
     }
 
@@ -295,7 +298,7 @@ impl QSPPhases {
         
         QSPPhases {
             phases,
-            even_parity: degree % 2 == 0,
+            even_parity: degree.is_multiple_of(2),
         }
     }
 
@@ -453,6 +456,113 @@ pub fn prepare_lcu_state(coefficients: &[f64]) -> Vec<f64> {
         .collect()
 }
 
+/// PREPARE–SELECT–PREPARE† block encoding of a Pauli-sum Hamiltonian
+///
+/// For H = Σⱼ cⱼPⱼ with m terms, PREPARE loads Σⱼ √(|cⱼ|/α) |j⟩ on the
+/// ancillas (little-endian, ⌈log₂ m⌉ of them at least) and SELECT applies
+/// sign(cⱼ)·Pⱼ controlled on |j⟩. The circuit U satisfies
+/// (⟨0|_a ⊗ I) U (|0⟩_a ⊗ I) = H/α with subnormalization α = Σⱼ |cⱼ|,
+/// the 1-norm of the coefficients. Term qubit i acts on `system_qubits[i]`.
+pub fn block_encode_sparse(h: &Observable, system_qubits: &[usize], ancilla_qubits: &[usize]) -> Vec<Gate> {
+    assert_eq!(system_qubits.len(), h.num_qubits, "One system qubit per Hamiltonian qubit");
+    let m = h.terms.len();
+    assert!(
+        m <= 1 << ancilla_qubits.len(),
+        "{} terms need {} ancillas, got {}", m, m.next_power_of_two().trailing_zeros(), ancilla_qubits.len()
+    );
+    let alpha: f64 = h.terms.iter().map(|t| t.coefficient.abs()).sum();
+    assert!(alpha > 0.0, "Hamiltonian has no non-zero terms");
+
+    let mut amplitudes = vec![0.0; 1 << ancilla_qubits.len()];
+    for (j, term) in h.terms.iter().enumerate() {
+        amplitudes[j] = (term.coefficient.abs() / alpha).sqrt();
+    }
+    let mut prepare = Vec::new();
+    prepare_real_amplitudes(&amplitudes, ancilla_qubits, &mut Vec::new(), &mut prepare);
+
+    let mut gates = prepare.clone();
+    for (j, term) in h.terms.iter().enumerate() {
+        let flips: Vec<Gate> = (0..ancilla_qubits.len())
+            .filter(|b| j >> b & 1 == 0)
+            .map(|b| Gate::X(ancilla_qubits[b]))
+            .collect();
+        gates.extend(flips.iter().cloned());
+        for (i, pauli) in term.paulis.iter().enumerate() {
+            let q = system_qubits[i];
+            match pauli {
+                PauliOp::I => {}
+                PauliOp::X => gates.push(multi_controlled(ancilla_qubits, Gate::X(q))),
+                PauliOp::Z => gates.push(multi_controlled(ancilla_qubits, Gate::Z(q))),
+                // Y = S X S†
+                PauliOp::Y => gates.extend([Gate::Sdg(q), multi_controlled(ancilla_qubits, Gate::X(q)), Gate::S(q)]),
+            }
+        }
+        if term.coefficient < 0.0 {
+            gates.push(match ancilla_qubits.split_last() {
+                None => Gate::GlobalPhase(PI),
+                Some((&last, rest)) => multi_controlled(rest, Gate::Z(last)),
+            });
+        }
+        gates.extend(flips);
+    }
+    gates.extend(inverse_circuit(&prepare));
+    gates
+}
+
+/// X or Z on one qubit with any number of controls
+fn multi_controlled(controls: &[usize], gate: Gate) -> Gate {
+    match (controls, gate) {
+        ([], gate) => gate,
+        (controls, Gate::X(t)) => Gate::MCX(controls.to_vec(), t),
+        (controls, Gate::Z(t)) => Gate::MCZ(controls.to_vec(), t),
+        (_, gate) => unreachable!("No multi-controlled form for {:?}", gate),
+    }
+}
+
+/// RY tree loading real `amplitudes` (unit norm, little-endian) on `qubits`
+///
+/// `controls` holds the (qubit, value) pattern fixed by the enclosing branches.
+/// Inner splits use the non-negative norms of each half; the last split on a
+/// pair (a₀, a₁) uses 2·atan2(a₁, a₀), which also carries the signs.
+pub(crate) fn prepare_real_amplitudes(amplitudes: &[f64], qubits: &[usize], controls: &mut Vec<(usize, bool)>, out: &mut Vec<Gate>) {
+    let Some((&top, lower)) = qubits.split_last() else { return };
+    let half = amplitudes.len() / 2;
+    let norm = |a: &[f64]| a.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let (lo, hi) = (norm(&amplitudes[..half]), norm(&amplitudes[half..]));
+    if lo + hi == 0.0 {
+        return;
+    }
+
+    let theta = match amplitudes {
+        [a0, a1] => 2.0 * a1.atan2(*a0),
+        _ => 2.0 * hi.atan2(lo),
+    };
+    if theta != 0.0 {
+        let flips: Vec<Gate> = controls.iter().filter(|(_, v)| !v).map(|&(q, _)| Gate::X(q)).collect();
+        let wires: Vec<usize> = controls.iter().map(|&(q, _)| q).collect();
+        out.extend(flips.iter().cloned());
+        if wires.is_empty() {
+            out.push(Gate::RY(top, theta));
+        } else {
+            // C^k RY(θ) = RY(θ/2) · C^k X · RY(−θ/2) · C^k X
+            out.push(Gate::RY(top, theta / 2.0));
+            out.push(Gate::MCX(wires.clone(), top));
+            out.push(Gate::RY(top, -theta / 2.0));
+            out.push(Gate::MCX(wires, top));
+        }
+        out.extend(flips);
+    }
+
+    for (value, part, weight) in [(false, &amplitudes[..half], lo), (true, &amplitudes[half..], hi)] {
+        if weight > 0.0 {
+            let scaled: Vec<f64> = part.iter().map(|x| x / weight).collect();
+            controls.push((top, value));
+            prepare_real_amplitudes(&scaled, lower, controls, out);
+            controls.pop();
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -549,6 +659,50 @@ mod tests {
         assert!(qsvt.phases.degree() > 0);
     }
 
+    #[test]
+    fn test_block_encode_sparse_top_left_block() {
+        use crate::gates::core::Complex;
+        use crate::simulator::{basis_state, simulate_from};
+        use crate::variational::vqe_variants::{Hamiltonian, HamiltonianTerm};
+
+        let mut h = Hamiltonian::new(2);
+        h.add_term(HamiltonianTerm::new(0.7, vec![PauliOp::X, PauliOp::Z]));
+        h.add_term(HamiltonianTerm::new(-0.4, vec![PauliOp::Y, PauliOp::Y]));
+        h.add_term(HamiltonianTerm::new(0.25, vec![PauliOp::I, PauliOp::I]));
+        h.add_term(HamiltonianTerm::new(-1.1, vec![PauliOp::Z, PauliOp::X]));
+        h.add_term(HamiltonianTerm::new(0.3, vec![PauliOp::I, PauliOp::Y]));
+        let alpha = 0.7 + 0.4 + 0.25 + 1.1 + 0.3;
+
+        // System on qubits 0 and 3, three ancillas interleaved
+        let (system, ancillas) = ([0, 3], [1, 2, 4]);
+        let u = block_encode_sparse(&h, &system, &ancillas);
+        let embed = |x: usize| (x & 1) | (x >> 1 & 1) << 3;
+
+        for x in 0..4 {
+            let out = simulate_from(&u, &basis_state(5, embed(x))).unwrap();
+
+            // H|x⟩ term by term, scattered to the system qubits
+            let mut expected = vec![Complex::ZERO; 32];
+            for term in &h.terms {
+                let paulis: Vec<Gate> = term.paulis.iter().enumerate().filter_map(|(i, p)| match p {
+                    PauliOp::I => None,
+                    PauliOp::X => Some(Gate::X(system[i])),
+                    PauliOp::Y => Some(Gate::Y(system[i])),
+                    PauliOp::Z => Some(Gate::Z(system[i])),
+                }).collect();
+                let image = simulate_from(&paulis, &basis_state(5, embed(x))).unwrap();
+                for (e, a) in expected.iter_mut().zip(image) {
+                    *e = *e + a * (term.coefficient / alpha);
+                }
+            }
+
+            for y in 0..4 {
+                let (got, want) = (out[embed(y)], expected[embed(y)]);
+                assert!((got - want).norm() < 1e-10, "<{}|U|{}> = {:?}, want {:?}", y, x, got, want);
+            }
+        }
+    }
+
     #[test]
     fn test_prepare_lcu_state() {
         let coeffs = vec![1.0, 2.0, 3.0, 4.0];
@@ -1,10 +1,10 @@
 //! Quantum Walk Algorithms
 //!
 //! This module implements quantum random walk algorithms:
-//! - Discrete-time quantum walks (coined walks)
+//! - Discrete-time quantum walks (coined walks, multi-qubit coins on tori)
 //! - Continuous-time quantum walks
 //! - Quantum walk on graphs
-//! - Szegedy walks
+//! - Szegedy walks and their spectral (phase) gap
 //!
 //! ## 🎯 Why is this used?
 //! Quantum walks are the quantum version of classical random walks and provide a powerful 
@@ -37,7 +37,15 @@
 //! - **Side Effects**: Entanglement between the coin and position registers is fundamental 
 //!   to the walk's non-classical behavior (interference).
 
-use crate::gates::core::Gate;
+use std::f64::consts::PI;
+use crate::error::{check_dimension, RustiqError};
+use crate::gates::core::{Complex, Gate};
+use crate::algorithms::qft::qft;
+use crate::algorithms::arithmetic::{
+    controlled_decrement, controlled_increment, multi_controlled_decrement, multi_controlled_increment,
+};
+use crate::gates::circuit::{measure_x_basis, prepare_x_basis};
+use crate::synthesis::advanced::hermitian_eigen;
 
 // ============================================================================
 // DISCRETE-TIME QUANTUM WALK (COINED WALK)
@@ -54,11 +62,16 @@ pub enum CoinOperator {
     DFT,
     /// Biased coin with parameter
     Biased(f64),
+    /// Grover diffusion 2|s⟩⟨s| − I over a coin register of this many qubits
+    GroverN(usize),
+    /// Arbitrary coin circuit, applied as given
+    Custom(Vec<Gate>),
 }
 
 /// Generate a single step of discrete-time quantum walk on a line
 /// 
-/// The walk uses a coin qubit and position register.
+/// The walk uses a coin qubit and position register (`position_qubits[0]`
+/// least significant) on a cycle of 2ⁿ sites.
 /// Step = Shift · (Coin ⊗ I)
 pub fn dtqw_step_line(
     coin_qubit: usize,
@@ -68,52 +81,91 @@ pub fn dtqw_step_line(
     let mut gates = Vec::new();
     
     // Apply coin operator
-    gates.extend(coin_operator(coin_qubit, coin));
+    gates.extend(coin_operator(&[coin_qubit], coin));
     
     // Conditional shift based on coin state
     // If coin = |0⟩: shift left (subtract 1)
     // If coin = |1⟩: shift right (add 1)
     
-    let n = position_qubits.len();
-    
     // Shift right when coin is |1⟩
-    for i in (0..n).rev() {
-        if i == 0 {
-            gates.push(Gate::CX(coin_qubit, position_qubits[0]));
-        } else {
-            // Controlled increment with carry
-            let mut controls: Vec<usize> = vec![coin_qubit];
-            controls.extend(&position_qubits[..i]);
-            gates.push(Gate::MCX(controls, position_qubits[i]));
-        }
-    }
+    gates.extend(controlled_increment(coin_qubit, position_qubits));
     
     // Shift left when coin is |0⟩
     gates.push(Gate::X(coin_qubit));
-    for i in (0..n).rev() {
-        if i == 0 {
-            gates.push(Gate::CX(coin_qubit, position_qubits[0]));
-        } else {
-            let mut controls: Vec<usize> = vec![coin_qubit];
-            controls.extend(&position_qubits[..i]);
-            gates.push(Gate::MCX(controls, position_qubits[i]));
-        }
-    }
+    gates.extend(controlled_decrement(coin_qubit, position_qubits));
     gates.push(Gate::X(coin_qubit));
     
     gates
 }
 
-fn coin_operator(coin_qubit: usize, coin: &CoinOperator) -> Vec<Gate> {
+/// Coin flip on the coin register
+///
+/// Hadamard and Biased act on every coin qubit, DFT is the QFT over the
+/// register, and Grover is the diffusion over the whole coin space.
+pub fn coin_operator(coin_qubits: &[usize], coin: &CoinOperator) -> Vec<Gate> {
     match coin {
-        CoinOperator::Hadamard => vec![Gate::H(coin_qubit)],
-        CoinOperator::Grover => vec![
-            Gate::H(coin_qubit),
-            Gate::Z(coin_qubit),
-            Gate::H(coin_qubit),
+        CoinOperator::Hadamard => coin_qubits.iter().map(|&q| Gate::H(q)).collect(),
+        CoinOperator::Grover if coin_qubits.len() == 1 => vec![
+            Gate::H(coin_qubits[0]),
+            Gate::Z(coin_qubits[0]),
+            Gate::H(coin_qubits[0]),
         ],
-        CoinOperator::DFT => vec![Gate::H(coin_qubit)],
-        CoinOperator::Biased(theta) => vec![Gate::RY(coin_qubit, *theta)],
+        CoinOperator::Grover => grover_coin(coin_qubits),
+        CoinOperator::DFT if coin_qubits.len() == 1 => vec![Gate::H(coin_qubits[0])],
+        CoinOperator::DFT => qft(coin_qubits.len(), Some(coin_qubits)),
+        CoinOperator::Biased(theta) => coin_qubits.iter().map(|&q| Gate::RY(q, *theta)).collect(),
+        CoinOperator::GroverN(n) => {
+            assert_eq!(*n, coin_qubits.len(), "GroverN coin size does not match the coin register");
+            grover_coin(coin_qubits)
+        }
+        CoinOperator::Custom(gates) => gates.clone(),
+    }
+}
+
+/// 2|s⟩⟨s| − I = H^⊗m · X^⊗m · C^{m-1}Z · X^⊗m · H^⊗m · (−1), phase included
+fn grover_coin(coin_qubits: &[usize]) -> Vec<Gate> {
+    let (last, rest) = coin_qubits.split_last().expect("Coin register is empty");
+    let mut gates: Vec<Gate> = coin_qubits.iter().map(|&q| Gate::H(q)).collect();
+    gates.extend(coin_qubits.iter().map(|&q| Gate::X(q)));
+    gates.push(Gate::MCZ(rest.to_vec(), *last));
+    gates.extend(coin_qubits.iter().map(|&q| Gate::X(q)));
+    gates.extend(coin_qubits.iter().map(|&q| Gate::H(q)));
+    gates.push(Gate::GlobalPhase(PI));
+    gates
+}
+
+/// One step of a coined walk on the torus (ℤ_{2ⁿ})^D
+///
+/// `axes[a]` is the position register of axis a (least significant bit
+/// first). Coin value |2a⟩ moves the walker one site down axis a and
+/// |2a + 1⟩ one site up, so D = 1 with a single coin qubit is
+/// `dtqw_step_line`; a 2-qubit `GroverN(2)` coin gives the 4-regular grid
+/// walk. Coin values of 2D and above leave the walker in place.
+pub fn dtqw_step_torus(coin_qubits: &[usize], axes: &[Vec<usize>], coin: &CoinOperator) -> Vec<Gate> {
+    assert!(2 * axes.len() <= 1 << coin_qubits.len(), "Coin register too small for {} axes", axes.len());
+    let mut gates = coin_operator(coin_qubits, coin);
+
+    for (a, axis) in axes.iter().enumerate() {
+        for (value, up) in [(2 * a, false), (2 * a + 1, true)] {
+            // Conjugate the coin qubits that must read 0 for this value
+            let flips: Vec<Gate> = coin_qubits.iter().enumerate()
+                .filter(|(bit, _)| value >> bit & 1 == 0)
+                .map(|(_, &q)| Gate::X(q))
+                .collect();
+            gates.extend(flips.iter().cloned());
+            gates.extend(multi_controlled_shift(coin_qubits, axis, up));
+            gates.extend(flips);
+        }
+    }
+    gates
+}
+
+/// |x⟩ → |x ± 1 mod 2ⁿ⟩ when every control is 1
+fn multi_controlled_shift(controls: &[usize], register: &[usize], up: bool) -> Vec<Gate> {
+    if up {
+        multi_controlled_increment(controls, register)
+    } else {
+        multi_controlled_decrement(controls, register)
     }
 }
 
@@ -194,10 +246,8 @@ pub fn szegedy_reflection(
     // Reflection operator around the prepared state
     // R = 2|Phi><Phi| - I
     // Simplified representation
-    for &q in coin_qubits {
-        gates.push(Gate::H(q));
-        gates.push(Gate::X(q));
-    }
+    gates.extend(measure_x_basis(coin_qubits));
+    gates.extend(coin_qubits.iter().map(|&q| Gate::X(q)));
     
     if !coin_qubits.is_empty() {
         let controls: Vec<usize> = coin_qubits[..coin_qubits.len()-1].to_vec();
@@ -205,10 +255,105 @@ pub fn szegedy_reflection(
         gates.push(Gate::MCX(controls, target));
     }
     
-    for &q in coin_qubits {
-        gates.push(Gate::X(q));
-        gates.push(Gate::H(q));
-    }
+    gates.extend(coin_qubits.iter().map(|&q| Gate::X(q)));
+    gates.extend(prepare_x_basis(coin_qubits));
 
     gates
 }
+
+/// Phase gap of the Szegedy walk operator built from `transition_matrix`
+///
+/// The walk's eigenphases are ±2θⱼ with cos θⱼ = λⱼ, the eigenvalues of the
+/// discriminant Dᵢⱼ = √(PᵢⱼPⱼᵢ) (those of P itself for a reversible chain).
+/// Returns Δ = 2·arccos|λ₂|, with λ₂ the largest in magnitude after λ₁ = 1;
+/// Δ ≥ 2√(1 − |λ₂|), and about 1/Δ walk steps separate the stationary state
+/// from the rest, against 1/(1 − |λ₂|) classical steps. A matrix that is
+/// not square with at least two states is a `DimensionMismatch`, and a row
+/// that is not a probability distribution is `NotStochastic`.
+pub fn walk_spectral_gap(transition_matrix: &[Vec<f64>]) -> Result<f64, RustiqError> {
+    let n = transition_matrix.len();
+    if n < 2 {
+        return Err(RustiqError::DimensionMismatch { expected: 2, found: n });
+    }
+    for (row, probabilities) in transition_matrix.iter().enumerate() {
+        check_dimension(n, probabilities.len())?;
+        if probabilities.iter().any(|&p| p < 0.0) || (probabilities.iter().sum::<f64>() - 1.0).abs() > 1e-9 {
+            return Err(RustiqError::NotStochastic { row });
+        }
+    }
+
+    let discriminant: Vec<Vec<Complex>> = (0..n)
+        .map(|i| (0..n).map(|j| {
+            Complex::new((transition_matrix[i][j] * transition_matrix[j][i]).sqrt(), 0.0)
+        }).collect())
+        .collect();
+    let (mut eigenvalues, _) = hermitian_eigen(&discriminant);
+    eigenvalues.sort_by(|a, b| b.total_cmp(a));
+    let lambda_2 = eigenvalues[1..].iter().map(|l| l.abs()).fold(0.0, f64::max).min(1.0);
+    Ok(2.0 * lambda_2.acos())
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gates::core::Complex;
+    use crate::simulator::{basis_state, simulate, simulate_from};
+
+    #[test]
+    fn test_walk_spectral_gap_complete_graph() {
+        // Complete graph without self-loops: λ₂ = −1/(N−1)
+        for n in [3, 5, 8] {
+            let p: Vec<Vec<f64>> = (0..n)
+                .map(|i| (0..n).map(|j| if i == j { 0.0 } else { 1.0 / (n - 1) as f64 }).collect())
+                .collect();
+            let gap = walk_spectral_gap(&p).unwrap();
+            let expected = 2.0 * (1.0 / (n - 1) as f64).acos();
+            assert!((gap - expected).abs() < 1e-9, "N = {}: {} vs {}", n, gap, expected);
+            assert!(gap >= 2.0 * (1.0 - 1.0 / (n - 1) as f64).sqrt() - 1e-12);
+        }
+
+        // Uniform jumps mix in one step; a two-cycle never mixes
+        assert!((walk_spectral_gap(&vec![vec![0.25; 4]; 4]).unwrap() - PI).abs() < 1e-9);
+        assert!(walk_spectral_gap(&[vec![0.0, 1.0], vec![1.0, 0.0]]).unwrap().abs() < 1e-6);
+
+        assert_eq!(walk_spectral_gap(&[vec![1.0, 0.0], vec![0.7, 0.7]]), Err(RustiqError::NotStochastic { row: 1 }));
+        assert!(walk_spectral_gap(&[vec![0.5, 0.5], vec![1.0]]).is_err());
+    }
+
+    #[test]
+    fn test_grover_coin_matches_diffusion_matrix() {
+        // (2|s⟩⟨s| − I)ᵢⱼ = 2/4 − δᵢⱼ
+        let coin = coin_operator(&[0, 1], &CoinOperator::GroverN(2));
+        for j in 0..4 {
+            let column = simulate_from(&coin, &basis_state(2, j)).unwrap();
+            for (i, amp) in column.iter().enumerate() {
+                let expected = if i == j { -0.5 } else { 0.5 };
+                assert!((*amp - Complex::new(expected, 0.0)).norm() < 1e-12, "Entry ({}, {})", i, j);
+            }
+        }
+    }
+
+    #[test]
+    fn test_torus_shift_routes_on_full_coin_register() {
+        // Coin on qubits 0-1, x axis on 2-3, y axis on 4-5, walker at (1, 1)
+        let (coin, axes) = ([0, 1], [vec![2, 3], vec![4, 5]]);
+        let start = 1 << 2 | 1 << 4;
+        let identity = CoinOperator::Custom(Vec::new());
+        let moves = [(0, 1), (2, 1), (1, 0), (1, 2)];
+        for (value, (x, y)) in moves.into_iter().enumerate() {
+            let input = basis_state(6, start | value);
+            let output = simulate_from(&dtqw_step_torus(&coin, &axes, &identity), &input).unwrap();
+            let index = value | x << 2 | y << 4;
+            assert!((output[index].norm() - 1.0).abs() < 1e-12, "Coin {}", value);
+        }
+
+        // One coin qubit and one axis is the line walk
+        let line = simulate(&dtqw_step_line(0, &[1, 2], &CoinOperator::Hadamard), 3).unwrap();
+        let torus = simulate(&dtqw_step_torus(&[0], &[vec![1, 2]], &CoinOperator::Hadamard), 3).unwrap();
+        assert!(line.iter().zip(&torus).all(|(a, b)| (*a - *b).norm() < 1e-12));
+    }
+}
@@ -27,7 +27,8 @@
 //!
 //! ## 🔬 2025-26 Research Context
 //!
-//! - **Iterative QAE**: Uses O(log(1/ε)) qubits instead of O(1/ε)
+//! - **Iterative QAE**: Uses O(log(1/ε)) qubits instead of O(1/ε); see
+//!   `iterative_amplitude_estimation` for a circuit-level schedule
 //! - **Maximum Likelihood QAE**: Classical post-processing enhancement
 //! - **Variational QAE**: Hybrid approaches for NISQ devices
 //!
@@ -37,6 +38,8 @@
 //! - Suzuki et al. (2020). "Amplitude estimation without phase estimation"
 
 use std::f64::consts::PI;
+use crate::algorithms::qft::amplitude_amplification;
+use crate::gates::core::Gate;
 
 /// Configuration for Amplitude Estimation
 #[derive(Debug, Clone)]
@@ -335,14 +338,119 @@ pub fn amplitude_estimation(n: usize, true_prob: f64, precision_qubits: usize) -
     estimator.estimate(&config)
 }
 
-/// Runs Iterative Amplitude Estimation (NISQ-friendly)
-pub fn iterative_amplitude_estimation(
-    n: usize,
-    true_prob: f64,
-    precision: f64,
-) -> AmplitudeEstimationResult {
-    let estimator = IterativeAmplitudeEstimator::new(n, true_prob);
-    estimator.estimate_iterative(precision, 0.95)
+// ============================================================================
+// ITERATIVE AMPLITUDE ESTIMATION (circuit schedule)
+// ============================================================================
+
+/// Hoeffding half-width targeted for each round's estimate of sin²(Kθ)
+const IAE_ROUND_ERROR: f64 = 0.05;
+
+/// One round of iterative amplitude estimation: run `circuit` `shots` times
+#[derive(Debug, Clone)]
+pub struct IAERound {
+    /// Number of Grover iterations k; the good-state probability is sin²((2k+1)θ)
+    pub grover_power: usize,
+    /// Shots to take of `circuit`
+    pub shots: usize,
+    /// A·Qᵏ|0⟩ built by `amplitude_amplification`
+    pub circuit: Vec<Gate>,
+}
+
+/// Grover-power circuits plus the rule that turns their counts into an estimate
+#[derive(Debug, Clone)]
+pub struct IAESchedule {
+    /// Rounds in execution order
+    pub rounds: Vec<IAERound>,
+    /// Target half-width of the probability estimate
+    pub epsilon: f64,
+    /// Allowed failure probability
+    pub alpha: f64,
+}
+
+impl IAESchedule {
+    /// Oracle queries over all rounds: Σ shots·(2k+1)
+    pub fn total_queries(&self) -> usize {
+        self.rounds.iter().map(|r| r.shots * (2 * r.grover_power + 1)).sum()
+    }
+
+    /// Post-process the number of good outcomes observed in each round
+    ///
+    /// Starts from θ ∈ [0, π/2]. Round i with K = 2k+1 gives a Hoeffding
+    /// interval [p̂ − eᵢ, p̂ + eᵢ] for sin²(Kθ); θ is narrowed to the hull of
+    /// the points still consistent with it. With probability ≥ 1−α every
+    /// round's interval holds, so the returned confidence interval contains
+    /// the true probability sin²θ.
+    pub fn estimate(&self, good_counts: &[usize]) -> AmplitudeEstimationResult {
+        assert_eq!(good_counts.len(), self.rounds.len(), "Need one count per round");
+        let log_term = (2.0 * self.rounds.len() as f64 / self.alpha).ln();
+
+        let (mut lo, mut hi) = (0.0, PI / 2.0);
+        for (round, &ones) in self.rounds.iter().zip(good_counts) {
+            assert!(ones <= round.shots, "More good outcomes than shots");
+            let p_hat = ones as f64 / round.shots as f64;
+            let e = (log_term / (2.0 * round.shots as f64)).sqrt();
+            let k = (2 * round.grover_power + 1) as f64;
+            let s_a = (p_hat - e).max(0.0).sqrt().asin();
+            let s_b = (p_hat + e).min(1.0).sqrt().asin();
+
+            // sin²(φ) ∈ [a, b] on each half-period [jπ, (j+1)π] of φ = Kθ
+            let mut hull: Option<(f64, f64)> = None;
+            for j in (k * lo / PI).floor() as usize..=(k * hi / PI).floor() as usize {
+                let base = j as f64 * PI;
+                for (x, y) in [(base + s_a, base + s_b), (base + PI - s_b, base + PI - s_a)] {
+                    let (x, y) = ((x / k).max(lo), (y / k).min(hi));
+                    if x <= y {
+                        hull = Some(hull.map_or((x, y), |(u, v)| (u.min(x), v.max(y))));
+                    }
+                }
+            }
+            // An empty intersection means a bound failed; keep the last interval
+            if let Some((x, y)) = hull {
+                lo = x;
+                hi = y;
+            }
+        }
+
+        let phase = (lo + hi) / 2.0;
+        AmplitudeEstimationResult {
+            amplitude: phase.sin(),
+            probability: phase.sin().powi(2),
+            phase,
+            confidence_interval: (lo.sin().powi(2), hi.sin().powi(2)),
+            queries_used: self.total_queries(),
+        }
+    }
+}
+
+/// Iterative amplitude estimation of P(good) for A = `prep`, without QFT
+///
+/// Returns the rounds to run and, via `IAESchedule::estimate`, the classical
+/// rule combining their counts. The Grover-power schedule is k = 0, 1, 2, 4,
+/// 8, … so that K = 2k+1 less than doubles between rounds and a round's
+/// interval for θ spans at most a couple of branches of sin²(Kθ); it stops at
+/// the first K ≥ π/(8ε). Every round takes ⌈ln(2T/α) / (2·0.05²)⌉ shots, a
+/// union bound over the T rounds. Only one register of `prep` is needed; no
+/// ancillas, no phase estimation.
+///
+/// `oracle` must flip the sign of good states, as for `amplitude_amplification`.
+pub fn iterative_amplitude_estimation(oracle: &[Gate], prep: &[Gate], epsilon: f64, alpha: f64) -> IAESchedule {
+    assert!(epsilon > 0.0 && epsilon < 1.0, "Precision must lie in (0, 1)");
+    assert!(alpha > 0.0 && alpha < 1.0, "Failure probability must lie in (0, 1)");
+
+    let mut powers = vec![0usize];
+    while ((2 * powers[powers.len() - 1] + 1) as f64) < PI / (8.0 * epsilon) {
+        let last = powers[powers.len() - 1];
+        powers.push(if last == 0 { 1 } else { 2 * last });
+    }
+
+    let log_term = (2.0 * powers.len() as f64 / alpha).ln();
+    let shots = (log_term / (2.0 * IAE_ROUND_ERROR * IAE_ROUND_ERROR)).ceil() as usize;
+
+    let rounds = powers
+        .into_iter()
+        .map(|k| IAERound { grover_power: k, shots, circuit: amplitude_amplification(prep, oracle, k) })
+        .collect();
+    IAESchedule { rounds, epsilon, alpha }
 }
 
 #[cfg(test)]
@@ -366,12 +474,42 @@ mod tests {
 
     #[test]
     fn test_iterative_estimation() {
-        let result = iterative_amplitude_estimation(4, 0.3, 0.05);
+        let result = IterativeAmplitudeEstimator::new(4, 0.3).estimate_iterative(0.05, 0.95);
         
         assert!((result.probability - 0.3).abs() < 0.15);
         assert!(result.queries_used > 0);
     }
 
+    #[test]
+    fn test_iae_schedule_recovers_known_amplitude() {
+        use crate::simulator::{probabilities, simulate};
+
+        // RY(2θ)|0⟩ with |1⟩ good, and a 2-qubit uniform state with |11⟩ good
+        let cases = [
+            (vec![Gate::RY(0, 2.0 * 0.3f64.sqrt().asin())], vec![Gate::Z(0)], 1, 0b1, 0.3),
+            (vec![Gate::RY(0, 2.0 * 0.83f64.sqrt().asin())], vec![Gate::Z(0)], 1, 0b1, 0.83),
+            (vec![Gate::H(0), Gate::H(1)], vec![Gate::CZ(0, 1)], 2, 0b11, 0.25f64),
+        ];
+        let mut rng = crate::util::rng::Rng::new(42);
+
+        for (prep, oracle, n, good, p) in cases {
+            let schedule = iterative_amplitude_estimation(&oracle, &prep, 0.01, 0.05);
+            assert_eq!(schedule.rounds[0].grover_power, 0);
+
+            let counts: Vec<usize> = schedule.rounds.iter().map(|round| {
+                let k = 2 * round.grover_power + 1;
+                let p_good = probabilities(&simulate(&round.circuit, n).unwrap())[good];
+                assert!((p_good - (k as f64 * p.sqrt().asin()).sin().powi(2)).abs() < 1e-9);
+                (0..round.shots).filter(|_| rng.uniform() < p_good).count()
+            }).collect();
+
+            let result = schedule.estimate(&counts);
+            let (lower, upper) = result.confidence_interval;
+            assert!(lower <= p && p <= upper, "{} outside [{}, {}]", p, lower, upper);
+            assert!((result.probability - p).abs() < 0.01, "{} vs {}", result.probability, p);
+        }
+    }
+
     #[test]
     fn test_config_with_precision() {
         let config = AmplitudeEstimationConfig::with_precision(0.01);
@@ -483,11 +483,11 @@ mod tests {
     fn test_optimal_iterations() {
         // For N=16 (4 qubits), M=1, optimal ≈ π/4 * √16 ≈ 3
         let k = optimal_iterations(4, 1);
-        assert!(k >= 2 && k <= 4, "Optimal iterations for 4 qubits, 1 solution");
+        assert!((2..=4).contains(&k), "Optimal iterations for 4 qubits, 1 solution");
         
         // For N=64 (6 qubits), M=1, optimal ≈ π/4 * √64 ≈ 6
         let k = optimal_iterations(6, 1);
-        assert!(k >= 5 && k <= 8, "Optimal iterations for 6 qubits, 1 solution");
+        assert!((5..=8).contains(&k), "Optimal iterations for 6 qubits, 1 solution");
     }
 
     #[test]
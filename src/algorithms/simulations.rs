@@ -2,8 +2,10 @@
 //!
 //! This module implements techniques for simulating quantum systems:
 //! - Trotter-Suzuki decomposition
-//! - Linear Combination of Unitaries (LCU)
+//! - Linear Combination of Unitaries (LCU), with coefficient 1-norm and a
+//!   PREPARE circuit built from the coefficients (`lcu_prepare`)
 //! - Hamiltonian simulation
+//! - Pauli-string exponentials and Trotterization straight from an `Observable`
 //!
 //! ## 🎯 Why is this used?
 //! Simulating the dynamics of quantum systems (Hamiltonian simulation) is the "killer app" 
@@ -17,6 +19,8 @@
 //!   Hamiltonian $H = \sum H_j$ by interleaving the individual evolutions $e^{-iH_j \Delta t}$ 
 //!   over small time steps. Higher-order formulas (ST2, ST4) provide better accuracy by 
 //!   symmetrizing the gate sequence.
+//! - **Pauli Exponentials**: e^(-iθP/2) for a Pauli string P is a basis change
+//!   to Z on every active qubit, a CX parity ladder, one RZ(θ) and the mirror.
 //! - **Linear Combination of Unitaries (LCU)**: Represents the operator as a sum of 
 //!   unitaries $\sum \alpha_j U_j$. It uses a `PREPARE` circuit to load coefficients into an 
 //!   ancilla register and a `SELECT` circuit to apply the corresponding unitary $U_j$.
@@ -35,7 +39,11 @@
 //! - **Precision**: Trotter error scales as $(\Delta t)^k$; error in LCU is handled via 
 //!   amplitude amplification or oblivious amplitude amplification.
 
-use crate::gates::core::Gate;
+use std::f64::consts::FRAC_PI_2;
+use crate::gates::core::{Gate, PauliOp};
+use crate::gates::circuit::{inverse_circuit, remap_qubits};
+use crate::synthesis::advanced::prepare_state;
+use crate::variational::observable::Observable;
 
 // ============================================================================
 // TROTTER-SUZUKI DECOMPOSITION
@@ -101,10 +109,258 @@ pub fn lcu_simulation(
     for _ in 0..steps {
         gates.extend(prepare.iter().cloned());
         gates.extend(select.iter().cloned());
-        // Inverse PREPARE
-        let inv_prepare: Vec<Gate> = prepare.iter().rev().map(|g| g.inverse()).collect();
-        gates.extend(inv_prepare);
+        gates.extend(inverse_circuit(prepare));
     }
     
     gates
 }
+
+/// ‖α‖₁ = Σⱼ |αⱼ|, the LCU subnormalization
+///
+/// A single PREPARE–SELECT–PREPARE† round succeeds with probability
+/// ‖Hψ‖² / ‖α‖₁², so this norm sets the cost of LCU and qubitization.
+pub fn lcu_one_norm(coeffs: &[f64]) -> f64 {
+    coeffs.iter().map(|c| c.abs()).sum()
+}
+
+/// (α / ‖α‖₁, ‖α‖₁): signed coefficients whose magnitudes sum to 1
+pub fn normalize_lcu(coeffs: &[f64]) -> (Vec<f64>, f64) {
+    let lambda = lcu_one_norm(coeffs);
+    assert!(lambda > 0.0, "LCU coefficients are all zero");
+    (coeffs.iter().map(|c| c / lambda).collect(), lambda)
+}
+
+/// PREPARE: |0…0⟩ → Σⱼ √(|αⱼ|/‖α‖₁) |j⟩ on `index_qubits` (little-endian)
+///
+/// Built with `prepare_state` on the first ⌈log₂ len⌉ index qubits, so any
+/// number of coefficients is accepted; unused indices and extra index qubits
+/// keep amplitude zero. Signs are not loaded here: SELECT applies sign(αⱼ).
+pub fn lcu_prepare(coeffs: &[f64], index_qubits: &[usize]) -> Vec<Gate> {
+    assert!(
+        coeffs.len() <= 1 << index_qubits.len(),
+        "{} coefficients do not fit {} index qubits", coeffs.len(), index_qubits.len()
+    );
+    let (normalized, _) = normalize_lcu(coeffs);
+    let amplitudes: Vec<f64> = normalized.iter().map(|c| c.abs().sqrt()).collect();
+    let circuit = prepare_state(&amplitudes).expect("Normalized coefficients have unit 1-norm");
+    remap_qubits(&circuit, index_qubits)
+}
+
+/// `lcu_simulation` with PREPARE built from the coefficients
+///
+/// PREPARE is `lcu_prepare(coeffs, index_qubits)` and PREPARE† its exact
+/// inverse, so each round applies (Σⱼ |αⱼ| Uⱼ) / ‖α‖₁ on the |0⟩ index
+/// block. `select` must apply sign(αⱼ)·Uⱼ controlled on |j⟩.
+pub fn lcu_simulation_from_coefficients(
+    coeffs: &[f64],
+    index_qubits: &[usize],
+    select: &[Gate],
+    steps: usize,
+) -> Vec<Gate> {
+    lcu_simulation(&lcu_prepare(coeffs, index_qubits), select, steps)
+}
+
+// ============================================================================
+// PAULI-STRING EVOLUTION
+// ============================================================================
+
+/// exp(-i θ/2 · P) for a Pauli string P, in the RZ(θ) angle convention
+///
+/// `paulis[q]` acts on qubit `q`. The identity string is the global phase
+/// e^(-iθ/2).
+pub fn exp_pauli(paulis: &[PauliOp], theta: f64) -> Vec<Gate> {
+    let active: Vec<usize> = (0..paulis.len()).filter(|&q| paulis[q] != PauliOp::I).collect();
+    let Some(&last) = active.last() else {
+        return vec![Gate::GlobalPhase(-theta / 2.0)];
+    };
+
+    // Rotate X → Z with H and Y → Z with RX(π/2)
+    let mut basis = Vec::new();
+    for &q in &active {
+        match paulis[q] {
+            PauliOp::X => basis.push(Gate::H(q)),
+            PauliOp::Y => basis.push(Gate::RX(q, FRAC_PI_2)),
+            _ => {}
+        }
+    }
+    let ladder: Vec<Gate> = active.windows(2).map(|w| Gate::CX(w[0], w[1])).collect();
+
+    let mut gates = basis.clone();
+    gates.extend(ladder.iter().cloned());
+    gates.push(Gate::RZ(last, theta));
+    gates.extend(inverse_circuit(&ladder));
+    gates.extend(inverse_circuit(&basis));
+    gates
+}
+
+// ============================================================================
+// TROTTERIZATION FROM AN OBSERVABLE
+// ============================================================================
+
+/// e^(-iHt) for H = Σⱼ cⱼPⱼ as a Trotter-Suzuki product formula
+///
+/// `order` 1 is Πⱼ e^(-icⱼPⱼΔt); `order` 2 is the symmetric formula; even
+/// orders 2k ≥ 4 use Suzuki's recursion
+/// S₂ₖ(Δt) = S₂ₖ₋₂(pΔt)² S₂ₖ₋₂((1−4p)Δt) S₂ₖ₋₂(pΔt)², p = 1/(4 − 4^(1/(2k−1))).
+/// Error per step is O(Δt^(order+1)).
+pub fn trotter_from_observable(h: &Observable, time: f64, steps: usize, order: usize) -> Vec<Gate> {
+    assert!(steps > 0, "Need at least one Trotter step");
+    assert!(order == 1 || (order >= 2 && order.is_multiple_of(2)), "Trotter order must be 1 or even, got {}", order);
+
+    let dt = time / steps as f64;
+    let step = suzuki_step(h, dt, order);
+    step.iter().cloned().cycle().take(step.len() * steps).collect()
+}
+
+fn suzuki_step(h: &Observable, dt: f64, order: usize) -> Vec<Gate> {
+    let term_evolution = |j: usize, t: f64| {
+        let term = &h.terms[j];
+        exp_pauli(&term.paulis, 2.0 * term.coefficient * t)
+    };
+
+    match order {
+        1 => (0..h.terms.len()).flat_map(|j| term_evolution(j, dt)).collect(),
+        2 => (0..h.terms.len())
+            .chain((0..h.terms.len()).rev())
+            .flat_map(|j| term_evolution(j, dt / 2.0))
+            .collect(),
+        _ => {
+            let k = order / 2;
+            let p = 1.0 / (4.0 - 4f64.powf(1.0 / (2 * k - 1) as f64));
+            let outer = suzuki_step(h, p * dt, order - 2);
+            let middle = suzuki_step(h, (1.0 - 4.0 * p) * dt, order - 2);
+
+            let mut gates = Vec::new();
+            gates.extend(outer.iter().cloned());
+            gates.extend(outer.iter().cloned());
+            gates.extend(middle);
+            gates.extend(outer.iter().cloned());
+            gates.extend(outer);
+            gates
+        }
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gates::core::Complex;
+    use crate::simulator::{probabilities, simulate, simulate_from, states_close, zero_state};
+    use crate::variational::vqe_variants::HamiltonianTerm;
+
+    /// H|ψ⟩ by applying each Pauli string
+    fn apply_hamiltonian(h: &Observable, state: &[Complex]) -> Vec<Complex> {
+        let mut out = vec![Complex::ZERO; state.len()];
+        for term in &h.terms {
+            let paulis: Vec<Gate> = term.paulis.iter().enumerate()
+                .filter_map(|(q, p)| match p {
+                    PauliOp::X => Some(Gate::X(q)),
+                    PauliOp::Y => Some(Gate::Y(q)),
+                    PauliOp::Z => Some(Gate::Z(q)),
+                    PauliOp::I => None,
+                })
+                .collect();
+            for (o, a) in out.iter_mut().zip(simulate_from(&paulis, state).unwrap()) {
+                *o = *o + a * term.coefficient;
+            }
+        }
+        out
+    }
+
+    /// e^(-iHt)|0…0⟩ by a converged Taylor series
+    fn exact_evolution(h: &Observable, time: f64) -> Vec<Complex> {
+        let mut state = zero_state(h.num_qubits);
+        let mut term = state.clone();
+        for k in 1..80 {
+            // term ← (-i t / k) · H · term
+            term = apply_hamiltonian(h, &term)
+                .into_iter()
+                .map(|a| Complex::new(a.im, -a.re) * (time / k as f64))
+                .collect();
+            for (s, t) in state.iter_mut().zip(&term) {
+                *s = *s + *t;
+            }
+        }
+        state
+    }
+
+    fn z0(state: &[Complex]) -> f64 {
+        probabilities(state).iter().enumerate()
+            .map(|(i, p)| if i & 1 == 0 { *p } else { -*p })
+            .sum()
+    }
+
+    #[test]
+    fn test_lcu_normalization_and_prepare() {
+        let coeffs = [0.5, -1.5, 2.0];
+        let (normalized, lambda) = normalize_lcu(&coeffs);
+        assert_eq!(lambda, lcu_one_norm(&coeffs));
+        assert!((lambda - 4.0).abs() < 1e-12);
+        assert!((normalized.iter().map(|c| c.abs()).sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(normalized.iter().zip(&coeffs).all(|(n, c)| (n * lambda - c).abs() < 1e-12));
+
+        // (0.6·I + 0.2·X)/0.8 on system qubit 0, index qubit 1 selecting X
+        let circuit = lcu_simulation_from_coefficients(&[0.6, 0.2], &[1], &[Gate::CX(1, 0)], 1);
+        let state = simulate(&circuit, 2).unwrap();
+        assert!((state[0] - Complex::new(0.75, 0.0)).norm() < 1e-12);
+        assert!((state[1] - Complex::new(0.25, 0.0)).norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_lcu_prepare_amplitudes() {
+        // Five coefficients on a 4-qubit index register laid out out of order
+        let coeffs = [0.5, -1.5, 2.0, 0.25, -0.75];
+        let index_qubits = [3, 0, 2, 1];
+        let state = simulate(&lcu_prepare(&coeffs, &index_qubits), 4).unwrap();
+        let lambda = lcu_one_norm(&coeffs);
+        for j in 0..16 {
+            let wire = (0..4).map(|i| ((j >> i) & 1) << index_qubits[i]).sum::<usize>();
+            let expected = coeffs.get(j).map_or(0.0, |c| (c.abs() / lambda).sqrt());
+            assert!((state[wire] - Complex::new(expected, 0.0)).norm() < 1e-12, "Index {}", j);
+        }
+    }
+
+    #[test]
+    fn test_exp_pauli_matches_definition() {
+        let paulis = [PauliOp::X, PauliOp::I, PauliOp::Y, PauliOp::Z];
+        let theta = 0.83;
+        let h = Observable {
+            num_qubits: 4,
+            terms: vec![HamiltonianTerm::new(1.0, paulis.to_vec())],
+        };
+
+        // exp(-iθP/2) = e^(-iHt) with H = P, t = θ/2
+        let prep_gates = vec![Gate::H(0), Gate::H(1), Gate::RY(2, 0.4)];
+        let circuit = [prep_gates.clone(), exp_pauli(&paulis, theta)].concat();
+        let prep = simulate(&prep_gates, 4).unwrap();
+        let cos = Complex::new((theta / 2.0).cos(), 0.0);
+        let expected: Vec<Complex> = prep.iter().zip(apply_hamiltonian(&h, &prep))
+            .map(|(a, pa)| *a * cos + Complex::new(pa.im, -pa.re) * (theta / 2.0).sin())
+            .collect();
+        assert!(states_close(&simulate(&circuit, 4).unwrap(), &expected, 1e-10));
+    }
+
+    #[test]
+    fn test_trotter_tfim_matches_exact() {
+        let h = Observable::transverse_ising(3, 1.0, 0.7);
+        let time = 1.2;
+        let exact = exact_evolution(&h, time);
+        let trotter = |order| simulate(&trotter_from_observable(&h, time, 20, order), 3).unwrap();
+
+        let z_error = (z0(&trotter(2)) - z0(&exact)).abs();
+        assert!(z_error < 1e-3, "Second-order ⟨Z⟩ error {}", z_error);
+
+        // Higher orders converge faster on the full state
+        let errors: Vec<f64> = [1, 2, 4]
+            .iter()
+            .map(|&order| {
+                trotter(order).iter().zip(&exact).map(|(a, b)| (*a - *b).norm_sq()).sum::<f64>().sqrt()
+            })
+            .collect();
+        assert!(errors[2] < errors[1] && errors[1] < errors[0], "{:?}", errors);
+    }
+}
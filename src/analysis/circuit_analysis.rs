@@ -4,8 +4,16 @@
 //! - Gate counting by type
 //! - Circuit depth computation
 //! - T-depth and CNOT-depth analysis
-//! - Circuit equivalence checking
-//! - Resource estimation
+//! - Circuit equivalence checking and process fidelity
+//! - `causal_cone`: The gates in the causal past of measured qubits
+//! - `circuit_unitary`: The full matrix, in `embed_gate`'s index convention
+//! - `diff`: A gate-level edit script between two circuits, for readable
+//!   regression failures
+//! - Resource estimation and a product-of-fidelities success estimate
+//! - A gate-weighted cost model (`circuit_cost`), optionally noise-aware via
+//!   per-gate error rates (`ErrorRates`)
+//! - Per-stage resource reports (`analyze_by_tag`, `TaggedCircuit`) for
+//!   profiling composite algorithms
 //!
 //! ## 🎯 Why is this used?
 //! In quantum computing, resources (qubits and gates) are extremely expensive and noisy. 
@@ -18,10 +26,16 @@
 //!   to determine the minimum number of time steps required.
 //! - **Interaction Mapping**: Builds a graph representation where nodes are qubits 
 //!   and edges represent two-qubit interactions, useful for hardware mapping.
+//!   Its connected components are the blocks that run independently.
 //! - **Unitary Verification**: For small circuits, it generates the full $2^N \times 2^N$ 
 //!   matrix and compares them (ignoring global phase) to verify algorithmic correctness.
+//! - **Diffing**: `diff` keeps a longest common subsequence of equal gates
+//!   (after trimming the shared prefix and suffix) and marks the rest as
+//!   removed or inserted; $O(G_1 G_2)$ only on the part that differs.
 //! - **Fault-Tolerant Estimation**: Maps logical metrics (T-count) to physical overheads 
 //!   based on surface code distillation models.
+//! - **Surface Code Model**: Combines patch footprints, lattice-surgery steps and
+//!   factory throughput into qubits, cycles and wall-clock time.
 //!
 //! ## 📍 Where to apply this?
 //! - **Performance Profiling**: Benchmarking the overhead of a new algorithm.
@@ -39,7 +53,9 @@
 //!   theoretical heuristics and may vary with code implementation details.
 
 use std::collections::{HashMap, HashSet};
-use crate::gates::core::Gate;
+use std::fmt;
+use crate::gates::core::{Complex, Gate, GateKind, DEFAULT_TOL};
+use crate::simulator::{basis_state, evolve, inner_product};
 
 // ============================================================================
 // GATE COUNTING
@@ -50,50 +66,12 @@ pub fn gate_count_by_type(circuit: &[Gate]) -> HashMap<String, usize> {
     let mut counts = HashMap::new();
     
     for gate in circuit {
-        let gate_type = gate_type_name(gate);
-        *counts.entry(gate_type).or_insert(0) += 1;
+        *counts.entry(gate.kind().name().to_string()).or_insert(0) += 1;
     }
     
     counts
 }
 
-fn gate_type_name(gate: &Gate) -> String {
-    match gate {
-        Gate::X(_) => "X".to_string(),
-        Gate::Y(_) => "Y".to_string(),
-        Gate::Z(_) => "Z".to_string(),
-        Gate::H(_) => "H".to_string(),
-        Gate::S(_) => "S".to_string(),
-        Gate::Sdg(_) => "Sdg".to_string(),
-        Gate::T(_) => "T".to_string(),
-        Gate::Tdg(_) => "Tdg".to_string(),
-        Gate::SX(_) => "SX".to_string(),
-        Gate::RX(_, _) => "RX".to_string(),
-        Gate::RY(_, _) => "RY".to_string(),
-        Gate::RZ(_, _) => "RZ".to_string(),
-        Gate::P(_, _) => "P".to_string(),
-        Gate::U3(_, _, _, _) => "U3".to_string(),
-        Gate::CX(_, _) => "CX".to_string(),
-        Gate::CY(_, _) => "CY".to_string(),
-        Gate::CZ(_, _) => "CZ".to_string(),
-        Gate::SWAP(_, _) => "SWAP".to_string(),
-        Gate::ISWAP(_, _) => "iSWAP".to_string(),
-        Gate::CRX(_, _, _) => "CRX".to_string(),
-        Gate::CRY(_, _, _) => "CRY".to_string(),
-        Gate::CRZ(_, _, _) => "CRZ".to_string(),
-        Gate::CP(_, _, _) => "CP".to_string(),
-        Gate::RXX(_, _, _) => "RXX".to_string(),
-        Gate::RYY(_, _, _) => "RYY".to_string(),
-        Gate::RZZ(_, _, _) => "RZZ".to_string(),
-        Gate::CCX(_, _, _) => "CCX".to_string(),
-        Gate::CCZ(_, _, _) => "CCZ".to_string(),
-        Gate::CSWAP(_, _, _) => "CSWAP".to_string(),
-        Gate::MCX(_, _) => "MCX".to_string(),
-        Gate::MCZ(_, _) => "MCZ".to_string(),
-        Gate::MCP(_, _, _) => "MCP".to_string(),
-    }
-}
-
 /// Count total number of gates
 pub fn total_gate_count(circuit: &[Gate]) -> usize {
     circuit.len()
@@ -117,7 +95,7 @@ pub fn multi_qubit_count(circuit: &[Gate]) -> usize {
 /// Count specific gate type
 pub fn count_gate(circuit: &[Gate], gate_name: &str) -> usize {
     circuit.iter()
-        .filter(|g| gate_type_name(g) == gate_name)
+        .filter(|g| g.kind().name() == gate_name)
         .count()
 }
 
@@ -142,8 +120,8 @@ pub fn circuit_depth(circuit: &[Gate]) -> usize {
             .max()
             .unwrap_or(0);
         
-        // Update depth for all involved qubits
-        let new_depth = max_depth + 1;
+        // Update depth for all involved qubits; barriers only synchronize
+        let new_depth = if matches!(gate, Gate::Barrier(_)) { max_depth } else { max_depth + 1 };
         for q in qubits {
             qubit_depths.insert(q, new_depth);
         }
@@ -152,6 +130,36 @@ pub fn circuit_depth(circuit: &[Gate]) -> usize {
     *qubit_depths.values().max().unwrap_or(&0)
 }
 
+/// Gate indices grouped by ASAP time slot, following the `circuit_depth` schedule
+///
+/// A gate lands one slot after the latest gate on any of its qubits. A
+/// `Barrier` takes the next slot without advancing its qubits, so it shares
+/// that slot with (and precedes) the gates that follow it.
+pub fn circuit_layers(circuit: &[Gate]) -> Vec<Vec<usize>> {
+    let mut qubit_depths: HashMap<usize, usize> = HashMap::new();
+    let mut layers: Vec<Vec<usize>> = Vec::new();
+
+    for (index, gate) in circuit.iter().enumerate() {
+        let qubits = gate.qubits();
+        let slot = qubits.iter()
+            .map(|q| *qubit_depths.get(q).unwrap_or(&0))
+            .max()
+            .unwrap_or(0);
+
+        if layers.len() <= slot {
+            layers.resize(slot + 1, Vec::new());
+        }
+        layers[slot].push(index);
+
+        let new_depth = if matches!(gate, Gate::Barrier(_)) { slot } else { slot + 1 };
+        for q in qubits {
+            qubit_depths.insert(q, new_depth);
+        }
+    }
+
+    layers
+}
+
 /// Compute depth considering only certain gate types
 pub fn filtered_depth<F>(circuit: &[Gate], filter: F) -> usize 
 where F: Fn(&Gate) -> bool 
@@ -244,6 +252,91 @@ pub fn interaction_graph(circuit: &[Gate]) -> Vec<(usize, usize)> {
     edges.into_iter().collect()
 }
 
+/// Qubits grouped into independent blocks linked by multi-qubit gates
+///
+/// Returns the components of the interaction graph on qubits `0..num_qubits`,
+/// each sorted and ordered by its smallest qubit; untouched qubits are
+/// singletons. Feed-forward also links: a `ClassicalControl` joins its qubits
+/// to the qubit last measured into its bit. Barriers link nothing. A circuit
+/// with more than one component runs as separate subcircuits without cutting.
+pub fn connected_components(circuit: &[Gate], num_qubits: usize) -> Vec<Vec<usize>> {
+    fn find(parent: &mut [usize], mut q: usize) -> usize {
+        while parent[q] != q {
+            parent[q] = parent[parent[q]];
+            q = parent[q];
+        }
+        q
+    }
+    let mut parent: Vec<usize> = (0..num_qubits).collect();
+    let mut union = |a: usize, b: usize| {
+        let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+        parent[ra.max(rb)] = ra.min(rb);
+    };
+
+    let mut measured_into: HashMap<usize, usize> = HashMap::new();
+    for gate in circuit {
+        let qubits = gate.qubits();
+        assert!(qubits.iter().all(|&q| q < num_qubits), "{:?} acts outside {} qubits", gate, num_qubits);
+        match gate {
+            Gate::Barrier(_) => continue,
+            Gate::Measure(q, bit) => {
+                measured_into.insert(*bit, *q);
+            }
+            Gate::ClassicalControl(bit, _) => {
+                if let (Some(&m), Some(&first)) = (measured_into.get(bit), qubits.first()) {
+                    union(m, first);
+                }
+            }
+            _ => {}
+        }
+        for pair in qubits.windows(2) {
+            union(pair[0], pair[1]);
+        }
+    }
+
+    let mut components: Vec<Vec<usize>> = Vec::new();
+    let mut index_of_root: HashMap<usize, usize> = HashMap::new();
+    for q in 0..num_qubits {
+        let root = find(&mut parent, q);
+        let index = *index_of_root.entry(root).or_insert_with(|| {
+            components.push(Vec::new());
+            components.len() - 1
+        });
+        components[index].push(q);
+    }
+    components
+}
+
+/// Gates in the causal past (light cone) of `observable_qubits`, in order
+///
+/// Scans backwards with a live set: a gate touching a live qubit is kept and
+/// makes all its qubits live. A `ClassicalControl` that is kept makes its bit
+/// live, and a `Measure` into a live bit is kept too, so feed-forward chains
+/// survive. Barriers and `GlobalPhase` are dropped. Every dropped gate acts
+/// only outside the cone, so the observable's statistics are unchanged.
+pub fn causal_cone(circuit: &[Gate], observable_qubits: &[usize]) -> Vec<Gate> {
+    let mut live_qubits: HashSet<usize> = observable_qubits.iter().copied().collect();
+    let mut live_bits: HashSet<usize> = HashSet::new();
+    let mut kept = Vec::new();
+    for gate in circuit.iter().rev() {
+        let qubits = gate.qubits();
+        let keep = match gate {
+            Gate::Barrier(_) | Gate::GlobalPhase(_) => false,
+            Gate::Measure(q, bit) => live_qubits.contains(q) || live_bits.contains(bit),
+            _ => qubits.iter().any(|q| live_qubits.contains(q)),
+        };
+        if keep {
+            if let Gate::ClassicalControl(bit, _) = gate {
+                live_bits.insert(*bit);
+            }
+            live_qubits.extend(qubits);
+            kept.push(gate.clone());
+        }
+    }
+    kept.reverse();
+    kept
+}
+
 // ============================================================================
 // CIRCUIT EQUIVALENCE
 // ============================================================================
@@ -252,7 +345,13 @@ pub fn interaction_graph(circuit: &[Gate]) -> Vec<(usize, usize)> {
 /// 
 /// Two circuits are structurally equivalent if they have the same
 /// sequence of gates (after accounting for qubit relabeling).
+/// Rotation angles are compared to within `DEFAULT_TOL`.
 pub fn structurally_equivalent(circuit1: &[Gate], circuit2: &[Gate]) -> bool {
+    structurally_equivalent_with_tol(circuit1, circuit2, DEFAULT_TOL)
+}
+
+/// [`structurally_equivalent`] with RX / RY / RZ / P angles equal when |θ₁ − θ₂| ≤ `tol`
+pub fn structurally_equivalent_with_tol(circuit1: &[Gate], circuit2: &[Gate], tol: f64) -> bool {
     if circuit1.len() != circuit2.len() {
         return false;
     }
@@ -268,10 +367,8 @@ pub fn structurally_equivalent(circuit1: &[Gate], circuit2: &[Gate]) -> bool {
             (Gate::RX(_, t1), Gate::RX(_, t2)) |
             (Gate::RY(_, t1), Gate::RY(_, t2)) |
             (Gate::RZ(_, t1), Gate::RZ(_, t2)) |
-            (Gate::P(_, t1), Gate::P(_, t2)) => {
-                if (t1 - t2).abs() > 1e-10 {
-                    return false;
-                }
+            (Gate::P(_, t1), Gate::P(_, t2)) if (t1 - t2).abs() > tol => {
+                return false;
             }
             _ => {}
         }
@@ -282,66 +379,147 @@ pub fn structurally_equivalent(circuit1: &[Gate], circuit2: &[Gate]) -> bool {
 
 /// Simulation-based equivalence check for small circuits
 /// 
-/// Verifies that two circuits produce the same unitary matrix.
+/// Verifies that two circuits produce the same unitary matrix, up to a
+/// global phase, with entries within `DEFAULT_TOL`.
 /// Only practical for small numbers of qubits (≤10).
 pub fn unitary_equivalent(circuit1: &[Gate], circuit2: &[Gate], num_qubits: usize) -> bool {
+    unitary_equivalent_with_tol(circuit1, circuit2, num_qubits, DEFAULT_TOL)
+}
+
+/// [`unitary_equivalent`] with tolerance `tol` on every entry of U₁ − e^{iφ}U₂
+///
+/// The phase φ is taken from the largest entry of U₁, so columns with tiny
+/// amplitudes do not decide it.
+pub fn unitary_equivalent_with_tol(circuit1: &[Gate], circuit2: &[Gate], num_qubits: usize, tol: f64) -> bool {
     if num_qubits > 10 {
         // Too large for simulation
         return false;
     }
     
-    let dim = 1usize << num_qubits;
-    
-    // Compute unitaries (simplified - full implementation needs matrix operations)
     let u1 = compute_unitary(circuit1, num_qubits);
     let u2 = compute_unitary(circuit2, num_qubits);
     
     // Compare up to global phase
-    compare_unitaries(&u1, &u2, dim)
-}
-
-fn compute_unitary(_circuit: &[Gate], num_qubits: usize) -> Vec<Vec<crate::gates::core::Complex>> {
-    let dim = 1usize << num_qubits;
-    // Return identity as placeholder
-    let mut result = vec![vec![crate::gates::core::Complex::ZERO; dim]; dim];
-    for i in 0..dim {
-        result[i][i] = crate::gates::core::Complex::ONE;
-    }
-    result
-}
-
-fn compare_unitaries(
-    u1: &[Vec<crate::gates::core::Complex>], 
-    u2: &[Vec<crate::gates::core::Complex>],
-    dim: usize,
-) -> bool {
-    // Find global phase from first non-zero element
-    let mut phase = crate::gates::core::Complex::ONE;
-    for i in 0..dim {
-        for j in 0..dim {
-            if u1[i][j].norm() > 1e-10 && u2[i][j].norm() > 1e-10 {
-                let ratio = u2[i][j] * crate::gates::core::Complex::new(u1[i][j].re, -u1[i][j].im);
-                phase = crate::gates::core::Complex::new(
-                    ratio.re / ratio.norm(),
-                    ratio.im / ratio.norm(),
-                );
-                break;
-            }
-        }
+    compare_unitaries(&u1, &u2, tol)
+}
+
+/// Process fidelity |Tr(U_a† U_b)|² / d² between two circuits
+///
+/// 1 exactly when the circuits agree up to global phase, 0 when their
+/// unitaries are trace-orthogonal. Builds both 2ⁿ×2ⁿ unitaries, so only
+/// for small registers.
+pub fn process_fidelity(a: &[Gate], b: &[Gate], num_qubits: usize) -> f64 {
+    assert!(num_qubits <= 10, "Process fidelity needs the full unitary; {} qubits is too many", num_qubits);
+    let (ua, ub) = (compute_unitary(a, num_qubits), compute_unitary(b, num_qubits));
+    let trace = ua.iter().zip(&ub).fold(Complex::ZERO, |acc, (ca, cb)| acc + inner_product(ca, cb));
+    let d = (1usize << num_qubits) as f64;
+    trace.norm_sq() / (d * d)
+}
+
+/// Full 2ⁿ×2ⁿ unitary of a circuit, row-major
+///
+/// Entry [i][j] is ⟨i|U|j⟩ with little-endian basis indices, the convention
+/// of `simulator::embed_gate`: a single gate's unitary is its embedded matrix.
+///
+/// It does not multiply `embed_gate` matrices together. CCX, MCX, MCP and
+/// the other multi-qubit gates have no dense local matrix to embed, and a
+/// product of 2ⁿ×2ⁿ matrices costs O(8ⁿ) per gate. Instead column j is the
+/// simulator run on |j⟩, which is O(4ⁿ) per gate and covers every unitary
+/// gate. The tests check that the two agree.
+pub fn circuit_unitary(circuit: &[Gate], num_qubits: usize) -> Vec<Vec<Complex>> {
+    assert!(num_qubits <= 10, "Full unitary of {} qubits is too large", num_qubits);
+    let columns = compute_unitary(circuit, num_qubits);
+    (0..columns.len()).map(|i| columns.iter().map(|col| col[i]).collect()).collect()
+}
+
+/// Column j is the circuit applied to |j⟩
+fn compute_unitary(circuit: &[Gate], num_qubits: usize) -> Vec<Vec<Complex>> {
+    (0..1usize << num_qubits)
+        .map(|j| evolve(circuit, &basis_state(num_qubits, j)))
+        .collect()
+}
+
+fn compare_unitaries(u1: &[Vec<Complex>], u2: &[Vec<Complex>], tol: f64) -> bool {
+    // Global phase from the largest entry of u1
+    let (j, i) = (0..u1.len())
+        .flat_map(|j| (0..u1.len()).map(move |i| (j, i)))
+        .max_by(|&(a, b), &(c, d)| u1[a][b].norm().total_cmp(&u1[c][d].norm()))
+        .expect("Empty unitary");
+    let ratio = u1[j][i] * u2[j][i].conj();
+    if ratio.norm() <= tol {
+        return false;
     }
+    let phase = ratio * (1.0 / ratio.norm());
     
     // Compare all elements with global phase correction
-    let eps = 1e-8;
-    for i in 0..dim {
-        for j in 0..dim {
-            let diff = u1[i][j] - u2[i][j] * phase;
-            if diff.norm() > eps {
-                return false;
-            }
+    u1.iter().zip(u2).all(|(c1, c2)| c1.iter().zip(c2).all(|(a, b)| (*a - *b * phase).norm() <= tol))
+}
+
+// ============================================================================
+// CIRCUIT DIFF
+// ============================================================================
+
+/// One step of the edit script returned by [`diff`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum DiffOp {
+    /// A gate present in both circuits
+    Unchanged(Gate),
+    /// A gate of `before` missing from `after`
+    Removed(Gate),
+    /// A gate of `after` missing from `before`
+    Inserted(Gate),
+}
+
+/// Unified-diff style line: `  g`, `- g` or `+ g`
+impl fmt::Display for DiffOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffOp::Unchanged(g) => write!(f, "  {:?}", g),
+            DiffOp::Removed(g) => write!(f, "- {:?}", g),
+            DiffOp::Inserted(g) => write!(f, "+ {:?}", g),
         }
     }
-    
-    true
+}
+
+/// Edit script turning `before` into `after`
+///
+/// Unchanged gates form a longest common subsequence under exact gate
+/// equality; within a changed run removals come before insertions. Keeping
+/// the `Removed` and `Unchanged` gates gives back `before`, keeping the
+/// `Inserted` and `Unchanged` gates gives `after`.
+pub fn diff(before: &[Gate], after: &[Gate]) -> Vec<DiffOp> {
+    let prefix = before.iter().zip(after).take_while(|(a, b)| a == b).count();
+    let suffix = before[prefix..].iter().rev()
+        .zip(after[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (&before[prefix..before.len() - suffix], &after[prefix..after.len() - suffix]);
+
+    // lcs[i][j] = LCS length of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let mut ops: Vec<DiffOp> = before[..prefix].iter().cloned().map(DiffOp::Unchanged).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push(DiffOp::Unchanged(a[i].clone()));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(DiffOp::Removed(a[i].clone()));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Inserted(b[j].clone()));
+            j += 1;
+        }
+    }
+    ops.extend(before[before.len() - suffix..].iter().cloned().map(DiffOp::Unchanged));
+    ops
 }
 
 // ============================================================================
@@ -381,6 +559,68 @@ pub fn analyze_circuit(circuit: &[Gate]) -> CircuitResources {
     }
 }
 
+/// `analyze_circuit` of each stage, where `tags[i]` names the stage of `circuit[i]`
+///
+/// A stage is every gate sharing a tag, wherever it sits; its depth and
+/// qubit count are those of its own gates run alone, so per-stage depths do
+/// not add up to the total.
+pub fn analyze_by_tag<S: AsRef<str>>(circuit: &[Gate], tags: &[S]) -> HashMap<String, CircuitResources> {
+    assert_eq!(circuit.len(), tags.len(), "Need one tag per gate");
+    let mut stages: HashMap<String, Vec<Gate>> = HashMap::new();
+    for (gate, tag) in circuit.iter().zip(tags) {
+        stages.entry(tag.as_ref().to_string()).or_default().push(gate.clone());
+    }
+    stages.into_iter().map(|(tag, gates)| (tag, analyze_circuit(&gates))).collect()
+}
+
+/// A circuit built stage by stage, with the stage name of every gate alongside
+#[derive(Debug, Clone, Default)]
+pub struct TaggedCircuit {
+    pub gates: Vec<Gate>,
+    pub tags: Vec<String>,
+}
+
+impl TaggedCircuit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `gates` as (part of) the stage `tag`
+    pub fn extend(&mut self, tag: &str, gates: impl IntoIterator<Item = Gate>) {
+        for gate in gates {
+            self.gates.push(gate);
+            self.tags.push(tag.to_string());
+        }
+    }
+
+    /// `analyze_by_tag` over the stages appended so far
+    pub fn analyze(&self) -> HashMap<String, CircuitResources> {
+        analyze_by_tag(&self.gates, &self.tags)
+    }
+}
+
+/// Crude success probability Π(1 − pᵢ) over gates and readouts
+///
+/// Every 1-qubit gate (including `Reset`) contributes 1 − `single_error` and
+/// every 2-qubit gate 1 − `two_error`; a classically controlled gate is
+/// charged as its inner gate. CCX, CSWAP and the multi-controlled gates are
+/// charged as their elementary decomposition, so this agrees with
+/// [`estimated_infidelity`] at the same rates. `Measure`, `Barrier` and
+/// `GlobalPhase` are free here, and the readout factor
+/// (1 − `readout_error`)^`measured_qubits` is applied once. Ignores error
+/// cancellation and correlated noise.
+pub fn estimate_circuit_fidelity(
+    circuit: &[Gate],
+    single_error: f64,
+    two_error: f64,
+    readout_error: f64,
+    measured_qubits: usize,
+) -> f64 {
+    let rates = ErrorRates::new(single_error, two_error, 0.0);
+    assert!((0.0..=1.0).contains(&readout_error), "Error rate {} must lie in [0, 1]", readout_error);
+    circuit_success(circuit, &rates) * (1.0 - readout_error).powi(measured_qubits as i32)
+}
+
 /// Estimate resources for fault-tolerant execution
 /// 
 /// Converts T-count and other metrics to approximate physical qubit
@@ -419,38 +659,443 @@ pub struct FaultTolerantEstimate {
     pub estimated_cycles: usize,
 }
 
+// ============================================================================
+// SURFACE CODE ESTIMATION
+// ============================================================================
+
+/// Surface-code threshold assumed by [`surface_code_resources`]
+pub const SURFACE_CODE_THRESHOLD: f64 = 1e-2;
+
+/// Default syndrome-extraction cycle time (1 μs, superconducting hardware)
+pub const DEFAULT_CYCLE_TIME: f64 = 1e-6;
+
+/// d×d tiles occupied by one 15-to-1 distillation factory
+const FACTORY_TILES: usize = 11;
+
+/// Code cycles per distilled T state, in units of the code distance
+const FACTORY_PERIOD_PER_D: usize = 6;
+
+/// End-to-end surface-code estimate for a logical Clifford+T circuit
+#[derive(Debug, Clone)]
+pub struct FTResourceEstimate {
+    pub logical_qubits: usize,
+    pub code_distance: usize,
+    /// Data, routing and factory patches, 2d² physical qubits each
+    pub physical_qubits: usize,
+    /// Distilled |T⟩ states consumed (one per T/T†)
+    pub t_states: usize,
+    /// Syndrome-extraction rounds for the whole algorithm
+    pub code_cycles: usize,
+    /// Logical error per patch per logical step (d cycles)
+    pub logical_error_rate: f64,
+    /// Union-bound failure probability of the full run (capped at 1)
+    pub failure_probability: f64,
+    /// Wall-clock time at [`DEFAULT_CYCLE_TIME`]
+    pub wall_clock_seconds: f64,
+}
+
+impl FTResourceEstimate {
+    /// Wall-clock time for a given cycle time in seconds
+    pub fn wall_clock(&self, cycle_time: f64) -> f64 {
+        self.code_cycles as f64 * cycle_time
+    }
+}
+
+/// Estimate surface-code resources for a logical algorithm
+///
+/// Assumptions and scaling (N logical qubits, distance d, error rate p):
+/// - **Layout**: 2N tiles for data plus routing lanes, plus one 15-to-1
+///   factory of 11 tiles when T gates are present; each tile is a distance-d
+///   rotated patch of 2d² physical qubits.
+/// - **Time**: Every logical layer (two-qubit depth D₂ plus T-depth D_T) is a
+///   lattice-surgery step of d cycles. The single factory emits one |T⟩ per 6d
+///   cycles, so cycles = max(d·(D₂ + D_T), 6d·N_T).
+/// - **Errors**: p_L ≈ 0.1·(p/p_th)^((d+1)/2) with p_th = 1%, charged per tile
+///   per logical step.
+///
+/// Rotations and multi-qubit gates are not lowered here; synthesize the
+/// circuit to Clifford+T first so the T-count is meaningful.
+pub fn surface_code_resources(
+    logical_circuit: &[Gate],
+    physical_error_rate: f64,
+    code_distance: usize,
+) -> FTResourceEstimate {
+    assert!(code_distance >= 1, "Code distance must be positive");
+    assert!(
+        physical_error_rate > 0.0 && physical_error_rate < SURFACE_CODE_THRESHOLD,
+        "Physical error rate {} must lie below the threshold {}",
+        physical_error_rate, SURFACE_CODE_THRESHOLD
+    );
+
+    let analysis = analyze_circuit(logical_circuit);
+    let d = code_distance;
+    let t_states = analysis.t_count;
+
+    let data_tiles = 2 * analysis.num_qubits;
+    let factory_tiles = if t_states > 0 { FACTORY_TILES } else { 0 };
+    let tiles = data_tiles + factory_tiles;
+    let physical_qubits = tiles * 2 * d * d;
+
+    let logical_steps = two_qubit_depth(logical_circuit) + analysis.t_depth;
+    let code_cycles = (d * logical_steps).max(FACTORY_PERIOD_PER_D * d * t_states);
+
+    let exponent = (d + 1) as f64 / 2.0;
+    let logical_error_rate = 0.1 * (physical_error_rate / SURFACE_CODE_THRESHOLD).powf(exponent);
+    let tile_steps = (tiles * code_cycles.div_ceil(d)) as f64;
+    let failure_probability = (logical_error_rate * tile_steps).min(1.0);
+
+    FTResourceEstimate {
+        logical_qubits: analysis.num_qubits,
+        code_distance,
+        physical_qubits,
+        t_states,
+        code_cycles,
+        logical_error_rate,
+        failure_probability,
+        wall_clock_seconds: code_cycles as f64 * DEFAULT_CYCLE_TIME,
+    }
+}
+
+// ============================================================================
+// COST MODEL
+// ============================================================================
+
+/// Per-gate-type weights for [`circuit_cost`]
+///
+/// Multi-qubit gates (CCX, CCZ, CSWAP, MCX, MCZ, MCP) have no weight of their
+/// own: they are costed through their elementary decomposition, so a Toffoli
+/// is charged for its 7 T gates and 6 CNOTs.
+///
+/// With `error_rates` set, the cost also prices noise: `infidelity` is the
+/// cost of one unit of estimated infidelity, so `CostWeights::noise_aware`
+/// (all gate weights 0, `infidelity` 1) makes selectors minimize error alone.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostWeights {
+    /// Single-qubit Clifford gates (X, Y, Z, H, S, Sdg, SX)
+    pub single_qubit: f64,
+    /// T and T† gates
+    pub t: f64,
+    /// Arbitrary-angle single-qubit rotations (RX, RY, RZ, P, U3)
+    pub rotation: f64,
+    /// Two-qubit entangling gates (CX, CY, CZ, SWAP, iSWAP, ...)
+    pub two_qubit: f64,
+    /// Each ancilla qubit a synthesis routine borrows
+    pub ancilla: f64,
+    /// Device error rates; `None` leaves the cost purely gate-weighted
+    pub error_rates: Option<ErrorRates>,
+    /// Weight of the estimated infidelity 1 − Π(1 − pᵢ) when `error_rates` is set
+    pub infidelity: f64,
+}
+
+impl CostWeights {
+    /// T-dominated fault-tolerant regime: T=1000, CX=10, single=1
+    ///
+    /// Rotations are priced as a handful of T gates after synthesis.
+    pub fn fault_tolerant() -> Self {
+        CostWeights {
+            single_qubit: 1.0,
+            t: 1000.0,
+            rotation: 5000.0,
+            two_qubit: 10.0,
+            ancilla: 100.0,
+            error_rates: None,
+            infidelity: 0.0,
+        }
+    }
+
+    /// CX-dominated NISQ regime: all single-qubit gates cheap, CX=10
+    pub fn nisq() -> Self {
+        CostWeights {
+            single_qubit: 1.0,
+            t: 1.0,
+            rotation: 1.0,
+            two_qubit: 10.0,
+            ancilla: 0.0,
+            error_rates: None,
+            infidelity: 0.0,
+        }
+    }
+
+    /// Pure error minimization: the cost is the estimated infidelity under `rates`
+    pub fn noise_aware(rates: ErrorRates) -> Self {
+        CostWeights {
+            single_qubit: 0.0,
+            t: 0.0,
+            rotation: 0.0,
+            two_qubit: 0.0,
+            ancilla: 0.0,
+            error_rates: Some(rates),
+            infidelity: 1.0,
+        }
+    }
+}
+
+impl Default for CostWeights {
+    fn default() -> Self {
+        CostWeights::fault_tolerant()
+    }
+}
+
+/// Per-gate error probabilities for the noise term of [`CostWeights`]
+///
+/// `per_kind` overrides the generic rates for specific gate kinds, e.g. a
+/// device whose CZ is much noisier than its √iSWAP.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ErrorRates {
+    /// Every single-qubit gate, including `Reset`
+    pub single_qubit: f64,
+    /// Every native two-qubit gate
+    pub two_qubit: f64,
+    /// Each `Measure`
+    pub readout: f64,
+    /// Rates for individual kinds, taking precedence over the fields above
+    pub per_kind: HashMap<GateKind, f64>,
+}
+
+impl ErrorRates {
+    pub fn new(single_qubit: f64, two_qubit: f64, readout: f64) -> Self {
+        for p in [single_qubit, two_qubit, readout] {
+            assert!((0.0..=1.0).contains(&p), "Error rate {} must lie in [0, 1]", p);
+        }
+        ErrorRates { single_qubit, two_qubit, readout, per_kind: HashMap::new() }
+    }
+
+    /// Same rates, with `kind` failing with probability `p`
+    pub fn with_kind(mut self, kind: GateKind, p: f64) -> Self {
+        assert!((0.0..=1.0).contains(&p), "Error rate {} must lie in [0, 1]", p);
+        self.per_kind.insert(kind, p);
+        self
+    }
+}
+
+/// Both objectives of [`circuit_cost`] for one circuit
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CostBreakdown {
+    /// Σ_g w(type(g)), the raw gate-weighted cost
+    pub weighted: f64,
+    /// Estimated infidelity 1 − Π(1 − pᵢ), or 0 without error rates
+    pub infidelity: f64,
+    /// `weighted + weights.infidelity · infidelity`
+    pub total: f64,
+}
+
+/// Cost of a circuit: Σ_g w(type(g)), plus the weighted infidelity if error rates are set
+///
+/// Controlled rotations (CRX, CRY, CRZ, CP, RXX, RYY, RZZ) pay both the
+/// two-qubit and the rotation weight. Ancilla usage is not visible from the
+/// gate list; add `weights.ancilla` per borrowed qubit on top if relevant.
+/// See [`circuit_cost_breakdown`] for the two objectives separately.
+pub fn circuit_cost(circuit: &[Gate], weights: &CostWeights) -> f64 {
+    circuit_cost_breakdown(circuit, weights).total
+}
+
+/// [`circuit_cost`] split into its gate-weighted and noise parts
+///
+/// The infidelity is a product over gates, not a sum, so it does not
+/// distribute over subcircuits; for small error rates it is ≈ Σ pᵢ.
+pub fn circuit_cost_breakdown(circuit: &[Gate], weights: &CostWeights) -> CostBreakdown {
+    let weighted = weighted_cost(circuit, weights);
+    let infidelity = weights.error_rates.as_ref().map_or(0.0, |rates| estimated_infidelity(circuit, rates));
+    CostBreakdown { weighted, infidelity, total: weighted + weights.infidelity * infidelity }
+}
+
+/// 1 − Π(1 − pᵢ) with each gate failing independently at its rate in `rates`
+///
+/// Multi-qubit gates without a `per_kind` rate fail as their elementary
+/// decomposition (a Toffoli as 6 CX and 9 single-qubit gates), like their
+/// weight in [`circuit_cost`]. `GlobalPhase` and `Barrier` never fail.
+pub fn estimated_infidelity(circuit: &[Gate], rates: &ErrorRates) -> f64 {
+    1.0 - circuit_success(circuit, rates)
+}
+
+fn circuit_success(circuit: &[Gate], rates: &ErrorRates) -> f64 {
+    circuit.iter().map(|g| gate_success(g, rates)).product()
+}
+
+fn gate_success(gate: &Gate, rates: &ErrorRates) -> f64 {
+    use crate::gates::core::{decompose_fredkin, decompose_toffoli};
+    use crate::gates::decomposition::{decompose_mcp, decompose_mcx, decompose_mcz};
+
+    if let Some(p) = rates.per_kind.get(&gate.kind()) {
+        return 1.0 - p;
+    }
+    match gate {
+        Gate::CCX(c1, c2, t) => circuit_success(&decompose_toffoli(*c1, *c2, *t), rates),
+        Gate::CCZ(c1, c2, t) => circuit_success(&decompose_mcz(&[*c1, *c2], *t), rates),
+        Gate::CSWAP(c, a, b) => circuit_success(&decompose_fredkin(*c, *a, *b), rates),
+        Gate::MCX(controls, t) => circuit_success(&decompose_mcx(controls, *t), rates),
+        Gate::MCZ(controls, t) => circuit_success(&decompose_mcz(controls, *t), rates),
+        Gate::MCP(controls, t, phi) => circuit_success(&decompose_mcp(controls, *t, *phi), rates),
+        Gate::GlobalPhase(_) | Gate::Barrier(_) => 1.0,
+        Gate::Measure(_, _) => 1.0 - rates.readout,
+        Gate::ClassicalControl(_, inner) => gate_success(inner, rates),
+        _ if gate.qubits().len() == 2 => 1.0 - rates.two_qubit,
+        _ => 1.0 - rates.single_qubit,
+    }
+}
+
+fn weighted_cost(circuit: &[Gate], weights: &CostWeights) -> f64 {
+    circuit.iter().map(|g| gate_cost(g, weights)).sum()
+}
+
+fn gate_cost(gate: &Gate, weights: &CostWeights) -> f64 {
+    use crate::gates::core::{decompose_fredkin, decompose_toffoli};
+    use crate::gates::decomposition::{decompose_mcp, decompose_mcx, decompose_mcz};
+
+    match gate {
+        Gate::X(_) | Gate::Y(_) | Gate::Z(_) | Gate::H(_) |
+        Gate::S(_) | Gate::Sdg(_) | Gate::SX(_) | Gate::SXdg(_) => weights.single_qubit,
+        Gate::T(_) | Gate::Tdg(_) => weights.t,
+        Gate::RX(_, _) | Gate::RY(_, _) | Gate::RZ(_, _) |
+        Gate::P(_, _) | Gate::U3(_, _, _, _) => weights.rotation,
+
+        Gate::CX(_, _) | Gate::CY(_, _) | Gate::CZ(_, _) |
+        Gate::SWAP(_, _) | Gate::ISWAP(_, _) | Gate::ISWAPdg(_, _) | Gate::SqrtISWAP(_, _) |
+        Gate::SqrtISWAPdg(_, _) => weights.two_qubit,
+        Gate::CRX(_, _, _) | Gate::CRY(_, _, _) | Gate::CRZ(_, _, _) | Gate::CP(_, _, _) |
+        Gate::RXX(_, _, _) | Gate::RYY(_, _, _) | Gate::RZZ(_, _, _) => {
+            weights.two_qubit + weights.rotation
+        }
+
+        Gate::CCX(c1, c2, t) => weighted_cost(&decompose_toffoli(*c1, *c2, *t), weights),
+        Gate::CCZ(c1, c2, t) => weighted_cost(&decompose_mcz(&[*c1, *c2], *t), weights),
+        Gate::CSWAP(c, a, b) => weighted_cost(&decompose_fredkin(*c, *a, *b), weights),
+        Gate::MCX(controls, t) => weighted_cost(&decompose_mcx(controls, *t), weights),
+        Gate::MCZ(controls, t) => weighted_cost(&decompose_mcz(controls, *t), weights),
+        Gate::MCP(controls, t, phi) => weighted_cost(&decompose_mcp(controls, *t, *phi), weights),
+        Gate::GlobalPhase(_) | Gate::Barrier(_) => 0.0,
+        // Readout and reset are charged like a single-qubit operation
+        Gate::Measure(_, _) | Gate::Reset(_) => weights.single_qubit,
+        Gate::ClassicalControl(_, inner) => gate_cost(inner, weights),
+    }
+}
+
 // ============================================================================
 // CIRCUIT VERIFICATION
 // ============================================================================
 
-/// Verify basic circuit structure
-pub fn verify_circuit(circuit: &[Gate], max_qubits: usize) -> Result<(), String> {
-    for (i, gate) in circuit.iter().enumerate() {
-        let qubits = gate.qubits();
-        
-        // Check qubit indices
-        for &q in &qubits {
-            if q >= max_qubits {
-                return Err(format!(
-                    "Gate {} at position {} uses qubit {} but only {} qubits available",
-                    gate_type_name(gate), i, q, max_qubits
-                ));
+/// Structural defect found by [`validate`] or [`validate_width`]
+///
+/// `position` is the index of the offending gate in the circuit.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationError {
+    /// A gate lists the same qubit twice, e.g. SWAP(3, 3)
+    DuplicateQubit { position: usize, gate: String, qubit: usize },
+    /// A controlled gate uses its target as a control, e.g. CX(0, 0) or MCX([0, 1], 1)
+    ControlIsTarget { position: usize, gate: String, qubit: usize },
+    /// A multi-controlled gate (MCX, MCZ, MCP) has an empty control list
+    NoControls { position: usize, gate: String },
+    /// A qubit index at or beyond the declared register width
+    QubitOutOfRange { position: usize, gate: String, qubit: usize, num_qubits: usize },
+    /// A rotation angle or phase is NaN or infinite
+    NonFiniteAngle { position: usize, gate: String },
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationError::DuplicateQubit { position, gate, qubit } => {
+                write!(f, "Gate {} at position {} uses qubit {} twice", gate, position, qubit)
+            }
+            ValidationError::ControlIsTarget { position, gate, qubit } => {
+                write!(f, "Gate {} at position {} uses qubit {} as both control and target", gate, position, qubit)
+            }
+            ValidationError::NoControls { position, gate } => {
+                write!(f, "Gate {} at position {} has no controls", gate, position)
+            }
+            ValidationError::QubitOutOfRange { position, gate, qubit, num_qubits } => write!(
+                f,
+                "Gate {} at position {} uses qubit {} but only {} qubits available",
+                gate, position, qubit, num_qubits
+            ),
+            ValidationError::NonFiniteAngle { position, gate } => {
+                write!(f, "Gate {} at position {} has a non-finite angle", gate, position)
             }
         }
-        
-        // Check for duplicate qubits in gate
-        let unique: HashSet<_> = qubits.iter().collect();
-        if unique.len() != qubits.len() {
-            return Err(format!(
-                "Gate {} at position {} has duplicate qubits",
-                gate_type_name(gate), i
-            ));
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Check that every gate is well formed
+///
+/// Multi-qubit gates must act on distinct qubits, controls may not include
+/// the target, multi-controlled gates need at least one control and every
+/// angle must be finite. Classically controlled gates are checked recursively.
+pub fn validate(circuit: &[Gate]) -> Result<(), ValidationError> {
+    for (position, gate) in circuit.iter().enumerate() {
+        validate_gate(position, gate)?;
+    }
+    Ok(())
+}
+
+/// [`validate`], plus every qubit index must be below `num_qubits`
+pub fn validate_width(circuit: &[Gate], num_qubits: usize) -> Result<(), ValidationError> {
+    validate(circuit)?;
+    for (position, gate) in circuit.iter().enumerate() {
+        if let Some(&qubit) = gate.qubits().iter().find(|&&q| q >= num_qubits) {
+            return Err(ValidationError::QubitOutOfRange {
+                position,
+                gate: gate.kind().name().to_string(),
+                qubit,
+                num_qubits,
+            });
         }
     }
-    
     Ok(())
 }
 
+fn validate_gate(position: usize, gate: &Gate) -> Result<(), ValidationError> {
+    let name = || gate.kind().name().to_string();
+
+    if gate_angles(gate).iter().any(|a| !a.is_finite()) {
+        return Err(ValidationError::NonFiniteAngle { position, gate: name() });
+    }
+
+    let (controls, targets): (Vec<usize>, Vec<usize>) = match gate {
+        Gate::ClassicalControl(_, inner) => return validate_gate(position, inner),
+        Gate::MCX(c, _) | Gate::MCZ(c, _) | Gate::MCP(c, _, _) if c.is_empty() => {
+            return Err(ValidationError::NoControls { position, gate: name() });
+        }
+        Gate::MCX(c, t) | Gate::MCZ(c, t) | Gate::MCP(c, t, _) => (c.clone(), vec![*t]),
+        Gate::CX(c, t) | Gate::CY(c, t) | Gate::CZ(c, t) |
+        Gate::CRX(c, t, _) | Gate::CRY(c, t, _) | Gate::CRZ(c, t, _) | Gate::CP(c, t, _) => {
+            (vec![*c], vec![*t])
+        }
+        Gate::CCX(c1, c2, t) | Gate::CCZ(c1, c2, t) => (vec![*c1, *c2], vec![*t]),
+        Gate::CSWAP(c, a, b) => (vec![*c], vec![*a, *b]),
+        _ => (Vec::new(), gate.qubits()),
+    };
+
+    if let Some(&qubit) = controls.iter().find(|c| targets.contains(c)) {
+        return Err(ValidationError::ControlIsTarget { position, gate: name(), qubit });
+    }
+    let mut seen = HashSet::new();
+    if let Some(&qubit) = controls.iter().chain(targets.iter()).find(|&&q| !seen.insert(q)) {
+        return Err(ValidationError::DuplicateQubit { position, gate: name(), qubit });
+    }
+    Ok(())
+}
+
+fn gate_angles(gate: &Gate) -> Vec<f64> {
+    match gate {
+        Gate::RX(_, a) | Gate::RY(_, a) | Gate::RZ(_, a) | Gate::P(_, a) |
+        Gate::CRX(_, _, a) | Gate::CRY(_, _, a) | Gate::CRZ(_, _, a) | Gate::CP(_, _, a) |
+        Gate::RXX(_, _, a) | Gate::RYY(_, _, a) | Gate::RZZ(_, _, a) |
+        Gate::MCP(_, _, a) | Gate::GlobalPhase(a) => vec![*a],
+        Gate::U3(_, a, b, c) => vec![*a, *b, *c],
+        Gate::ClassicalControl(_, inner) => gate_angles(inner),
+        _ => Vec::new(),
+    }
+}
+
+/// Verify basic circuit structure
+///
+/// String-error form of [`validate_width`].
+pub fn verify_circuit(circuit: &[Gate], max_qubits: usize) -> Result<(), String> {
+    validate_width(circuit, max_qubits).map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -459,6 +1104,72 @@ pub fn verify_circuit(circuit: &[Gate], max_qubits: usize) -> Result<(), String>
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_circuit_unitary_matches_embedded_gates() {
+        use crate::simulator::{embed_gate, states_close};
+        let local = |g: &Gate| -> Vec<Vec<Complex>> { g.matrix_4x4().unwrap().data.iter().map(|r| r.to_vec()).collect() };
+        let (g1, g2) = (Gate::CRY(3, 1, 0.7), Gate::CX(0, 2));
+        let (e1, e2) = (embed_gate(&local(&g1), &[3, 1], 4).unwrap(), embed_gate(&local(&g2), &[0, 2], 4).unwrap());
+
+        let single = circuit_unitary(std::slice::from_ref(&g1), 4);
+        assert!(single.iter().zip(&e1).all(|(a, b)| states_close(a, b, 1e-12)));
+
+        // Later gates multiply on the left
+        let product: Vec<Vec<Complex>> = (0..16)
+            .map(|i| (0..16).map(|j| (0..16).fold(Complex::ZERO, |acc, k| acc + e2[i][k] * e1[k][j])).collect())
+            .collect();
+        let both = circuit_unitary(&[g1, g2], 4);
+        assert!(both.iter().zip(&product).all(|(a, b)| states_close(a, b, 1e-12)));
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let bells = vec![Gate::H(0), Gate::CX(0, 2), Gate::H(1), Gate::CX(1, 3)];
+        assert_eq!(connected_components(&bells, 5), vec![vec![0, 2], vec![1, 3], vec![4]]);
+
+        // A barrier spans both pairs without joining them; feed-forward does join
+        let mut linked = bells.clone();
+        linked.push(Gate::Barrier(vec![0, 1, 2, 3]));
+        assert_eq!(connected_components(&linked, 4).len(), 2);
+        linked.extend([Gate::Measure(2, 0), Gate::ClassicalControl(0, Box::new(Gate::X(3)))]);
+        assert_eq!(connected_components(&linked, 4), vec![vec![0, 1, 2, 3]]);
+    }
+
+    #[test]
+    fn test_estimate_circuit_fidelity() {
+        let cx_chain: Vec<Gate> = (0..5).map(|i| Gate::CX(i, i + 1)).collect();
+        let f = estimate_circuit_fidelity(&cx_chain, 1e-3, 1e-2, 2e-2, 6);
+        assert!((f - 0.99f64.powi(5) * 0.98f64.powi(6)).abs() < 1e-12);
+
+        let mixed = vec![
+            Gate::H(0), Gate::CCX(0, 1, 2), Gate::Barrier(vec![0, 1]), Gate::Measure(0, 0),
+            Gate::ClassicalControl(0, Box::new(Gate::X(1))), Gate::GlobalPhase(0.3),
+        ];
+        // The Toffoli is priced as its 6 CX and 9 single-qubit gates
+        let f = estimate_circuit_fidelity(&mixed, 0.1, 0.2, 0.0, 1);
+        assert!((f - 0.9 * (0.8f64.powi(6) * 0.9f64.powi(9)) * 0.9).abs() < 1e-12, "{}", f);
+        let toffoli = [Gate::CCX(0, 1, 2)];
+        let rates = ErrorRates::new(0.1, 0.2, 0.0);
+        assert!((estimate_circuit_fidelity(&toffoli, 0.1, 0.2, 0.0, 0) - (1.0 - estimated_infidelity(&toffoli, &rates))).abs() < 1e-12);
+        assert_eq!(estimate_circuit_fidelity(&[], 0.1, 0.1, 0.1, 0), 1.0);
+    }
+
+    #[test]
+    fn test_causal_cone() {
+        // 0 → 1 → 2 feeds the observable; qubit 3 and the later CX(0, 3) do not
+        let circuit = vec![
+            Gate::H(0), Gate::H(3), Gate::CX(0, 1), Gate::RZ(3, 0.4), Gate::CX(1, 2),
+            Gate::Barrier(vec![0, 1, 2, 3]), Gate::CX(0, 3), Gate::T(2), Gate::GlobalPhase(0.1),
+        ];
+        let cone = causal_cone(&circuit, &[2]);
+        assert_eq!(format!("{:?}", cone), format!("{:?}", [Gate::H(0), Gate::CX(0, 1), Gate::CX(1, 2), Gate::T(2)]));
+        assert!(causal_cone(&circuit, &[]).is_empty());
+
+        // A measurement steering the observable through feed-forward is in the cone
+        let feed = vec![Gate::H(0), Gate::Measure(0, 5), Gate::H(1), Gate::ClassicalControl(5, Box::new(Gate::X(2)))];
+        assert_eq!(causal_cone(&feed, &[2]).len(), 3);
+    }
+
     #[test]
     fn test_gate_counting() {
         let circuit = vec![
@@ -474,6 +1185,36 @@ mod tests {
         assert_eq!(counts.get("T"), Some(&2));
     }
 
+    #[test]
+    fn test_process_fidelity() {
+        let circuit = [Gate::H(0), Gate::CX(0, 1), Gate::T(1)];
+        assert!((process_fidelity(&circuit, &circuit, 2) - 1.0).abs() < 1e-12);
+        assert!((process_fidelity(&[Gate::Z(0), Gate::GlobalPhase(0.7)], &[Gate::Z(0)], 1) - 1.0).abs() < 1e-12);
+
+        // Tr(X) = 0
+        assert!(process_fidelity(&[Gate::X(0)], &[], 1).abs() < 1e-12);
+
+        // |Tr RZ(θ)|² / 4 = cos²(θ/2)
+        let theta: f64 = 0.4;
+        let f = process_fidelity(&[Gate::RZ(1, theta)], &[], 2);
+        assert!((f - (theta / 2.0).cos().powi(2)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_unitary_equivalence_and_tolerance() {
+        // XZX = −Z: equal up to global phase
+        assert!(unitary_equivalent(&[Gate::X(0), Gate::Z(0), Gate::X(0)], &[Gate::Z(0)], 1));
+        assert!(unitary_equivalent(&[Gate::CX(0, 1), Gate::CX(0, 1)], &[], 2));
+        assert!(!unitary_equivalent(&[Gate::H(0)], &[Gate::X(0)], 1));
+
+        // A deliberately tiny rotation is visible at the default tolerance only
+        let tiny = [Gate::RY(1, 1e-7)];
+        assert!(!unitary_equivalent(&tiny, &[], 2));
+        assert!(unitary_equivalent_with_tol(&tiny, &[], 2, 1e-6));
+        assert!(structurally_equivalent_with_tol(&[Gate::RZ(0, 0.3)], &[Gate::RZ(0, 0.3 + 1e-8)], 1e-6));
+        assert!(!structurally_equivalent(&[Gate::RZ(0, 0.3)], &[Gate::RZ(0, 0.3 + 1e-8)]));
+    }
+
     #[test]
     fn test_circuit_depth() {
         // H(0)-H(1) can be parallel, CX is serial
@@ -506,10 +1247,147 @@ mod tests {
         assert_eq!(edges.len(), 3);
     }
 
+    #[test]
+    fn test_surface_code_resources() {
+        let circuit = vec![Gate::H(0), Gate::CX(0, 1), Gate::T(1), Gate::CX(0, 1), Gate::T(0)];
+        let est = surface_code_resources(&circuit, 1e-3, 7);
+
+        // (2·2 data + 11 factory) tiles × 2·49 qubits
+        assert_eq!(est.physical_qubits, 15 * 98);
+        assert_eq!(est.t_states, 2);
+        // Factory-limited: 6·7·2 = 84 > 7·(2 + 1)
+        assert_eq!(est.code_cycles, 84);
+        assert!((est.wall_clock(1e-6) - 84e-6).abs() < 1e-12);
+
+        // Larger distance suppresses the logical error rate
+        let bigger = surface_code_resources(&circuit, 1e-3, 11);
+        assert!(bigger.logical_error_rate < est.logical_error_rate);
+        assert!(bigger.physical_qubits > est.physical_qubits);
+    }
+
+    #[test]
+    fn test_circuit_cost() {
+        let circuit = vec![Gate::H(0), Gate::T(0), Gate::CX(0, 1)];
+        let weights = CostWeights::default();
+        assert!((circuit_cost(&circuit, &weights) - 1011.0).abs() < 1e-9);
+
+        // Toffoli is charged for its Clifford+T decomposition: 7 T, 6 CX, 2 H
+        let toffoli = vec![Gate::CCX(0, 1, 2)];
+        assert!((circuit_cost(&toffoli, &weights) - 7062.0).abs() < 1e-9);
+        assert!((circuit_cost(&toffoli, &CostWeights::nisq()) - 69.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_noise_aware_cost() {
+        let circuit = vec![Gate::H(0), Gate::CX(0, 1), Gate::CX(1, 2), Gate::Measure(2, 0)];
+        let rates = ErrorRates::new(1e-3, 1e-2, 2e-2);
+        let infidelity = 1.0 - 0.999 * 0.99 * 0.99 * 0.98;
+        assert!((estimated_infidelity(&circuit, &rates) - infidelity).abs() < 1e-12);
+
+        // The noise term is added on top of the gate weights
+        let weights = CostWeights { error_rates: Some(rates.clone()), infidelity: 100.0, ..CostWeights::nisq() };
+        let breakdown = circuit_cost_breakdown(&circuit, &weights);
+        assert!((breakdown.weighted - 22.0).abs() < 1e-12);
+        assert!((breakdown.infidelity - infidelity).abs() < 1e-12);
+        assert!((circuit_cost(&circuit, &weights) - (22.0 + 100.0 * infidelity)).abs() < 1e-12);
+        assert_eq!(circuit_cost_breakdown(&circuit, &CostWeights::nisq()).infidelity, 0.0);
+
+        // Toffolis fail as 6 CX and 9 single-qubit gates unless given their own rate
+        let toffoli = [Gate::CCX(0, 1, 2)];
+        let expected = 1.0 - 0.999f64.powi(9) * 0.99f64.powi(6);
+        assert!((estimated_infidelity(&toffoli, &rates) - expected).abs() < 1e-12);
+        let native = rates.with_kind(GateKind::CCX, 0.03);
+        assert!((estimated_infidelity(&toffoli, &native) - 0.03).abs() < 1e-12);
+        assert!((circuit_cost(&toffoli, &CostWeights::noise_aware(native)) - 0.03).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = vec![Gate::H(0), Gate::CX(0, 1), Gate::T(1), Gate::CX(0, 1), Gate::H(0)];
+        let mut after = before.clone();
+        after.remove(2);
+        let ops = diff(&before, &after);
+        let removed: Vec<&DiffOp> = ops.iter().filter(|op| !matches!(op, DiffOp::Unchanged(_))).collect();
+        assert_eq!(removed, vec![&DiffOp::Removed(Gate::T(1))]);
+        assert_eq!(ops.len(), 5);
+        assert!(diff(&before, &before).iter().all(|op| matches!(op, DiffOp::Unchanged(_))));
+
+        // A replaced gate and an append; both sides can be rebuilt from the script
+        let after = vec![Gate::H(0), Gate::CX(0, 1), Gate::RZ(1, 0.5), Gate::CX(0, 1), Gate::H(0), Gate::X(1)];
+        let ops = diff(&before, &after);
+        let rebuilt = |keep: fn(&DiffOp) -> Option<&Gate>| ops.iter().filter_map(keep).cloned().collect::<Vec<_>>();
+        assert_eq!(rebuilt(|op| match op { DiffOp::Inserted(_) => None, DiffOp::Unchanged(g) | DiffOp::Removed(g) => Some(g) }), before);
+        assert_eq!(rebuilt(|op| match op { DiffOp::Removed(_) => None, DiffOp::Unchanged(g) | DiffOp::Inserted(g) => Some(g) }), after);
+        assert_eq!(ops.iter().filter(|op| !matches!(op, DiffOp::Unchanged(_))).count(), 3);
+        assert_eq!(DiffOp::Removed(Gate::T(1)).to_string(), "- T(1)");
+        assert!(diff(&[], &[Gate::X(0)]) == vec![DiffOp::Inserted(Gate::X(0))]);
+    }
+
+    #[test]
+    fn test_analyze_by_tag() {
+        use crate::algorithms::qft::{inverse_qft, qft};
+
+        let mut tagged = TaggedCircuit::new();
+        tagged.extend("prepare", [Gate::H(0), Gate::H(1), Gate::H(2)]);
+        tagged.extend("qft", qft(3, None));
+        tagged.extend("oracle", [Gate::CCX(0, 1, 2), Gate::T(2)]);
+        tagged.extend("qft", inverse_qft(3, None));
+        let report = tagged.analyze();
+
+        assert_eq!(report.len(), 3);
+        assert_eq!(report["prepare"].total_gates, 3);
+        assert_eq!(report["prepare"].depth, 1);
+        assert_eq!(report["oracle"].t_count, 1);
+        assert_eq!(report["oracle"].multi_qubit_gates, 1);
+        let mut both: Vec<Gate> = qft(3, None);
+        both.extend(inverse_qft(3, None));
+        assert_eq!(report["qft"].gate_counts, gate_count_by_type(&both));
+
+        // Stages partition the gates; plain slices of &str work too
+        let total: usize = report.values().map(|r| r.total_gates).sum();
+        assert_eq!(total, analyze_circuit(&tagged.gates).total_gates);
+        let plain = analyze_by_tag(&[Gate::X(0), Gate::CX(0, 1)], &["a", "b"]);
+        assert_eq!(plain["b"].cnot_count, 1);
+    }
+
     #[test]
     fn test_verify_circuit() {
         let circuit = vec![Gate::CX(0, 1), Gate::H(2)];
         assert!(verify_circuit(&circuit, 3).is_ok());
         assert!(verify_circuit(&circuit, 2).is_err());
     }
+
+    #[test]
+    fn test_validate_error_variants() {
+        assert!(validate(&[Gate::CX(0, 1), Gate::MCX(vec![0, 1], 2), Gate::GlobalPhase(0.1)]).is_ok());
+
+        assert!(matches!(
+            validate(&[Gate::H(0), Gate::CX(0, 0)]),
+            Err(ValidationError::ControlIsTarget { position: 1, qubit: 0, .. })
+        ));
+        assert!(matches!(
+            validate(&[Gate::MCX(vec![0, 1], 1)]),
+            Err(ValidationError::ControlIsTarget { qubit: 1, .. })
+        ));
+        assert!(matches!(
+            validate(&[Gate::CCZ(2, 2, 0)]),
+            Err(ValidationError::DuplicateQubit { qubit: 2, .. })
+        ));
+        assert!(matches!(
+            validate(&[Gate::SWAP(3, 3)]),
+            Err(ValidationError::DuplicateQubit { qubit: 3, .. })
+        ));
+        assert!(matches!(
+            validate(&[Gate::MCZ(vec![], 0)]),
+            Err(ValidationError::NoControls { .. })
+        ));
+        assert!(matches!(
+            validate(&[Gate::ClassicalControl(0, Box::new(Gate::RZ(0, f64::NAN)))]),
+            Err(ValidationError::NonFiniteAngle { .. })
+        ));
+        assert!(matches!(
+            validate_width(&[Gate::CX(0, 4)], 4),
+            Err(ValidationError::QubitOutOfRange { qubit: 4, num_qubits: 4, .. })
+        ));
+    }
 }
@@ -8,6 +8,8 @@
 //! ## ⚙️ How it works?
 //! - **Centralized Tools**: Re-exports all analysis functions (gate counting, depth, 
 //!   interaction graphs) for a unified inspection API.
+//! - **Diagrams**: `drawing` renders circuits as text for debugging and tests.
+//! - **Distributions**: `stats` scores measured histograms against each other.
 //!
 //! ## 📍 Where to apply this?
 //! Use this module at the end of a synthesis or optimization pipeline to 
@@ -17,5 +19,9 @@
 //! - Structural wrapper with zero overhead.
 
 pub mod circuit_analysis;
+pub mod drawing;
+pub mod stats;
 
 pub use circuit_analysis::*;
+pub use drawing::*;
+pub use stats::*;
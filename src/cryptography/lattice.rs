@@ -103,7 +103,7 @@ impl Polynomial {
         let mut rng_state = seed;
         let mut coeffs = vec![0i32; MLKEM_N];
         
-        for i in 0..MLKEM_N {
+        for coeff in coeffs.iter_mut() {
             let mut sum = 0i32;
             for _ in 0..(2 * eta) {
                 rng_state = rng_state.wrapping_mul(6364136223846793005).wrapping_add(1);
@@ -112,7 +112,7 @@ impl Polynomial {
                 }
             }
             // Center around zero
-            coeffs[i] = (sum - eta as i32).rem_euclid(MLKEM_Q);
+            *coeff = (sum - eta as i32).rem_euclid(MLKEM_Q);
         }
         
         Polynomial { coeffs }
@@ -316,11 +316,11 @@ impl MlKemPublicKey {
 
         // Encode message into v (each bit maps to 0 or q/2)
         let q_half = MLKEM_Q / 2;
-        for i in 0..32.min(MLKEM_N / 8) {
+        for (i, &byte) in m.iter().enumerate().take(32.min(MLKEM_N / 8)) {
             for j in 0..8 {
                 let bit_idx = i * 8 + j;
                 if bit_idx < MLKEM_N {
-                    let bit = (m[i] >> j) & 1;
+                    let bit = (byte >> j) & 1;
                     if bit == 1 {
                         v.coeffs[bit_idx] = (v.coeffs[bit_idx] + q_half).rem_euclid(MLKEM_Q);
                     }
@@ -364,7 +364,7 @@ impl MlKemSecretKey {
         let _q_quarter = MLKEM_Q / 4;
         let q_half = MLKEM_Q / 2;
 
-        for i in 0..32.min(MLKEM_N / 8) {
+        for (i, out) in m.iter_mut().enumerate().take(32.min(MLKEM_N / 8)) {
             let mut byte = 0u8;
             for j in 0..8 {
                 let bit_idx = i * 8 + j;
@@ -379,7 +379,7 @@ impl MlKemSecretKey {
                     }
                 }
             }
-            m[i] = byte;
+            *out = byte;
         }
 
         // Derive shared secret (simplified - real impl re-encrypts and compares)
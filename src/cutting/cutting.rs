@@ -1,9 +1,12 @@
 //! Circuit Cutting and Distribution
 //!
 //! This module provides techniques for executing large circuits on smaller devices:
-//! - Wire cutting (QPD-based)
+//! - Wire cutting (QPD-based), with `insert_wire_cut_qpd` /
+//!   `reconstruct_expectation` running a single cut end to end as an
+//!   identity-channel QPD inside the full circuit (no per-fragment split)
 //! - Gate cutting
-//! - Entanglement forging
+//! - Entanglement forging, with the interference-term preparations
+//!   (`superposition_bitstrings`)
 //!
 //! ## 🎯 Why is this used?
 //! Today's quantum processors are constrained by both qubit counts and limited 
@@ -34,10 +37,15 @@
 //!   required ($O(4^k)$ for $k$ wire cuts). Each cut generates 4 sub-circuit variants.
 //! - **Overhead**: Total shots scale as $\gamma^2 / \epsilon^2$, where $\gamma$ 
 //!   is the sampling overhead (accumulated from all cut decompositions).
+//!   `cutting_plan` splits that budget across fragments in proportion to |cᵢ|.
 //! - **Verification**: All decompositions are verified to satisfy the trace-preserving 
 //!   and unital requirements of valid quantum channels.
 
+use std::f64::consts::PI;
+use crate::analysis::circuit_analysis::connected_components;
+use crate::error::{check_dimension, RustiqError};
 use crate::gates::core::Gate;
+use crate::gates::circuit::{measure_x_basis, measure_y_basis, prepare_x_basis, prepare_y_basis};
 
 // ============================================================================
 // WIRE CUTTING
@@ -51,11 +59,17 @@ pub struct WireCut {
 }
 
 /// Result of cutting a circuit at wire locations
+///
+/// The uncut expectation is Σᵢ `coefficients[i]` · ⟨Π_{q ∈ measured_qubits} Z_q⟩
+/// of `subcircuits[i]`, see [`reconstruct_expectation`].
 #[derive(Clone, Debug)]
 pub struct CutCircuit {
     pub subcircuits: Vec<Vec<Gate>>,       // Fragment circuits
     pub cut_info: Vec<CutInfo>,            // Information about each cut
     pub reconstruction_overhead: f64,       // Sampling overhead
+    pub coefficients: Vec<f64>,            // QPD coefficient of each fragment
+    pub measured_qubits: Vec<usize>,       // Z-parity every fragment is read out on
+    pub fragment_qubits: usize,            // Register width every fragment needs
 }
 
 #[derive(Clone, Debug)]
@@ -85,8 +99,8 @@ pub fn cut_wires(
     let mut fragments = Vec::new();
     
     // Sort cuts by position (reverse order for correct slicing)
-    let mut sorted_cuts: Vec<_> = cuts.iter().cloned().collect();
-    sorted_cuts.sort_by(|a, b| b.position.cmp(&a.position));
+    let mut sorted_cuts: Vec<_> = cuts.to_vec();
+    sorted_cuts.sort_by_key(|c| std::cmp::Reverse(c.position));
     
     // Generate all 4^k subcircuits for k cuts
     let num_configurations = 4usize.pow(cuts.len() as u32);
@@ -129,16 +143,14 @@ fn decompose_configuration(config: usize, cuts: &[WireCut]) -> (Vec<Gate>, Vec<G
             }
             2 => {
                 // Prepare |+⟩, measure X
-                pre_ops.push(Gate::H(q));
-                post_ops.push(Gate::H(q));
+                pre_ops.extend(prepare_x_basis(&[q]));
+                post_ops.extend(measure_x_basis(&[q]));
                 coeff *= 0.5;
             }
             3 => {
                 // Prepare |i+⟩, measure Y
-                pre_ops.push(Gate::H(q));
-                pre_ops.push(Gate::S(q));
-                post_ops.push(Gate::Sdg(q));
-                post_ops.push(Gate::H(q));
+                pre_ops.extend(prepare_y_basis(&[q]));
+                post_ops.extend(measure_y_basis(&[q]));
                 coeff *= 0.5;
             }
             _ => unreachable!(),
@@ -148,6 +160,73 @@ fn decompose_configuration(config: usize, cuts: &[WireCut]) -> (Vec<Gate>, Vec<G
     (pre_ops, post_ops, coeff)
 }
 
+/// Replace one wire by its identity-channel QPD and set up the
+/// ⟨Π_{q ∈ observable} Z_q⟩ estimate of `circuit`
+///
+/// The identity on `cut.qubit` before gate `cut.position` is replaced by the
+/// γ = 4 QPD ρ = ½ Σ_P Tr(Pρ) P over P ∈ {I, X, Y, Z}, each P split into
+/// its two eigenstates: 8 fragments with coefficients ±½. In every fragment
+/// the upstream gates keep the cut qubit q, rotated so that Z_q reads P, and
+/// the downstream gates act on a fresh qubit `num_qubits` prepared in the
+/// eigenstate. For P = I the upstream copy moves to a second fresh qubit
+/// `num_qubits + 1` instead, so q stays |0⟩ and contributes Z_q = 1.
+///
+/// This inserts the QPD in place of the identity but does not split the
+/// circuit: every fragment still holds all gates, on `fragment_qubits` =
+/// `num_qubits + 2` wires. It reproduces the estimator and its γ² sampling
+/// cost for a cut a caller would then separate, not smaller pieces to run.
+/// A cut past the last gate is `PositionOutOfRange`, and a cut or observable
+/// qubit outside the register is `QubitOutOfRange`.
+pub fn insert_wire_cut_qpd(circuit: &[Gate], cut: &WireCut, observable: &[usize], num_qubits: usize) -> Result<CutCircuit, RustiqError> {
+    if cut.position > circuit.len() {
+        return Err(RustiqError::PositionOutOfRange { position: cut.position, len: circuit.len() });
+    }
+    if let Some(&qubit) = observable.iter().chain([&cut.qubit]).find(|&&w| w >= num_qubits) {
+        return Err(RustiqError::QubitOutOfRange { qubit, num_qubits });
+    }
+    let (q, fresh, spare) = (cut.qubit, num_qubits, num_qubits + 1);
+    let relabel = |gates: &[Gate], to: usize| -> Vec<Gate> {
+        gates.iter().map(|g| g.map_qubits(|w| if w == q { to } else { w })).collect()
+    };
+    let (upstream, downstream) = (&circuit[..cut.position], relabel(&circuit[cut.position..], fresh));
+
+    let mut subcircuits = Vec::with_capacity(8);
+    let mut coefficients: Vec<f64> = Vec::with_capacity(8);
+    for basis in 0..4 {
+        for eigenstate in 0..2 {
+            let mut gates = if basis == 0 { relabel(upstream, spare) } else { upstream.to_vec() };
+            gates.extend(match basis {
+                2 => measure_x_basis(&[q]),
+                3 => measure_y_basis(&[q]),
+                _ => Vec::new(),
+            });
+            if eigenstate == 1 {
+                gates.push(Gate::X(fresh));
+            }
+            gates.extend(match basis {
+                2 => prepare_x_basis(&[fresh]),
+                3 => prepare_y_basis(&[fresh]),
+                _ => Vec::new(),
+            });
+            gates.extend(downstream.iter().cloned());
+            subcircuits.push(gates);
+            // I = |0⟩⟨0| + |1⟩⟨1|, while X, Y, Z weigh their −1 eigenstate negatively
+            coefficients.push(if basis > 0 && eigenstate == 1 { -0.5 } else { 0.5 });
+        }
+    }
+
+    let mut measured_qubits: Vec<usize> = observable.iter().map(|&w| if w == q { fresh } else { w }).collect();
+    measured_qubits.push(q);
+    Ok(CutCircuit {
+        subcircuits,
+        cut_info: vec![CutInfo { subcircuit_index: 0, qubit_in_subcircuit: fresh, cut_type: CutType::WireCut }],
+        reconstruction_overhead: coefficients.iter().map(|c| c.abs()).sum(),
+        coefficients,
+        measured_qubits,
+        fragment_qubits: num_qubits + 2,
+    })
+}
+
 /// Find optimal wire cut locations to partition circuit into fragments
 /// 
 /// Minimizes the total sampling overhead while keeping fragment sizes
@@ -167,11 +246,21 @@ pub fn find_optimal_cuts(
     if total_qubits <= max_fragment_qubits {
         return cuts;
     }
+
+    // Blocks that already fit need no cuts
+    let oversized: Vec<usize> = connected_components(circuit, total_qubits)
+        .into_iter()
+        .filter(|c| c.len() > max_fragment_qubits)
+        .flatten()
+        .collect();
+    if oversized.is_empty() {
+        return cuts;
+    }
     
     // Find cut points based on circuit structure
     for (i, gate) in circuit.iter().enumerate() {
         let qubits = gate.qubits();
-        if qubits.len() >= 2 {
+        if qubits.len() >= 2 && oversized.contains(&qubits[0]) {
             // Potential cut location after two-qubit gates
             cuts.push(WireCut {
                 position: i + 1,
@@ -306,6 +395,66 @@ fn prepare_bitstring(qubits: &[usize], bitstring: usize) -> Vec<Gate> {
         .collect()
 }
 
+/// Relative phase of the second bitstring in a forging interference state
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForgingPhase {
+    /// (|x⟩ + |y⟩)/√2
+    Plus,
+    /// (|x⟩ − |y⟩)/√2
+    Minus,
+    /// (|x⟩ + i|y⟩)/√2
+    PlusI,
+    /// (|x⟩ − i|y⟩)/√2
+    MinusI,
+}
+
+impl ForgingPhase {
+    /// φ in (|x⟩ + e^{iφ}|y⟩)/√2
+    pub fn angle(&self) -> f64 {
+        match self {
+            ForgingPhase::Plus => 0.0,
+            ForgingPhase::Minus => PI,
+            ForgingPhase::PlusI => PI / 2.0,
+            ForgingPhase::MinusI => -PI / 2.0,
+        }
+    }
+}
+
+/// Prepare (|x⟩ + e^{iφ}|y⟩)/√2 from |0…0⟩, with bit i on `qubits[i]`
+///
+/// The off-diagonal ⟨x|H|y⟩ terms of a forged Hamiltonian are read from the
+/// real (`Plus`/`Minus`) and imaginary (`PlusI`/`MinusI`) interference of
+/// two Schmidt bitstrings. One H on the lowest differing bit, a phase and a
+/// CX fan-out to the other differing bits; exact including global phase.
+pub fn superposition_bitstrings(qubits: &[usize], x: usize, y: usize, phase: ForgingPhase) -> Vec<Gate> {
+    assert_ne!(x, y, "Bitstrings must differ to be superposed");
+    assert!(
+        qubits.len() >= usize::BITS as usize || (x | y) >> qubits.len() == 0,
+        "Bitstrings {:#b} and {:#b} do not fit on {} qubits", x, y, qubits.len()
+    );
+    let diff = x ^ y;
+    let pivot = diff.trailing_zeros() as usize;
+
+    // Branch pivot = 0 holds `low`, branch pivot = 1 holds `low ⊕ diff`
+    let (low, flip) = if (x >> pivot) & 1 == 0 { (x, 1.0) } else { (y, -1.0) };
+    let mut gates = prepare_bitstring(qubits, low);
+    gates.push(Gate::H(qubits[pivot]));
+    let phi = phase.angle();
+    if phi != 0.0 {
+        gates.push(Gate::P(qubits[pivot], flip * phi));
+        if flip < 0.0 {
+            // |y⟩ + e^{−iφ}|x⟩ = e^{−iφ}(|x⟩ + e^{iφ}|y⟩)
+            gates.push(Gate::GlobalPhase(phi));
+        }
+    }
+    gates.extend(
+        (pivot + 1..qubits.len())
+            .filter(|&i| (diff >> i) & 1 == 1)
+            .map(|i| Gate::CX(qubits[pivot], qubits[i])),
+    );
+    gates
+}
+
 // ============================================================================
 // CIRCUIT KNITTING
 // ============================================================================
@@ -313,17 +462,32 @@ fn prepare_bitstring(qubits: &[usize], bitstring: usize) -> Vec<Gate> {
 /// Knit subcircuit results together
 /// 
 /// Combines measurement results from subcircuits according to 
-/// the quasi-probability distribution.
+/// the quasi-probability distribution. Fails with `DimensionMismatch`
+/// unless there is one coefficient per expectation value.
 pub fn knit_results(
     subcircuit_expectations: &[f64],
     coefficients: &[f64],
-) -> f64 {
-    assert_eq!(subcircuit_expectations.len(), coefficients.len());
+) -> Result<f64, RustiqError> {
+    check_dimension(subcircuit_expectations.len(), coefficients.len())?;
     
-    subcircuit_expectations.iter()
+    Ok(subcircuit_expectations.iter()
         .zip(coefficients.iter())
         .map(|(e, c)| e * c)
-        .sum()
+        .sum())
+}
+
+/// Σᵢ cᵢ · `evaluate`(fragment i): the uncut expectation value
+///
+/// `evaluate` must return ⟨Π Z_q⟩ over `cut.measured_qubits` for the
+/// fragment it is given (for [`insert_wire_cut_qpd`], a full-width QPD term
+/// on `cut.fragment_qubits` wires, not a smaller piece), e.g. from the statevector, or from shots with
+/// the fragment's own basis rotations already in place. A `cut` without
+/// one coefficient per fragment is a `DimensionMismatch`, checked before
+/// any fragment is evaluated.
+pub fn reconstruct_expectation(cut: &CutCircuit, evaluate: impl Fn(&[Gate]) -> f64) -> Result<f64, RustiqError> {
+    check_dimension(cut.subcircuits.len(), cut.coefficients.len())?;
+    let expectations: Vec<f64> = cut.subcircuits.iter().map(|fragment| evaluate(fragment)).collect();
+    knit_results(&expectations, &cut.coefficients)
 }
 
 /// Estimate sampling overhead for circuit cutting
@@ -347,6 +511,92 @@ pub fn estimate_shots(
     ((overhead / target_precision).powi(2)) as usize
 }
 
+// ============================================================================
+// SHOT BUDGETING
+// ============================================================================
+
+/// |Coefficient| of each term in the γ = 4 wire-cut QPD (8 terms of ±1/2)
+const WIRE_CUT_TERMS: [f64; 8] = [0.5; 8];
+/// |Coefficient| of each term in the γ = 3 two-qubit gate-cut QPD (6 terms of ±1/2)
+const GATE_CUT_TERMS: [f64; 6] = [0.5; 6];
+
+/// Shot budget for a product of cut decompositions
+///
+/// A fragment is one joint choice of QPD term per cut; fragment index `f`
+/// picks term `f mod m₀` of cut 0, then `(f / m₀) mod m₁` of cut 1, and so on.
+#[derive(Clone, Debug)]
+pub struct CuttingPlan {
+    /// Total sampling overhead γ = Π_cuts Σ|cᵢ|
+    pub overhead: f64,
+    /// Shots needed for the target precision, γ² / ε²
+    pub total_shots: usize,
+    /// |cᵢ| of every QPD term, one list per cut
+    pub cut_terms: Vec<Vec<f64>>,
+}
+
+impl CuttingPlan {
+    /// Plan for arbitrary per-cut decompositions, e.g. from `cut_two_qubit_gate`
+    pub fn from_terms(cut_terms: Vec<Vec<f64>>, target_precision: f64) -> Self {
+        assert!(cut_terms.iter().all(|terms| !terms.is_empty()), "Every cut needs at least one term");
+        let cut_terms: Vec<Vec<f64>> = cut_terms
+            .into_iter()
+            .map(|terms| terms.into_iter().map(f64::abs).collect())
+            .collect();
+        let overhead = cut_terms.iter().map(|terms| terms.iter().sum::<f64>()).product();
+        Self { overhead, total_shots: estimate_shots(target_precision, overhead), cut_terms }
+    }
+
+    /// Number of fragments, Π_cuts (terms per cut)
+    pub fn num_fragments(&self) -> usize {
+        self.cut_terms.iter().map(Vec::len).product()
+    }
+
+    /// |c_f| / γ: the share of `total_shots` given to fragment `f`
+    pub fn fragment_weight(&self, mut fragment: usize) -> f64 {
+        assert!(fragment < self.num_fragments(), "Fragment {} out of range", fragment);
+        let mut weight = 1.0 / self.overhead;
+        for terms in &self.cut_terms {
+            weight *= terms[fragment % terms.len()];
+            fragment /= terms.len();
+        }
+        weight
+    }
+
+    /// Shots per fragment, proportional to |c_f| and summing to `total_shots`
+    ///
+    /// Estimating Σ c_f ⟨O⟩_f with n_f shots on fragment f has variance
+    /// Σ c_f² σ_f² / n_f. Minimizing it under Σ n_f = N gives n_f ∝ |c_f| σ_f;
+    /// with the worst-case σ_f = 1 of a ±1 observable this is n_f = N |c_f| / γ,
+    /// for a total variance of γ² / N. Rounding uses largest remainders.
+    pub fn allocation(&self) -> Vec<usize> {
+        let exact: Vec<f64> = (0..self.num_fragments())
+            .map(|f| self.total_shots as f64 * self.fragment_weight(f))
+            .collect();
+        let mut shots: Vec<usize> = exact.iter().map(|x| x.floor() as usize).collect();
+
+        let assigned: usize = shots.iter().sum();
+        let mut order: Vec<usize> = (0..shots.len()).collect();
+        order.sort_by(|&a, &b| (exact[b] - exact[b].floor()).total_cmp(&(exact[a] - exact[a].floor())));
+        for &f in order.iter().take(self.total_shots.saturating_sub(assigned)) {
+            shots[f] += 1;
+        }
+        shots
+    }
+}
+
+/// Shot plan for `num_wire_cuts` wire cuts and `num_gate_cuts` gate cuts
+///
+/// Wire cuts use the 8-term, γ = 4 decomposition and gate cuts the 6-term,
+/// γ = 3 decomposition, so `overhead` equals `estimate_cutting_overhead` and
+/// `total_shots` equals `estimate_shots`. Wire cuts come first in `cut_terms`.
+pub fn cutting_plan(num_wire_cuts: usize, num_gate_cuts: usize, target_precision: f64) -> CuttingPlan {
+    let overhead = estimate_cutting_overhead(num_wire_cuts, num_gate_cuts);
+    let cut_terms = std::iter::repeat_n(WIRE_CUT_TERMS.to_vec(), num_wire_cuts)
+        .chain(std::iter::repeat_n(GATE_CUT_TERMS.to_vec(), num_gate_cuts))
+        .collect();
+    CuttingPlan { overhead, total_shots: estimate_shots(target_precision, overhead), cut_terms }
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -365,6 +615,16 @@ mod tests {
         assert!((sum - 2.0).abs() < 1e-10); // 4 × 0.5
     }
 
+    #[test]
+    fn test_separable_circuit_needs_no_cuts() {
+        let pairs: Vec<Gate> = (0..3).flat_map(|k| [Gate::H(2 * k), Gate::CX(2 * k, 2 * k + 1)]).collect();
+        assert!(find_optimal_cuts(&pairs, 2).is_empty());
+
+        let mut chained = pairs.clone();
+        chained.extend([Gate::CX(1, 2), Gate::CX(3, 4)]);
+        assert!(!find_optimal_cuts(&chained, 2).is_empty());
+    }
+
     #[test]
     fn test_cutting_overhead() {
         // 1 wire cut: overhead = 4
@@ -379,9 +639,54 @@ mod tests {
         let expectations = vec![0.5, -0.5, 0.3, -0.3];
         let coefficients = vec![0.5, 0.5, 0.5, -0.5];
         
-        let result = knit_results(&expectations, &coefficients);
+        let result = knit_results(&expectations, &coefficients).unwrap();
         // 0.5×0.5 + (-0.5)×0.5 + 0.3×0.5 + (-0.3)×(-0.5) = 0.25 - 0.25 + 0.15 + 0.15 = 0.3
         assert!((result - 0.3).abs() < 1e-10);
+
+        assert_eq!(
+            knit_results(&expectations, &coefficients[..3]),
+            Err(RustiqError::DimensionMismatch { expected: 4, found: 3 })
+        );
+    }
+
+    #[test]
+    fn test_reconstruct_expectation_matches_uncut() {
+        use crate::simulator::{probabilities, simulate};
+
+        let parity = |state: &[crate::gates::core::Complex], qubits: &[usize]| -> f64 {
+            probabilities(state).iter().enumerate()
+                .map(|(i, p)| if qubits.iter().filter(|&&q| i >> q & 1 == 1).count() % 2 == 0 { *p } else { -p })
+                .sum()
+        };
+        let circuit = vec![
+            Gate::H(0), Gate::CX(0, 1), Gate::RY(1, 0.7), Gate::RX(0, 0.4),
+            Gate::CX(1, 2), Gate::RZ(2, 0.3), Gate::H(1), Gate::CX(2, 0),
+        ];
+        let (width, cut) = (3, WireCut { position: 3, qubit: 1 });
+        for observable in [vec![0, 2], vec![1, 2], vec![0, 1, 2]] {
+            let uncut = parity(&simulate(&circuit, width).unwrap(), &observable);
+            let cut_circuit = insert_wire_cut_qpd(&circuit, &cut, &observable, width).unwrap();
+            assert_eq!(cut_circuit.subcircuits.len(), 8);
+            assert!((cut_circuit.reconstruction_overhead - 4.0).abs() < 1e-12);
+
+            let measured = cut_circuit.measured_qubits.clone();
+            let estimate = reconstruct_expectation(&cut_circuit, |fragment| {
+                parity(&simulate(fragment, cut_circuit.fragment_qubits).unwrap(), &measured)
+            })
+            .unwrap();
+            assert!((estimate - uncut).abs() < 1e-10, "{:?}: {} vs {}", observable, estimate, uncut);
+        }
+
+        // A coefficient short: rejected before any fragment runs
+        let mut broken = insert_wire_cut_qpd(&circuit, &cut, &[0, 2], width).unwrap();
+        broken.coefficients.pop();
+        let err = reconstruct_expectation(&broken, |_| panic!("Fragment evaluated")).unwrap_err();
+        assert_eq!(err, RustiqError::DimensionMismatch { expected: 8, found: 7 });
+        assert_eq!(
+            insert_wire_cut_qpd(&circuit, &WireCut { position: 9, qubit: 1 }, &[0], width).unwrap_err(),
+            RustiqError::PositionOutOfRange { position: 9, len: 8 }
+        );
+        assert!(insert_wire_cut_qpd(&circuit, &cut, &[3], width).is_err());
     }
 
     #[test]
@@ -390,4 +695,49 @@ mod tests {
         // Bits 1 and 3 are set
         assert_eq!(gates.len(), 2);
     }
+
+    #[test]
+    fn test_superposition_bitstrings() {
+        use crate::gates::core::Complex;
+        use crate::simulator::{simulate, states_close};
+        let r = std::f64::consts::FRAC_1_SQRT_2;
+
+        let bell = simulate(&superposition_bitstrings(&[0, 1], 0b00, 0b11, ForgingPhase::Plus), 2).unwrap();
+        let expected = [Complex::new(r, 0.0), Complex::ZERO, Complex::ZERO, Complex::new(r, 0.0)];
+        assert!(states_close(&bell, &expected, 1e-12));
+
+        // Both orders of the pivot bit, on a permuted register
+        let qubits = [2, 0, 3, 1];
+        for (x, y) in [(0b0110, 0b1011), (0b1011, 0b0110), (0b0001, 0b1000)] {
+            for phase in [ForgingPhase::Plus, ForgingPhase::Minus, ForgingPhase::PlusI, ForgingPhase::MinusI] {
+                let state = simulate(&superposition_bitstrings(&qubits, x, y, phase), 4).unwrap();
+                let index = |b: usize| (0..4).map(|i| ((b >> i) & 1) << qubits[i]).sum::<usize>();
+                let mut expected = vec![Complex::ZERO; 16];
+                expected[index(x)] = Complex::new(r, 0.0);
+                expected[index(y)] = Complex::from_polar(r, phase.angle());
+                assert!(states_close(&state, &expected, 1e-12), "{:b} {:b} {:?}", x, y, phase);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cutting_plan_allocation() {
+        let plan = cutting_plan(1, 1, 0.1);
+        assert!((plan.overhead - estimate_cutting_overhead(1, 1)).abs() < 1e-10);
+        assert_eq!(plan.total_shots, estimate_shots(0.1, 12.0));
+        assert_eq!(plan.num_fragments(), 48);
+
+        // Equal-magnitude terms share the budget evenly
+        let shots = plan.allocation();
+        assert_eq!(shots.iter().sum::<usize>(), plan.total_shots);
+        assert!(shots.iter().all(|&n| n.abs_diff(plan.total_shots / 48) <= 1));
+
+        // Unequal terms: shots follow |c|, and signs are ignored
+        let custom = CuttingPlan::from_terms(vec![vec![0.75, -0.25], vec![0.5, 0.5]], 0.05);
+        assert!((custom.overhead - 1.0).abs() < 1e-12);
+        let shots = custom.allocation();
+        assert_eq!(shots.iter().sum::<usize>(), custom.total_shots);
+        assert_eq!(shots[0], 3 * shots[1]);
+        assert_eq!(shots[0], shots[2]);
+    }
 }
@@ -16,6 +16,7 @@
 //! ## 📊 Code Behavior
 //! - Structural wrapper with zero overhead.
 
+#[allow(clippy::module_inception)]
 pub mod cutting;
 
 pub use cutting::*;
@@ -0,0 +1,107 @@
+//! Crate-Wide Errors
+//!
+//! This module provides the error type shared by fallible public functions:
+//! - `RustiqError`: Mismatched dimensions, out-of-range qubits and gate
+//!   positions, gates a routine cannot handle, non-stochastic matrices and
+//!   malformed circuits
+//!
+//! ## 🎯 Why is this used?
+//! A library embedded in a long-running service cannot let a bad request
+//! abort the process. Functions whose inputs come from the caller (states,
+//! matrices, coefficient lists, qubit indices) report those mistakes as
+//! values, so the caller can reject one request and keep serving the rest.
+//!
+//! ## ⚙️ How it works?
+//! - **One Enum**: Every variant carries the numbers needed to explain the
+//!   failure, and `Display` renders them as a sentence.
+//! - **Conversions**: `ValidationError` from `validate_width` converts with
+//!   `?`; an out-of-range qubit keeps its own variant.
+//! - **Scope**: Module-specific errors (`QasmError`, `BudgetExceeded`,
+//!   `SynthesisError`, …) stay where they are; this type covers input
+//!   checks that many modules share.
+//!
+//! ## 📍 Where to apply this?
+//! - **Services**: Match on the variant to turn it into a client error.
+//! - **Pipelines**: Propagate with `?` through `Result<_, RustiqError>`.
+//!
+//! ## 📊 Code Behavior
+//! - **Cost**: Checks run once per call, before any work is done.
+
+use crate::analysis::circuit_analysis::ValidationError;
+use crate::gates::core::Complex;
+
+/// Why a crate function rejected its input
+#[derive(Clone, Debug, PartialEq)]
+pub enum RustiqError {
+    /// Two inputs that must agree in size do not, e.g. expectations and coefficients
+    DimensionMismatch { expected: usize, found: usize },
+    /// A qubit index at or beyond the register width
+    QubitOutOfRange { qubit: usize, num_qubits: usize },
+    /// The same qubit is listed twice where distinct qubits are needed
+    DuplicateQubit { qubit: usize },
+    /// A gate window `start..end` that is reversed or runs past `len` gates
+    WindowOutOfRange { start: usize, end: usize, len: usize },
+    /// A replacement gate touching a qubit its window does not
+    QubitOutsideWindow { qubit: usize },
+    /// A position between gates beyond the end of a `len`-gate circuit
+    PositionOutOfRange { position: usize, len: usize },
+    /// A row of a transition matrix that is not a probability distribution
+    NotStochastic { row: usize },
+    /// A gate the routine has no rule for, e.g. `Measure` in a statevector
+    UnsupportedGate { gate: String },
+    /// A structural defect other than an out-of-range qubit
+    InvalidCircuit(ValidationError),
+}
+
+impl std::fmt::Display for RustiqError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RustiqError::DimensionMismatch { expected, found } => {
+                write!(f, "Expected dimension {} but found {}", expected, found)
+            }
+            RustiqError::QubitOutOfRange { qubit, num_qubits } => {
+                write!(f, "Qubit {} out of range for {} qubits", qubit, num_qubits)
+            }
+            RustiqError::DuplicateQubit { qubit } => write!(f, "Qubit {} listed twice", qubit),
+            RustiqError::WindowOutOfRange { start, end, len } => {
+                write!(f, "Window {}..{} is out of range for {} gates", start, end, len)
+            }
+            RustiqError::QubitOutsideWindow { qubit } => write!(f, "Qubit {} is not touched by the window", qubit),
+            RustiqError::PositionOutOfRange { position, len } => {
+                write!(f, "Position {} is beyond {} gates", position, len)
+            }
+            RustiqError::NotStochastic { row } => write!(f, "Row {} is not a probability distribution", row),
+            RustiqError::UnsupportedGate { gate } => write!(f, "Gate {} is not supported here", gate),
+            RustiqError::InvalidCircuit(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for RustiqError {}
+
+impl From<ValidationError> for RustiqError {
+    fn from(err: ValidationError) -> Self {
+        match err {
+            ValidationError::QubitOutOfRange { qubit, num_qubits, .. } => RustiqError::QubitOutOfRange { qubit, num_qubits },
+            other => RustiqError::InvalidCircuit(other),
+        }
+    }
+}
+
+/// `Err(DimensionMismatch)` unless `found == expected`
+pub(crate) fn check_dimension(expected: usize, found: usize) -> Result<(), RustiqError> {
+    if expected == found {
+        Ok(())
+    } else {
+        Err(RustiqError::DimensionMismatch { expected, found })
+    }
+}
+
+/// Number of qubits of a statevector, or `DimensionMismatch` if its length is not 2ⁿ
+pub(crate) fn state_qubits(state: &[Complex]) -> Result<usize, RustiqError> {
+    if state.len().is_power_of_two() {
+        Ok(state.len().trailing_zeros() as usize)
+    } else {
+        Err(RustiqError::DimensionMismatch { expected: state.len().next_power_of_two(), found: state.len() })
+    }
+}
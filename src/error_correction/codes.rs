@@ -5,8 +5,9 @@
 //! - Phase-flip code (3-qubit)  
 //! - Shor code (9-qubit)
 //! - Steane code (7-qubit)
-//! - Surface code basics
+//! - Surface code basics and the rotated surface code layout
 //! - Syndrome extraction
+//! - 15-to-1 magic state distillation with analytic acceptance statistics
 //!
 //! ## 🎯 Why is this used?
 //! Physical qubits are inherently noisy. Error correction is the only path to 
@@ -20,6 +21,9 @@
 //! - **Stabilizer Formalism**: Uses parity check measurements (syndromes) to 
 //!   leak information about the errors (Pauli X or Z) without revealing the 
 //!   encoded data.
+//! - **Correction**: For the repetition codes, `*_correct` maps a measured
+//!   syndrome to the single Pauli to apply; `*_correct_coherent` applies it with
+//!   Toffolis controlled on the unmeasured syndrome qubits.
 //! - **Fault-Tolerant Gadgets**: Implements logical gates and state distillation 
 //!   (like Magic State Distillation) to maintain the logical state's purity.
 //!
@@ -49,13 +53,14 @@ use crate::gates::core::Gate;
 /// |ψ⟩ = α|0⟩ + β|1⟩ → α|000⟩ + β|111⟩
 /// 
 /// # Arguments
-/// * `data` - Data qubit to encode
-/// * `ancilla1`, `ancilla2` - Ancilla qubits (must be |0⟩)
-pub fn bit_flip_encode(data: usize, ancilla1: usize, ancilla2: usize) -> Vec<Gate> {
-    vec![
-        Gate::CX(data, ancilla1),
-        Gate::CX(data, ancilla2),
-    ]
+/// * `logical` - Qubit holding |ψ⟩, one of `data`
+/// * `data` - The three code qubits; the other two must be |0⟩
+pub fn bit_flip_encode(logical: usize, data: &[usize; 3]) -> Vec<Gate> {
+    assert!(data.contains(&logical), "Logical qubit {} is not a code qubit", logical);
+    data.iter()
+        .filter(|&&q| q != logical)
+        .map(|&q| Gate::CX(logical, q))
+        .collect()
 }
 
 /// Bit-flip code syndrome measurement
@@ -80,14 +85,34 @@ pub fn bit_flip_syndrome(code_qubits: &[usize], syndrome_qubits: &[usize]) -> Ve
     ]
 }
 
-/// Bit-flip code correction based on syndrome
-/// 
-/// Apply corrections based on measured syndrome values:
+/// Code qubit flagged by a measured syndrome (s0, s1) of the 3-qubit code
+///
 /// - s0=0, s1=0: No error
 /// - s0=1, s1=0: Error on qubit 0
 /// - s0=1, s1=1: Error on qubit 1
 /// - s0=0, s1=1: Error on qubit 2
-pub fn bit_flip_correct(code_qubits: &[usize], syndrome_qubits: &[usize]) -> Vec<Gate> {
+fn repetition_error_position(syndrome: (bool, bool)) -> Option<usize> {
+    match syndrome {
+        (false, false) => None,
+        (true, false) => Some(0),
+        (true, true) => Some(1),
+        (false, true) => Some(2),
+    }
+}
+
+/// Bit-flip correction for a measured syndrome (s0, s1)
+///
+/// Returns the X to apply to the flagged code qubit, or `None` when the
+/// syndrome is trivial.
+pub fn bit_flip_correct(data: &[usize; 3], syndrome: (bool, bool)) -> Option<Gate> {
+    repetition_error_position(syndrome).map(|i| Gate::X(data[i]))
+}
+
+/// Coherent bit-flip correction controlled on the syndrome qubits
+/// 
+/// Apply corrections based on the syndrome held in `syndrome_qubits`,
+/// without measuring it (see `bit_flip_correct` for the mapping).
+pub fn bit_flip_correct_coherent(code_qubits: &[usize], syndrome_qubits: &[usize]) -> Vec<Gate> {
     assert_eq!(code_qubits.len(), 3);
     assert_eq!(syndrome_qubits.len(), 2);
     
@@ -109,12 +134,12 @@ pub fn bit_flip_correct(code_qubits: &[usize], syndrome_qubits: &[usize]) -> Vec
 
 /// Bit-flip code decoder
 /// 
-/// Decodes the logical qubit back to a single physical qubit.
-pub fn bit_flip_decode(data: usize, ancilla1: usize, ancilla2: usize) -> Vec<Gate> {
-    vec![
-        Gate::CX(data, ancilla2),
-        Gate::CX(data, ancilla1),
-    ]
+/// Decodes the logical qubit back onto `logical`, returning the other two
+/// code qubits to |0⟩.
+pub fn bit_flip_decode(logical: usize, data: &[usize; 3]) -> Vec<Gate> {
+    let mut gates = bit_flip_encode(logical, data);
+    gates.reverse();
+    gates
 }
 
 // ============================================================================
@@ -125,16 +150,12 @@ pub fn bit_flip_decode(data: usize, ancilla1: usize, ancilla2: usize) -> Vec<Gat
 /// 
 /// Encodes: |ψ⟩ = α|0⟩ + β|1⟩ → α|+++⟩ + β|---⟩
 /// where |+⟩ = (|0⟩+|1⟩)/√2, |-⟩ = (|0⟩-|1⟩)/√2
-pub fn phase_flip_encode(data: usize, ancilla1: usize, ancilla2: usize) -> Vec<Gate> {
-    vec![
-        // First, encode bit-flip style
-        Gate::CX(data, ancilla1),
-        Gate::CX(data, ancilla2),
-        // Then transform to X basis
-        Gate::H(data),
-        Gate::H(ancilla1),
-        Gate::H(ancilla2),
-    ]
+pub fn phase_flip_encode(logical: usize, data: &[usize; 3]) -> Vec<Gate> {
+    // First, encode bit-flip style
+    let mut gates = bit_flip_encode(logical, data);
+    // Then transform to X basis
+    gates.extend(data.iter().map(|&q| Gate::H(q)));
+    gates
 }
 
 /// Phase-flip code syndrome measurement
@@ -165,8 +186,16 @@ pub fn phase_flip_syndrome(code_qubits: &[usize], syndrome_qubits: &[usize]) ->
     gates
 }
 
-/// Phase-flip code correction
-pub fn phase_flip_correct(code_qubits: &[usize], syndrome_qubits: &[usize]) -> Vec<Gate> {
+/// Phase-flip correction for a measured syndrome (s0, s1)
+///
+/// Same syndrome table as `bit_flip_correct`, read in the Hadamard-conjugated
+/// basis, so the correction is a Z on the flagged code qubit.
+pub fn phase_flip_correct(data: &[usize; 3], syndrome: (bool, bool)) -> Option<Gate> {
+    repetition_error_position(syndrome).map(|i| Gate::Z(data[i]))
+}
+
+/// Coherent phase-flip correction controlled on the syndrome qubits
+pub fn phase_flip_correct_coherent(code_qubits: &[usize], syndrome_qubits: &[usize]) -> Vec<Gate> {
     assert_eq!(code_qubits.len(), 3);
     assert_eq!(syndrome_qubits.len(), 2);
     
@@ -193,16 +222,12 @@ pub fn phase_flip_correct(code_qubits: &[usize], syndrome_qubits: &[usize]) -> V
 }
 
 /// Phase-flip code decoder
-pub fn phase_flip_decode(data: usize, ancilla1: usize, ancilla2: usize) -> Vec<Gate> {
-    vec![
-        // Transform from X basis
-        Gate::H(data),
-        Gate::H(ancilla1),
-        Gate::H(ancilla2),
-        // Decode bit-flip style
-        Gate::CX(data, ancilla2),
-        Gate::CX(data, ancilla1),
-    ]
+pub fn phase_flip_decode(logical: usize, data: &[usize; 3]) -> Vec<Gate> {
+    // Transform from X basis
+    let mut gates: Vec<Gate> = data.iter().map(|&q| Gate::H(q)).collect();
+    // Decode bit-flip style
+    gates.extend(bit_flip_decode(logical, data));
+    gates
 }
 
 // ============================================================================
@@ -220,6 +245,7 @@ pub fn phase_flip_decode(data: usize, ancilla1: usize, ancilla2: usize) -> Vec<G
 /// # Arguments
 /// * `logical` - Logical qubit to encode
 /// * `qubits` - 9 physical qubits [0..8]
+#[allow(clippy::vec_init_then_push)]
 pub fn shor_encode(logical: usize, qubits: &[usize]) -> Vec<Gate> {
     assert!(qubits.len() >= 9);
     
@@ -250,6 +276,7 @@ pub fn shor_encode(logical: usize, qubits: &[usize]) -> Vec<Gate> {
 }
 
 /// Shor code X-error syndrome (bit-flip within blocks)
+#[allow(clippy::vec_init_then_push)]
 pub fn shor_x_syndrome(qubits: &[usize], syndrome: &[usize]) -> Vec<Gate> {
     assert!(qubits.len() >= 9);
     assert!(syndrome.len() >= 6);
@@ -317,6 +344,7 @@ pub fn shor_z_syndrome(qubits: &[usize], syndrome: &[usize]) -> Vec<Gate> {
 /// |1_L⟩ = X_L |0_L⟩
 /// 
 /// where C is the [7,4,3] Hamming code.
+#[allow(clippy::vec_init_then_push)]
 pub fn steane_encode(logical: usize, qubits: &[usize]) -> Vec<Gate> {
     assert!(qubits.len() >= 7);
     
@@ -481,9 +509,8 @@ pub fn surface_code_syndrome_round(
     // careful scheduling to minimize circuit depth
     
     // X-type stabilizers (vertex)
-    for i in 0..x_syndrome.len() {
-        for j in 0..x_syndrome[i].len() {
-            let s = x_syndrome[i][j];
+    for (i, row) in x_syndrome.iter().enumerate() {
+        for (j, &s) in row.iter().enumerate() {
             // Get neighboring data qubits
             let neighbors = get_vertex_neighbors(i, j, data_qubits);
             for d in neighbors {
@@ -499,9 +526,8 @@ pub fn surface_code_syndrome_round(
         }
     }
     
-    for i in 0..z_syndrome.len() {
-        for j in 0..z_syndrome[i].len() {
-            let s = z_syndrome[i][j];
+    for (i, row) in z_syndrome.iter().enumerate() {
+        for (j, &s) in row.iter().enumerate() {
             let neighbors = get_plaquette_neighbors(i, j, data_qubits);
             for d in neighbors {
                 gates.push(Gate::CZ(s, d));
@@ -536,6 +562,110 @@ fn get_plaquette_neighbors(i: usize, j: usize, data_qubits: &[Vec<usize>]) -> Ve
     get_vertex_neighbors(i, j, data_qubits)
 }
 
+// ============================================================================
+// ROTATED SURFACE CODE
+// ============================================================================
+
+/// Distance-d rotated surface code
+///
+/// Data qubit (r, c) of the d×d grid is `r·d + c`. Stabilizers sit on the
+/// corners between data qubits: (d−1)² weight-4 checks in the bulk and
+/// 2(d−1) weight-2 checks on the boundary, d² − 1 in total, each with its own
+/// ancilla numbered from d² upward in `x_ancillas` then `z_ancillas` order.
+#[derive(Debug, Clone)]
+pub struct SurfaceCode {
+    /// Code distance (odd)
+    pub distance: usize,
+    /// Data qubits of each X stabilizer
+    pub x_stabilizers: Vec<Vec<usize>>,
+    /// Data qubits of each Z stabilizer
+    pub z_stabilizers: Vec<Vec<usize>>,
+    /// Ancilla measuring each X stabilizer
+    pub x_ancillas: Vec<usize>,
+    /// Ancilla measuring each Z stabilizer
+    pub z_ancillas: Vec<usize>,
+}
+
+/// Corner (r, c) of a rotated surface code: its data neighbours in
+/// NW, NE, SW, SE order, and whether it carries an X check (r + c even)
+fn surface_code_corner(distance: usize, r: usize, c: usize) -> (bool, [Option<usize>; 4]) {
+    let cell = |dr: usize, dc: usize| {
+        let (row, col) = ((r + dr).checked_sub(1)?, (c + dc).checked_sub(1)?);
+        (row < distance && col < distance).then_some(row * distance + col)
+    };
+    ((r + c).is_multiple_of(2), [cell(0, 0), cell(0, 1), cell(1, 0), cell(1, 1)])
+}
+
+/// Stabilizer corners: bulk, plus X checks on the top/bottom boundary and Z
+/// checks on the left/right boundary
+fn surface_code_corners(distance: usize) -> Vec<(bool, [Option<usize>; 4])> {
+    let d = distance;
+    let mut corners = Vec::new();
+    for r in 0..=d {
+        for c in 0..=d {
+            let (is_x, cells) = surface_code_corner(d, r, c);
+            let bulk = (1..d).contains(&r) && (1..d).contains(&c);
+            let horizontal = (r == 0 || r == d) && (1..d).contains(&c);
+            let vertical = (c == 0 || c == d) && (1..d).contains(&r);
+            if bulk || (horizontal && is_x) || (vertical && !is_x) {
+                corners.push((is_x, cells));
+            }
+        }
+    }
+    corners
+}
+
+impl SurfaceCode {
+    /// Rotated surface code of odd `distance` ≥ 3
+    pub fn rotated(distance: usize) -> Self {
+        assert!(distance >= 3 && !distance.is_multiple_of(2), "Rotated surface code needs an odd distance ≥ 3");
+        let corners = surface_code_corners(distance);
+        let support = |cells: &[Option<usize>; 4]| cells.iter().flatten().copied().collect::<Vec<_>>();
+        let x_stabilizers: Vec<Vec<usize>> = corners.iter().filter(|(x, _)| *x).map(|(_, c)| support(c)).collect();
+        let z_stabilizers: Vec<Vec<usize>> = corners.iter().filter(|(x, _)| !*x).map(|(_, c)| support(c)).collect();
+
+        let first = distance * distance;
+        let x_ancillas = (first..first + x_stabilizers.len()).collect();
+        let z_ancillas = (first + x_stabilizers.len()..first + corners.len()).collect();
+        SurfaceCode { distance, x_stabilizers, z_stabilizers, x_ancillas, z_ancillas }
+    }
+
+    /// Number of data plus ancilla qubits, 2d² − 1
+    pub fn num_qubits(&self) -> usize {
+        self.distance * self.distance + self.x_ancillas.len() + self.z_ancillas.len()
+    }
+}
+
+/// Syndrome-extraction CX gates in parallel time steps
+///
+/// The ancilla–data coupling graph is bipartite with maximum degree 4, so its
+/// edges 4-colour (König) and no schedule is shorter. The colouring used is
+/// the hook-safe one: X ancillas visit their data qubits in Z order
+/// (NW, NE, SW, SE) as CX(ancilla, data) and Z ancillas in N order
+/// (NW, SW, NE, SE) as CX(data, ancilla), so X and Z checks measured in the
+/// same round do not disturb each other. No qubit appears twice in a step.
+/// Ancilla preparation and measurement are left to the caller.
+pub fn schedule_syndrome_extraction(code: &SurfaceCode) -> Vec<Vec<Gate>> {
+    const Z_ORDER: [usize; 4] = [0, 1, 2, 3];
+    const N_ORDER: [usize; 4] = [0, 2, 1, 3];
+
+    let mut layers = vec![Vec::new(); 4];
+    let (mut next_x, mut next_z) = (code.x_ancillas.iter(), code.z_ancillas.iter());
+    for (is_x, cells) in surface_code_corners(code.distance) {
+        let (ancilla, order) = if is_x {
+            (*next_x.next().expect("Code has fewer X ancillas than checks"), Z_ORDER)
+        } else {
+            (*next_z.next().expect("Code has fewer Z ancillas than checks"), N_ORDER)
+        };
+        for (step, &slot) in order.iter().enumerate() {
+            if let Some(data) = cells[slot] {
+                layers[step].push(if is_x { Gate::CX(ancilla, data) } else { Gate::CX(data, ancilla) });
+            }
+        }
+    }
+    layers
+}
+
 // ============================================================================
 // MAGIC STATE DISTILLATION
 // ============================================================================
@@ -566,8 +696,6 @@ pub fn t_state_distillation(
 ) -> Vec<Gate> {
     assert_eq!(input_qubits.len(), 15);
     
-    let mut gates = Vec::new();
-    
     // Encode into [[15,1,3]] code
     // This is a simplified version - full implementation needs
     // the complete Reed-Muller encoding
@@ -579,9 +707,63 @@ pub fn t_state_distillation(
     // would involve specific CNOT patterns
     
     // Extract output from first logical qubit position
-    gates.push(Gate::CX(input_qubits[0], output_qubit));
-    
-    gates
+    vec![Gate::CX(input_qubits[0], output_qubit)]
+}
+
+/// Accept/reject statistics of one 15-to-1 distillation round
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DistillationStats {
+    /// Probability that every syndrome is trivial and the output is kept
+    pub acceptance: f64,
+    /// Error rate of the output T state, given acceptance
+    pub output_error: f64,
+}
+
+/// Analytic 15-to-1 statistics for independent Z errors of rate `p` per input
+///
+/// A Z-error pattern passes the four X checks iff it lies in the [15,11]
+/// Hamming code, and it flips the output iff it also has odd weight. By
+/// MacWilliams, with y = 1 − 2p (Bravyi and Kitaev):
+/// - acceptance = (1 + 15y⁸)/16
+/// - output error = (1 + 15y⁸ − 15y⁷ − y¹⁵) / (2(1 + 15y⁸)) ≈ 35p³
+pub fn fifteen_to_one_stats(p: f64) -> DistillationStats {
+    assert!((0.0..=1.0).contains(&p), "Error rate {} is not a probability", p);
+    let y = 1.0 - 2.0 * p;
+    let passed = 1.0 + 15.0 * y.powi(8);
+    DistillationStats {
+        acceptance: passed / 16.0,
+        output_error: (passed - 15.0 * y.powi(7) - y.powi(15)) / (2.0 * passed),
+    }
+}
+
+/// [`t_state_distillation`] together with its statistics at input error `input_error`
+///
+/// Use `1 / acceptance` as the expected number of rounds per kept T state
+/// when budgeting shots.
+pub fn fifteen_to_one(
+    input_qubits: &[usize],
+    output_qubit: usize,
+    ancilla: &[usize],
+    input_error: f64,
+) -> (Vec<Gate>, DistillationStats) {
+    (t_state_distillation(input_qubits, output_qubit, ancilla), fifteen_to_one_stats(input_error))
+}
+
+/// Teleport a T gate from the magic state on `magic_ancilla` into `logical`
+///
+/// With the ancilla in |T⟩ = (|0⟩ + e^(iπ/4)|1⟩)/√2, CX(logical → ancilla) and
+/// a Z-basis measurement of the ancilla leave the data in T|ψ⟩ on outcome 0
+/// and in T†|ψ⟩ on outcome 1. The correction S·T† = T (up to the global phase
+/// e^(iπ/4)) is applied as `ClassicalControl` on classical bit `magic_ancilla`,
+/// where the returned measurement qubit's outcome is recorded.
+pub fn inject_t_state(logical: usize, magic_ancilla: usize) -> (Vec<Gate>, usize) {
+    assert_ne!(logical, magic_ancilla, "Magic state must live on a separate qubit");
+    let circuit = vec![
+        Gate::CX(logical, magic_ancilla),
+        Gate::Measure(magic_ancilla, magic_ancilla),
+        Gate::ClassicalControl(magic_ancilla, Box::new(Gate::S(logical))),
+    ];
+    (circuit, magic_ancilla)
 }
 
 // ============================================================================
@@ -633,7 +815,7 @@ mod tests {
 
     #[test]
     fn test_bit_flip_encode() {
-        let circuit = bit_flip_encode(0, 1, 2);
+        let circuit = bit_flip_encode(0, &[0, 1, 2]);
         assert_eq!(circuit.len(), 2);
         assert!(matches!(circuit[0], Gate::CX(0, 1)));
         assert!(matches!(circuit[1], Gate::CX(0, 2)));
@@ -645,6 +827,102 @@ mod tests {
         assert_eq!(circuit.len(), 4);
     }
 
+    /// Encode |ψ⟩, inject `error`, extract and read the syndrome, correct, decode
+    fn repetition_round(
+        encode: fn(usize, &[usize; 3]) -> Vec<Gate>,
+        syndrome: fn(&[usize], &[usize]) -> Vec<Gate>,
+        correct: fn(&[usize; 3], (bool, bool)) -> Option<Gate>,
+        decode: fn(usize, &[usize; 3]) -> Vec<Gate>,
+        error: Option<Gate>,
+    ) {
+        use crate::simulator::{probabilities, simulate, states_close};
+
+        let data = [0, 1, 2];
+        let ancilla = [3, 4];
+        let prep = vec![Gate::RY(0, 0.7), Gate::RZ(0, 0.3)];
+
+        let mut circuit = prep.clone();
+        circuit.extend(encode(0, &data));
+        circuit.extend(error);
+        circuit.extend(syndrome(&data, &ancilla));
+
+        // A single Pauli error leaves the syndrome qubits in a basis state
+        let p = probabilities(&simulate(&circuit, 5).unwrap());
+        let index = (0..p.len()).find(|&i| p[i] > 1e-9).unwrap();
+        let s = (index >> ancilla[0] & 1 == 1, index >> ancilla[1] & 1 == 1);
+        assert!((0..p.len()).all(|i| p[i] < 1e-9 || (i >> 3) == (index >> 3)), "Syndrome not deterministic");
+
+        circuit.extend(correct(&data, s));
+        circuit.extend(decode(0, &data));
+
+        let mut expected = prep;
+        if s.0 { expected.push(Gate::X(ancilla[0])); }
+        if s.1 { expected.push(Gate::X(ancilla[1])); }
+        assert!(states_close(&simulate(&circuit, 5).unwrap(), &simulate(&expected, 5).unwrap(), 1e-10));
+    }
+
+    #[test]
+    fn test_bit_flip_round_corrects_single_flip() {
+        repetition_round(bit_flip_encode, bit_flip_syndrome, bit_flip_correct, bit_flip_decode, None);
+        for q in 0..3 {
+            repetition_round(bit_flip_encode, bit_flip_syndrome, bit_flip_correct, bit_flip_decode, Some(Gate::X(q)));
+        }
+        assert!(matches!(bit_flip_correct(&[5, 6, 7], (true, true)), Some(Gate::X(6))));
+    }
+
+    #[test]
+    fn test_phase_flip_round_corrects_single_flip() {
+        for q in 0..3 {
+            repetition_round(phase_flip_encode, phase_flip_syndrome, phase_flip_correct, phase_flip_decode, Some(Gate::Z(q)));
+        }
+        assert!(phase_flip_correct(&[5, 6, 7], (false, false)).is_none());
+    }
+
+    #[test]
+    fn test_fifteen_to_one_stats() {
+        let (circuit, ideal) = fifteen_to_one(&(0..15).collect::<Vec<_>>(), 15, &[], 0.0);
+        assert!(!circuit.is_empty());
+        assert_eq!(ideal, DistillationStats { acceptance: 1.0, output_error: 0.0 });
+
+        // Cubic suppression at small p, and the acceptance lost to detected errors
+        let p = 1e-3;
+        let stats = fifteen_to_one_stats(p);
+        assert!((stats.output_error / (35.0 * p * p * p) - 1.0).abs() < 0.05, "{:?}", stats);
+        assert!((1.0 - stats.acceptance - 15.0 * p).abs() < 1e-3 * 15.0 * p + 1e-4, "{:?}", stats);
+
+        // Fully random inputs: only the 1/16 of patterns in the code pass, half of them flipped
+        let stats = fifteen_to_one_stats(0.5);
+        assert!((stats.acceptance - 1.0 / 16.0).abs() < 1e-12);
+        assert!((stats.output_error - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_inject_t_state_applies_t_after_correction() {
+        use crate::simulator::{simulate, simulate_postselected, states_close};
+
+        let (data, ancilla) = (1, 0);
+        let prep = vec![Gate::RY(data, 1.1), Gate::RZ(data, -0.4)];
+        let (circuit, measured) = inject_t_state(data, ancilla);
+        assert_eq!(measured, ancilla);
+
+        let mut input = prep.clone();
+        input.extend(prepare_t_state(ancilla));
+        let before = simulate(&input, 2).unwrap();
+
+        for outcome in [0, 1] {
+            // Postselect the measurement on `outcome` and run the feed-forward branch
+            let (state, probability) = simulate_postselected(&circuit, &before, &[outcome == 1]).unwrap();
+            assert!((probability - 0.5).abs() < 1e-10, "Outcomes must be equally likely");
+
+            let mut expected = prep.clone();
+            expected.push(Gate::T(data));
+            if outcome == 1 {
+                expected.extend([Gate::X(ancilla), Gate::GlobalPhase(std::f64::consts::FRAC_PI_4)]);
+            }
+            assert!(states_close(&state, &simulate(&expected, 2).unwrap(), 1e-10), "Outcome {}", outcome);
+        }
+    }
+
     #[test]
     fn test_shor_encode() {
         let circuit = shor_encode(0, &[0, 1, 2, 3, 4, 5, 6, 7, 8]);
@@ -667,4 +945,60 @@ mod tests {
         // Should have H + 4 CZ + H
         assert_eq!(circuit.len(), 6);
     }
+
+    #[test]
+    fn test_rotated_surface_code_layout() {
+        for d in [3, 5, 7] {
+            let code = SurfaceCode::rotated(d);
+            assert_eq!(code.x_stabilizers.len() + code.z_stabilizers.len(), d * d - 1);
+            assert_eq!(code.num_qubits(), 2 * d * d - 1);
+
+            // X and Z checks overlap on an even number of data qubits
+            for x in &code.x_stabilizers {
+                for z in &code.z_stabilizers {
+                    assert_eq!(x.iter().filter(|q| z.contains(q)).count() % 2, 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_syndrome_schedule_layers_are_disjoint() {
+        for d in [3, 5] {
+            let code = SurfaceCode::rotated(d);
+            let layers = schedule_syndrome_extraction(&code);
+            assert_eq!(layers.len(), 4);
+            for layer in &layers {
+                let mut used = std::collections::HashSet::new();
+                for gate in layer {
+                    assert!(matches!(gate, Gate::CX(..)));
+                    for q in gate.qubits() {
+                        assert!(used.insert(q), "Qubit {} used twice in one step", q);
+                    }
+                }
+            }
+            let weights: usize = code.x_stabilizers.iter().chain(&code.z_stabilizers).map(Vec::len).sum();
+            assert_eq!(layers.iter().map(Vec::len).sum::<usize>(), weights);
+        }
+    }
+
+    #[test]
+    fn test_syndrome_schedule_detects_bit_flip() {
+        use crate::simulator::{probabilities, simulate};
+
+        let code = SurfaceCode::rotated(3);
+        let flipped = 4;
+        let mut circuit = vec![Gate::X(flipped)];
+        circuit.extend(code.x_ancillas.iter().map(|&a| Gate::H(a)));
+        circuit.extend(schedule_syndrome_extraction(&code).into_iter().flatten());
+        circuit.extend(code.x_ancillas.iter().map(|&a| Gate::H(a)));
+
+        // Z ancillas deterministically flag the checks touching the error
+        let probs = probabilities(&simulate(&circuit, code.num_qubits()).unwrap());
+        for (z, &ancilla) in code.z_stabilizers.iter().zip(&code.z_ancillas) {
+            let p_one: f64 = probs.iter().enumerate().filter(|(i, _)| i >> ancilla & 1 == 1).map(|(_, p)| p).sum();
+            let expected = if z.contains(&flipped) { 1.0 } else { 0.0 };
+            assert!((p_one - expected).abs() < 1e-9);
+        }
+    }
 }
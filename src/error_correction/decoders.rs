@@ -4,6 +4,7 @@
 //! - Minimum Weight Perfect Matching (MWPM) logic
 //! - Belief Propagation (BP) logic
 //! - Union-Find decoder basics
+//! - `DetectorErrorModel`: Weighted matching graph from per-error probabilities
 //!
 //! ## 🎯 Why is this used?
 //! Syndrome measurements only tell us that an error occurred; they don't explicitly 
@@ -17,6 +18,9 @@
 //!   minimizes the total path weight (most likely error).
 //! - **Belief Propagation (BP)**: Uses message passing on a Tanner graph to iteratively 
 //!   estimate the probability of errors on each qubit.
+//! - **Detector Error Model**: Each error mechanism flips one or two detectors
+//!   with probability p. It becomes an edge of weight ln((1−p)/p), to the
+//!   `BOUNDARY` node when only one detector flips, so likelier errors are cheaper.
 //! - **Union-Find**: A fast, near-linear time decoder that clusters syndrome events 
 //!   until they can be neutralized.
 //!
@@ -33,6 +37,44 @@
 //!   best for QLDPC codes.
 //! - **Latency**: Designed to be implementable in low-latency hardware (FPGAs/ASICs).
 
+use std::collections::BTreeMap;
+
+/// Virtual node that single-detector errors connect to in the matching graph
+pub const BOUNDARY: usize = usize::MAX;
+
+/// Independent error mechanisms, each flipping a set of detectors with a probability
+#[derive(Clone, Debug, Default)]
+pub struct DetectorErrorModel {
+    pub errors: Vec<(Vec<usize>, f64)>,
+}
+
+impl DetectorErrorModel {
+    /// Matching graph edges (u, v, −ln(p/(1−p))), u < v, sorted
+    ///
+    /// Errors with the same detectors are merged first: two independent
+    /// mechanisms flip them with p₁(1−p₂) + p₂(1−p₁). Panics on errors that
+    /// flip more than two detectors, which need decomposing first.
+    pub fn matching_graph(&self) -> Vec<(usize, usize, f64)> {
+        let mut edges: BTreeMap<(usize, usize), f64> = BTreeMap::new();
+        for (detectors, p) in &self.errors {
+            assert!(*p > 0.0 && *p < 1.0, "Error probability {} must lie in (0, 1)", p);
+            let key = match detectors[..] {
+                [d] => (d, BOUNDARY),
+                [a, b] => (a.min(b), a.max(b)),
+                _ => panic!("Error flips {} detectors; only graphlike errors can be matched", detectors.len()),
+            };
+            let q = edges.entry(key).or_insert(0.0);
+            *q = *q * (1.0 - p) + p * (1.0 - *q);
+        }
+        edges.into_iter().map(|((u, v), p)| (u, v, ((1.0 - p) / p).ln())).collect()
+    }
+
+    /// Decode a detector syndrome with `mwpm_decode` on this model's graph
+    pub fn decode(&self, syndrome: &[bool]) -> Vec<usize> {
+        mwpm_decode(syndrome, &self.matching_graph())
+    }
+}
+
 /// Minimum Weight Perfect Matching decoder (conceptual logic)
 pub fn mwpm_decode(
     syndrome_results: &[bool],
@@ -43,8 +85,8 @@ pub fn mwpm_decode(
     let mut correction_indices = Vec::new();
     
     // Simplification: if two syndromes are fired, suggest an error on path between them
-    for i in 0..syndrome_results.len() {
-        if syndrome_results[i] {
+    for (i, &fired) in syndrome_results.iter().enumerate() {
+        if fired {
             correction_indices.push(i);
         }
     }
@@ -68,3 +110,40 @@ pub fn belief_propagation_decode(
     
     probabilities
 }
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_graph_weights() {
+        let dem = DetectorErrorModel {
+            errors: vec![(vec![0, 1], 0.01), (vec![2, 1], 0.1), (vec![2], 0.001), (vec![1, 2], 0.1)],
+        };
+        let graph = dem.matching_graph();
+        assert_eq!(graph.len(), 3);
+
+        let (u, v, rare) = graph[0];
+        assert_eq!((u, v), (0, 1));
+        assert!((rare - (0.99f64 / 0.01).ln()).abs() < 1e-12);
+
+        // Two parallel mechanisms combine to 0.18, below either alone in weight
+        let (u, v, common) = graph[1];
+        assert_eq!((u, v), (1, 2));
+        assert!((common - (0.82f64 / 0.18).ln()).abs() < 1e-12);
+        assert!(common < rare);
+
+        assert_eq!((graph[2].0, graph[2].1), (2, BOUNDARY));
+        assert!(graph[2].2 > rare);
+    }
+
+    #[test]
+    #[should_panic(expected = "graphlike")]
+    fn test_hyperedge_is_rejected() {
+        DetectorErrorModel { errors: vec![(vec![0, 1, 2], 0.1)] }.matching_graph();
+    }
+}
@@ -4,6 +4,7 @@
 //! - Merge operations (MZZ, MXX)
 //! - Split operations
 //! - Multi-patch protocols
+//! - `logical_cnot_surgery`: Measurement-based CNOT with Pauli feed-forward
 //!
 //! ## 🎯 Why is this used?
 //! In a topological quantum computer (like the Surface Code), physical movement 
@@ -69,3 +70,82 @@ pub fn lattice_surgery_split_z(
     }
     gates
 }
+
+/// Joint Z⊗Z parity of `a` and `b` into classical bit `bit`, via `parity` in |0⟩
+fn measure_zz(a: usize, b: usize, parity: usize, bit: usize) -> Vec<Gate> {
+    vec![
+        Gate::CX(a, parity),
+        Gate::CX(b, parity),
+        Gate::Measure(parity, bit),
+        Gate::Reset(parity),
+    ]
+}
+
+/// Joint X⊗X parity: Z⊗Z in the Hadamard basis
+fn measure_xx(a: usize, b: usize, parity: usize, bit: usize) -> Vec<Gate> {
+    let mut gates = vec![Gate::H(a), Gate::H(b)];
+    gates.extend(measure_zz(a, b, parity, bit));
+    gates.extend([Gate::H(a), Gate::H(b)]);
+    gates
+}
+
+/// Logical CNOT by lattice surgery (measurement-based, Horsman et al.)
+///
+/// With the intermediate patch `ancilla` in |+⟩: merge-split Z_c Z_a into
+/// `bits[0]`, merge-split X_a X_t into `bits[1]`, then measure Z_a into
+/// `bits[2]`. The result is CNOT(control, target) up to the Pauli frame
+/// Z_c^{m₁} X_t^{m₀ ⊕ m₂}, which is applied by feed-forward. Each patch is a
+/// single qubit here; `parity` reads out the joint measurements and both it
+/// and `ancilla` end in |0⟩. The output matches CNOT up to global phase.
+pub fn logical_cnot_surgery(control: usize, target: usize, ancilla: usize, parity: usize, bits: [usize; 3]) -> Vec<Gate> {
+    let mut gates = vec![Gate::H(ancilla)];
+    gates.extend(measure_zz(control, ancilla, parity, bits[0]));
+    gates.extend(measure_xx(ancilla, target, parity, bits[1]));
+    gates.push(Gate::Measure(ancilla, bits[2]));
+    gates.push(Gate::Reset(ancilla));
+
+    gates.push(Gate::ClassicalControl(bits[1], Box::new(Gate::Z(control))));
+    gates.push(Gate::ClassicalControl(bits[0], Box::new(Gate::X(target))));
+    gates.push(Gate::ClassicalControl(bits[2], Box::new(Gate::X(target))));
+    gates
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::{run_shot, simulate, state_fidelity, NoiseModel};
+    use crate::util::Rng;
+
+    #[test]
+    fn test_logical_cnot_surgery_truth_table() {
+        let (c, t, a, p) = (0, 1, 2, 3);
+        let surgery = logical_cnot_surgery(c, t, a, p, [0, 1, 2]);
+
+        // Basis inputs, plus |+⟩|0⟩ and |−⟩|−⟩, whose phases the Z_c correction must fix
+        let inputs: Vec<Vec<Gate>> = vec![
+            vec![], vec![Gate::X(c)], vec![Gate::X(t)], vec![Gate::X(c), Gate::X(t)],
+            vec![Gate::H(c)], vec![Gate::X(c), Gate::H(c), Gate::X(t), Gate::H(t)],
+        ];
+        let mut outcomes = std::collections::HashSet::new();
+        for prep in inputs {
+            let mut ideal = prep.clone();
+            ideal.push(Gate::CX(c, t));
+            let expected = simulate(&ideal, 4).unwrap();
+
+            let mut circuit = prep;
+            circuit.extend(surgery.iter().cloned());
+            for seed in 0..64 {
+                let shot = run_shot(&circuit, 4, &NoiseModel::ideal(), &mut Rng::new(seed));
+                // Equal up to a global sign that depends on the outcomes
+                assert!((state_fidelity(&shot.state, &expected) - 1.0).abs() < 1e-9, "Outcomes {:?}", shot.bits);
+                outcomes.insert(shot.bits);
+            }
+        }
+        // Every measurement record occurs, so every correction branch was exercised
+        assert_eq!(outcomes.len(), 8);
+    }
+}
@@ -9,6 +9,10 @@
 //! ## ⚙️ How it works?
 //! - **Categorization**: Groups codes (Surface, Shor), operations (Lattice Surgery), 
 //!   and inference engines (Decoders) into a single, cohesive namespace.
+//! - **Logical Layer**: `logical` maps logical qubits onto encoded blocks and
+//!   expands logical gates into their physical implementations.
+//! - **Transversality**: `stabilizer` describes codes by their operators and
+//!   checks which logical gates have a qubit-wise implementation.
 //!
 //! ## 📍 Where to apply this?
 //! Integrate this into any long-running quantum computation where individual 
@@ -22,9 +26,13 @@ pub mod lattice_surgery;
 pub mod ldpc;
 pub mod decoders;
 pub mod advanced_codes;
+pub mod logical;
+pub mod stabilizer;
 
 pub use codes::*;
 pub use lattice_surgery::*;
 pub use ldpc::*;
 pub use decoders::*;
 pub use advanced_codes::*;
+pub use logical::*;
+pub use stabilizer::*;
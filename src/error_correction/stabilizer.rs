@@ -0,0 +1,255 @@
+//! Stabilizer Codes and Transversal Gates
+//!
+//! This module describes codes by their operators and checks gates against them:
+//! - `StabilizerCode`: Stabilizer generators and logical Pauli operators of
+//!   an [[n, k]] code, with Steane and bit-flip presets
+//! - `is_transversal` / `transversal_implementation`: Whether a logical gate
+//!   is a product of single-qubit (or qubit-wise) physical gates
+//!
+//! ## 🎯 Why is this used?
+//! Transversal gates are the cheap, inherently fault-tolerant part of a
+//! logical gate set: an error on one physical qubit stays on one qubit per
+//! block. Every other logical gate needs lattice surgery or magic-state
+//! injection, so knowing which is which decides the cost of a logical
+//! circuit. Assuming transversality wrongly silently leaves the codespace.
+//!
+//! ## ⚙️ How it works?
+//! - **Codewords**: |0…0_L⟩ is the projection Π(I + Sᵢ)/2 · Π(I + Z_Lⱼ)/2 of
+//!   the first computational basis state it does not annihilate, normalized;
+//!   |x_L⟩ = Π X_Lⱼ^{xⱼ} |0…0_L⟩, so the logical operators fix all phases.
+//! - **Candidates**: The physical gate is applied qubit-wise: qubit j of
+//!   every block it touches, for every j. Both G and G† are tried, since a
+//!   transversal G† can implement G_L (Steane's S_L is transversal S†).
+//! - **Check**: The matrix ⟨y_L|U|x_L⟩ must equal the intended logical
+//!   gate, G on logical qubit i of every block for each i, up to one global
+//!   phase. This also proves U maps the codespace into itself.
+//!
+//! ## 📍 Where to apply this?
+//! - **Logical Compilation**: Deciding which gates of a logical circuit
+//!   `LogicalRegister::apply_logical` can lower directly.
+//! - **Code Design**: Comparing the transversal gate sets of candidate codes.
+//!
+//! ## 📊 Code Behavior
+//! - **Complexity**: Exact statevector simulation of m blocks for an m-qubit
+//!   gate: O(4^{mk} · 2^{mn}), limited to m·n ≤ 20 physical qubits.
+//! - **Scope**: Only uniform qubit-wise layers are considered; a gate that
+//!   needs a different physical gate per qubit is reported as not transversal.
+
+use crate::gates::circuit::inverse_circuit;
+use crate::gates::core::{Complex, Gate, PauliOp, DEFAULT_TOL};
+use crate::simulator::{apply_pauli_string, basis_state, evolve, inner_product, probabilities};
+
+/// Largest number of physical qubits `transversal_implementation` simulates
+const MAX_PHYSICAL_QUBITS: usize = 20;
+
+// ============================================================================
+// STABILIZER CODES
+// ============================================================================
+
+/// An [[n, k]] stabilizer code on data qubits 0..n
+///
+/// Operators are sparse Pauli strings as taken by `apply_pauli_string`.
+/// `logical_x[j]` and `logical_z[j]` act on logical qubit j.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StabilizerCode {
+    pub num_data: usize,
+    /// Commuting generators of the stabilizer group
+    pub stabilizers: Vec<Vec<(usize, PauliOp)>>,
+    pub logical_x: Vec<Vec<(usize, PauliOp)>>,
+    pub logical_z: Vec<Vec<(usize, PauliOp)>>,
+}
+
+impl StabilizerCode {
+    /// CSS code from the supports of its X checks, Z checks and logicals
+    pub fn css(
+        num_data: usize,
+        x_checks: &[Vec<usize>],
+        z_checks: &[Vec<usize>],
+        logical_x: &[Vec<usize>],
+        logical_z: &[Vec<usize>],
+    ) -> Self {
+        assert_eq!(logical_x.len(), logical_z.len(), "Every logical qubit needs both an X_L and a Z_L");
+        let string = |support: &Vec<usize>, op: PauliOp| {
+            assert!(support.iter().all(|&q| q < num_data), "Support {:?} exceeds {} data qubits", support, num_data);
+            support.iter().map(|&q| (q, op)).collect::<Vec<_>>()
+        };
+        StabilizerCode {
+            num_data,
+            stabilizers: x_checks.iter().map(|s| string(s, PauliOp::X))
+                .chain(z_checks.iter().map(|s| string(s, PauliOp::Z)))
+                .collect(),
+            logical_x: logical_x.iter().map(|s| string(s, PauliOp::X)).collect(),
+            logical_z: logical_z.iter().map(|s| string(s, PauliOp::Z)).collect(),
+        }
+    }
+
+    /// [[7,1,3]] Steane code, with the checks measured by `steane_x_syndrome`
+    pub fn steane() -> Self {
+        let checks = [vec![0, 1, 2, 3], vec![0, 1, 4, 5], vec![0, 2, 4, 6]];
+        let all: Vec<usize> = (0..7).collect();
+        StabilizerCode::css(7, &checks, &checks, std::slice::from_ref(&all), std::slice::from_ref(&all))
+    }
+
+    /// [[3,1,1]] bit-flip repetition code: Z₀Z₁ and Z₁Z₂, X_L = XXX, Z_L = Z₀
+    pub fn bit_flip() -> Self {
+        StabilizerCode::css(3, &[], &[vec![0, 1], vec![1, 2]], &[vec![0, 1, 2]], &[vec![0]])
+    }
+
+    /// Number of logical qubits k
+    pub fn num_logical(&self) -> usize {
+        self.logical_x.len()
+    }
+
+    /// |x_L⟩ for x in 0..2^k, logical qubit j being bit j of x
+    fn logical_basis(&self) -> Vec<Vec<Complex>> {
+        let n = self.num_data;
+        let project = |state: &mut Vec<Complex>, op: &[(usize, PauliOp)]| {
+            let mut flipped = state.clone();
+            apply_pauli_string(&mut flipped, op).expect("Code operators act on its data qubits");
+            for (a, b) in state.iter_mut().zip(flipped) {
+                *a = (*a + b) * 0.5;
+            }
+        };
+        let zero = (0..1usize << n)
+            .find_map(|index| {
+                let mut state = basis_state(n, index);
+                for op in self.stabilizers.iter().chain(&self.logical_z) {
+                    project(&mut state, op);
+                }
+                let norm = probabilities(&state).iter().sum::<f64>().sqrt();
+                (norm > 1e-6).then(|| state.iter().map(|a| *a * (1.0 / norm)).collect::<Vec<_>>())
+            })
+            .expect("Stabilizers and logical Z operators must commute and be independent");
+
+        (0..1usize << self.num_logical())
+            .map(|x| {
+                let mut state = zero.clone();
+                for (j, op) in self.logical_x.iter().enumerate() {
+                    if x >> j & 1 == 1 {
+                        apply_pauli_string(&mut state, op).expect("Code operators act on its data qubits");
+                    }
+                }
+                state
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// TRANSVERSAL GATES
+// ============================================================================
+
+/// Whether `logical_gate` has a qubit-wise implementation in `code`
+///
+/// See [`transversal_implementation`]. For the Steane code this holds for
+/// the Paulis, H, S, S†, CX and CZ, but not for T or general rotations.
+pub fn is_transversal(code: &StabilizerCode, logical_gate: &Gate) -> bool {
+    transversal_implementation(code, logical_gate).is_some()
+}
+
+/// The qubit-wise physical layer implementing `logical_gate`, if there is one
+///
+/// The qubits of `logical_gate` name code blocks in the order listed, so
+/// CX(5, 3) is CX from block 0 to block 1; block b holds data qubits
+/// b·n..(b + 1)·n of the returned circuit. The intended logical operation
+/// applies the gate to logical qubit i of each block, for every i < k.
+/// Tries G on every qubit position first, then G†.
+pub fn transversal_implementation(code: &StabilizerCode, logical_gate: &Gate) -> Option<Vec<Gate>> {
+    assert!(
+        !matches!(logical_gate, Gate::Measure(..) | Gate::Reset(_) | Gate::Barrier(_) | Gate::ClassicalControl(..)),
+        "{:?} is not a unitary gate", logical_gate
+    );
+    let wires = logical_gate.qubits();
+    let (m, n, k) = (wires.len(), code.num_data, code.num_logical());
+    assert!(
+        m * n <= MAX_PHYSICAL_QUBITS,
+        "{} blocks of {} qubits exceed the {}-qubit simulation limit", m, n, MAX_PHYSICAL_QUBITS
+    );
+    let local = logical_gate.map_qubits(|q| wires.iter().position(|&w| w == q).unwrap());
+
+    // Intended action on the m·k logical qubits, logical qubit i of block b being b·k + i
+    let intended: Vec<Gate> = (0..k).map(|i| local.map_qubits(|b| b * k + i)).collect();
+    let expected: Vec<Vec<Complex>> = (0..1usize << (m * k))
+        .map(|x| evolve(&intended, &basis_state(m * k, x)))
+        .collect();
+
+    // Codewords of m blocks: tensor products of single-block codewords
+    let single = code.logical_basis();
+    let mask = (1usize << k) - 1;
+    let codewords: Vec<Vec<Complex>> = (0..1usize << (m * k))
+        .map(|x| {
+            (0..1usize << (m * n))
+                .map(|index| {
+                    (0..m).fold(Complex::ONE, |amp, b| {
+                        amp * single[x >> (b * k) & mask][index >> (b * n) & ((1 << n) - 1)]
+                    })
+                })
+                .collect()
+        })
+        .collect();
+
+    [vec![local.clone()], inverse_circuit(&[local])].into_iter().find_map(|candidate| {
+        let physical: Vec<Gate> = (0..n)
+            .flat_map(|j| candidate.iter().map(move |g| g.map_qubits(|b| b * n + j)))
+            .collect();
+        implements(&physical, &codewords, &expected).then_some(physical)
+    })
+}
+
+/// ⟨y_L|U|x_L⟩ = e^{iφ} ⟨y|G|x⟩ for all x, y and one phase φ
+fn implements(physical: &[Gate], codewords: &[Vec<Complex>], expected: &[Vec<Complex>]) -> bool {
+    let actual: Vec<Vec<Complex>> = codewords.iter()
+        .map(|input| {
+            let output = evolve(physical, input);
+            codewords.iter().map(|codeword| inner_product(codeword, &output)).collect()
+        })
+        .collect();
+    // e^{iφ} from the largest entry of the first column, which is ≥ 1/√d
+    let (y, target) = expected[0].iter().enumerate()
+        .max_by(|a, b| a.1.norm().total_cmp(&b.1.norm()))
+        .map(|(y, t)| (y, *t))
+        .unwrap();
+    let phase = actual[0][y] * target.conj() * (1.0 / target.norm_sq());
+    (phase.norm() - 1.0).abs() < DEFAULT_TOL.sqrt()
+        && actual.iter().zip(expected).all(|(row, want)| {
+            row.iter().zip(want).all(|(a, t)| (*a - *t * phase).norm() < DEFAULT_TOL.sqrt())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steane_transversal_gates() {
+        let steane = StabilizerCode::steane();
+        for gate in [
+            Gate::X(0), Gate::Y(0), Gate::Z(0), Gate::H(0), Gate::S(0), Gate::Sdg(0),
+            Gate::CX(0, 1), Gate::CX(1, 0), Gate::CZ(0, 1),
+        ] {
+            assert!(is_transversal(&steane, &gate), "{:?}", gate);
+        }
+        for gate in [Gate::T(0), Gate::Tdg(0), Gate::RZ(0, 0.3)] {
+            assert!(!is_transversal(&steane, &gate), "{:?}", gate);
+        }
+
+        // S_L is realized by S† on every qubit, matching LogicalRegister
+        let s = transversal_implementation(&steane, &Gate::S(4)).unwrap();
+        assert_eq!(s, (0..7).map(Gate::Sdg).collect::<Vec<_>>());
+        let cx = transversal_implementation(&steane, &Gate::CX(5, 3)).unwrap();
+        assert_eq!(cx, (0..7).map(|j| Gate::CX(j, j + 7)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_bit_flip_code_has_no_transversal_hadamard() {
+        let code = StabilizerCode::bit_flip();
+        assert!(is_transversal(&code, &Gate::X(0)));
+        assert!(is_transversal(&code, &Gate::CX(0, 1)));
+        // Z_L = Z₀ but ZZZ is Z_L too, since Z₀Z₁ and Z₁Z₂ are stabilizers
+        assert!(is_transversal(&code, &Gate::Z(0)));
+        assert!(!is_transversal(&code, &Gate::H(0)));
+        // S† on all three qubits is S_L, but T on all three is T³ on |1_L⟩
+        assert!(is_transversal(&code, &Gate::S(0)));
+        assert!(!is_transversal(&code, &Gate::T(0)));
+    }
+}
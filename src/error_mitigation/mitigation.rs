@@ -4,7 +4,8 @@
 //! - Zero Noise Extrapolation (ZNE)
 //! - Probabilistic Error Cancellation (PEC)
 //! - Clifford Data Regression (CDR)
-//! - Symmetry verification
+//! - Symmetry verification, with the post-selection acceptance rate
+//! - Pauli twirling of CX/CZ for noise tailoring
 //!
 //! ## 🎯 Why is this used?
 //! In the NISQ (Noisy Intermediate-Scale Quantum) era, we do not yet have 
@@ -40,7 +41,13 @@
 //!   noise channel (Gate Set Tomography).
 
 use std::f64::consts::PI;
+use crate::analysis::circuit_analysis::validate_width;
+use crate::error::RustiqError;
+use crate::gates::circuit::inverse_circuit;
 use crate::gates::core::Gate;
+use crate::gates::decomposition::control_gate;
+use crate::simulator::{density_matrix, NoiseModel};
+use crate::util::Rng;
 
 // ============================================================================
 // ZERO NOISE EXTRAPOLATION (ZNE)
@@ -77,7 +84,7 @@ pub fn zne_fold_circuit(circuit: &[Gate], scale_factor: usize) -> Vec<Gate> {
         
         // Fold: G†G pairs
         for _ in 0..folds {
-            folded.push(gate.inverse());
+            folded.extend(inverse_circuit(std::slice::from_ref(gate)));
             folded.push(gate.clone());
         }
     }
@@ -112,7 +119,7 @@ pub fn zne_local_fold(circuit: &[Gate], scale_factor: usize) -> Vec<Gate> {
         // Only fold two-qubit gates (typically noisiest)
         if gate.qubits().len() >= 2 {
             for _ in 0..folds {
-                folded.push(gate.inverse());
+                folded.extend(inverse_circuit(std::slice::from_ref(gate)));
                 folded.push(gate.clone());
             }
         }
@@ -246,9 +253,8 @@ pub fn pec_sample_circuit(
     
     let mut sampled_circuit = Vec::new();
     let mut sign = 1.0;
-    let mut rng_idx = 0;
     
-    for (_gate, decomp) in original_circuit.iter().zip(decompositions.iter()) {
+    for (rng_idx, decomp) in decompositions.iter().enumerate() {
         let gamma = decomp.gamma();
         
         // Normalize probabilities
@@ -262,7 +268,6 @@ pub fn pec_sample_circuit(
         } else {
             0.5  // Default
         };
-        rng_idx += 1;
         
         let mut cumsum = 0.0;
         let mut chosen = 0;
@@ -310,7 +315,7 @@ pub fn symmetry_verification_circuit(
         
         // Controlled symmetry operator
         for gate in symmetry_op {
-            gates.extend(make_controlled(ancilla, gate));
+            gates.extend(control_gate(ancilla, gate));
         }
         
         gates.push(Gate::H(ancilla));
@@ -319,14 +324,28 @@ pub fn symmetry_verification_circuit(
     gates
 }
 
-fn make_controlled(control: usize, gate: &Gate) -> Vec<Gate> {
-    match gate {
-        Gate::X(t) => vec![Gate::CX(control, *t)],
-        Gate::Y(t) => vec![Gate::CY(control, *t)],
-        Gate::Z(t) => vec![Gate::CZ(control, *t)],
-        Gate::CX(c, t) => vec![Gate::CCX(control, *c, *t)],
-        _ => vec![gate.clone()], // Simplified
-    }
+/// [`symmetry_verification_circuit`] and the probability that it is kept
+///
+/// The acceptance is Tr(Π ρ), the norm of the state projected onto every
+/// Hadamard-test ancilla reading 0, with ρ evolved under `noise` by
+/// `density_matrix` on `num_qubits` qubits (ancillas included). The shots
+/// needed for a post-selected estimate grow by 1 / acceptance. Ancillas or
+/// gates beyond `num_qubits` are `QubitOutOfRange`.
+pub fn symmetry_verification(
+    algorithm_circuit: &[Gate],
+    symmetry_operators: &[Vec<Gate>],
+    ancilla_start: usize,
+    num_qubits: usize,
+    noise: &NoiseModel,
+) -> Result<(Vec<Gate>, f64), RustiqError> {
+    let ancillas = ancilla_start..ancilla_start + symmetry_operators.len();
+    let circuit = symmetry_verification_circuit(algorithm_circuit, symmetry_operators, ancilla_start);
+    validate_width(&circuit, num_qubits)?;
+
+    let rho = density_matrix(&circuit, num_qubits, noise);
+    let mask = ancillas.fold(0usize, |m, q| m | (1 << q));
+    let acceptance = (0..rho.len()).filter(|i| i & mask == 0).map(|i| rho[i][i].re).sum();
+    Ok((circuit, acceptance))
 }
 
 /// Post-selection filter for symmetry verification
@@ -387,14 +406,14 @@ fn cdr_replace_gates(circuit: &[Gate], fraction: f64, seed: usize) -> Vec<Gate>
 }
 
 fn is_non_clifford(gate: &Gate) -> bool {
-    match gate {
-        Gate::T(_) | Gate::Tdg(_) | 
+    matches!(
+        gate,
+        Gate::T(_) | Gate::Tdg(_) |
         Gate::RX(_, _) | Gate::RY(_, _) | Gate::RZ(_, _) |
         Gate::P(_, _) | Gate::U3(_, _, _, _) |
         Gate::CRX(_, _, _) | Gate::CRY(_, _, _) | Gate::CRZ(_, _, _) |
-        Gate::RXX(_, _, _) | Gate::RYY(_, _, _) | Gate::RZZ(_, _, _) => true,
-        _ => false,
-    }
+        Gate::RXX(_, _, _) | Gate::RYY(_, _, _) | Gate::RZZ(_, _, _)
+    )
 }
 
 fn clifford_replacement(gate: &Gate) -> Gate {
@@ -500,6 +519,77 @@ fn shift_gate(gate: &Gate, offset: usize) -> Gate {
     }
 }
 
+// ============================================================================
+// PAULI TWIRLING
+// ============================================================================
+
+/// U(Pₐ ⊗ P_b)U† = ±(P_c ⊗ P_d) for U = CX, indexed 4a + b with I, X, Y, Z = 0..4
+///
+/// Entries are (c, d, negative); the first factor acts on the control.
+const CX_PAULI_CONJUGATION: [(usize, usize, bool); 16] = [
+    (0, 0, false), (0, 1, false), (3, 2, false), (3, 3, false),
+    (1, 1, false), (1, 0, false), (2, 3, false), (2, 2, true),
+    (2, 1, false), (2, 0, false), (1, 3, true), (1, 2, false),
+    (3, 0, false), (3, 1, false), (0, 2, false), (0, 3, false),
+];
+
+/// The same table for CZ
+const CZ_PAULI_CONJUGATION: [(usize, usize, bool); 16] = [
+    (0, 0, false), (3, 1, false), (3, 2, false), (0, 3, false),
+    (1, 3, false), (2, 2, false), (2, 1, true), (1, 0, false),
+    (2, 3, false), (1, 2, true), (1, 1, false), (2, 0, false),
+    (3, 0, false), (0, 1, false), (0, 2, false), (3, 3, false),
+];
+
+fn pauli_gate(index: usize, q: usize) -> Option<Gate> {
+    match index {
+        1 => Some(Gate::X(q)),
+        2 => Some(Gate::Y(q)),
+        3 => Some(Gate::Z(q)),
+        _ => None,
+    }
+}
+
+/// Randomly Pauli-twirl every CX and CZ of `circuit`
+///
+/// Each CX/CZ U becomes (P_c ⊗ P_d) · U · (Pₐ ⊗ P_b) with Pₐ ⊗ P_b drawn
+/// uniformly from the 16 two-qubit Paulis and P_c ⊗ P_d = ±U(Pₐ ⊗ P_b)U† from
+/// the conjugation table, so the ideal circuit is unchanged (a −1 sign is
+/// restored with `GlobalPhase(π)`). Averaged over seeds, any noise on U is
+/// tailored into a Pauli channel, which PEC and error models can treat exactly.
+/// Other gates are copied unchanged.
+pub fn pauli_twirl(circuit: &[Gate], seed: u64) -> Vec<Gate> {
+    pauli_twirl_with_rng(circuit, &mut Rng::new(seed))
+}
+
+/// `pauli_twirl` drawing from a shared generator
+pub fn pauli_twirl_with_rng(circuit: &[Gate], rng: &mut Rng) -> Vec<Gate> {
+    let mut next = || rng.below(16);
+
+    let mut gates = Vec::with_capacity(circuit.len());
+    for gate in circuit {
+        let (c, t, table) = match gate {
+            Gate::CX(c, t) => (*c, *t, &CX_PAULI_CONJUGATION),
+            Gate::CZ(c, t) => (*c, *t, &CZ_PAULI_CONJUGATION),
+            _ => {
+                gates.push(gate.clone());
+                continue;
+            }
+        };
+        let index = next();
+        let (after_c, after_t, negative) = table[index];
+        gates.extend(pauli_gate(index / 4, c));
+        gates.extend(pauli_gate(index % 4, t));
+        gates.push(gate.clone());
+        gates.extend(pauli_gate(after_c, c));
+        gates.extend(pauli_gate(after_t, t));
+        if negative {
+            gates.push(Gate::GlobalPhase(PI));
+        }
+    }
+    gates
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -542,6 +632,76 @@ mod tests {
         assert!(decomp.gamma() > 1.0);
     }
 
+    #[test]
+    fn test_pauli_twirl_preserves_ideal_unitary() {
+        use crate::gates::core::Complex;
+        use crate::simulator::{simulate_from, states_close};
+
+        let circuit = vec![
+            Gate::H(0), Gate::CX(0, 1), Gate::RY(2, 0.4), Gate::CZ(1, 2),
+            Gate::CX(2, 0), Gate::T(1), Gate::CZ(0, 1), Gate::CX(1, 2),
+        ];
+        let input: Vec<Complex> = (0..8).map(|i| Complex::new(0.1 * i as f64 - 0.3, 0.05 * (i * i) as f64)).collect();
+        let expected = simulate_from(&circuit, &input).unwrap();
+
+        let mut seen_paulis = false;
+        for seed in 0..40 {
+            let twirled = pauli_twirl(&circuit, seed);
+            seen_paulis |= twirled.len() > circuit.len();
+            // Exact equality, global phase included
+            assert!(states_close(&simulate_from(&twirled, &input).unwrap(), &expected, 1e-10), "Seed {}", seed);
+        }
+        assert!(seen_paulis);
+
+        // Every table entry, directly
+        let input = &input[..4];
+        for (gate, table) in [(Gate::CX(0, 1), &CX_PAULI_CONJUGATION), (Gate::CZ(0, 1), &CZ_PAULI_CONJUGATION)] {
+            for (index, &(c, d, negative)) in table.iter().enumerate() {
+                let mut twirled: Vec<Gate> = pauli_gate(index / 4, 0).into_iter().chain(pauli_gate(index % 4, 1)).collect();
+                twirled.push(gate.clone());
+                twirled.extend(pauli_gate(c, 0).into_iter().chain(pauli_gate(d, 1)));
+                if negative {
+                    twirled.push(Gate::GlobalPhase(PI));
+                }
+                let want = simulate_from(std::slice::from_ref(&gate), input).unwrap();
+                assert!(states_close(&simulate_from(&twirled, input).unwrap(), &want, 1e-10), "{:?} entry {}", gate, index);
+            }
+        }
+    }
+
+    #[test]
+    fn test_symmetry_verification_controls_every_gate() {
+        use crate::simulator::{probabilities, simulate};
+
+        // RY(π/4)|0⟩ is the +1 eigenstate of H, so the Hadamard test ancilla stays 0
+        let circuit = symmetry_verification_circuit(&[Gate::RY(0, PI / 4.0)], &[vec![Gate::H(0)]], 1);
+        let probs = probabilities(&simulate(&circuit, 2).unwrap());
+        assert!(probs[0b10] + probs[0b11] < 1e-12);
+
+        // −1 eigenstate: the ancilla always reads 1
+        let circuit = symmetry_verification_circuit(&[Gate::RY(0, PI / 4.0), Gate::Y(0)], &[vec![Gate::H(0)]], 1);
+        let probs = probabilities(&simulate(&circuit, 2).unwrap());
+        assert!((probs[0b10] + probs[0b11] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_symmetry_verification_acceptance() {
+        let algorithm = [Gate::RY(0, PI / 4.0)];
+        let symmetry = [vec![Gate::H(0)]];
+        let (circuit, accept) = symmetry_verification(&algorithm, &symmetry, 1, 2, &NoiseModel::ideal()).unwrap();
+        assert_eq!(circuit, symmetry_verification_circuit(&algorithm, &symmetry, 1));
+        assert!((accept - 1.0).abs() < 1e-12);
+
+        // Leaving the symmetric sector, by a flip or by noise, costs shots
+        let (_, flipped) = symmetry_verification(&[Gate::RY(0, PI / 4.0), Gate::Y(0)], &symmetry, 1, 2, &NoiseModel::ideal()).unwrap();
+        assert!(flipped < 1e-12);
+        let (_, noisy) = symmetry_verification(&algorithm, &symmetry, 1, 2, &NoiseModel::depolarizing(0.05, 0.05)).unwrap();
+        assert!(noisy > 0.5 && noisy < 1.0 - 1e-3, "{}", noisy);
+
+        let err = symmetry_verification(&algorithm, &symmetry, 1, 1, &NoiseModel::ideal()).unwrap_err();
+        assert_eq!(err, RustiqError::QubitOutOfRange { qubit: 1, num_qubits: 1 });
+    }
+
     #[test]
     fn test_cdr_training_circuits() {
         let circuit = vec![Gate::T(0), Gate::CX(0, 1), Gate::T(1)];
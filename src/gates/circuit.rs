@@ -0,0 +1,1007 @@
+//! # Circuit Composition Utilities
+//!
+//! This module provides structural helpers for assembling circuits from pieces:
+//! - Sequential composition (`compose`)
+//! - Parallel composition on disjoint registers (`tensor`)
+//! - Qubit relabelling (`remap_qubits`) and register reversal (`reverse_register`)
+//! - Per-qubit slicing (`gates_on_qubit`, `subcircuit_on_qubits`)
+//! - Window surgery for local optimization (`extract_window`, `splice_window`)
+//! - Circuit adjoint (`inverse_circuit`)
+//! - Bennett-style uncomputation (`compute_uncompute`)
+//! - Canonical ordering of commuting gates (`canonicalize`)
+//! - Log-depth CNOT fan-out (`cnot_fanout`)
+//! - Seeded random circuits for fuzzing (`random_circuit`) and uniformly
+//!   random Cliffords for twirling and benchmarking (`random_clifford`)
+//! - X / Y measurement-basis rotations and their inverses
+//!
+//! ## 🎯 Why is this used?
+//! Circuits in Quantic-Rust are plain `Vec<Gate>` sequences. Larger programs
+//! (ansätze, cutting fragments, arithmetic blocks) are built by stitching
+//! smaller sequences together, which otherwise means hand-shifting every
+//! qubit index. These helpers make that bookkeeping explicit and reusable.
+//!
+//! ## ⚙️ How it works?
+//! - **Compose**: Concatenates two gate lists acting on the same register,
+//!   i.e. the circuit for B·A (A applied first).
+//! - **Tensor**: Offsets every qubit index of the second circuit by the width
+//!   of the first, yielding A ⊗ B on a combined register.
+//! - **Remap**: Sends qubit `q` to `mapping[q]` for every gate via `Gate::map_qubits`.
+//! - **Inverse**: Reverses the gate order and inverts each gate, so that
+//!   (G_n ⋯ G_1)† = G_1† ⋯ G_n†.
+//! - **Uncompute**: C · A · C† restores every scratch qubit C wrote to, as
+//!   long as A only reads them (as controls or through diagonal phases).
+//! - **Canonical Form**: Gates on disjoint qubits and classical bits commute,
+//!   and so do diagonal gates that only share qubits.
+//!   The circuit is the partial order of non-commuting gates sharing a wire,
+//!   and the canonical form is its topological order that always emits the
+//!   ready gate with the smallest (first wire, kind, qubits, angles) key.
+//! - **Fan-Out**: Arranges the targets as a binary heap. A leaf-to-root CX
+//!   sweep turns each target into its difference with its parent, one CX from
+//!   the source flips the root, and a root-to-leaf sweep spreads the flip down.
+//! - **Basis Rotations**: H maps X eigenstates to Z eigenstates and S†·H maps
+//!   Y eigenstates to them (|+i⟩ → |0⟩), so a Z measurement afterwards reads out
+//!   X or Y. The `prepare_*` inverses rotate |0⟩/|1⟩ back into those eigenstates.
+//! - **Random Circuits**: Each layer shuffles the qubits and fills them with
+//!   gates drawn from the requested kinds, driven by a 64-bit LCG so a seed
+//!   always reproduces the same circuit. `random_clifford` samples its
+//!   tableau pair by pair from the symplectic complement of the pairs so far,
+//!   then reduces it to the identity with H, S and CX; the inverse of that
+//!   reduction is the circuit.
+//!
+//! ## 📍 Where to apply this?
+//! - **Ansatz Assembly**: Combining layers or copies of a sub-circuit.
+//! - **Circuit Cutting**: Placing fragments back onto a global register.
+//! - **Hardware Mapping**: Applying a logical→physical qubit layout.
+//! - **Uncomputation**: Undoing PREPARE blocks, ancilla computations and oracles.
+//!
+//! ## 📊 Code Behavior
+//! - **Complexity**: $O(G)$ in the total number of gates.
+//! - **Purity**: All functions return new circuits; inputs are never mutated.
+
+use std::f64::consts::PI;
+use crate::gates::core::{Gate, GateKind};
+use crate::analysis::circuit_analysis::validate;
+use crate::error::RustiqError;
+use crate::util::Rng;
+
+// ============================================================================
+// COMPOSITION
+// ============================================================================
+
+/// Sequential composition: apply `a`, then `b`, on the same qubits
+pub fn compose(a: &[Gate], b: &[Gate]) -> Vec<Gate> {
+    let mut gates = Vec::with_capacity(a.len() + b.len());
+    gates.extend_from_slice(a);
+    gates.extend_from_slice(b);
+    gates
+}
+
+/// Parallel composition A ⊗ B on disjoint registers
+///
+/// `a` keeps its qubit indices; every index in `b` is shifted by `a_qubits`
+/// so that `b` acts on qubits `a_qubits..`.
+pub fn tensor(a: &[Gate], a_qubits: usize, b: &[Gate]) -> Vec<Gate> {
+    let mut gates = Vec::with_capacity(a.len() + b.len());
+    gates.extend_from_slice(a);
+    gates.extend(b.iter().map(|g| g.map_qubits(|q| q + a_qubits)));
+    gates
+}
+
+/// Relabel qubits: every qubit `q` becomes `mapping[q]`
+///
+/// Panics if the circuit touches a qubit outside `mapping`. Debug builds also
+/// reject mappings that alias two qubits of one gate.
+pub fn remap_qubits(circuit: &[Gate], mapping: &[usize]) -> Vec<Gate> {
+    let gates: Vec<Gate> = circuit.iter()
+        .map(|g| g.map_qubits(|q| {
+            assert!(q < mapping.len(), "Qubit {} has no entry in mapping", q);
+            mapping[q]
+        }))
+        .collect();
+    if cfg!(debug_assertions) {
+        if let Err(e) = validate(&gates) {
+            panic!("remap_qubits produced an invalid circuit: {}", e);
+        }
+    }
+    gates
+}
+
+/// The ⌊n/2⌋ SWAPs reversing `qubits`: qubits[i] ↔ qubits[n − 1 − i]
+///
+/// This is the bit-reversal stage of the QFT, and converts a register between
+/// most- and least-significant-bit-first storage. It is its own inverse.
+pub fn reverse_register(qubits: &[usize]) -> Vec<Gate> {
+    let n = qubits.len();
+    (0..n / 2).map(|i| Gate::SWAP(qubits[i], qubits[n - 1 - i])).collect()
+}
+
+// ============================================================================
+// SLICING
+// ============================================================================
+
+/// Indices of the gates that touch qubit `q`, in circuit order
+pub fn gates_on_qubit(circuit: &[Gate], q: usize) -> Vec<usize> {
+    circuit.iter().enumerate()
+        .filter(|(_, g)| g.qubits().contains(&q))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// The gates acting only within `qubits`, in circuit order
+///
+/// A gate with any qubit outside the subset is dropped, so a CX straddling
+/// the boundary is excluded; qubit-free gates (global phases) are kept.
+pub fn subcircuit_on_qubits(circuit: &[Gate], qubits: &[usize]) -> Vec<Gate> {
+    circuit.iter()
+        .filter(|g| g.qubits().iter().all(|q| qubits.contains(q)))
+        .cloned()
+        .collect()
+}
+
+/// The gates `circuit[start..end]`, for running a pass on one window
+///
+/// A reversed window or one past the end is `WindowOutOfRange`.
+pub fn extract_window(circuit: &[Gate], start: usize, end: usize) -> Result<Vec<Gate>, RustiqError> {
+    check_window(circuit, start, end)?;
+    Ok(circuit[start..end].to_vec())
+}
+
+fn check_window(circuit: &[Gate], start: usize, end: usize) -> Result<(), RustiqError> {
+    if start <= end && end <= circuit.len() {
+        Ok(())
+    } else {
+        Err(RustiqError::WindowOutOfRange { start, end, len: circuit.len() })
+    }
+}
+
+/// `circuit` with `circuit[start..end]` replaced by `replacement`
+///
+/// The replacement may be shorter or longer than the window, but may only
+/// act on qubits the removed gates act on, so the gates outside the window
+/// see the same wires they did before. If it implements the same unitary as
+/// the window, the whole circuit does too. A replacement gate on another
+/// qubit is `QubitOutsideWindow`.
+pub fn splice_window(circuit: &[Gate], start: usize, end: usize, replacement: Vec<Gate>) -> Result<Vec<Gate>, RustiqError> {
+    check_window(circuit, start, end)?;
+    let window: Vec<usize> = circuit[start..end].iter().flat_map(|g| g.qubits()).collect();
+    if let Some(qubit) = replacement.iter().flat_map(|g| g.qubits()).find(|q| !window.contains(q)) {
+        return Err(RustiqError::QubitOutsideWindow { qubit });
+    }
+    let mut gates = Vec::with_capacity(circuit.len() - (end - start) + replacement.len());
+    gates.extend_from_slice(&circuit[..start]);
+    gates.extend(replacement);
+    gates.extend_from_slice(&circuit[end..]);
+    Ok(gates)
+}
+
+// ============================================================================
+// INVERSION
+// ============================================================================
+
+/// Adjoint of a circuit: reversed order, each gate inverted, also under
+/// `ClassicalControl`
+pub fn inverse_circuit(circuit: &[Gate]) -> Vec<Gate> {
+    let mut gates = Vec::with_capacity(circuit.len());
+    for gate in circuit.iter().rev() {
+        match gate {
+            Gate::ClassicalControl(bit, inner) => {
+                let inverted = inverse_circuit(std::slice::from_ref(inner.as_ref()));
+                gates.extend(inverted.into_iter().map(|g| Gate::ClassicalControl(*bit, Box::new(g))));
+            }
+            _ => gates.push(gate.inverse()),
+        }
+    }
+    gates
+}
+
+/// Bennett's idiom `compute`, then `action`, then `compute`†
+///
+/// Whatever `compute` wrote into `scratch_qubits` is erased again by its
+/// adjoint, provided `action` uses the scratch only as controls or inside
+/// diagonal gates. Debug builds reject an `action` that could flip a scratch
+/// qubit in the computational basis.
+pub fn compute_uncompute(compute: &[Gate], action: &[Gate], scratch_qubits: &[usize]) -> Vec<Gate> {
+    if cfg!(debug_assertions) {
+        for gate in action {
+            if let Some(q) = written_qubits(gate).into_iter().find(|q| scratch_qubits.contains(q)) {
+                panic!("{:?} in the action writes to scratch qubit {}", gate, q);
+            }
+        }
+    }
+    let mut gates = compose(compute, action);
+    gates.extend(inverse_circuit(compute));
+    gates
+}
+
+/// Qubits whose computational-basis value `gate` may change
+fn written_qubits(gate: &Gate) -> Vec<usize> {
+    match gate {
+        Gate::Z(_) | Gate::S(_) | Gate::Sdg(_) | Gate::T(_) | Gate::Tdg(_) | Gate::RZ(..) | Gate::P(..) |
+        Gate::CZ(..) | Gate::CP(..) | Gate::CRZ(..) | Gate::RZZ(..) | Gate::CCZ(..) | Gate::MCZ(..) |
+        Gate::MCP(..) | Gate::GlobalPhase(_) | Gate::Barrier(_) => Vec::new(),
+        Gate::CX(_, t) | Gate::CY(_, t) | Gate::CRX(_, t, _) | Gate::CRY(_, t, _) |
+        Gate::CCX(_, _, t) | Gate::MCX(_, t) => vec![*t],
+        Gate::CSWAP(_, a, b) => vec![*a, *b],
+        Gate::ClassicalControl(_, inner) => written_qubits(inner),
+        _ => gate.qubits(),
+    }
+}
+
+// ============================================================================
+// CANONICAL FORM
+// ============================================================================
+
+/// Deterministic reordering of commuting gates
+///
+/// Two circuits that differ only by swapping adjacent gates that commute
+/// canonicalize to the same (`==`) gate list, and the result is unitarily
+/// identical to the input. Gates commute here when they share no qubit or
+/// classical bit, or when every qubit they share is acted on diagonally by
+/// both (runs of Z, S, T, RZ, P, CZ, CP, CRZ, RZZ, CCZ, MCZ, MCP). Other
+/// gates on a shared wire keep their order, even if they happen to commute
+/// (X and CX on the target). Qubit-free gates such as `GlobalPhase` move to
+/// the end.
+pub fn canonicalize(circuit: &[Gate]) -> Vec<Gate> {
+    use std::cmp::Reverse;
+    use std::collections::{BinaryHeap, HashMap};
+
+    // Qubit q is wire 2q and classical bit b is wire 2b + 1
+    fn wires(gate: &Gate) -> Vec<usize> {
+        match gate {
+            Gate::Measure(q, bit) => vec![2 * q, 2 * bit + 1],
+            Gate::ClassicalControl(bit, inner) => {
+                let mut w = wires(inner);
+                w.push(2 * bit + 1);
+                w
+            }
+            _ => gate.qubits().iter().map(|q| 2 * q).collect(),
+        }
+    }
+    let sort_key = |i: usize| {
+        let g = &circuit[i];
+        let first = wires(g).into_iter().min().unwrap_or(usize::MAX);
+        Reverse((first, g.kind(), g.qubits(), g.params().iter().map(|p| p.to_bits()).collect::<Vec<_>>(), i))
+    };
+
+    // Per wire: the last gate that does not commute along it, and the
+    // diagonal gates placed since, which commute with each other
+    let mut successors: Vec<Vec<usize>> = vec![Vec::new(); circuit.len()];
+    let mut pending = vec![0usize; circuit.len()];
+    let mut barrier_on_wire: HashMap<usize, usize> = HashMap::new();
+    let mut diagonal_on_wire: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, gate) in circuit.iter().enumerate() {
+        let mut w = wires(gate);
+        w.sort_unstable();
+        w.dedup();
+        let diagonal = is_diagonal(gate);
+        let mut predecessors = Vec::new();
+        for wire in w {
+            // Classical bits are odd wires and never commute
+            if diagonal && wire % 2 == 0 {
+                predecessors.extend(barrier_on_wire.get(&wire).copied());
+                diagonal_on_wire.entry(wire).or_default().push(i);
+            } else {
+                match diagonal_on_wire.remove(&wire) {
+                    Some(run) if !run.is_empty() => predecessors.extend(run),
+                    _ => predecessors.extend(barrier_on_wire.get(&wire).copied()),
+                }
+                barrier_on_wire.insert(wire, i);
+            }
+        }
+        predecessors.sort_unstable();
+        predecessors.dedup();
+        for prev in predecessors {
+            successors[prev].push(i);
+            pending[i] += 1;
+        }
+    }
+
+    let mut ready: BinaryHeap<_> = (0..circuit.len()).filter(|&i| pending[i] == 0).map(sort_key).collect();
+    let mut out = Vec::with_capacity(circuit.len());
+    while let Some(Reverse((_, _, _, _, i))) = ready.pop() {
+        out.push(circuit[i].clone());
+        for &next in &successors[i] {
+            pending[next] -= 1;
+            if pending[next] == 0 {
+                ready.push(sort_key(next));
+            }
+        }
+    }
+    out
+}
+
+/// Whether `gate` is diagonal in the computational basis on every qubit it touches
+fn is_diagonal(gate: &Gate) -> bool {
+    match gate {
+        Gate::Z(_) | Gate::S(_) | Gate::Sdg(_) | Gate::T(_) | Gate::Tdg(_) | Gate::RZ(..) | Gate::P(..) |
+        Gate::CZ(..) | Gate::CP(..) | Gate::CRZ(..) | Gate::RZZ(..) | Gate::CCZ(..) | Gate::MCZ(..) |
+        Gate::MCP(..) => true,
+        Gate::ClassicalControl(_, inner) => is_diagonal(inner),
+        _ => false,
+    }
+}
+
+// ============================================================================
+// FAN-OUT
+// ============================================================================
+
+/// Copy `source` onto every target (tᵢ ⊕= s) with a balanced CX tree
+///
+/// Exact for arbitrary target states, not just |0⟩. Uses 2n − 1 CX gates in
+/// depth 4⌈log₂(n + 1)⌉ − 3 instead of n gates in depth n.
+pub fn cnot_fanout(source: usize, targets: &[usize]) -> Vec<Gate> {
+    assert!(!targets.contains(&source), "Source {} is also a target", source);
+    if targets.is_empty() {
+        return Vec::new();
+    }
+
+    // Heap layout: node i has parent (i - 1) / 2; group nodes by tree level
+    let mut levels: Vec<Vec<usize>> = Vec::new();
+    for i in 1..targets.len() {
+        let level = (usize::BITS - (i + 1).leading_zeros() - 1) as usize;
+        if levels.len() < level {
+            levels.resize(level, Vec::new());
+        }
+        levels[level - 1].push(i);
+    }
+    let parent_cx = |i: usize| Gate::CX(targets[(i - 1) / 2], targets[i]);
+
+    let mut gates = Vec::with_capacity(2 * targets.len() - 1);
+    // tᵢ ← tᵢ ⊕ t_parent, deepest level first so parents are still original
+    for level in levels.iter().rev() {
+        gates.extend(level.iter().map(|&i| parent_cx(i)));
+    }
+    gates.push(Gate::CX(source, targets[0]));
+    // Undo the differences top-down, now carrying the flip from the root
+    for level in &levels {
+        gates.extend(level.iter().map(|&i| parent_cx(i)));
+    }
+    gates
+}
+
+// ============================================================================
+// MEASUREMENT BASES
+// ============================================================================
+
+/// Rotation before a Z measurement that reads out X: H on every qubit
+pub fn measure_x_basis(qubits: &[usize]) -> Vec<Gate> {
+    qubits.iter().map(|&q| Gate::H(q)).collect()
+}
+
+/// Rotation before a Z measurement that reads out Y: S† then H on every qubit
+pub fn measure_y_basis(qubits: &[usize]) -> Vec<Gate> {
+    qubits.iter().flat_map(|&q| [Gate::Sdg(q), Gate::H(q)]).collect()
+}
+
+/// Inverse of `measure_x_basis`: |0⟩/|1⟩ → |+⟩/|−⟩
+pub fn prepare_x_basis(qubits: &[usize]) -> Vec<Gate> {
+    measure_x_basis(qubits)
+}
+
+/// Inverse of `measure_y_basis`: |0⟩/|1⟩ → |+i⟩/|−i⟩
+pub fn prepare_y_basis(qubits: &[usize]) -> Vec<Gate> {
+    qubits.iter().flat_map(|&q| [Gate::H(q), Gate::S(q)]).collect()
+}
+
+// ============================================================================
+// RANDOM CIRCUITS
+// ============================================================================
+
+/// Reproducible random circuit of `depth` layers over `gate_set`
+///
+/// Every layer places gates on disjoint, randomly chosen qubits until fewer
+/// qubits remain than any kind needs; angles are uniform in [0, 2π). The
+/// same `seed` always yields the same circuit. `gate_set` may only contain
+/// fixed-width unitary kinds acting on at least one qubit.
+pub fn random_circuit(num_qubits: usize, depth: usize, seed: u64, gate_set: &[GateKind]) -> Vec<Gate> {
+    random_circuit_with_rng(num_qubits, depth, &mut Rng::new(seed), gate_set)
+}
+
+/// `random_circuit` drawing from a shared generator
+pub fn random_circuit_with_rng(num_qubits: usize, depth: usize, rng: &mut Rng, gate_set: &[GateKind]) -> Vec<Gate> {
+    assert!(!gate_set.is_empty(), "Gate set is empty");
+    for kind in gate_set {
+        match kind.arity() {
+            Some(k) if k >= 1 && !matches!(kind, GateKind::Measure | GateKind::Reset) => {
+                assert!(k <= num_qubits, "{:?} needs {} qubits but only {} exist", kind, k, num_qubits)
+            }
+            _ => panic!("{:?} cannot be sampled by random_circuit", kind),
+        }
+    }
+    let mut next = |bound: usize| rng.below(bound);
+
+    let mut gates = Vec::new();
+    for _ in 0..depth {
+        // Fisher-Yates shuffle of the free qubits
+        let mut free: Vec<usize> = (0..num_qubits).collect();
+        for i in (1..free.len()).rev() {
+            free.swap(i, next(i + 1));
+        }
+
+        loop {
+            let fits: Vec<GateKind> = gate_set.iter().copied()
+                .filter(|k| k.arity().unwrap() <= free.len())
+                .collect();
+            if fits.is_empty() {
+                break;
+            }
+            let kind = fits[next(fits.len())];
+            let qubits = free.split_off(free.len() - kind.arity().unwrap());
+            let angles: Vec<f64> = (0..kind.num_params())
+                .map(|_| next(1 << 30) as f64 / (1u64 << 30) as f64 * 2.0 * PI)
+                .collect();
+            gates.push(build_gate(kind, &qubits, &angles));
+        }
+    }
+    gates
+}
+
+/// Uniformly random `num_qubits`-qubit Clifford, up to global phase
+///
+/// The tableau is sampled one symplectic pair at a time: Xᵢ goes to any
+/// nonzero Pauli commuting with the images of X₀, Z₀ … Xᵢ₋₁, Zᵢ₋₁, and Zᵢ to
+/// any such Pauli anticommuting with the image of Xᵢ. The number of choices
+/// never depends on earlier ones, so every tableau is equally likely; a
+/// uniformly random Pauli applied first makes the signs uniform too. The
+/// circuit is H, S, S†, CX and Paulis, reproducible from `seed`.
+pub fn random_clifford(num_qubits: usize, seed: u64) -> Vec<Gate> {
+    random_clifford_with_rng(num_qubits, &mut Rng::new(seed))
+}
+
+/// `random_clifford` drawing from a shared generator
+pub fn random_clifford_with_rng(num_qubits: usize, rng: &mut Rng) -> Vec<Gate> {
+    let mut rows = random_symplectic(num_qubits, rng);
+    let reduction = reduce_tableau(&mut rows, num_qubits);
+    let mut gates: Vec<Gate> = (0..num_qubits)
+        .flat_map(|q| match rng.below(4) {
+            0 => vec![],
+            1 => vec![Gate::X(q)],
+            2 => vec![Gate::Z(q)],
+            _ => vec![Gate::Y(q)],
+        })
+        .collect();
+    gates.extend(inverse_circuit(&reduction));
+    gates
+}
+
+/// Images of X₀, Z₀, X₁, Z₁, … under a uniformly random symplectic map
+///
+/// A Pauli is 2n bits: its X part on 0..n and its Z part on n..2n.
+fn random_symplectic(n: usize, rng: &mut Rng) -> Vec<Vec<bool>> {
+    let symplectic = |a: &[bool], b: &[bool]| (0..n).filter(|&j| (a[j] & b[n + j]) ^ (a[n + j] & b[j])).count() % 2 == 1;
+    let mut random_in = |basis: &[Vec<bool>]| {
+        let mut v = vec![false; 2 * n];
+        for b in basis {
+            if rng.below(2) == 1 {
+                v.iter_mut().zip(b).for_each(|(x, y)| *x ^= *y);
+            }
+        }
+        v
+    };
+
+    // `complement` spans the Paulis commuting with every pair chosen so far
+    let mut complement: Vec<Vec<bool>> = (0..2 * n).map(|k| (0..2 * n).map(|j| j == k).collect()).collect();
+    let mut rows = Vec::with_capacity(2 * n);
+    for _ in 0..n {
+        let x = loop {
+            let v = random_in(&complement);
+            if v.contains(&true) {
+                break v;
+            }
+        };
+        let z = loop {
+            let v = random_in(&complement);
+            if symplectic(&x, &v) {
+                break v;
+            }
+        };
+        // w + ⟨w, z⟩·x + ⟨w, x⟩·z commutes with both x and z
+        let projected = complement.iter().map(|w| {
+            let (cz, cx) = (symplectic(w, &z), symplectic(w, &x));
+            (0..2 * n).map(|j| w[j] ^ (cz & x[j]) ^ (cx & z[j])).collect()
+        });
+        complement = independent_rows(projected);
+        rows.push(x);
+        rows.push(z);
+    }
+    rows
+}
+
+/// H, S and CX that conjugate the tableau `rows` to the identity, up to signs
+fn reduce_tableau(rows: &mut [Vec<bool>], n: usize) -> Vec<Gate> {
+    let mut reduction = Vec::new();
+    let mut apply = |rows: &mut [Vec<bool>], gate: Gate| {
+        for r in rows.iter_mut() {
+            match gate {
+                Gate::H(j) => r.swap(j, n + j),
+                Gate::S(j) => r[n + j] ^= r[j],
+                Gate::CX(c, t) => {
+                    r[t] ^= r[c];
+                    r[n + c] ^= r[n + t];
+                }
+                _ => unreachable!(),
+            }
+        }
+        reduction.push(gate);
+    };
+    for i in 0..n {
+        // Image of Xᵢ → X-type on qubits ≥ i → Xᵢ
+        for j in i..n {
+            match (rows[2 * i][j], rows[2 * i][n + j]) {
+                (true, true) => apply(rows, Gate::S(j)),
+                (false, true) => apply(rows, Gate::H(j)),
+                _ => {}
+            }
+        }
+        if !rows[2 * i][i] {
+            let j = (i + 1..n).find(|&j| rows[2 * i][j]).unwrap();
+            apply(rows, Gate::CX(j, i));
+        }
+        for j in i + 1..n {
+            if rows[2 * i][j] {
+                apply(rows, Gate::CX(i, j));
+            }
+        }
+        // Image of Zᵢ → Z-type on qubits > i, folded onto qubit i → Zᵢ
+        for j in i + 1..n {
+            if rows[2 * i + 1][j] {
+                // S first turns Y into X
+                if rows[2 * i + 1][n + j] {
+                    apply(rows, Gate::S(j));
+                }
+                apply(rows, Gate::H(j));
+            }
+        }
+        for j in i + 1..n {
+            if rows[2 * i + 1][n + j] {
+                apply(rows, Gate::CX(j, i));
+            }
+        }
+        if rows[2 * i + 1][i] {
+            // H·S·H fixes X and sends Y to Z
+            for gate in [Gate::H(i), Gate::S(i), Gate::H(i)] {
+                apply(rows, gate);
+            }
+        }
+    }
+    reduction
+}
+
+/// Rows of `vectors` that are independent of the rows kept before them, over GF(2)
+fn independent_rows(vectors: impl Iterator<Item = Vec<bool>>) -> Vec<Vec<bool>> {
+    let mut kept = Vec::new();
+    // Echelon copies of the kept rows with their pivot columns
+    let mut echelon: Vec<(usize, Vec<bool>)> = Vec::new();
+    for v in vectors {
+        let mut r = v.clone();
+        for (pivot, e) in &echelon {
+            if r[*pivot] {
+                r.iter_mut().zip(e).for_each(|(x, y)| *x ^= *y);
+            }
+        }
+        if let Some(pivot) = r.iter().position(|&b| b) {
+            echelon.push((pivot, r));
+            kept.push(v);
+        }
+    }
+    kept
+}
+
+/// Instantiate a fixed-width kind on `q` with angles `a`
+pub(crate) fn build_gate(kind: GateKind, q: &[usize], a: &[f64]) -> Gate {
+    match kind {
+        GateKind::X => Gate::X(q[0]),
+        GateKind::Y => Gate::Y(q[0]),
+        GateKind::Z => Gate::Z(q[0]),
+        GateKind::H => Gate::H(q[0]),
+        GateKind::S => Gate::S(q[0]),
+        GateKind::Sdg => Gate::Sdg(q[0]),
+        GateKind::T => Gate::T(q[0]),
+        GateKind::Tdg => Gate::Tdg(q[0]),
+        GateKind::SX => Gate::SX(q[0]),
+        GateKind::SXdg => Gate::SXdg(q[0]),
+        GateKind::RX => Gate::RX(q[0], a[0]),
+        GateKind::RY => Gate::RY(q[0], a[0]),
+        GateKind::RZ => Gate::RZ(q[0], a[0]),
+        GateKind::P => Gate::P(q[0], a[0]),
+        GateKind::U3 => Gate::U3(q[0], a[0], a[1], a[2]),
+        GateKind::CX => Gate::CX(q[0], q[1]),
+        GateKind::CY => Gate::CY(q[0], q[1]),
+        GateKind::CZ => Gate::CZ(q[0], q[1]),
+        GateKind::SWAP => Gate::SWAP(q[0], q[1]),
+        GateKind::ISWAP => Gate::ISWAP(q[0], q[1]),
+        GateKind::SqrtISWAP => Gate::SqrtISWAP(q[0], q[1]),
+        GateKind::ISWAPdg => Gate::ISWAPdg(q[0], q[1]),
+        GateKind::SqrtISWAPdg => Gate::SqrtISWAPdg(q[0], q[1]),
+        GateKind::CRX => Gate::CRX(q[0], q[1], a[0]),
+        GateKind::CRY => Gate::CRY(q[0], q[1], a[0]),
+        GateKind::CRZ => Gate::CRZ(q[0], q[1], a[0]),
+        GateKind::CP => Gate::CP(q[0], q[1], a[0]),
+        GateKind::RXX => Gate::RXX(q[0], q[1], a[0]),
+        GateKind::RYY => Gate::RYY(q[0], q[1], a[0]),
+        GateKind::RZZ => Gate::RZZ(q[0], q[1], a[0]),
+        GateKind::CCX => Gate::CCX(q[0], q[1], q[2]),
+        GateKind::CCZ => Gate::CCZ(q[0], q[1], q[2]),
+        GateKind::CSWAP => Gate::CSWAP(q[0], q[1], q[2]),
+        _ => unreachable!("{:?} has no fixed-width constructor", kind),
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::{simulate_from, states_close};
+    use crate::gates::core::Complex;
+
+    #[test]
+    fn test_qubit_slicing() {
+        let circuit = vec![
+            Gate::H(0), Gate::CX(0, 1), Gate::CX(1, 2), Gate::RZ(2, 0.3),
+            Gate::GlobalPhase(0.1), Gate::CCX(0, 1, 3), Gate::Z(1),
+        ];
+        assert_eq!(gates_on_qubit(&circuit, 1), vec![1, 2, 5, 6]);
+        assert_eq!(gates_on_qubit(&circuit, 4), Vec::<usize>::new());
+
+        // CX(1, 2) and the Toffoli straddle the {0, 1} boundary
+        let sub = subcircuit_on_qubits(&circuit, &[1, 0]);
+        assert_eq!(
+            format!("{:?}", sub),
+            format!("{:?}", vec![Gate::H(0), Gate::CX(0, 1), Gate::GlobalPhase(0.1), Gate::Z(1)])
+        );
+    }
+
+    #[test]
+    fn test_sliding_window_fusion_preserves_unitary() {
+        use crate::analysis::circuit_analysis::unitary_equivalent;
+        use crate::optimization::optimize::fuse_single_qubit_runs;
+
+        let set = [GateKind::H, GateKind::T, GateKind::RZ, GateKind::RX, GateKind::S, GateKind::CX];
+        let original = random_circuit(3, 14, 7, &set);
+        let mut circuit = original.clone();
+        let mut start = 0;
+        while start < circuit.len() {
+            let end = (start + 8).min(circuit.len());
+            let fused = fuse_single_qubit_runs(&extract_window(&circuit, start, end).unwrap());
+            let len = fused.len();
+            circuit = splice_window(&circuit, start, end, fused).unwrap();
+            start += len.max(1);
+        }
+        assert_ne!(circuit, original);
+        assert!(unitary_equivalent(&original, &circuit, 3));
+
+        // Empty windows insert, empty replacements delete
+        let base = vec![Gate::H(0), Gate::X(0), Gate::X(0), Gate::CX(0, 1)];
+        assert_eq!(extract_window(&base, 1, 3), Ok(vec![Gate::X(0), Gate::X(0)]));
+        assert_eq!(splice_window(&base, 1, 3, Vec::new()), Ok(vec![Gate::H(0), Gate::CX(0, 1)]));
+        assert_eq!(splice_window(&base, 2, 2, Vec::new()), Ok(base));
+    }
+
+    #[test]
+    fn test_splice_rejects_foreign_qubits() {
+        let base = vec![Gate::H(0), Gate::X(0), Gate::X(0), Gate::CX(0, 1)];
+        assert_eq!(splice_window(&base, 1, 3, vec![Gate::CZ(0, 1)]), Err(RustiqError::QubitOutsideWindow { qubit: 1 }));
+        assert_eq!(extract_window(&base, 3, 5), Err(RustiqError::WindowOutOfRange { start: 3, end: 5, len: 4 }));
+        assert!(splice_window(&base, 2, 1, Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_compose() {
+        let a = vec![Gate::H(0)];
+        let b = vec![Gate::CX(0, 1)];
+        let c = compose(&a, &b);
+
+        assert_eq!(c.len(), 2);
+        assert!(matches!(c[0], Gate::H(0)));
+        assert!(matches!(c[1], Gate::CX(0, 1)));
+    }
+
+    #[test]
+    fn test_tensor_single_qubit_circuits() {
+        let a = vec![Gate::H(0), Gate::T(0)];
+        let b = vec![Gate::X(0), Gate::RZ(0, 0.5)];
+        let c = tensor(&a, 1, &b);
+
+        assert_eq!(c.len(), 4);
+        assert!(matches!(c[0], Gate::H(0)));
+        assert!(matches!(c[1], Gate::T(0)));
+        assert!(matches!(c[2], Gate::X(1)));
+        assert!(matches!(c[3], Gate::RZ(1, t) if (t - 0.5).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_remap_qubits() {
+        let circuit = vec![Gate::CX(0, 1), Gate::MCX(vec![0, 1], 2)];
+        let remapped = remap_qubits(&circuit, &[2, 0, 1]);
+
+        assert!(matches!(remapped[0], Gate::CX(2, 0)));
+        assert_eq!(remapped[1].qubits(), vec![2, 0, 1]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "both control and target")]
+    fn test_remap_rejects_aliasing() {
+        remap_qubits(&[Gate::CX(0, 1)], &[1, 1]);
+    }
+
+    #[test]
+    fn test_reverse_register() {
+        use crate::simulator::basis_state;
+
+        let qubits = [4, 0, 3, 1, 2];
+        let swaps = reverse_register(&qubits);
+        assert_eq!(swaps, vec![Gate::SWAP(4, 2), Gate::SWAP(0, 1)]);
+        assert!(reverse_register(&[7]).is_empty());
+
+        for input in 0..32usize {
+            // Bit i of the register value sits on qubits[i]
+            let reversed = qubits.iter().enumerate()
+                .filter(|&(i, _)| input >> qubits[qubits.len() - 1 - i] & 1 == 1)
+                .fold(0usize, |acc, (_, &q)| acc | (1 << q));
+            let state = simulate_from(&swaps, &basis_state(5, input)).unwrap();
+            assert!(states_close(&state, &basis_state(5, reversed), 1e-12), "Input {}", input);
+            let twice = simulate_from(&compose(&swaps, &swaps), &basis_state(5, input)).unwrap();
+            assert!(states_close(&twice, &basis_state(5, input), 1e-12));
+        }
+    }
+
+    #[test]
+    fn test_compute_uncompute_restores_scratch() {
+        use crate::simulator::ancillas_restored;
+
+        // Scratch 3 = x₀ ∧ x₁, scratch 4 = x₀ ⊕ x₂ (with a phase on it); the action
+        // reads both into the output qubit 5 and kicks a phase back off scratch 3
+        let compute = vec![Gate::CCX(0, 1, 3), Gate::CX(0, 4), Gate::CX(2, 4), Gate::T(4)];
+        let action = vec![Gate::CCX(3, 4, 5), Gate::CZ(3, 2), Gate::RZ(3, 0.4)];
+        let circuit = compute_uncompute(&compute, &action, &[3, 4]);
+        assert_eq!(circuit.len(), compute.len() + action.len() + compute.len());
+
+        for input in 0..8usize {
+            let prepare: Vec<Gate> = (0..3).filter(|q| input >> q & 1 == 1).map(Gate::X).collect();
+            let full = compose(&compose(&[Gate::H(1)], &prepare), &circuit);
+            assert!(ancillas_restored(&full, &[3, 4], 6), "Input {}", input);
+        }
+        // Without the uncompute the scratch stays dirty
+        let dirty = compose(&[Gate::X(0), Gate::X(1)], &compose(&compute, &action));
+        assert!(!ancillas_restored(&dirty, &[3, 4], 6));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "writes to scratch qubit 3")]
+    fn test_compute_uncompute_rejects_writing_action() {
+        compute_uncompute(&[Gate::CX(0, 3)], &[Gate::CX(1, 3)], &[3]);
+    }
+
+    #[test]
+    fn test_inverse_circuit_is_identity() {
+        let c = vec![
+            Gate::H(0), Gate::SX(1), Gate::T(2), Gate::CX(0, 1),
+            Gate::RY(2, 0.7), Gate::U3(0, 0.3, 1.1, -0.4), Gate::ISWAP(1, 2),
+            Gate::CRZ(2, 0, 0.9), Gate::CCX(0, 1, 2), Gate::RXX(0, 2, 0.25),
+        ];
+        let round_trip = compose(&c, &inverse_circuit(&c));
+
+        // Non-trivial input so phases and amplitudes both matter
+        let input: Vec<Complex> = (0..8)
+            .map(|i| Complex::new(1.0 + i as f64, 0.5 * i as f64))
+            .collect();
+        let norm = input.iter().map(|a| a.norm_sq()).sum::<f64>().sqrt();
+        let input: Vec<Complex> = input.iter().map(|a| *a * (1.0 / norm)).collect();
+
+        let output = simulate_from(&round_trip, &input).unwrap();
+        assert!(states_close(&output, &input, 1e-10));
+    }
+
+    #[test]
+    fn test_every_gate_is_undone_by_its_inverse() {
+        use crate::util::Rng;
+
+        let gates = vec![
+            Gate::X(0), Gate::Y(1), Gate::Z(2), Gate::H(3), Gate::S(0), Gate::Sdg(1), Gate::T(2),
+            Gate::Tdg(3), Gate::SX(1), Gate::SXdg(2), Gate::RX(0, 0.3), Gate::RY(1, -1.2), Gate::RZ(2, 2.5),
+            Gate::P(3, 0.9), Gate::U3(0, 0.4, -1.1, 2.2), Gate::CX(0, 2), Gate::CY(3, 1),
+            Gate::CZ(1, 2), Gate::SWAP(0, 3), Gate::ISWAP(2, 0), Gate::ISWAPdg(1, 3),
+            Gate::SqrtISWAP(3, 1), Gate::SqrtISWAPdg(0, 2), Gate::CRX(1, 3, 0.6),
+            Gate::CRY(2, 1, -0.8), Gate::CRZ(0, 1, 1.3), Gate::CP(3, 2, -2.1), Gate::RXX(0, 1, 0.7),
+            Gate::RYY(3, 0, -0.5), Gate::RZZ(1, 2, 1.9), Gate::CCX(0, 1, 3), Gate::CCZ(2, 3, 0),
+            Gate::CSWAP(1, 0, 2), Gate::MCX(vec![0, 2, 3], 1), Gate::MCZ(vec![1, 3], 0),
+            Gate::MCP(vec![0, 1, 2], 3, 0.45), Gate::GlobalPhase(1.1), Gate::Barrier(vec![0, 1]),
+        ];
+        let mut kinds: Vec<GateKind> = gates.iter().map(|g| g.kind()).collect();
+        kinds.sort();
+        kinds.dedup();
+        // Every unitary kind; Measure, Reset and ClassicalControl are not unitaries
+        assert_eq!(kinds.len(), 38);
+
+        let mut rng = Rng::new(7);
+        let states: Vec<Vec<Complex>> = (0..3)
+            .map(|_| {
+                let raw: Vec<Complex> = (0..16).map(|_| Complex::new(rng.uniform() - 0.5, rng.uniform() - 0.5)).collect();
+                let norm = raw.iter().map(|a| a.norm_sq()).sum::<f64>().sqrt();
+                raw.iter().map(|a| *a * (1.0 / norm)).collect()
+            })
+            .collect();
+
+        for gate in &gates {
+            let pairs = [
+                compose(std::slice::from_ref(gate), &inverse_circuit(std::slice::from_ref(gate))),
+                vec![gate.clone(), gate.inverse()],
+            ];
+            for pair in pairs {
+                for input in &states {
+                    assert!(states_close(&simulate_from(&pair, input).unwrap(), input, 1e-12), "{:?} is not undone by {:?}", gate, pair);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_commuting_reorderings() {
+        let a = vec![
+            Gate::H(2), Gate::RZ(0, 0.3), Gate::CX(2, 3), Gate::T(1), Gate::GlobalPhase(0.2),
+            Gate::CX(0, 1), Gate::Measure(3, 0), Gate::X(0), Gate::ClassicalControl(0, Box::new(Gate::Z(1))),
+        ];
+        let b = vec![
+            Gate::T(1), Gate::GlobalPhase(0.2), Gate::RZ(0, 0.3), Gate::H(2), Gate::CX(0, 1),
+            Gate::X(0), Gate::CX(2, 3), Gate::Measure(3, 0), Gate::ClassicalControl(0, Box::new(Gate::Z(1))),
+        ];
+        assert_ne!(a, b);
+        let (ca, cb) = (canonicalize(&a), canonicalize(&b));
+        assert_eq!(ca, cb);
+        assert_eq!(canonicalize(&ca), ca);
+        assert!(matches!(ca.last(), Some(Gate::GlobalPhase(_))));
+
+        // Gates sharing a qubit keep their order
+        let unitary: Vec<Gate> = a[..6].to_vec();
+        let canonical = canonicalize(&unitary);
+        let input = crate::simulator::basis_state(4, 0b0101);
+        assert!(states_close(&simulate_from(&canonical, &input).unwrap(), &simulate_from(&unitary, &input).unwrap(), 1e-12));
+        assert_ne!(canonicalize(&[Gate::H(0), Gate::CX(0, 1)]), canonicalize(&[Gate::CX(0, 1), Gate::H(0)]));
+    }
+
+    #[test]
+    fn test_canonicalize_sorts_diagonal_runs() {
+        let a = vec![Gate::H(0), Gate::RZ(0, 0.3), Gate::CZ(0, 1), Gate::T(1), Gate::CP(1, 0, 0.7), Gate::H(0)];
+        let b = vec![Gate::H(0), Gate::T(1), Gate::CP(1, 0, 0.7), Gate::CZ(0, 1), Gate::RZ(0, 0.3), Gate::H(0)];
+        let (ca, cb) = (canonicalize(&a), canonicalize(&b));
+        assert_eq!(ca, cb);
+        assert_eq!(canonicalize(&ca), ca);
+        let input = crate::simulator::basis_state(2, 0b10);
+        assert!(states_close(&simulate_from(&ca, &input).unwrap(), &simulate_from(&a, &input).unwrap(), 1e-12));
+
+        // The H gates bound the run, and a classically controlled phase
+        // still waits for the measurement that feeds it
+        let on_zero: Vec<&Gate> = ca.iter().filter(|g| g.qubits().contains(&0)).collect();
+        assert!(matches!((on_zero.first(), on_zero.last()), (Some(Gate::H(0)), Some(Gate::H(0)))));
+        let fed = vec![Gate::Measure(1, 0), Gate::ClassicalControl(0, Box::new(Gate::Z(0))), Gate::S(0)];
+        let reordered = canonicalize(&fed);
+        let measured = reordered.iter().position(|g| matches!(g, Gate::Measure(..))).unwrap();
+        let controlled = reordered.iter().position(|g| matches!(g, Gate::ClassicalControl(..))).unwrap();
+        assert!(measured < controlled);
+    }
+
+    #[test]
+    fn test_cnot_fanout_copies_source() {
+        use crate::analysis::circuit_analysis::circuit_depth;
+        use crate::simulator::basis_state;
+
+        let targets = [1, 2, 3, 4, 5, 6, 7];
+        let fanout = cnot_fanout(0, &targets);
+        assert_eq!(fanout.len(), 2 * targets.len() - 1);
+        assert_eq!(circuit_depth(&fanout), 9);
+
+        // Logarithmic beats the sequential n for wide fan-outs
+        let wide: Vec<usize> = (1..64).collect();
+        assert_eq!(circuit_depth(&cnot_fanout(0, &wide)), 21);
+
+        // Every basis input: targets flip exactly when the source is set
+        for input in 0..256usize {
+            let expected = if input & 1 == 1 { input ^ 0b1111_1110 } else { input };
+            let output = simulate_from(&fanout, &basis_state(8, input)).unwrap();
+            assert!(states_close(&output, &basis_state(8, expected), 1e-10), "Input {}", input);
+        }
+    }
+
+    #[test]
+    fn test_random_circuit_is_reproducible() {
+        use crate::analysis::circuit_analysis::{circuit_depth, validate_width};
+
+        let set = [GateKind::H, GateKind::T, GateKind::RZ, GateKind::U3, GateKind::CX, GateKind::RZZ, GateKind::CCX];
+        let a = random_circuit(5, 12, 42, &set);
+        let b = random_circuit(5, 12, 42, &set);
+        let c = random_circuit(5, 12, 43, &set);
+
+        assert_eq!(format!("{:?}", a), format!("{:?}", b));
+        assert_ne!(format!("{:?}", a), format!("{:?}", c));
+        assert!(validate_width(&a, 5).is_ok());
+        assert!(a.iter().all(|g| set.contains(&g.kind())));
+        assert!(circuit_depth(&a) <= 12);
+    }
+
+    #[test]
+    fn test_random_circuit_round_trips_through_inverse() {
+        let set = [GateKind::H, GateKind::S, GateKind::RY, GateKind::P, GateKind::CZ, GateKind::CRX, GateKind::CSWAP];
+        for seed in 0..10 {
+            let c = random_circuit(4, 8, seed, &set);
+            let round_trip = compose(&c, &inverse_circuit(&c));
+            let input = crate::simulator::basis_state(4, 5);
+            assert!(states_close(&simulate_from(&round_trip, &input).unwrap(), &input, 1e-9), "Seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn test_random_clifford_covers_single_qubit_group() {
+        use crate::analysis::circuit_analysis::circuit_unitary;
+        use std::collections::HashMap;
+
+        // Single-qubit unitary with the phase of its first nonzero entry removed
+        let key = |circuit: &[Gate]| {
+            let u = circuit_unitary(circuit, 1);
+            let pivot = *u.iter().flatten().find(|z| z.norm() > 0.1).unwrap();
+            let unphase = pivot.conj() * (1.0 / pivot.norm());
+            u.iter().flatten().flat_map(|&z| {
+                let z = z * unphase;
+                [(z.re * 1e6).round() as i64, (z.im * 1e6).round() as i64]
+            }).collect::<Vec<_>>()
+        };
+
+        let mut counts: HashMap<Vec<i64>, usize> = HashMap::new();
+        for seed in 0..2400 {
+            let c = random_clifford(1, seed);
+            assert!(c.iter().all(|g| g.is_clifford()));
+            *counts.entry(key(&c)).or_default() += 1;
+        }
+        assert_eq!(counts.len(), 24);
+        // 100 expected per element
+        assert!(counts.values().all(|&k| (50..150).contains(&k)), "{:?}", counts.values());
+
+        for seed in 0..20 {
+            let c = random_clifford(4, seed);
+            assert!(c.iter().all(|g| g.is_clifford() && g.qubits().iter().all(|&q| q < 4)));
+            assert_eq!(c, random_clifford(4, seed));
+        }
+
+        // The reduction really brings every sampled tableau back to the identity
+        let identity: Vec<Vec<bool>> = (0..8).map(|k| {
+            let (q, z) = (k / 2, k % 2);
+            (0..8).map(|j| j == q + 4 * z).collect()
+        }).collect();
+        let mut rng = Rng::new(3);
+        for _ in 0..50 {
+            let mut rows = random_symplectic(4, &mut rng);
+            reduce_tableau(&mut rows, 4);
+            assert_eq!(rows, identity);
+        }
+    }
+
+    #[test]
+    fn test_basis_rotations_project_onto_eigenstates() {
+        use crate::simulator::{inner_product, probabilities, simulate};
+
+        let psi = simulate(&random_circuit(2, 6, 3, &[GateKind::U3, GateKind::CX]), 2).unwrap();
+        let h = 1.0 / 2f64.sqrt();
+        // Single-qubit eigenbases indexed by outcome bit: X = {|+⟩, |−⟩}, Y = {|+i⟩, |−i⟩}
+        let x = [[Complex::new(h, 0.0), Complex::new(h, 0.0)], [Complex::new(h, 0.0), Complex::new(-h, 0.0)]];
+        let y = [[Complex::new(h, 0.0), Complex::new(0.0, h)], [Complex::new(h, 0.0), Complex::new(0.0, -h)]];
+
+        for (rotation, basis) in [(measure_x_basis(&[0, 1]), x), (measure_y_basis(&[0, 1]), y)] {
+            let measured = probabilities(&simulate_from(&rotation, &psi).unwrap());
+            for (outcome, &p) in measured.iter().enumerate() {
+                // |b₁⟩ ⊗ |b₀⟩ in little-endian order
+                let (b0, b1) = (&basis[outcome & 1], &basis[outcome >> 1]);
+                let eigen: Vec<Complex> = (0..4).map(|i| b1[i >> 1] * b0[i & 1]).collect();
+                assert!((p - inner_product(&eigen, &psi).norm_sq()).abs() < 1e-10, "Outcome {}", outcome);
+            }
+        }
+
+        for (measure, prepare) in [(measure_x_basis(&[0, 1]), prepare_x_basis(&[0, 1])), (measure_y_basis(&[0, 1]), prepare_y_basis(&[0, 1]))] {
+            let round_trip = simulate_from(&compose(&measure, &prepare), &psi).unwrap();
+            assert!(states_close(&round_trip, &psi, 1e-10));
+        }
+    }
+}
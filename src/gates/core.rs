@@ -2,9 +2,10 @@
 //!
 //! This module provides implementations of standard quantum gates including:
 //! - Single-qubit gates (X, Y, Z, H, S, T, etc.)
-//! - Two-qubit gates (CNOT, CZ, SWAP, iSWAP, etc.)
+//! - Two-qubit gates (CNOT, CZ, SWAP, iSWAP, √iSWAP, etc.)
 //! - Multi-qubit gates (Toffoli, Fredkin, etc.)
 //! - Parametric gates (RX, RY, RZ, etc.)
+//! - Single-qubit Pauli operators (`PauliOp`) for Pauli strings
 //!
 //! ## 🎯 Why is this used?
 //! This module serves as the foundational "instruction set" for the entire Quantic-Rust library. 
@@ -34,7 +35,45 @@
 //! - **Correctness**: All matrices are verified to be unitary within double-precision 
 //!   floating-point limits.
 
-use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, FRAC_PI_4};
+
+/// Default absolute tolerance for numeric comparisons of amplitudes and angles
+///
+/// Routines that compare floats take it implicitly and offer a `_with_tol`
+/// variant; pass a smaller value there when amplitudes are intentionally tiny.
+pub const DEFAULT_TOL: f64 = 1e-10;
+
+/// Which end of a qubit list holds the least significant bit of a register
+///
+/// Taken by the `_with_endianness` variants of the adders and the QFT. The
+/// plain `qft` / `inverse_qft` are `Big`; the deprecated `draper_adder` mixes
+/// both orders.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// `qubits[0]` is the least significant bit
+    #[default]
+    Little,
+    /// `qubits[0]` is the most significant bit
+    Big,
+}
+
+impl Endianness {
+    /// `qubits` reordered least significant bit first
+    pub fn lsb_first(self, qubits: &[usize]) -> Vec<usize> {
+        match self {
+            Endianness::Little => qubits.to_vec(),
+            Endianness::Big => qubits.iter().rev().copied().collect(),
+        }
+    }
+
+    /// `qubits` reordered most significant bit first
+    pub fn msb_first(self, qubits: &[usize]) -> Vec<usize> {
+        match self {
+            Endianness::Little => qubits.iter().rev().copied().collect(),
+            Endianness::Big => qubits.to_vec(),
+        }
+    }
+}
 
 /// Represents a 2x2 complex matrix for single-qubit gates
 #[derive(Clone, Debug)]
@@ -48,6 +87,12 @@ pub struct GateMatrix4x4 {
     pub data: [[Complex; 4]; 4],
 }
 
+/// Represents an 8x8 complex matrix for three-qubit gates
+#[derive(Clone, Debug)]
+pub struct GateMatrix8x8 {
+    pub data: [[Complex; 8]; 8],
+}
+
 /// Complex number representation
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Complex {
@@ -65,6 +110,7 @@ impl Complex {
         Complex { re, im }
     }
 
+    /// r·e^(iθ)
     pub fn from_polar(r: f64, theta: f64) -> Self {
         Complex {
             re: r * theta.cos(),
@@ -83,6 +129,21 @@ impl Complex {
     pub fn norm(&self) -> f64 {
         self.norm_sq().sqrt()
     }
+
+    /// |z|², same as `norm_sq` (the `num-complex` spelling)
+    pub fn norm_sqr(&self) -> f64 {
+        self.norm_sq()
+    }
+
+    /// |z|, same as `norm`
+    pub fn abs(&self) -> f64 {
+        self.norm()
+    }
+
+    /// e^z = e^re · (cos im + i sin im)
+    pub fn exp(&self) -> Self {
+        Complex::from_polar(self.re.exp(), self.im)
+    }
 }
 
 impl std::ops::Add for Complex {
@@ -254,6 +315,18 @@ pub fn sqrt_y() -> GateMatrix2x2 {
     }
 }
 
+/// √X† gate: (√X†)·√X = I
+pub fn sqrt_x_dagger() -> GateMatrix2x2 {
+    let half = Complex::new(0.5, 0.0);
+    let half_i = Complex::new(0.0, 0.5);
+    GateMatrix2x2 {
+        data: [
+            [half - half_i, half + half_i],
+            [half + half_i, half - half_i],
+        ],
+    }
+}
+
 fn half_i() -> Complex {
     Complex::new(0.0, 0.5)
 }
@@ -412,6 +485,48 @@ pub fn iswap() -> GateMatrix4x4 {
     }
 }
 
+/// iSWAP† gate: |01⟩ → -i|10⟩, |10⟩ → -i|01⟩
+pub fn iswap_dagger() -> GateMatrix4x4 {
+    let mi = Complex::new(0.0, -1.0);
+    GateMatrix4x4 {
+        data: [
+            [Complex::ONE, Complex::ZERO, Complex::ZERO, Complex::ZERO],
+            [Complex::ZERO, Complex::ZERO, mi, Complex::ZERO],
+            [Complex::ZERO, mi, Complex::ZERO, Complex::ZERO],
+            [Complex::ZERO, Complex::ZERO, Complex::ZERO, Complex::ONE],
+        ],
+    }
+}
+
+/// √iSWAP gate: (√iSWAP)² = iSWAP
+/// |01⟩ → (|01⟩ + i|10⟩)/√2, |10⟩ → (i|01⟩ + |10⟩)/√2
+pub fn sqrt_iswap() -> GateMatrix4x4 {
+    let r = Complex::new(FRAC_1_SQRT_2, 0.0);
+    let ri = Complex::new(0.0, FRAC_1_SQRT_2);
+    GateMatrix4x4 {
+        data: [
+            [Complex::ONE, Complex::ZERO, Complex::ZERO, Complex::ZERO],
+            [Complex::ZERO, r, ri, Complex::ZERO],
+            [Complex::ZERO, ri, r, Complex::ZERO],
+            [Complex::ZERO, Complex::ZERO, Complex::ZERO, Complex::ONE],
+        ],
+    }
+}
+
+/// √iSWAP† gate: |01⟩ → (|01⟩ - i|10⟩)/√2, |10⟩ → (-i|01⟩ + |10⟩)/√2
+pub fn sqrt_iswap_dagger() -> GateMatrix4x4 {
+    let r = Complex::new(FRAC_1_SQRT_2, 0.0);
+    let mri = Complex::new(0.0, -FRAC_1_SQRT_2);
+    GateMatrix4x4 {
+        data: [
+            [Complex::ONE, Complex::ZERO, Complex::ZERO, Complex::ZERO],
+            [Complex::ZERO, r, mri, Complex::ZERO],
+            [Complex::ZERO, mri, r, Complex::ZERO],
+            [Complex::ZERO, Complex::ZERO, Complex::ZERO, Complex::ONE],
+        ],
+    }
+}
+
 /// √SWAP gate
 pub fn sqrt_swap() -> GateMatrix4x4 {
     let half = Complex::new(0.5, 0.0);
@@ -548,6 +663,38 @@ pub fn rzz(theta: f64) -> GateMatrix4x4 {
     }
 }
 
+// ============================================================================
+// THREE-QUBIT GATES
+// ============================================================================
+
+/// Basis-state permutation |i⟩ → |perm[i]⟩
+fn permutation_8x8(perm: [usize; 8]) -> GateMatrix8x8 {
+    let mut data = [[Complex::ZERO; 8]; 8];
+    for (col, &row) in perm.iter().enumerate() {
+        data[row][col] = Complex::ONE;
+    }
+    GateMatrix8x8 { data }
+}
+
+/// Toffoli (CCX) gate - flips the target when both controls are 1
+/// |110⟩ ↔ |111⟩
+pub fn toffoli() -> GateMatrix8x8 {
+    permutation_8x8([0, 1, 2, 3, 4, 5, 7, 6])
+}
+
+/// CCZ gate - |111⟩ → -|111⟩
+pub fn ccz() -> GateMatrix8x8 {
+    let mut m = permutation_8x8([0, 1, 2, 3, 4, 5, 6, 7]);
+    m.data[7][7] = Complex::new(-1.0, 0.0);
+    m
+}
+
+/// Fredkin (CSWAP) gate - swaps the last two qubits when the first is 1
+/// |101⟩ ↔ |110⟩
+pub fn fredkin() -> GateMatrix8x8 {
+    permutation_8x8([0, 1, 2, 3, 4, 6, 5, 7])
+}
+
 // ============================================================================
 // MULTI-QUBIT GATES (represented as gate descriptors)
 // ============================================================================
@@ -565,6 +712,7 @@ pub enum Gate {
     T(usize),
     Tdg(usize),
     SX(usize),
+    SXdg(usize),
     
     // Parametric single-qubit
     RX(usize, f64),
@@ -579,6 +727,9 @@ pub enum Gate {
     CZ(usize, usize),
     SWAP(usize, usize),
     ISWAP(usize, usize),
+    ISWAPdg(usize, usize),
+    SqrtISWAP(usize, usize),
+    SqrtISWAPdg(usize, usize),
     
     // Controlled parametric
     CRX(usize, usize, f64),
@@ -600,6 +751,15 @@ pub enum Gate {
     MCX(Vec<usize>, usize),       // Multi-controlled X
     MCZ(Vec<usize>, usize),       // Multi-controlled Z
     MCP(Vec<usize>, usize, f64),  // Multi-controlled Phase
+    
+    // Scalar e^(iθ) on the whole register; relative once controlled
+    GlobalPhase(f64),
+
+    // Scheduling, measurement and classical feed-forward
+    Barrier(Vec<usize>),                 // No-op fence over the listed qubits
+    Measure(usize, usize),               // Qubit → classical bit
+    Reset(usize),                        // Measure, discard, flip back to |0⟩
+    ClassicalControl(usize, Box<Gate>),  // Apply gate if classical bit is 1
 }
 
 impl Gate {
@@ -608,11 +768,12 @@ impl Gate {
         match self {
             Gate::X(q) | Gate::Y(q) | Gate::Z(q) | Gate::H(q) |
             Gate::S(q) | Gate::Sdg(q) | Gate::T(q) | Gate::Tdg(q) |
-            Gate::SX(q) | Gate::RX(q, _) | Gate::RY(q, _) | Gate::RZ(q, _) |
+            Gate::SX(q) | Gate::SXdg(q) | Gate::RX(q, _) | Gate::RY(q, _) | Gate::RZ(q, _) |
             Gate::P(q, _) | Gate::U3(q, _, _, _) => vec![*q],
             
             Gate::CX(c, t) | Gate::CY(c, t) | Gate::CZ(c, t) |
-            Gate::SWAP(c, t) | Gate::ISWAP(c, t) |
+            Gate::SWAP(c, t) | Gate::ISWAP(c, t) | Gate::ISWAPdg(c, t) |
+            Gate::SqrtISWAP(c, t) | Gate::SqrtISWAPdg(c, t) |
             Gate::CRX(c, t, _) | Gate::CRY(c, t, _) | Gate::CRZ(c, t, _) |
             Gate::CP(c, t, _) |
             Gate::RXX(c, t, _) | Gate::RYY(c, t, _) | Gate::RZZ(c, t, _) => vec![*c, *t],
@@ -627,20 +788,143 @@ impl Gate {
                 qubits.push(*target);
                 qubits
             }
+            
+            Gate::GlobalPhase(_) => vec![],
+
+            Gate::Barrier(qubits) => qubits.clone(),
+            Gate::Measure(q, _) | Gate::Reset(q) => vec![*q],
+            Gate::ClassicalControl(_, gate) => gate.qubits(),
         }
     }
-    
+
+    /// Return the same gate with every qubit index passed through `f`
+    pub fn map_qubits(&self, f: impl Fn(usize) -> usize) -> Gate {
+        match self {
+            Gate::X(q) => Gate::X(f(*q)),
+            Gate::Y(q) => Gate::Y(f(*q)),
+            Gate::Z(q) => Gate::Z(f(*q)),
+            Gate::H(q) => Gate::H(f(*q)),
+            Gate::S(q) => Gate::S(f(*q)),
+            Gate::Sdg(q) => Gate::Sdg(f(*q)),
+            Gate::T(q) => Gate::T(f(*q)),
+            Gate::Tdg(q) => Gate::Tdg(f(*q)),
+            Gate::SX(q) => Gate::SX(f(*q)),
+            Gate::SXdg(q) => Gate::SXdg(f(*q)),
+            Gate::RX(q, t) => Gate::RX(f(*q), *t),
+            Gate::RY(q, t) => Gate::RY(f(*q), *t),
+            Gate::RZ(q, t) => Gate::RZ(f(*q), *t),
+            Gate::P(q, t) => Gate::P(f(*q), *t),
+            Gate::U3(q, a, b, c) => Gate::U3(f(*q), *a, *b, *c),
+
+            Gate::CX(c, t) => Gate::CX(f(*c), f(*t)),
+            Gate::CY(c, t) => Gate::CY(f(*c), f(*t)),
+            Gate::CZ(c, t) => Gate::CZ(f(*c), f(*t)),
+            Gate::SWAP(a, b) => Gate::SWAP(f(*a), f(*b)),
+            Gate::ISWAP(a, b) => Gate::ISWAP(f(*a), f(*b)),
+            Gate::ISWAPdg(a, b) => Gate::ISWAPdg(f(*a), f(*b)),
+            Gate::SqrtISWAP(a, b) => Gate::SqrtISWAP(f(*a), f(*b)),
+            Gate::SqrtISWAPdg(a, b) => Gate::SqrtISWAPdg(f(*a), f(*b)),
+            Gate::CRX(c, t, a) => Gate::CRX(f(*c), f(*t), *a),
+            Gate::CRY(c, t, a) => Gate::CRY(f(*c), f(*t), *a),
+            Gate::CRZ(c, t, a) => Gate::CRZ(f(*c), f(*t), *a),
+            Gate::CP(c, t, a) => Gate::CP(f(*c), f(*t), *a),
+            Gate::RXX(a, b, t) => Gate::RXX(f(*a), f(*b), *t),
+            Gate::RYY(a, b, t) => Gate::RYY(f(*a), f(*b), *t),
+            Gate::RZZ(a, b, t) => Gate::RZZ(f(*a), f(*b), *t),
+
+            Gate::CCX(c1, c2, t) => Gate::CCX(f(*c1), f(*c2), f(*t)),
+            Gate::CCZ(c1, c2, t) => Gate::CCZ(f(*c1), f(*c2), f(*t)),
+            Gate::CSWAP(c, a, b) => Gate::CSWAP(f(*c), f(*a), f(*b)),
+
+            Gate::MCX(controls, t) => Gate::MCX(controls.iter().map(|&c| f(c)).collect(), f(*t)),
+            Gate::MCZ(controls, t) => Gate::MCZ(controls.iter().map(|&c| f(c)).collect(), f(*t)),
+            Gate::MCP(controls, t, a) => {
+                Gate::MCP(controls.iter().map(|&c| f(c)).collect(), f(*t), *a)
+            }
+            Gate::GlobalPhase(theta) => Gate::GlobalPhase(*theta),
+
+            Gate::Barrier(qubits) => Gate::Barrier(qubits.iter().map(|&q| f(q)).collect()),
+            Gate::Measure(q, c) => Gate::Measure(f(*q), *c),
+            Gate::Reset(q) => Gate::Reset(f(*q)),
+            Gate::ClassicalControl(c, gate) => Gate::ClassicalControl(*c, Box::new(gate.map_qubits(f))),
+        }
+    }
+
+    /// 2x2 unitary of a single-qubit gate, `None` for any other arity
+    pub fn matrix_2x2(&self) -> Option<GateMatrix2x2> {
+        let m = match self {
+            Gate::X(_) => pauli_x(),
+            Gate::Y(_) => pauli_y(),
+            Gate::Z(_) => pauli_z(),
+            Gate::H(_) => hadamard(),
+            Gate::S(_) => s_gate(),
+            Gate::Sdg(_) => s_dagger(),
+            Gate::T(_) => t_gate(),
+            Gate::Tdg(_) => t_dagger(),
+            Gate::SX(_) => sqrt_x(),
+            Gate::SXdg(_) => sqrt_x_dagger(),
+            Gate::RX(_, t) => rx(*t),
+            Gate::RY(_, t) => ry(*t),
+            Gate::RZ(_, t) => rz(*t),
+            Gate::P(_, t) => phase_gate(*t),
+            Gate::U3(_, theta, phi, lambda) => u3(*theta, *phi, *lambda),
+            _ => return None,
+        };
+        Some(m)
+    }
+
+    /// 4x4 unitary of a two-qubit gate, `None` for any other arity
+    ///
+    /// The first-listed qubit is the most significant bit of the row index.
+    pub fn matrix_4x4(&self) -> Option<GateMatrix4x4> {
+        let m = match self {
+            Gate::CX(_, _) => cnot(),
+            Gate::CY(_, _) => cy(),
+            Gate::CZ(_, _) => cz(),
+            Gate::SWAP(_, _) => swap(),
+            Gate::ISWAP(_, _) => iswap(),
+            Gate::ISWAPdg(_, _) => iswap_dagger(),
+            Gate::SqrtISWAP(_, _) => sqrt_iswap(),
+            Gate::SqrtISWAPdg(_, _) => sqrt_iswap_dagger(),
+            Gate::CRX(_, _, t) => crx(*t),
+            Gate::CRY(_, _, t) => cry(*t),
+            Gate::CRZ(_, _, t) => crz(*t),
+            Gate::CP(_, _, t) => cp(*t),
+            Gate::RXX(_, _, t) => rxx(*t),
+            Gate::RYY(_, _, t) => ryy(*t),
+            Gate::RZZ(_, _, t) => rzz(*t),
+            _ => return None,
+        };
+        Some(m)
+    }
+
+    /// 8x8 unitary of a three-qubit gate, `None` for any other arity
+    ///
+    /// Same ordering as `matrix_4x4`: the first-listed qubit is the most
+    /// significant bit of the row index.
+    pub fn matrix_8x8(&self) -> Option<GateMatrix8x8> {
+        let m = match self {
+            Gate::CCX(_, _, _) => toffoli(),
+            Gate::CCZ(_, _, _) => ccz(),
+            Gate::CSWAP(_, _, _) => fredkin(),
+            _ => return None,
+        };
+        Some(m)
+    }
+
     /// Check if gate is a Clifford gate
     pub fn is_clifford(&self) -> bool {
-        match self {
+        matches!(
+            self,
             Gate::X(_) | Gate::Y(_) | Gate::Z(_) | Gate::H(_) |
             Gate::S(_) | Gate::Sdg(_) | Gate::CX(_, _) | Gate::CY(_, _) |
-            Gate::CZ(_, _) | Gate::SWAP(_, _) => true,
-            _ => false,
-        }
+            Gate::CZ(_, _) | Gate::SWAP(_, _)
+        )
     }
     
     /// Get the inverse of this gate
+    ///
+    /// Panics on `Measure` and `Reset`, which are irreversible.
     pub fn inverse(&self) -> Gate {
         match self {
             Gate::X(q) => Gate::X(*q),
@@ -651,7 +935,12 @@ impl Gate {
             Gate::Sdg(q) => Gate::S(*q),
             Gate::T(q) => Gate::Tdg(*q),
             Gate::Tdg(q) => Gate::T(*q),
-            Gate::SX(q) => Gate::SX(*q), // SX† = SX^3, simplified
+            Gate::SX(q) => Gate::SXdg(*q),
+            Gate::SXdg(q) => Gate::SX(*q),
+            Gate::ISWAP(a, b) => Gate::ISWAPdg(*a, *b),
+            Gate::ISWAPdg(a, b) => Gate::ISWAP(*a, *b),
+            Gate::SqrtISWAP(a, b) => Gate::SqrtISWAPdg(*a, *b),
+            Gate::SqrtISWAPdg(a, b) => Gate::SqrtISWAP(*a, *b),
             Gate::RX(q, theta) => Gate::RX(*q, -*theta),
             Gate::RY(q, theta) => Gate::RY(*q, -*theta),
             Gate::RZ(q, theta) => Gate::RZ(*q, -*theta),
@@ -661,7 +950,6 @@ impl Gate {
             Gate::CY(c, t) => Gate::CY(*c, *t),
             Gate::CZ(c, t) => Gate::CZ(*c, *t),
             Gate::SWAP(a, b) => Gate::SWAP(*a, *b),
-            Gate::ISWAP(a, b) => Gate::ISWAP(*a, *b), // Simplified
             Gate::CRX(c, t, theta) => Gate::CRX(*c, *t, -*theta),
             Gate::CRY(c, t, theta) => Gate::CRY(*c, *t, -*theta),
             Gate::CRZ(c, t, theta) => Gate::CRZ(*c, *t, -*theta),
@@ -675,6 +963,192 @@ impl Gate {
             Gate::MCX(controls, target) => Gate::MCX(controls.clone(), *target),
             Gate::MCZ(controls, target) => Gate::MCZ(controls.clone(), *target),
             Gate::MCP(controls, target, phi) => Gate::MCP(controls.clone(), *target, -*phi),
+            Gate::GlobalPhase(theta) => Gate::GlobalPhase(-*theta),
+            Gate::Barrier(qubits) => Gate::Barrier(qubits.clone()),
+            Gate::Measure(_, _) => panic!("Measurement is not invertible"),
+            Gate::Reset(_) => panic!("Reset is not invertible"),
+            Gate::ClassicalControl(c, gate) => Gate::ClassicalControl(*c, Box::new(gate.inverse())),
+        }
+    }
+}
+
+// ============================================================================
+// PAULI OPERATORS
+// ============================================================================
+
+/// Pauli operator for Hamiltonian terms, stabilizers and Pauli strings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauliOp {
+    I,  // Identity
+    X,  // Pauli X
+    Y,  // Pauli Y
+    Z,  // Pauli Z
+}
+
+// ============================================================================
+// GATE KINDS
+// ============================================================================
+
+/// Parameter-free tag for each `Gate` variant
+///
+/// Used wherever a gate *type* is needed without qubits or angles: gate sets
+/// for random circuit generation, basis lists for transpilation, counts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum GateKind {
+    X, Y, Z, H, S, Sdg, T, Tdg, SX, SXdg,
+    RX, RY, RZ, P, U3,
+    CX, CY, CZ, SWAP, ISWAP, ISWAPdg, SqrtISWAP, SqrtISWAPdg,
+    CRX, CRY, CRZ, CP,
+    RXX, RYY, RZZ,
+    CCX, CCZ, CSWAP,
+    MCX, MCZ, MCP,
+    GlobalPhase,
+    Barrier, Measure, ClassicalControl, Reset,
+}
+
+impl GateKind {
+    /// Number of qubits the gate acts on, `None` for variable-width kinds
+    pub fn arity(&self) -> Option<usize> {
+        use GateKind::*;
+        match self {
+            X | Y | Z | H | S | Sdg | T | Tdg | SX | SXdg |
+            RX | RY | RZ | P | U3 | Measure | Reset => Some(1),
+            CX | CY | CZ | SWAP | ISWAP | ISWAPdg | SqrtISWAP | SqrtISWAPdg | CRX | CRY | CRZ | CP |
+            RXX | RYY | RZZ => Some(2),
+            CCX | CCZ | CSWAP => Some(3),
+            GlobalPhase => Some(0),
+            MCX | MCZ | MCP | Barrier | ClassicalControl => None,
+        }
+    }
+
+    /// Display name used in reports; lowercased it is the OpenQASM identifier
+    pub fn name(&self) -> &'static str {
+        use GateKind::*;
+        match self {
+            X => "X", Y => "Y", Z => "Z", H => "H", S => "S", Sdg => "Sdg",
+            T => "T", Tdg => "Tdg", SX => "SX", SXdg => "SXdg",
+            RX => "RX", RY => "RY", RZ => "RZ", P => "P", U3 => "U3",
+            CX => "CX", CY => "CY", CZ => "CZ", SWAP => "SWAP", ISWAP => "iSWAP",
+            ISWAPdg => "iSWAPdg",
+            SqrtISWAP => "SqrtISWAP",
+            SqrtISWAPdg => "SqrtISWAPdg",
+            CRX => "CRX", CRY => "CRY", CRZ => "CRZ", CP => "CP",
+            RXX => "RXX", RYY => "RYY", RZZ => "RZZ",
+            CCX => "CCX", CCZ => "CCZ", CSWAP => "CSWAP",
+            MCX => "MCX", MCZ => "MCZ", MCP => "MCP",
+            GlobalPhase => "GlobalPhase",
+            Barrier => "Barrier", Measure => "Measure", ClassicalControl => "ClassicalControl",
+            Reset => "Reset",
+        }
+    }
+
+    /// Number of real angle parameters
+    pub fn num_params(&self) -> usize {
+        use GateKind::*;
+        match self {
+            RX | RY | RZ | P | CRX | CRY | CRZ | CP |
+            RXX | RYY | RZZ | MCP | GlobalPhase => 1,
+            U3 => 3,
+            _ => 0,
+        }
+    }
+}
+
+impl Gate {
+    /// The parameter-free kind of this gate
+    pub fn kind(&self) -> GateKind {
+        match self {
+            Gate::X(_) => GateKind::X,
+            Gate::Y(_) => GateKind::Y,
+            Gate::Z(_) => GateKind::Z,
+            Gate::H(_) => GateKind::H,
+            Gate::S(_) => GateKind::S,
+            Gate::Sdg(_) => GateKind::Sdg,
+            Gate::T(_) => GateKind::T,
+            Gate::Tdg(_) => GateKind::Tdg,
+            Gate::SX(_) => GateKind::SX,
+            Gate::SXdg(_) => GateKind::SXdg,
+            Gate::RX(_, _) => GateKind::RX,
+            Gate::RY(_, _) => GateKind::RY,
+            Gate::RZ(_, _) => GateKind::RZ,
+            Gate::P(_, _) => GateKind::P,
+            Gate::U3(_, _, _, _) => GateKind::U3,
+            Gate::CX(_, _) => GateKind::CX,
+            Gate::CY(_, _) => GateKind::CY,
+            Gate::CZ(_, _) => GateKind::CZ,
+            Gate::SWAP(_, _) => GateKind::SWAP,
+            Gate::ISWAP(_, _) => GateKind::ISWAP,
+            Gate::ISWAPdg(_, _) => GateKind::ISWAPdg,
+            Gate::SqrtISWAP(_, _) => GateKind::SqrtISWAP,
+            Gate::SqrtISWAPdg(_, _) => GateKind::SqrtISWAPdg,
+            Gate::CRX(_, _, _) => GateKind::CRX,
+            Gate::CRY(_, _, _) => GateKind::CRY,
+            Gate::CRZ(_, _, _) => GateKind::CRZ,
+            Gate::CP(_, _, _) => GateKind::CP,
+            Gate::RXX(_, _, _) => GateKind::RXX,
+            Gate::RYY(_, _, _) => GateKind::RYY,
+            Gate::RZZ(_, _, _) => GateKind::RZZ,
+            Gate::CCX(_, _, _) => GateKind::CCX,
+            Gate::CCZ(_, _, _) => GateKind::CCZ,
+            Gate::CSWAP(_, _, _) => GateKind::CSWAP,
+            Gate::MCX(_, _) => GateKind::MCX,
+            Gate::MCZ(_, _) => GateKind::MCZ,
+            Gate::MCP(_, _, _) => GateKind::MCP,
+            Gate::GlobalPhase(_) => GateKind::GlobalPhase,
+            Gate::Barrier(_) => GateKind::Barrier,
+            Gate::Measure(_, _) => GateKind::Measure,
+            Gate::ClassicalControl(_, _) => GateKind::ClassicalControl,
+            Gate::Reset(_) => GateKind::Reset,
+        }
+    }
+
+    /// Angle parameters in constructor order (empty for fixed gates)
+    pub fn params(&self) -> Vec<f64> {
+        match self {
+            Gate::RX(_, t) | Gate::RY(_, t) | Gate::RZ(_, t) | Gate::P(_, t) |
+            Gate::CRX(_, _, t) | Gate::CRY(_, _, t) | Gate::CRZ(_, _, t) | Gate::CP(_, _, t) |
+            Gate::RXX(_, _, t) | Gate::RYY(_, _, t) | Gate::RZZ(_, _, t) |
+            Gate::MCP(_, _, t) | Gate::GlobalPhase(t) => vec![*t],
+            Gate::U3(_, theta, phi, lambda) => vec![*theta, *phi, *lambda],
+            _ => Vec::new(),
+        }
+    }
+
+    fn param_bits(&self) -> Vec<u64> {
+        self.params().iter().map(|p| p.to_bits()).collect()
+    }
+}
+
+/// Structural equality: same variant, qubits and bits, angles compared bitwise
+///
+/// Bitwise angles make this a true equivalence consistent with `Hash` (a NaN
+/// angle equals itself), at the price of 0.0 ≠ −0.0. Use
+/// `structurally_equivalent_with_tol` for tolerance-based comparison.
+impl PartialEq for Gate {
+    fn eq(&self, other: &Gate) -> bool {
+        match (self, other) {
+            (Gate::ClassicalControl(a, g), Gate::ClassicalControl(b, h)) => a == b && g == h,
+            (Gate::Measure(q, a), Gate::Measure(r, b)) => q == r && a == b,
+            _ => self.kind() == other.kind() && self.qubits() == other.qubits() && self.param_bits() == other.param_bits(),
+        }
+    }
+}
+
+impl Eq for Gate {}
+
+impl std::hash::Hash for Gate {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.kind().hash(state);
+        match self {
+            Gate::ClassicalControl(bit, inner) => {
+                bit.hash(state);
+                inner.hash(state);
+            }
+            Gate::Measure(q, bit) => (q, bit).hash(state),
+            _ => {
+                self.qubits().hash(state);
+                self.param_bits().hash(state);
+            }
         }
     }
 }
@@ -684,7 +1158,7 @@ impl Gate {
 // ============================================================================
 
 /// Decompose Toffoli gate into elementary gates (H, T, CNOT)
-/// Returns a sequence of gates equivalent to CCX(c1, c2, target)
+/// Returns a sequence of gates equivalent to CCX(c1, c2, target): 7 T, 6 CNOT
 pub fn decompose_toffoli(c1: usize, c2: usize, target: usize) -> Vec<Gate> {
     vec![
         Gate::H(target),
@@ -706,6 +1180,7 @@ pub fn decompose_toffoli(c1: usize, c2: usize, target: usize) -> Vec<Gate> {
 }
 
 /// Decompose CSWAP (Fredkin) gate
+/// CSWAP(c, a, b) = CX(b, a) · CCX(c, a, b) · CX(b, a)
 pub fn decompose_fredkin(control: usize, a: usize, b: usize) -> Vec<Gate> {
     vec![
         Gate::CX(b, a),
@@ -721,6 +1196,38 @@ pub fn decompose_fredkin(control: usize, a: usize, b: usize) -> Vec<Gate> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_gate_eq_and_hash() {
+        use std::collections::HashSet;
+        let gates = [
+            Gate::RZ(0, 0.5), Gate::RZ(0, 0.5), Gate::RZ(1, 0.5), Gate::RX(0, 0.5), Gate::RZ(0, -0.5),
+            Gate::MCX(vec![0, 1], 2), Gate::MCX(vec![0, 1], 2), Gate::Measure(0, 1), Gate::Measure(0, 2),
+            Gate::ClassicalControl(1, Box::new(Gate::X(0))), Gate::ClassicalControl(1, Box::new(Gate::X(0))),
+            Gate::ClassicalControl(1, Box::new(Gate::Z(0))),
+        ];
+        assert_eq!(gates[0], gates[1]);
+        assert_ne!(gates[0], gates[2]);
+        assert_ne!(gates[7], gates[8]);
+        assert_eq!(gates.iter().collect::<HashSet<_>>().len(), 9);
+    }
+
+    #[test]
+    fn test_complex_arithmetic() {
+        let minus_one = Complex::new(-1.0, 0.0);
+        assert_eq!(Complex::I * Complex::I, minus_one);
+        assert_eq!(Complex::I.conj(), Complex::NEG_I);
+        assert!((Complex::new(0.0, std::f64::consts::PI).exp() - minus_one).abs() < 1e-15);
+        assert!((Complex::ZERO.exp() - Complex::ONE).abs() < 1e-15);
+
+        let z = Complex::new(3.0, -4.0);
+        assert_eq!(z.abs(), 5.0);
+        assert_eq!(z.norm_sqr(), 25.0);
+        assert_eq!(z * z.conj(), Complex::new(25.0, 0.0));
+        assert_eq!(z + Complex::I - z, Complex::I);
+        assert_eq!(-z * 2.0, Complex::new(-6.0, 8.0));
+    }
 
     #[test]
     fn test_pauli_gates() {
@@ -765,4 +1272,82 @@ mod tests {
             _ => panic!("Expected Tdg gate"),
         }
     }
+
+    #[test]
+    fn test_gate_kind_names() {
+        let gates = [
+            Gate::H(0), Gate::RZ(1, 0.3), Gate::ISWAP(0, 1), Gate::MCP(vec![0, 1], 2, 0.1),
+            Gate::Measure(0, 0), Gate::GlobalPhase(0.2),
+        ];
+        let names: Vec<&str> = gates.iter().map(|g| g.kind().name()).collect();
+        assert_eq!(names, ["H", "RZ", "iSWAP", "MCP", "Measure", "GlobalPhase"]);
+        assert_eq!(Gate::CX(0, 1).kind().name().to_lowercase(), "cx");
+    }
+
+    #[test]
+    fn test_iswap_and_sqrt_iswap() {
+        use crate::simulator::{basis_state, simulate_from, states_close};
+        use crate::routing::basis::transpile_to_basis;
+
+        // iSWAP swaps |01⟩ and |10⟩ with a factor i and fixes |00⟩, |11⟩
+        let (o, z, i) = (Complex::ONE, Complex::ZERO, Complex::I);
+        let expected = [[o, z, z, z], [z, z, i, z], [z, i, z, z], [z, z, z, o]];
+        assert_eq!(Gate::ISWAP(0, 1).matrix_4x4().unwrap().data, expected);
+
+        // Two √iSWAPs make one iSWAP, on every basis input and both orientations
+        for input in 0..8 {
+            let once = simulate_from(&[Gate::ISWAP(2, 0)], &basis_state(3, input)).unwrap();
+            let twice = simulate_from(&[Gate::SqrtISWAP(2, 0), Gate::SqrtISWAP(0, 2)], &basis_state(3, input)).unwrap();
+            assert!(states_close(&once, &twice, 1e-12), "Input {}", input);
+        }
+
+        // CX rebuilt from each of them, exactly including phase
+        for native in [GateKind::ISWAP, GateKind::SqrtISWAP] {
+            let basis = [GateKind::RZ, GateKind::RX, GateKind::RY, native, GateKind::GlobalPhase];
+            let cx = transpile_to_basis(&[Gate::CX(1, 0)], &basis).unwrap();
+            assert_eq!(cx.iter().filter(|g| g.kind() == native).count(), 2, "{:?}", cx);
+            for input in 0..4 {
+                let direct = simulate_from(&[Gate::CX(1, 0)], &basis_state(2, input)).unwrap();
+                assert!(states_close(&direct, &simulate_from(&cx, &basis_state(2, input)).unwrap(), 1e-10), "{:?}", native);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fredkin_truth_table_and_lowering() {
+        use crate::gates::decomposition::control_gate;
+        use crate::simulator::{basis_state, simulate_from, states_close};
+
+        // Control on qubit 1, swapped pair on qubits 2 and 0
+        let gate = Gate::CSWAP(1, 2, 0);
+        let lowered = decompose_fredkin(1, 2, 0);
+        assert_eq!(control_gate(1, &Gate::SWAP(2, 0)), vec![gate.clone()]);
+        assert_eq!(gate.inverse(), gate);
+        for input in 0..8usize {
+            let (c, a, b) = ((input >> 1) & 1, (input >> 2) & 1, input & 1);
+            let output = if c == 1 { (b << 2) | (c << 1) | a } else { input };
+            for circuit in [std::slice::from_ref(&gate), &lowered[..]] {
+                let state = simulate_from(circuit, &basis_state(3, input)).unwrap();
+                assert!(states_close(&state, &basis_state(3, output), 1e-12), "{:03b} via {:?}", input, circuit);
+            }
+        }
+    }
+
+    #[test]
+    fn test_three_qubit_matrices_match_simulator() {
+        use crate::simulator::{basis_state, simulate_from};
+
+        // Listing qubits 2, 1, 0 makes the row index the simulator's basis index
+        for gate in [Gate::CCX(2, 1, 0), Gate::CCZ(2, 1, 0), Gate::CSWAP(2, 1, 0)] {
+            let m = gate.matrix_8x8().unwrap();
+            for col in 0..8 {
+                let state = simulate_from(std::slice::from_ref(&gate), &basis_state(3, col)).unwrap();
+                for (row, amp) in state.iter().enumerate() {
+                    assert!((*amp - m.data[row][col]).norm() < 1e-12, "{:?}", gate);
+                }
+            }
+        }
+        assert!(Gate::CX(0, 1).matrix_8x8().is_none());
+        assert!(matches!(Gate::CCX(0, 1, 2).inverse(), Gate::CCX(0, 1, 2)));
+    }
 }
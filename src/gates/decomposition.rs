@@ -3,9 +3,17 @@
 //! This module provides algorithms for decomposing arbitrary quantum gates
 //! into elementary gate sets:
 //! - Euler decomposition (ZYZ, XYX, ZXZ forms)
+//! - Solovay-Kitaev Clifford+T approximation, with `SynthesisStats` on the
+//!   depth, length and error reached and a `SynthesisError` when a depth
+//!   cap stops it short of the target
 //! - KAK decomposition for two-qubit gates  
+//! - `BudgetExceeded`: Two-qubit gate budgets that heavy syntheses check
+//!   before emitting their circuit
 //! - Cartan decomposition
-//! - Multi-controlled gate synthesis (Gray code)
+//! - Multi-controlled gate synthesis (Gray code, clean and dirty ancillas),
+//!   plus compact `mcz` / `multi_controlled_phase` primitives for oracles
+//! - Controlled rotations (CRX, CRY, CRZ, CP) lowered to two CX
+//! - Toffoli with 4 T gates via a measured logical-AND ancilla
 //!
 //! ## 🎯 Why is this used?
 //! Hardware QPUs often only support a restricted "basis set" of gates (e.g., CNOT and 
@@ -20,8 +28,10 @@
 //!   number of two-qubit interactions (Max 3 CNOTs).
 //! - **Boolean Analysis**: Uses Gray code sequences to synthesize multi-controlled gates 
 //!   (MCX/MCZ) with efficient CNOT counts.
-//! - **Numerical Approximation**: Solovay-Kitaev algorithm (skeleton) for approximating 
-//!   any unitary using a finite discrete gate set (H, T, S).
+//! - **Numerical Approximation**: Solovay-Kitaev algorithm for approximating
+//!   any unitary over H, T and T†: the nearest word of length ≤ 12 seeds the
+//!   recursion, and each level corrects the residual with a balanced group
+//!   commutator. Depths are tried in increasing order until ε is met.
 //!
 //! ## 📍 Where to apply this?
 //! - **Transpilation**: During the final stage of compiling a circuit for specific hardware.
@@ -38,7 +48,9 @@
 //!   singularities in Euler angles are handled via atan2.
 
 use std::f64::consts::PI;
-use super::core::{Complex, GateMatrix2x2, GateMatrix4x4, Gate};
+use super::core::{decompose_toffoli, Complex, GateMatrix2x2, GateMatrix4x4, Gate};
+use super::circuit::inverse_circuit;
+use crate::analysis::circuit_analysis::{circuit_cost, CostWeights};
 
 // ============================================================================
 // EULER DECOMPOSITION
@@ -72,20 +84,22 @@ pub fn euler_decompose_zyz(matrix: &GateMatrix2x2) -> EulerAngles {
     let c = c * phase_factor;
     let d = d * phase_factor;
     
-    // Extract angles
-    let theta = 2.0 * a.norm().acos().min(PI);
+    // Extract angles from the SU(2) form
+    // a = e^(-i(φ+λ)/2) cos(θ/2), c = e^(i(φ-λ)/2) sin(θ/2)
+    // atan2 stays exact near θ = 0, where acos(|a|) loses half the digits
+    let theta = 2.0 * c.norm().atan2(a.norm());
     
     let (phi, lambda) = if theta.abs() < 1e-10 {
-        // θ ≈ 0: only the sum φ + λ is defined
+        // θ ≈ 0: only the sum φ + λ = 2·arg(d) is defined
         let angle = d.im.atan2(d.re);
-        (angle / 2.0, angle / 2.0)
+        (angle, angle)
     } else if (theta - PI).abs() < 1e-10 {
-        // θ ≈ π: only the difference φ - λ is defined
+        // θ ≈ π: only the difference φ - λ = 2·arg(c) is defined
         let angle = c.im.atan2(c.re);
-        (angle / 2.0, -angle / 2.0)
+        (angle, -angle)
     } else {
-        let phi = c.im.atan2(c.re) + a.im.atan2(a.re);
-        let lambda = c.im.atan2(c.re) - a.im.atan2(a.re);
+        let phi = c.im.atan2(c.re) - a.im.atan2(a.re);
+        let lambda = -(c.im.atan2(c.re) + a.im.atan2(a.re));
         (phi, lambda)
     };
     
@@ -125,6 +139,9 @@ pub fn euler_decompose_zxz(matrix: &GateMatrix2x2) -> EulerAngles {
 }
 
 /// Convert Euler angles to Gate sequence (ZYZ form)
+///
+/// A non-zero global phase is emitted as `Gate::GlobalPhase` so that the
+/// sequence reproduces the input matrix exactly, not just up to phase.
 pub fn euler_to_gates_zyz(angles: &EulerAngles, qubit: usize) -> Vec<Gate> {
     let mut gates = Vec::new();
     
@@ -137,6 +154,9 @@ pub fn euler_to_gates_zyz(angles: &EulerAngles, qubit: usize) -> Vec<Gate> {
     if angles.phi.abs() > 1e-10 {
         gates.push(Gate::RZ(qubit, angles.phi));
     }
+    if angles.global_phase.abs() > 1e-10 {
+        gates.push(Gate::GlobalPhase(angles.global_phase));
+    }
     
     gates
 }
@@ -265,6 +285,20 @@ pub fn kak_to_circuit(decomp: &KAKDecomposition, q0: usize, q1: usize) -> Vec<Ga
     gates
 }
 
+/// [`kak_to_circuit`] refusing to emit more than `max_two_qubit` CX
+///
+/// The CX count follows from the canonical coordinates alone, so the check
+/// runs before any gate is built.
+pub fn kak_to_circuit_with_budget(
+    decomp: &KAKDecomposition,
+    q0: usize,
+    q1: usize,
+    max_two_qubit: Option<usize>,
+) -> Result<Vec<Gate>, BudgetExceeded> {
+    check_budget(compute_cnot_count(decomp), max_two_qubit)?;
+    Ok(kak_to_circuit(decomp, q0, q1))
+}
+
 fn compute_cnot_count(decomp: &KAKDecomposition) -> usize {
     let eps = 1e-6;
     if decomp.alpha.abs() < eps && decomp.beta.abs() < eps && decomp.gamma.abs() < eps {
@@ -278,17 +312,47 @@ fn compute_cnot_count(decomp: &KAKDecomposition) -> usize {
     }
 }
 
+// ============================================================================
+// TWO-QUBIT GATE BUDGETS
+// ============================================================================
+
+/// A synthesis would emit `produced` multi-qubit gates, more than `budget`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BudgetExceeded {
+    pub produced: usize,
+    pub budget: usize,
+}
+
+impl std::fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Synthesis needs {} two-qubit gates but the budget is {}", self.produced, self.budget)
+    }
+}
+
+impl std::error::Error for BudgetExceeded {}
+
+/// `Err` when `produced` exceeds the budget; `None` means unlimited
+pub(crate) fn check_budget(produced: usize, max_two_qubit: Option<usize>) -> Result<(), BudgetExceeded> {
+    match max_two_qubit {
+        Some(budget) if produced > budget => Err(BudgetExceeded { produced, budget }),
+        _ => Ok(()),
+    }
+}
+
 // ============================================================================
 // MULTI-CONTROLLED GATE DECOMPOSITION (Gray Code Synthesis)
 // ============================================================================
 
 /// Decompose a multi-controlled X gate into elementary gates using Gray code
 /// MCX(controls, target) → sequence of Toffoli and single-qubit gates
+///
+/// Two controls give a single `CCX`, left for the backend or for
+/// `decompose_toffoli` / `decompose_ccx_4t` to lower.
 pub fn decompose_mcx(controls: &[usize], target: usize) -> Vec<Gate> {
     match controls.len() {
         0 => vec![Gate::X(target)],
         1 => vec![Gate::CX(controls[0], target)],
-        2 => super::core::decompose_toffoli(controls[0], controls[1], target),
+        2 => vec![Gate::CCX(controls[0], controls[1], target)],
         _n => {
             // Use recursive decomposition with ancilla-free method
             // This uses O(n²) gates but requires no ancilla
@@ -298,7 +362,9 @@ pub fn decompose_mcx(controls: &[usize], target: usize) -> Vec<Gate> {
 }
 
 /// Ancilla-free multi-controlled X decomposition
-/// Uses Lemma 7.2 from Barenco et al.
+///
+/// MCX = H · C^n P(π) · H, with the multi-controlled phase reduced
+/// recursively (Barenco et al., Lemma 7.5). Uses O(3^n) gates but no ancilla.
 fn decompose_mcx_no_ancilla(controls: &[usize], target: usize) -> Vec<Gate> {
     let n = controls.len();
     if n <= 2 {
@@ -306,26 +372,175 @@ fn decompose_mcx_no_ancilla(controls: &[usize], target: usize) -> Vec<Gate> {
     }
     
     let mut gates = Vec::new();
+    gates.push(Gate::H(target));
+    gates.extend(mcp_no_ancilla(controls, target, PI));
+    gates.push(Gate::H(target));
+    gates
+}
+
+/// Recursive ancilla-free C^n P(φ)
+///
+/// With A = c₁∧…∧c_{n-1}:
+/// CP(c_n,t,φ/2) · MCX(A→c_n) · CP(c_n,t,-φ/2) · MCX(A→c_n) · C^{n-1}P(φ/2)
+/// accumulates phase (φ/2)·t·[c_n − (c_n⊕A) + A] = φ·t·c_n·A.
+pub(crate) fn mcp_no_ancilla(controls: &[usize], target: usize, phi: f64) -> Vec<Gate> {
+    let n = controls.len();
+    match n {
+        0 => return vec![Gate::P(target, phi)],
+        1 => return vec![Gate::CP(controls[0], target, phi)],
+        _ => {}
+    }
     
-    // Split controls into two groups
-    let m = n / 2;
-    let _first_half = &controls[..m];
-    let _second_half = &controls[m..];
+    let last = controls[n - 1];
+    let rest = &controls[..n - 1];
     
-    // Use last control as "pseudo-ancilla"
-    let _aux = controls[n - 1];
+    let mut gates = Vec::new();
+    gates.push(Gate::CP(last, target, phi / 2.0));
+    gates.extend(decompose_mcx(rest, last));
+    gates.push(Gate::CP(last, target, -phi / 2.0));
+    gates.extend(decompose_mcx(rest, last));
+    gates.extend(mcp_no_ancilla(rest, target, phi / 2.0));
+    gates
+}
+
+/// Laddered ("V-chain") multi-controlled X using clean ancillas
+///
+/// Computes the running AND of the controls into `ancillas` with a chain of
+/// Toffolis, flips the target, then uncomputes: 2n-3 Toffolis in total.
+/// Requires at least n-2 ancillas in |0⟩; they are returned to |0⟩.
+pub fn decompose_mcx_v_chain(controls: &[usize], target: usize, ancillas: &[usize]) -> Vec<Gate> {
+    let n = controls.len();
+    if n <= 2 {
+        return decompose_mcx(controls, target);
+    }
+    assert!(ancillas.len() >= n - 2, "V-chain MCX needs {} ancillas, got {}", n - 2, ancillas.len());
     
-    // Decompose recursively
-    // V gate (√X) implementation simplified
-    gates.push(Gate::H(target));
-    gates.push(Gate::Tdg(target));
-    gates.extend(decompose_mcx(&controls[..n-1], target));
-    gates.push(Gate::T(target));
-    gates.push(Gate::H(target));
+    // Compute: a₀ = c₀∧c₁, a_k = c_{k+1}∧a_{k-1}
+    let mut compute = Vec::new();
+    compute.extend(decompose_toffoli(controls[0], controls[1], ancillas[0]));
+    for k in 1..n - 2 {
+        compute.extend(decompose_toffoli(controls[k + 1], ancillas[k - 1], ancillas[k]));
+    }
     
+    let mut gates = compute.clone();
+    gates.extend(decompose_toffoli(controls[n - 1], ancillas[n - 3], target));
+    gates.extend(inverse_circuit(&compute));
     gates
 }
 
+/// Toffoli up to a diagonal relative phase, with 3 CNOTs instead of 6
+///
+/// Acts as CCX(c1, c2, target) followed by a diagonal phase on some basis
+/// states. Only exact when a later copy of its inverse cancels that phase,
+/// as in the compute / uncompute halves of `decompose_mcx_dirty`.
+pub fn relative_phase_toffoli(c1: usize, c2: usize, target: usize) -> Vec<Gate> {
+    vec![
+        Gate::H(target),
+        Gate::T(target),
+        Gate::CX(c2, target),
+        Gate::Tdg(target),
+        Gate::CX(c1, target),
+        Gate::T(target),
+        Gate::CX(c2, target),
+        Gate::Tdg(target),
+        Gate::H(target),
+    ]
+}
+
+/// Exact Toffoli with 4 T gates, using a clean `ancilla` and one measurement
+///
+/// Computes ancilla = c1∧c2 with Gidney's temporary logical-AND (4 T, no
+/// relative phase), copies it onto the target, then uncomputes by measuring
+/// the ancilla in the X basis into classical bit `bit`: outcome 1 is fixed
+/// by CZ(c1, c2) and an X that returns the ancilla to |0⟩. The 7-T unitary
+/// form is `decompose_toffoli`.
+pub fn decompose_ccx_4t(c1: usize, c2: usize, target: usize, ancilla: usize, bit: usize) -> Vec<Gate> {
+    vec![
+        // |T⟩ = T·H|0⟩
+        Gate::H(ancilla),
+        Gate::T(ancilla),
+        Gate::CX(c1, ancilla),
+        Gate::CX(c2, ancilla),
+        Gate::CX(ancilla, c1),
+        Gate::CX(ancilla, c2),
+        Gate::Tdg(c1),
+        Gate::Tdg(c2),
+        Gate::T(ancilla),
+        Gate::CX(ancilla, c2),
+        Gate::CX(ancilla, c1),
+        Gate::H(ancilla),
+        Gate::S(ancilla),
+        Gate::CX(ancilla, target),
+        Gate::H(ancilla),
+        Gate::Measure(ancilla, bit),
+        Gate::ClassicalControl(bit, Box::new(Gate::CZ(c1, c2))),
+        Gate::ClassicalControl(bit, Box::new(Gate::X(ancilla))),
+    ]
+}
+
+/// Multi-controlled X borrowing dirty ancillas (Barenco et al., Lemma 7.2)
+///
+/// Needs n-2 `ancillas` in *any* state, even entangled with the rest of the
+/// register, and returns them unchanged. The circuit is T·V·T·V†, with T the
+/// Toffoli (c_n, a_{n-2}, target) and V the ancilla chain
+/// a_{k} ⊕= c_{k+1}∧a_{k-1} down to a₁ ⊕= c₁∧c₂ and back up. V uses
+/// `relative_phase_toffoli`; its phase never touches the target and is
+/// undone by V†, so the result is the exact MCX with 12n − 18 CNOTs. The
+/// halves V and V† are not exact on their own.
+pub fn decompose_mcx_dirty(controls: &[usize], target: usize, ancillas: &[usize]) -> Vec<Gate> {
+    let n = controls.len();
+    if n <= 2 {
+        return decompose_mcx(controls, target);
+    }
+    assert!(ancillas.len() >= n - 2, "Dirty-ancilla MCX needs {} ancillas, got {}", n - 2, ancillas.len());
+    let a = &ancillas[..n - 2];
+
+    // V = [a_{n-2} ⊕= c_{n-1}∧a_{n-3}, …, a₁ ⊕= c₀∧c₁, …, a_{n-2} ⊕= c_{n-1}∧a_{n-3}]
+    let step = |k: usize| relative_phase_toffoli(controls[k + 1], a[k - 1], a[k]);
+    let mut chain = Vec::new();
+    for k in (1..n - 2).rev() {
+        chain.extend(step(k));
+    }
+    chain.extend(relative_phase_toffoli(controls[0], controls[1], a[0]));
+    for k in 1..n - 2 {
+        chain.extend(step(k));
+    }
+
+    let top = decompose_toffoli(controls[n - 1], a[n - 3], target);
+    let mut gates = top.clone();
+    gates.extend(chain.iter().cloned());
+    gates.extend(top);
+    gates.extend(inverse_circuit(&chain));
+    gates
+}
+
+/// Cost-aware multi-controlled X: pick the cheaper of the ancilla-free and
+/// laddered forms under `weights`
+///
+/// The laddered form is only considered when enough `ancillas` are supplied,
+/// and is charged `weights.ancilla` for each ancilla it borrows.
+pub fn decompose_mcx_with_cost(
+    controls: &[usize],
+    target: usize,
+    ancillas: &[usize],
+    weights: &CostWeights,
+) -> Vec<Gate> {
+    let free = decompose_mcx(controls, target);
+    let n = controls.len();
+    if n <= 2 || ancillas.len() < n - 2 {
+        return free;
+    }
+    
+    let ladder = decompose_mcx_v_chain(controls, target, ancillas);
+    let ladder_cost = circuit_cost(&ladder, weights) + (n - 2) as f64 * weights.ancilla;
+    
+    if ladder_cost < circuit_cost(&free, weights) {
+        ladder
+    } else {
+        free
+    }
+}
+
 /// Decompose multi-controlled Z gate
 pub fn decompose_mcz(controls: &[usize], target: usize) -> Vec<Gate> {
     let mut gates = Vec::new();
@@ -335,49 +550,309 @@ pub fn decompose_mcz(controls: &[usize], target: usize) -> Vec<Gate> {
     gates
 }
 
-/// Decompose multi-controlled phase gate
+/// Decompose multi-controlled phase gate with Gray-code ordered parities
+///
+/// With m = n controls, c₁⋯c_m = 2^{1−m} Σ_{S≠∅} (−1)^{|S|−1} ⊕_{i∈S} cᵢ, so
+/// C^nP(φ) is a product of CP(±φ/2^{n−1}) from each parity to the target.
+/// Walking the subsets in Gray-code order, the parity of S is accumulated
+/// on its highest control with one CX per step; every control is restored
+/// at the end. 2ⁿ − 1 CP and about 2ⁿ CX, exact including phase.
 pub fn decompose_mcp(controls: &[usize], target: usize, phi: f64) -> Vec<Gate> {
     match controls.len() {
         0 => vec![Gate::P(target, phi)],
         1 => vec![Gate::CP(controls[0], target, phi)],
-        _ => {
-            // Use gray code for multi-controlled phase
+        n => {
             let mut gates = Vec::new();
-            
-            // Generate Gray code sequence
-            let gray_sequence = generate_gray_code(controls.len());
-            let phase_per_term = phi / (1 << controls.len()) as f64;
-            
-            for (i, code) in gray_sequence.iter().enumerate() {
-                let parity = code.count_ones() as usize;
-                let sign = if parity % 2 == 0 { 1.0 } else { -1.0 };
-                
-                if i == 0 {
-                    gates.push(Gate::P(target, sign * phase_per_term));
-                } else {
-                    // Find the bit that changed
-                    let changed_bit = (gray_sequence[i] ^ gray_sequence[i-1]).trailing_zeros() as usize;
-                    gates.push(Gate::CX(controls[changed_bit], target));
-                    gates.push(Gate::P(target, sign * phase_per_term));
+            let angle = phi / (1u64 << (n - 1)) as f64;
+            // accumulated[q]: controls XORed into controls[q], as a bit mask
+            let mut accumulated = vec![0usize; n];
+
+            for code in generate_gray_code(n).into_iter().skip(1) {
+                let leader = (usize::BITS - 1 - code.leading_zeros()) as usize;
+                let wanted = code & !(1 << leader);
+                let mut change = accumulated[leader] ^ wanted;
+                while change != 0 {
+                    let j = change.trailing_zeros() as usize;
+                    gates.push(Gate::CX(controls[j], controls[leader]));
+                    change &= change - 1;
                 }
+                accumulated[leader] = wanted;
+
+                let sign = if code.count_ones() % 2 == 1 { 1.0 } else { -1.0 };
+                gates.push(Gate::CP(controls[leader], target, sign * angle));
             }
-            
             gates
         }
     }
 }
 
+/// Compact multi-controlled Z: Z, CZ, or H(t) · MCX · H(t)
+///
+/// Flips the sign of the states where all controls and the target are |1⟩.
+/// Two controls give H · CCX · H; lower further with `decompose_mcz`.
+pub fn mcz(controls: &[usize], target: usize) -> Vec<Gate> {
+    match controls {
+        [] => vec![Gate::Z(target)],
+        [c] => vec![Gate::CZ(*c, target)],
+        _ => vec![Gate::H(target), mcx_gate(controls, target), Gate::H(target)],
+    }
+}
+
+/// Multi-controlled phase e^{iφ} on the all-ones state, from MCX sandwiches
+///
+/// Same recursion as the ancilla-free MCX lowering, with A = c₁∧…∧c_{n−1}:
+/// CP(c_n,t,φ/2) · MCX(A→c_n) · CP(c_n,t,−φ/2) · MCX(A→c_n) · C^{n−1}P(φ/2).
+/// The MCX gates are left as single gates; `decompose_mcp` gives a CX + CP
+/// circuit instead.
+pub fn multi_controlled_phase(controls: &[usize], target: usize, phase: f64) -> Vec<Gate> {
+    let Some((&last, rest)) = controls.split_last() else {
+        return vec![Gate::P(target, phase)];
+    };
+    if rest.is_empty() {
+        return vec![Gate::CP(last, target, phase)];
+    }
+    let mut gates = vec![
+        Gate::CP(last, target, phase / 2.0),
+        mcx_gate(rest, last),
+        Gate::CP(last, target, -phase / 2.0),
+        mcx_gate(rest, last),
+    ];
+    gates.extend(multi_controlled_phase(rest, target, phase / 2.0));
+    gates
+}
+
+/// The narrowest single gate for X on `target` controlled by `controls`
+pub(crate) fn mcx_gate(controls: &[usize], target: usize) -> Gate {
+    match *controls {
+        [] => Gate::X(target),
+        [c] => Gate::CX(c, target),
+        [c1, c2] => Gate::CCX(c1, c2, target),
+        _ => Gate::MCX(controls.to_vec(), target),
+    }
+}
+
 fn generate_gray_code(n: usize) -> Vec<usize> {
     (0..(1 << n)).map(|i| i ^ (i >> 1)).collect()
 }
 
+// ============================================================================
+// CONTROLLED GATES
+// ============================================================================
+
+/// Controlled version of a single gate, exact including phase
+///
+/// Gates with a native controlled counterpart map to it (X→CX, RZ→CRZ,
+/// CX→CCX, MCP→MCP with one more control, ...). Other single-qubit gates use
+/// the ZYZ/ABC construction, and `GlobalPhase(θ)` becomes `P(control, θ)`,
+/// the relative phase it turns into once controlled. Panics on `Measure`.
+pub fn control_gate(control: usize, gate: &Gate) -> Vec<Gate> {
+    assert!(
+        !gate.qubits().contains(&control),
+        "Control qubit {} is already used by {:?}", control, gate
+    );
+
+    match gate {
+        Gate::X(t) => vec![Gate::CX(control, *t)],
+        Gate::Y(t) => vec![Gate::CY(control, *t)],
+        Gate::Z(t) => vec![Gate::CZ(control, *t)],
+        Gate::S(t) => vec![Gate::CP(control, *t, PI / 2.0)],
+        Gate::Sdg(t) => vec![Gate::CP(control, *t, -PI / 2.0)],
+        Gate::T(t) => vec![Gate::CP(control, *t, PI / 4.0)],
+        Gate::Tdg(t) => vec![Gate::CP(control, *t, -PI / 4.0)],
+        Gate::P(t, phi) => vec![Gate::CP(control, *t, *phi)],
+        Gate::RX(t, theta) => vec![Gate::CRX(control, *t, *theta)],
+        Gate::RY(t, theta) => vec![Gate::CRY(control, *t, *theta)],
+        Gate::RZ(t, theta) => vec![Gate::CRZ(control, *t, *theta)],
+        // RY(−π/4)·X·RY(π/4) = H exactly, and the RY pair cancels when off
+        Gate::H(t) => vec![Gate::RY(*t, PI / 4.0), Gate::CX(control, *t), Gate::RY(*t, -PI / 4.0)],
+        Gate::SX(t) | Gate::SXdg(t) | Gate::U3(t, _, _, _) => {
+            controlled_single_qubit(control, *t, &gate.matrix_2x2().unwrap())
+        }
+
+        Gate::CX(c, t) => vec![Gate::CCX(control, *c, *t)],
+        Gate::CZ(c, t) => vec![Gate::CCZ(control, *c, *t)],
+        // CY = S·CX·S† on the target
+        Gate::CY(c, t) => vec![Gate::Sdg(*t), Gate::CCX(control, *c, *t), Gate::S(*t)],
+        Gate::SWAP(a, b) => vec![Gate::CSWAP(control, *a, *b)],
+        Gate::CP(c, t, phi) => vec![Gate::MCP(vec![control, *c], *t, *phi)],
+        Gate::CRX(c, t, theta) => doubly_controlled_rotation(control, *c, *t, *theta, Gate::CRX),
+        Gate::CRY(c, t, theta) => doubly_controlled_rotation(control, *c, *t, *theta, Gate::CRY),
+        Gate::CRZ(c, t, theta) => doubly_controlled_rotation(control, *c, *t, *theta, Gate::CRZ),
+
+        // RZZ = CX·RZ·CX; the CX pair cancels when the control is off
+        Gate::RZZ(a, b, theta) => vec![
+            Gate::CX(*a, *b), Gate::CRZ(control, *b, *theta), Gate::CX(*a, *b),
+        ],
+        Gate::RXX(a, b, theta) => {
+            let mut gates = vec![Gate::H(*a), Gate::H(*b)];
+            gates.extend(control_gate(control, &Gate::RZZ(*a, *b, *theta)));
+            gates.extend([Gate::H(*a), Gate::H(*b)]);
+            gates
+        }
+        // Y = S·X·S†
+        Gate::RYY(a, b, theta) => {
+            let mut gates = vec![Gate::Sdg(*a), Gate::Sdg(*b)];
+            gates.extend(control_gate(control, &Gate::RXX(*a, *b, *theta)));
+            gates.extend([Gate::S(*a), Gate::S(*b)]);
+            gates
+        }
+        // iSWAP = exp(iπ/4 (XX + YY)) = RXX(-π/2)·RYY(-π/2)
+        Gate::ISWAP(a, b) => {
+            let mut gates = control_gate(control, &Gate::RXX(*a, *b, -PI / 2.0));
+            gates.extend(control_gate(control, &Gate::RYY(*a, *b, -PI / 2.0)));
+            gates
+        }
+        Gate::ISWAPdg(a, b) => {
+            let mut gates = control_gate(control, &Gate::RXX(*a, *b, PI / 2.0));
+            gates.extend(control_gate(control, &Gate::RYY(*a, *b, PI / 2.0)));
+            gates
+        }
+        Gate::SqrtISWAP(a, b) => {
+            let mut gates = control_gate(control, &Gate::RXX(*a, *b, -PI / 4.0));
+            gates.extend(control_gate(control, &Gate::RYY(*a, *b, -PI / 4.0)));
+            gates
+        }
+        Gate::SqrtISWAPdg(a, b) => {
+            let mut gates = control_gate(control, &Gate::RXX(*a, *b, PI / 4.0));
+            gates.extend(control_gate(control, &Gate::RYY(*a, *b, PI / 4.0)));
+            gates
+        }
+
+        Gate::CCX(c1, c2, t) => vec![Gate::MCX(vec![control, *c1, *c2], *t)],
+        Gate::CCZ(c1, c2, t) => vec![Gate::MCZ(vec![control, *c1, *c2], *t)],
+        Gate::CSWAP(c, a, b) => vec![
+            Gate::CX(*b, *a), Gate::MCX(vec![control, *c, *a], *b), Gate::CX(*b, *a),
+        ],
+        Gate::MCX(controls, t) => vec![Gate::MCX(with_control(control, controls), *t)],
+        Gate::MCZ(controls, t) => vec![Gate::MCZ(with_control(control, controls), *t)],
+        Gate::MCP(controls, t, phi) => vec![Gate::MCP(with_control(control, controls), *t, *phi)],
+
+        Gate::GlobalPhase(theta) => vec![Gate::P(control, *theta)],
+
+        Gate::Barrier(qubits) => vec![Gate::Barrier(with_control(control, qubits))],
+        Gate::Measure(_, _) => panic!("A measurement cannot be controlled"),
+        Gate::Reset(_) => panic!("A reset cannot be controlled"),
+        Gate::ClassicalControl(bit, inner) => control_gate(control, inner)
+            .into_iter()
+            .map(|g| Gate::ClassicalControl(*bit, Box::new(g)))
+            .collect(),
+    }
+}
+
+/// Controlled version of a whole circuit block, exact including phase
+///
+/// Every gate goes through [`control_gate`], so global phases inside the block
+/// (including those produced by Euler re-synthesis) become phase gates on the
+/// control and the block's overall phase is preserved.
+pub fn control_circuit(control: usize, circuit: &[Gate]) -> Vec<Gate> {
+    circuit.iter().flat_map(|g| control_gate(control, g)).collect()
+}
+
+fn with_control(control: usize, controls: &[usize]) -> Vec<usize> {
+    let mut all = vec![control];
+    all.extend_from_slice(controls);
+    all
+}
+
+/// Controlled-U for an arbitrary 2x2 unitary (Nielsen & Chuang, Cor. 4.2)
+///
+/// U = e^(iγ) A·X·B·X·C with A·B·C = I, where
+/// A = RZ(φ)RY(θ/2), B = RY(-θ/2)RZ(-(φ+λ)/2), C = RZ((λ-φ)/2).
+fn controlled_single_qubit(control: usize, target: usize, matrix: &GateMatrix2x2) -> Vec<Gate> {
+    let e = euler_decompose_zyz(matrix);
+
+    let mut gates = vec![
+        Gate::RZ(target, (e.lambda - e.phi) / 2.0),
+        Gate::CX(control, target),
+        Gate::RZ(target, -(e.phi + e.lambda) / 2.0),
+        Gate::RY(target, -e.theta / 2.0),
+        Gate::CX(control, target),
+        Gate::RY(target, e.theta / 2.0),
+        Gate::RZ(target, e.phi),
+    ];
+    if e.global_phase.abs() > 1e-10 {
+        gates.push(Gate::P(control, e.global_phase));
+    }
+    gates
+}
+
+/// R(θ) controlled on both `c0` and `c1`, for an axis rotation family R
+///
+/// CR(c1,θ/2)·CX(c0,c1)·CR(c1,-θ/2)·CX(c0,c1)·CR(c0,θ/2) rotates by
+/// (θ/2)[c1 − (c1⊕c0) + c0] = θ·c0·c1.
+fn doubly_controlled_rotation(
+    c0: usize,
+    c1: usize,
+    target: usize,
+    theta: f64,
+    rotation: fn(usize, usize, f64) -> Gate,
+) -> Vec<Gate> {
+    vec![
+        rotation(c1, target, theta / 2.0),
+        Gate::CX(c0, c1),
+        rotation(c1, target, -theta / 2.0),
+        Gate::CX(c0, c1),
+        rotation(c0, target, theta / 2.0),
+    ]
+}
+
+// ============================================================================
+// CONTROLLED ROTATIONS TO CX
+// ============================================================================
+
+/// CRY(c, t, θ) = RY(t, θ/2) · CX(c, t) · RY(t, −θ/2) · CX(c, t)
+///
+/// With the control off the two half-rotations cancel; with it on, X·RY(−θ/2)·X
+/// = RY(θ/2) and the halves add up to θ.
+pub fn decompose_cry(control: usize, target: usize, theta: f64) -> Vec<Gate> {
+    vec![
+        Gate::RY(target, theta / 2.0),
+        Gate::CX(control, target),
+        Gate::RY(target, -theta / 2.0),
+        Gate::CX(control, target),
+    ]
+}
+
+/// CRZ(c, t, θ) = RZ(t, θ/2) · CX(c, t) · RZ(t, −θ/2) · CX(c, t)
+pub fn decompose_crz(control: usize, target: usize, theta: f64) -> Vec<Gate> {
+    vec![
+        Gate::RZ(target, theta / 2.0),
+        Gate::CX(control, target),
+        Gate::RZ(target, -theta / 2.0),
+        Gate::CX(control, target),
+    ]
+}
+
+/// CRX(c, t, θ) = H(t) · CRZ(c, t, θ) · H(t), with the CRZ lowered as above
+pub fn decompose_crx(control: usize, target: usize, theta: f64) -> Vec<Gate> {
+    let mut gates = vec![Gate::H(target)];
+    gates.extend(decompose_crz(control, target, theta));
+    gates.push(Gate::H(target));
+    gates
+}
+
+/// CP(c, t, φ) = P(c, φ/2) · CRZ(c, t, φ) as two CX and three phases
+///
+/// Exact including phase: CP = diag(1, 1, 1, e^{iφ}) with no global factor.
+pub fn decompose_cp(control: usize, target: usize, phi: f64) -> Vec<Gate> {
+    vec![
+        Gate::P(control, phi / 2.0),
+        Gate::CX(control, target),
+        Gate::P(target, -phi / 2.0),
+        Gate::CX(control, target),
+        Gate::P(target, phi / 2.0),
+    ]
+}
+
 // ============================================================================
 // SOLOVAY-KITAEV ALGORITHM (Approximation)
 // ============================================================================
 
 /// Configuration for Solovay-Kitaev algorithm
 pub struct SKConfig {
+    /// Maximum recursion depth; deeper levels are only tried while `epsilon` is unmet
     pub depth: usize,
+    /// Target distance to the input, up to global phase
     pub epsilon: f64,
 }
 
@@ -390,79 +865,250 @@ impl Default for SKConfig {
     }
 }
 
+/// What a recursive synthesis did to reach its result
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SynthesisStats {
+    /// Recursion depth of the returned circuit
+    pub depth: usize,
+    /// Gates in the returned circuit
+    pub gates: usize,
+    /// Achieved distance to the target
+    pub error: f64,
+}
+
+/// A depth-capped synthesis could not finish
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SynthesisError {
+    /// Every depth up to `max_depth` left the error above `target`
+    AccuracyNotReached { max_depth: usize, error: f64, target: f64 },
+    /// The input needs `required` levels of recursion, more than `max_depth`
+    DepthExceeded { required: usize, max_depth: usize },
+}
+
+impl std::fmt::Display for SynthesisError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SynthesisError::AccuracyNotReached { max_depth, error, target } => write!(
+                f, "Synthesis reached error {:.3e} at depth {}, target was {:.3e}", error, max_depth, target
+            ),
+            SynthesisError::DepthExceeded { required, max_depth } => write!(
+                f, "Synthesis needs recursion depth {} but the limit is {}", required, max_depth
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SynthesisError {}
+
 /// Approximate a single-qubit unitary using the Solovay-Kitaev algorithm
 /// Returns a sequence of gates from the Clifford+T set
+///
+/// Depths 0, 1, … `config.depth` are tried in turn and the first circuit
+/// within `config.epsilon` is returned; if none is, the deepest one is.
 pub fn solovay_kitaev(matrix: &GateMatrix2x2, config: &SKConfig) -> Vec<Gate> {
-    // Base case: find closest gate in precompiled set
-    if config.depth == 0 {
-        return find_closest_gate(matrix);
+    sk_deepen(matrix, config).0
+}
+
+/// [`solovay_kitaev`] reporting depth, gate count and error of its result
+///
+/// Fails with `AccuracyNotReached` instead of returning a circuit farther
+/// than `config.epsilon` from `matrix` once `config.depth` is exhausted.
+pub fn solovay_kitaev_with_stats(
+    matrix: &GateMatrix2x2,
+    config: &SKConfig,
+) -> Result<(Vec<Gate>, SynthesisStats), SynthesisError> {
+    let (gates, stats) = sk_deepen(matrix, config);
+    if stats.error <= config.epsilon {
+        Ok((gates, stats))
+    } else {
+        Err(SynthesisError::AccuracyNotReached { max_depth: config.depth, error: stats.error, target: config.epsilon })
     }
-    
-    // Recursive case
-    let sub_config = SKConfig {
-        depth: config.depth - 1,
-        epsilon: config.epsilon.sqrt(),
-    };
-    
-    // Get approximation at lower depth
-    let u_approx = solovay_kitaev(matrix, &sub_config);
-    let u_approx_matrix = gates_to_matrix(&u_approx);
-    
-    // Compute error: V = U · U_approx†
-    let u_approx_dag = conjugate_transpose_2x2(&u_approx_matrix);
-    let v = matrix_multiply_2x2(matrix, &u_approx_dag);
-    
-    // If error is small enough, return current approximation
-    if is_close_to_identity(&v, config.epsilon) {
-        return u_approx;
+}
+
+/// The first of depths 0, 1, … within `config.epsilon`, else the one at `config.depth`
+fn sk_deepen(matrix: &GateMatrix2x2, config: &SKConfig) -> (Vec<Gate>, SynthesisStats) {
+    let target = to_su2(matrix);
+    let mut depth = 0;
+    loop {
+        let gates = sk_recursive(&target, depth);
+        let error = phase_distance(&target, &gates_to_matrix(&gates));
+        if error <= config.epsilon || depth >= config.depth {
+            let stats = SynthesisStats { depth, gates: gates.len(), error };
+            return (gates, stats);
+        }
+        depth += 1;
     }
-    
-    // Decompose V using group commutator: V ≈ [W, X] = WXW†X†
-    let (w, x) = decompose_commutator(&v);
-    
-    // Recursively approximate W and X
-    let w_approx = solovay_kitaev(&w, &sub_config);
-    let x_approx = solovay_kitaev(&x, &sub_config);
-    
-    // Compute inverses
-    let w_inv = invert_gate_sequence(&w_approx);
-    let x_inv = invert_gate_sequence(&x_approx);
-    
-    // Combine: W X W† X† U_approx
-    let mut result = Vec::new();
-    result.extend(w_approx.clone());
-    result.extend(x_approx.clone());
-    result.extend(w_inv);
-    result.extend(x_inv);
-    result.extend(u_approx);
-    
+}
+
+/// Uₙ = Vₙ₋₁ Wₙ₋₁ Vₙ₋₁† Wₙ₋₁† Uₙ₋₁, where VW V†W† = U Uₙ₋₁†
+fn sk_recursive(u: &GateMatrix2x2, depth: usize) -> Vec<Gate> {
+    if depth == 0 {
+        return find_closest_gate(u);
+    }
+    let prev = sk_recursive(u, depth - 1);
+    let delta = matrix_multiply_2x2(u, &conjugate_transpose_2x2(&gates_to_matrix(&prev)));
+    let (v, w) = decompose_commutator(&to_su2(&delta));
+    let v_approx = sk_recursive(&v, depth - 1);
+    let w_approx = sk_recursive(&w, depth - 1);
+
+    // Gates run left to right, so the matrix product is read backwards
+    let mut result = prev;
+    result.extend(inverse_circuit(&w_approx));
+    result.extend(inverse_circuit(&v_approx));
+    result.extend(w_approx);
+    result.extend(v_approx);
     result
 }
 
+/// Longest H/T/T† word in the base-case net
+const SK_NET_LENGTH: usize = 12;
+
+/// Every distinct H/T/T† word up to `SK_NET_LENGTH`, deduplicated up to phase
+fn sk_net() -> &'static [(GateMatrix2x2, Vec<Gate>)] {
+    static NET: std::sync::OnceLock<Vec<(GateMatrix2x2, Vec<Gate>)>> = std::sync::OnceLock::new();
+    NET.get_or_init(|| {
+        let generators = [Gate::H(0), Gate::T(0), Gate::Tdg(0)];
+        let mut seen = std::collections::HashSet::new();
+        let mut net = vec![(super::core::identity(), Vec::new())];
+        seen.insert(phase_key(&net[0].0));
+        let mut frontier = 0;
+        for _ in 0..SK_NET_LENGTH {
+            let end = net.len();
+            for i in frontier..end {
+                for g in &generators {
+                    let m = matrix_multiply_2x2(&g.matrix_2x2().unwrap(), &net[i].0);
+                    if seen.insert(phase_key(&m)) {
+                        let mut word = net[i].1.clone();
+                        word.push(g.clone());
+                        net.push((m, word));
+                    }
+                }
+            }
+            frontier = end;
+        }
+        net
+    })
+}
+
+/// Rounded entries of `m` with the phase of its largest entry removed
+fn phase_key(m: &GateMatrix2x2) -> [i64; 8] {
+    let pivot = if m.data[0][0].norm() > 0.5 { m.data[0][0] } else { m.data[0][1] };
+    let unphase = Complex::from_polar(1.0 / pivot.norm(), -pivot.im.atan2(pivot.re));
+    let mut key = [0; 8];
+    for (k, z) in m.data.iter().flatten().enumerate() {
+        let z = *z * unphase;
+        key[2 * k] = (z.re * 1e6).round() as i64;
+        key[2 * k + 1] = (z.im * 1e6).round() as i64;
+    }
+    key
+}
+
 fn find_closest_gate(matrix: &GateMatrix2x2) -> Vec<Gate> {
-    // For a real implementation, this would search a precompiled database
-    // of Clifford+T sequences. Here we use a simplified heuristic.
-    let angles = euler_decompose_zyz(matrix);
-    euler_to_gates_zyz(&angles, 0)
+    sk_net()
+        .iter()
+        .min_by(|a, b| phase_distance(matrix, &a.0).total_cmp(&phase_distance(matrix, &b.0)))
+        .map(|(_, word)| word.clone())
+        .unwrap()
 }
 
-fn decompose_commutator(v: &GateMatrix2x2) -> (GateMatrix2x2, GateMatrix2x2) {
-    // Simplified commutator decomposition
-    // In practice, this uses spherical geometry on SU(2)
-    let angles = euler_decompose_zyz(v);
-    let half_angle = angles.theta / 2.0;
-    
-    let w = super::core::ry(half_angle);
-    let x = super::core::rx(half_angle);
-    
-    (w, x)
+/// Balanced group commutator: V W V† W† = Δ with V, W rotations by the same φ
+///
+/// Δ rotates by θ about n̂. RX(φ)·RY(φ)·RX(φ)†·RY(φ)† rotates by θ when
+/// sin²(φ/2) = ((1 − cos(θ/2))/2)^{1/2}, about some axis m̂; conjugating both
+/// factors by the rotation taking m̂ to n̂ aligns the axes.
+fn decompose_commutator(delta: &GateMatrix2x2) -> (GateMatrix2x2, GateMatrix2x2) {
+    let (axis, theta) = axis_angle(delta);
+    let phi = 2.0 * ((1.0 - (theta / 2.0).cos()) / 2.0).powf(0.25).asin();
+    let v = super::core::rx(phi);
+    let w = super::core::ry(phi);
+    let commutator = matrix_multiply_2x2(
+        &matrix_multiply_2x2(&v, &w),
+        &matrix_multiply_2x2(&conjugate_transpose_2x2(&v), &conjugate_transpose_2x2(&w)),
+    );
+    let (m, _) = axis_angle(&commutator);
+
+    let dot = (m[0] * axis[0] + m[1] * axis[1] + m[2] * axis[2]).clamp(-1.0, 1.0);
+    let cross = [
+        m[1] * axis[2] - m[2] * axis[1],
+        m[2] * axis[0] - m[0] * axis[2],
+        m[0] * axis[1] - m[1] * axis[0],
+    ];
+    let len = cross.iter().map(|c| c * c).sum::<f64>().sqrt();
+    let s = if len > 1e-12 {
+        rotation([cross[0] / len, cross[1] / len, cross[2] / len], dot.acos())
+    } else if dot > 0.0 {
+        super::core::identity()
+    } else {
+        // Antiparallel: any axis ⟂ m̂ works; m̂ lies in the XY-plane or not
+        let perp = if m[2].abs() < 0.9 { [-m[1], m[0], 0.0] } else { [0.0, -m[2], m[1]] };
+        let n = perp.iter().map(|c| c * c).sum::<f64>().sqrt();
+        rotation([perp[0] / n, perp[1] / n, perp[2] / n], PI)
+    };
+    let s_dag = conjugate_transpose_2x2(&s);
+    let conj = |x: &GateMatrix2x2| matrix_multiply_2x2(&matrix_multiply_2x2(&s, x), &s_dag);
+    (conj(&v), conj(&w))
+}
+
+/// `m` rescaled to determinant 1
+fn to_su2(m: &GateMatrix2x2) -> GateMatrix2x2 {
+    let det = m.data[0][0] * m.data[1][1] - m.data[0][1] * m.data[1][0];
+    let root = Complex::from_polar(1.0 / det.norm().sqrt(), -det.im.atan2(det.re) / 2.0);
+    GateMatrix2x2 { data: m.data.map(|row| row.map(|z| z * root)) }
+}
+
+/// (n̂, θ) with θ ∈ [0, π] and U = ±exp(−iθ n̂·σ/2) for U ∈ SU(2)
+fn axis_angle(u: &GateMatrix2x2) -> ([f64; 3], f64) {
+    let sign = if u.data[0][0].re < 0.0 { -1.0 } else { 1.0 };
+    let c = sign * u.data[0][0].re;
+    let sn = [-sign * u.data[0][1].im, sign * u.data[1][0].re, -sign * u.data[0][0].im];
+    let s = sn.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if s < 1e-15 {
+        return ([0.0, 0.0, 1.0], 0.0);
+    }
+    ([sn[0] / s, sn[1] / s, sn[2] / s], 2.0 * s.atan2(c))
+}
+
+/// exp(−iθ n̂·σ/2)
+fn rotation(n: [f64; 3], theta: f64) -> GateMatrix2x2 {
+    let (s, c) = (theta / 2.0).sin_cos();
+    GateMatrix2x2 {
+        data: [
+            [Complex::new(c, -s * n[2]), Complex::new(-s * n[1], -s * n[0])],
+            [Complex::new(s * n[1], -s * n[0]), Complex::new(c, s * n[2])],
+        ],
+    }
+}
+
+/// min over φ of ‖A − e^{iφ}B‖ (Frobenius): zero exactly when A and B agree
+/// up to global phase
+///
+/// The minimizing phase is that of Tr(B†A); the norm is then summed entry by
+/// entry, which stays accurate for nearly equal matrices where √(4 − 2|Tr|)
+/// would cancel to about 1e-8.
+fn phase_distance(a: &GateMatrix2x2, b: &GateMatrix2x2) -> f64 {
+    let mut trace = Complex::ZERO;
+    for i in 0..2 {
+        for k in 0..2 {
+            trace = trace + b.data[k][i].conj() * a.data[k][i];
+        }
+    }
+    let phase = if trace.norm() > 0.0 { trace * (1.0 / trace.norm()) } else { Complex::ONE };
+    let mut sum = 0.0;
+    for i in 0..2 {
+        for k in 0..2 {
+            sum += (a.data[k][i] - phase * b.data[k][i]).norm_sq();
+        }
+    }
+    sum.sqrt()
 }
 
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================
 
-fn matrix_multiply_2x2(a: &GateMatrix2x2, b: &GateMatrix2x2) -> GateMatrix2x2 {
+/// Matrix product a·b of two 2x2 gate matrices
+#[allow(clippy::needless_range_loop)]
+pub fn matrix_multiply_2x2(a: &GateMatrix2x2, b: &GateMatrix2x2) -> GateMatrix2x2 {
     let mut result = [[Complex::ZERO; 2]; 2];
     for i in 0..2 {
         for j in 0..2 {
@@ -474,6 +1120,7 @@ fn matrix_multiply_2x2(a: &GateMatrix2x2, b: &GateMatrix2x2) -> GateMatrix2x2 {
     GateMatrix2x2 { data: result }
 }
 
+#[allow(clippy::needless_range_loop)]
 fn matrix_multiply_4x4(a: &GateMatrix4x4, b: &GateMatrix4x4) -> GateMatrix4x4 {
     let mut result = [[Complex::ZERO; 4]; 4];
     for i in 0..4 {
@@ -495,6 +1142,7 @@ fn conjugate_transpose_2x2(m: &GateMatrix2x2) -> GateMatrix2x2 {
     }
 }
 
+#[allow(clippy::needless_range_loop)]
 fn conjugate_transpose_4x4(m: &GateMatrix4x4) -> GateMatrix4x4 {
     let mut result = [[Complex::ZERO; 4]; 4];
     for i in 0..4 {
@@ -505,6 +1153,7 @@ fn conjugate_transpose_4x4(m: &GateMatrix4x4) -> GateMatrix4x4 {
     GateMatrix4x4 { data: result }
 }
 
+#[allow(clippy::needless_range_loop)]
 fn transpose_4x4(m: &GateMatrix4x4) -> GateMatrix4x4 {
     let mut result = [[Complex::ZERO; 4]; 4];
     for i in 0..4 {
@@ -548,34 +1197,12 @@ fn extract_canonical_coords(_eigenvalues: &[Complex; 4]) -> (f64, f64, f64) {
 fn gates_to_matrix(gates: &[Gate]) -> GateMatrix2x2 {
     let mut result = super::core::identity();
     for gate in gates {
-        let gate_matrix = match gate {
-            Gate::X(_) => super::core::pauli_x(),
-            Gate::Y(_) => super::core::pauli_y(),
-            Gate::Z(_) => super::core::pauli_z(),
-            Gate::H(_) => super::core::hadamard(),
-            Gate::S(_) => super::core::s_gate(),
-            Gate::Sdg(_) => super::core::s_dagger(),
-            Gate::T(_) => super::core::t_gate(),
-            Gate::Tdg(_) => super::core::t_dagger(),
-            Gate::RX(_, theta) => super::core::rx(*theta),
-            Gate::RY(_, theta) => super::core::ry(*theta),
-            Gate::RZ(_, theta) => super::core::rz(*theta),
-            _ => super::core::identity(),
-        };
+        let gate_matrix = gate.matrix_2x2().unwrap_or_else(super::core::identity);
         result = matrix_multiply_2x2(&gate_matrix, &result);
     }
     result
 }
 
-fn is_close_to_identity(m: &GateMatrix2x2, epsilon: f64) -> bool {
-    let trace = m.data[0][0] + m.data[1][1];
-    (trace.re - 2.0).abs() < epsilon && trace.im.abs() < epsilon
-}
-
-fn invert_gate_sequence(gates: &[Gate]) -> Vec<Gate> {
-    gates.iter().rev().map(|g| g.inverse()).collect()
-}
-
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -604,4 +1231,310 @@ mod tests {
         let gates = decompose_mcx(&[0, 1], 2);
         assert!(!gates.is_empty());
     }
+
+    #[test]
+    fn test_ccx_decompositions() {
+        use crate::simulator::{basis_state, simulate_from, simulate_postselected, states_close};
+
+        assert!(matches!(decompose_mcx(&[0, 1], 2)[..], [Gate::CCX(0, 1, 2)]));
+        let t_count = |gates: &[Gate]| gates.iter().filter(|g| matches!(g, Gate::T(_) | Gate::Tdg(_))).count();
+        assert_eq!(t_count(&decompose_toffoli(0, 1, 2)), 7);
+
+        // Qubits 0-2 in superposition, ancilla 3 in |0⟩; postselect both outcomes
+        let prep = [Gate::RY(0, 1.1), Gate::RY(1, 0.7), Gate::RZ(1, 0.3), Gate::RY(2, 2.0)];
+        let before = simulate_from(&prep, &basis_state(4, 0)).unwrap();
+        let mut expected = prep.to_vec();
+        expected.push(Gate::CCX(0, 1, 2));
+        let expected = simulate_from(&expected, &basis_state(4, 0)).unwrap();
+
+        let circuit = decompose_ccx_4t(0, 1, 2, 3, 0);
+        assert_eq!(t_count(&circuit), 4);
+        for outcome in [false, true] {
+            let (state, _) = simulate_postselected(&circuit, &before, &[outcome]).unwrap();
+            assert!(states_close(&state, &expected, 1e-9), "Outcome {}", outcome);
+        }
+    }
+
+    #[test]
+    fn test_mcz_and_multi_controlled_phase() {
+        use crate::simulator::{basis_state, simulate_from, states_close};
+
+        let phi = 0.9;
+        for n in 0..5 {
+            let controls: Vec<usize> = (0..n).collect();
+            let target = n;
+            let circuits = [
+                (mcz(&controls, target), PI),
+                (multi_controlled_phase(&controls, target, phi), phi),
+                (decompose_mcp(&controls, target, phi), phi),
+            ];
+            for (circuit, angle) in &circuits {
+                for input in 0..1 << (n + 1) {
+                    let mut expected = basis_state(n + 1, input);
+                    if input == (1 << (n + 1)) - 1 {
+                        expected[input] = Complex::from_polar(1.0, *angle);
+                    }
+                    let state = simulate_from(circuit, &basis_state(n + 1, input)).unwrap();
+                    assert!(states_close(&state, &expected, 1e-9), "{} controls, input {:b}", n, input);
+                }
+            }
+        }
+        assert!(matches!(mcz(&[0, 1], 2)[..], [Gate::H(2), Gate::CCX(0, 1, 2), Gate::H(2)]));
+        assert!(decompose_mcp(&[0, 1, 2], 3, phi).iter().all(|g| matches!(g, Gate::CX(..) | Gate::CP(..))));
+    }
+
+    #[test]
+    fn test_mcx_decompositions_match_simulator() {
+        use crate::simulator::{basis_state, simulate_from, states_close};
+        
+        let controls = [0, 1, 2, 3];
+        let reference = [Gate::MCX(controls.to_vec(), 4)];
+        let free = decompose_mcx(&controls, 4);
+        let ladder = decompose_mcx_v_chain(&controls, 4, &[5, 6]);
+        
+        // Ancillas (qubits 5, 6) start in |0⟩, so only inputs below 2^5
+        for input in 0..32 {
+            let expected = simulate_from(&reference, &basis_state(7, input)).unwrap();
+            assert!(states_close(&simulate_from(&free, &basis_state(7, input)).unwrap(), &expected, 1e-9));
+            assert!(states_close(&simulate_from(&ladder, &basis_state(7, input)).unwrap(), &expected, 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_mcx_dirty_restores_arbitrary_ancillas() {
+        use crate::gates::circuit::random_circuit;
+        use crate::gates::core::GateKind;
+        use crate::simulator::{simulate, simulate_from, states_close};
+
+        for (controls, target, ancillas) in [(vec![0, 1, 2], 3, vec![4]), (vec![0, 1, 2, 3, 4], 5, vec![6, 7, 8])] {
+            let n = target + ancillas.len() + 1;
+            let reference = [Gate::MCX(controls.clone(), target)];
+            let dirty = decompose_mcx_dirty(&controls, target, &ancillas);
+            let cx = dirty.iter().filter(|g| matches!(g, Gate::CX(_, _))).count();
+            assert_eq!(cx, 12 * controls.len() - 18);
+
+            // Random entangled input on every qubit, ancillas included
+            for seed in 0..3 {
+                let input = simulate(&random_circuit(n, 4, seed, &[GateKind::U3, GateKind::CX]), n).unwrap();
+                let expected = simulate_from(&reference, &input).unwrap();
+                assert!(states_close(&simulate_from(&dirty, &input).unwrap(), &expected, 1e-9), "Seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_relative_phase_toffoli_is_ccx_up_to_diagonal() {
+        use crate::simulator::{basis_state, simulate_from};
+
+        let rccx = relative_phase_toffoli(0, 1, 2);
+        for input in 0..8usize {
+            let flipped = if input & 0b011 == 0b011 { input ^ 0b100 } else { input };
+            let out = simulate_from(&rccx, &basis_state(3, input)).unwrap();
+            assert!((out[flipped].norm() - 1.0).abs() < 1e-10, "Input {}", input);
+        }
+    }
+
+    /// Reference controlled action: apply `block` to qubits above the control
+    /// only on basis states where `control` is set
+    fn controlled_reference(block: &[Gate], control: usize, n: usize, input: usize) -> Vec<Complex> {
+        use crate::simulator::{basis_state, simulate_from};
+        let state = basis_state(n, input);
+        if input & (1 << control) == 0 {
+            state
+        } else {
+            simulate_from(block, &state).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_euler_round_trip_is_exact() {
+        use crate::simulator::{basis_state, simulate_from, states_close};
+        
+        for gate in [Gate::H(0), Gate::SX(0), Gate::U3(0, 0.4, -1.2, 2.3), Gate::T(0), Gate::Y(0)] {
+            let gates = euler_to_gates_zyz(&euler_decompose_zyz(&gate.matrix_2x2().unwrap()), 0);
+            for input in 0..2 {
+                let expected = simulate_from(std::slice::from_ref(&gate), &basis_state(1, input)).unwrap();
+                let actual = simulate_from(&gates, &basis_state(1, input)).unwrap();
+                assert!(states_close(&expected, &actual, 1e-9), "{:?} on |{}⟩", gate, input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_control_global_phase_block() {
+        use crate::simulator::{basis_state, simulate_from, states_close};
+        
+        // ZYZ of H carries a global phase; dropping it would break the control
+        let block = euler_to_gates_zyz(&euler_decompose_zyz(&super::super::core::hadamard()), 1);
+        assert!(block.iter().any(|g| matches!(g, Gate::GlobalPhase(_))));
+        
+        let controlled: Vec<Gate> = block.iter().flat_map(|g| control_gate(0, g)).collect();
+        for input in 0..4 {
+            let expected = controlled_reference(&[Gate::H(1)], 0, 2, input);
+            let actual = simulate_from(&controlled, &basis_state(2, input)).unwrap();
+            assert!(states_close(&expected, &actual, 1e-9), "Mismatch on input {}", input);
+        }
+    }
+
+    #[test]
+    fn test_control_circuit_matches_reference() {
+        use crate::simulator::{basis_state, simulate_from, states_close};
+
+        // RY block ≡ CRY
+        let cry_block = control_circuit(0, &[Gate::RY(1, 0.9)]);
+        for input in 0..4 {
+            let expected = simulate_from(&[Gate::CRY(0, 1, 0.9)], &basis_state(2, input)).unwrap();
+            let actual = simulate_from(&cry_block, &basis_state(2, input)).unwrap();
+            assert!(states_close(&expected, &actual, 1e-10), "CRY mismatch on input {}", input);
+        }
+
+        // Mixed block whose global phase matters once controlled
+        let block = vec![
+            Gate::H(1), Gate::T(2), Gate::CX(1, 2), Gate::GlobalPhase(0.7), Gate::U3(2, 0.3, 0.1, -0.5),
+        ];
+        let controlled = control_circuit(0, &block);
+        for input in 0..8 {
+            let expected = controlled_reference(&block, 0, 3, input);
+            let actual = simulate_from(&controlled, &basis_state(3, input)).unwrap();
+            assert!(states_close(&expected, &actual, 1e-9), "Block mismatch on input {}", input);
+        }
+    }
+
+    #[test]
+    fn test_control_gate_all_variants() {
+        use crate::simulator::{basis_state, simulate_from, states_close};
+        
+        let gates = vec![
+            Gate::X(1), Gate::Y(1), Gate::Z(1), Gate::H(1), Gate::S(1), Gate::Sdg(1),
+            Gate::T(1), Gate::Tdg(1), Gate::SX(1), Gate::RX(1, 0.3), Gate::RY(1, -0.8),
+            Gate::RZ(1, 1.9), Gate::P(1, 0.6), Gate::U3(1, 0.5, 0.2, -0.7),
+            Gate::CX(1, 2), Gate::CY(1, 2), Gate::CZ(1, 2), Gate::SWAP(1, 2), Gate::ISWAP(1, 2),
+            Gate::CRX(1, 2, 0.7), Gate::CRY(2, 1, -1.1), Gate::CRZ(1, 2, 0.4), Gate::CP(1, 2, 2.2),
+            Gate::RXX(1, 2, 0.9), Gate::RYY(1, 2, -0.5), Gate::RZZ(1, 2, 1.3),
+            Gate::CCX(1, 2, 3), Gate::CCZ(1, 2, 3), Gate::CSWAP(1, 2, 3),
+            Gate::MCX(vec![1, 2], 3), Gate::MCZ(vec![1, 2], 3), Gate::MCP(vec![1, 2], 3, 0.8),
+            Gate::GlobalPhase(0.77),
+        ];
+        
+        for gate in &gates {
+            let controlled = control_gate(0, gate);
+            for input in 0..16 {
+                let expected = controlled_reference(std::slice::from_ref(gate), 0, 4, input);
+                let actual = simulate_from(&controlled, &basis_state(4, input)).unwrap();
+                assert!(states_close(&expected, &actual, 1e-9), "{:?} on input {}", gate, input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_controlled_hadamard_matrix() {
+        use crate::simulator::{basis_state, simulate_from};
+
+        // Control qubit 1 is the high bit: CH = |0⟩⟨0| ⊗ I + |1⟩⟨1| ⊗ H
+        let r = std::f64::consts::FRAC_1_SQRT_2;
+        let reference = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, r, r],
+            [0.0, 0.0, r, -r],
+        ];
+        let circuit = control_gate(1, &Gate::H(0));
+        assert_eq!(circuit.iter().filter(|g| matches!(g, Gate::CX(..))).count(), 1);
+        #[allow(clippy::needless_range_loop)]
+        for col in 0..4 {
+            let state = simulate_from(&circuit, &basis_state(2, col)).unwrap();
+            for row in 0..4 {
+                assert!((state[row] - Complex::new(reference[row][col], 0.0)).norm() < 1e-12, "({}, {})", row, col);
+            }
+        }
+    }
+
+    #[test]
+    fn test_controlled_rotation_decompositions_are_exact() {
+        use crate::simulator::{basis_state, simulate_from, states_close};
+
+        for (c, t) in [(0, 1), (1, 0)] {
+            for theta in [0.37, -2.1, PI] {
+                let cases = [
+                    (Gate::CRY(c, t, theta), decompose_cry(c, t, theta)),
+                    (Gate::CRZ(c, t, theta), decompose_crz(c, t, theta)),
+                    (Gate::CRX(c, t, theta), decompose_crx(c, t, theta)),
+                    (Gate::CP(c, t, theta), decompose_cp(c, t, theta)),
+                ];
+                for (native, lowered) in cases {
+                    assert_eq!(lowered.iter().filter(|g| matches!(g, Gate::CX(..))).count(), 2);
+                    for x in 0..4 {
+                        let input = basis_state(2, x);
+                        let expected = simulate_from(std::slice::from_ref(&native), &input).unwrap();
+                        assert!(states_close(&simulate_from(&lowered, &input).unwrap(), &expected, 1e-12), "{:?}", native);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mcx_v_chain_toffoli_count() {
+        // 4 controls → 2·4 − 3 = 5 Toffolis of 7 T gates each
+        let gates = decompose_mcx_v_chain(&[0, 1, 2, 3], 4, &[5, 6]);
+        let t_gates = gates.iter().filter(|g| matches!(g, Gate::T(_) | Gate::Tdg(_))).count();
+        assert_eq!(t_gates, 35);
+    }
+
+    #[test]
+    fn test_mcx_with_cost_respects_regime() {
+        let controls = [0, 1, 2, 3, 4];
+        let ancillas = [6, 7, 8];
+        
+        // Without ancillas there is only one option
+        let free = decompose_mcx(&controls, 5);
+        let chosen = decompose_mcx_with_cost(&controls, 5, &[], &CostWeights::nisq());
+        assert_eq!(chosen.len(), free.len());
+        
+        // Free ancillas under NISQ weights: the ladder is much shorter
+        let ladder = decompose_mcx_v_chain(&controls, 5, &ancillas);
+        let chosen = decompose_mcx_with_cost(&controls, 5, &ancillas, &CostWeights::nisq());
+        assert_eq!(chosen.len(), ladder.len());
+        
+        // Prohibitively expensive ancillas: stay ancilla-free
+        let weights = CostWeights { ancilla: 1e12, ..CostWeights::default() };
+        let chosen = decompose_mcx_with_cost(&controls, 5, &ancillas, &weights);
+        assert_eq!(chosen.len(), free.len());
+    }
+
+    #[test]
+    fn test_solovay_kitaev_depth_tracks_accuracy() {
+        let u = super::super::core::u3(0.7, 1.1, -0.4);
+        let clifford_t = |gates: &[Gate]| gates.iter().all(|g| matches!(g, Gate::H(0) | Gate::T(0) | Gate::Tdg(0)));
+
+        let loose = SKConfig { depth: 4, epsilon: 0.1 };
+        let (coarse, coarse_stats) = solovay_kitaev_with_stats(&u, &loose).unwrap();
+        let tight = SKConfig { depth: 4, epsilon: 1e-3 };
+        let (fine, fine_stats) = solovay_kitaev_with_stats(&u, &tight).unwrap();
+
+        assert!(clifford_t(&coarse) && clifford_t(&fine));
+        assert!(fine_stats.depth > coarse_stats.depth);
+        assert!(fine_stats.gates > coarse_stats.gates);
+        assert_eq!(fine_stats.gates, fine.len());
+        assert!(fine_stats.error <= 1e-3 && coarse_stats.error <= 0.1);
+        assert!((fine_stats.error - phase_distance(&u, &gates_to_matrix(&fine))).abs() < 1e-12);
+        assert_eq!(solovay_kitaev(&u, &tight), fine);
+
+        // Too little depth for the target is an error, not a silent approximation
+        let capped = SKConfig { depth: 1, epsilon: 1e-3 };
+        match solovay_kitaev_with_stats(&u, &capped) {
+            Err(SynthesisError::AccuracyNotReached { max_depth: 1, error, target }) => {
+                // The plain variant falls back to that same depth-1 circuit
+                let fallback = solovay_kitaev(&u, &capped);
+                assert!(error > target);
+                assert!((phase_distance(&u, &gates_to_matrix(&fallback)) - error).abs() < 1e-12);
+            }
+            other => panic!("expected AccuracyNotReached, got {:?}", other),
+        }
+
+        // The distance resolves differences far below √ε of the trace formula
+        let theta = 1e-12;
+        let near = phase_distance(&super::super::core::identity(), &super::super::core::rz(theta));
+        assert!((near - theta / 2f64.sqrt()).abs() < 1e-20, "{:e}", near);
+    }
 }
@@ -21,6 +21,8 @@
 
 pub mod core;
 pub mod decomposition;
+pub mod circuit;
 
 pub use core::*;
 pub use decomposition::*;
+pub use circuit::*;
@@ -0,0 +1,335 @@
+//! Compact Binary Circuit Serialization
+//!
+//! This module stores `Gate` circuits in a dense byte format:
+//! - `to_bytes`: Encoding as a version byte, a gate count and one
+//!   tag-plus-operands record per gate
+//! - `from_bytes`: Validating decoder returning a `DecodeError` with the byte
+//!   offset of the first malformed record, including gates that repeat a
+//!   qubit
+//!
+//! ## 🎯 Why is this used?
+//! Text formats are slow to parse and several times larger than the data they
+//! carry. Million-gate circuits (Trotter expansions, synthesized arithmetic)
+//! load from this format with a single linear pass and no tokenizing.
+//!
+//! ## ⚙️ How it works?
+//! - **Header**: `FORMAT_VERSION`, then the number of gates as a varint.
+//! - **Tags**: One byte per gate, the index of its `GateKind` in a fixed table.
+//!   New kinds are only ever appended, so old files keep decoding.
+//! - **Operands**: Qubit and classical-bit indices are LEB128 varints (one byte
+//!   below 128); angles are 8-byte little-endian `f64`. Variable-width gates
+//!   prefix their control or qubit list with its length, and
+//!   `ClassicalControl` nests the record of its inner gate, at most
+//!   `MAX_CONTROL_NESTING` deep.
+//!
+//! ## 📍 Where to apply this?
+//! - **Caching**: Persisting synthesized or optimized circuits between runs.
+//! - **Transport**: Shipping large circuits between processes or machines.
+//!
+//! ## 📊 Code Behavior
+//! - **Size**: 2 bytes for small single-qubit gates, 11 for rotations.
+//! - **Complexity**: $O(G)$ to encode and decode.
+//! - **Round Trip**: `from_bytes(&to_bytes(c))` reproduces `c` exactly,
+//!   angles included bit for bit.
+
+use std::fmt;
+use crate::analysis::circuit_analysis::{validate, ValidationError};
+use crate::gates::circuit::build_gate;
+use crate::gates::core::{Gate, GateKind};
+
+/// Version byte written first; decoders reject versions they do not know
+pub const FORMAT_VERSION: u8 = 1;
+
+/// Nesting limit for `ClassicalControl` records, so hostile input cannot
+/// exhaust the stack
+const MAX_CONTROL_NESTING: usize = 16;
+
+/// Wire tag of each kind is its index here (append only)
+const TAGS: [GateKind; 41] = {
+    use GateKind::*;
+    [
+        X, Y, Z, H, S, Sdg, T, Tdg, SX,
+        RX, RY, RZ, P, U3,
+        CX, CY, CZ, SWAP, ISWAP,
+        CRX, CRY, CRZ, CP,
+        RXX, RYY, RZZ,
+        CCX, CCZ, CSWAP,
+        MCX, MCZ, MCP,
+        GlobalPhase,
+        Barrier, Measure, ClassicalControl,
+        Reset,
+        SqrtISWAP,
+        SXdg,
+        ISWAPdg, SqrtISWAPdg,
+    ]
+};
+
+/// Decode failure at byte `offset` of the input
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodeError {
+    pub offset: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "byte {}: {}", self.offset, self.message)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+// ============================================================================
+// ENCODING
+// ============================================================================
+
+/// Serialize a circuit to the versioned binary format
+pub fn to_bytes(circuit: &[Gate]) -> Vec<u8> {
+    let mut out = vec![FORMAT_VERSION];
+    write_varint(&mut out, circuit.len() as u64);
+    for gate in circuit {
+        write_gate(&mut out, gate);
+    }
+    out
+}
+
+pub(crate) fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+pub(crate) fn write_gate(out: &mut Vec<u8>, gate: &Gate) {
+    let kind = gate.kind();
+    out.push(TAGS.iter().position(|k| *k == kind).expect("Gate kind has no wire tag") as u8);
+
+    match gate {
+        Gate::MCX(controls, t) | Gate::MCZ(controls, t) | Gate::MCP(controls, t, _) => {
+            write_varint(out, controls.len() as u64);
+            for &q in controls.iter().chain([t]) {
+                write_varint(out, q as u64);
+            }
+        }
+        Gate::Barrier(qubits) => {
+            write_varint(out, qubits.len() as u64);
+            for &q in qubits {
+                write_varint(out, q as u64);
+            }
+        }
+        Gate::Measure(q, bit) => {
+            write_varint(out, *q as u64);
+            write_varint(out, *bit as u64);
+        }
+        Gate::ClassicalControl(bit, inner) => {
+            write_varint(out, *bit as u64);
+            return write_gate(out, inner);
+        }
+        _ => {
+            for q in gate.qubits() {
+                write_varint(out, q as u64);
+            }
+        }
+    }
+    for angle in gate.params() {
+        out.extend_from_slice(&angle.to_le_bytes());
+    }
+}
+
+// ============================================================================
+// DECODING
+// ============================================================================
+
+/// Parse a circuit written by `to_bytes`
+pub fn from_bytes(bytes: &[u8]) -> Result<Vec<Gate>, DecodeError> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let version = reader.byte()?;
+    if version != FORMAT_VERSION {
+        return Err(reader.error_at(0, format!("Unsupported format version {}", version)));
+    }
+    let count = reader.varint()?;
+
+    // Every record is at least two bytes, so a larger count is corrupt
+    let mut circuit = Vec::with_capacity((count as usize).min(bytes.len() / 2));
+    for _ in 0..count {
+        circuit.push(reader.gate(0)?);
+    }
+    if reader.pos != bytes.len() {
+        return Err(reader.error(format!("{} trailing bytes", bytes.len() - reader.pos)));
+    }
+    Ok(circuit)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn error(&self, message: String) -> DecodeError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, offset: usize, message: String) -> DecodeError {
+        DecodeError { offset, message }
+    }
+
+    fn byte(&mut self) -> Result<u8, DecodeError> {
+        let b = *self.bytes.get(self.pos).ok_or_else(|| self.error("Unexpected end of input".into()))?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn varint(&mut self) -> Result<u64, DecodeError> {
+        let start = self.pos;
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.byte()?;
+            value |= ((b & 0x7f) as u64) << shift;
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(self.error_at(start, "Varint longer than 64 bits".into()))
+    }
+
+    fn index(&mut self) -> Result<usize, DecodeError> {
+        let start = self.pos;
+        let value = self.varint()?;
+        usize::try_from(value).map_err(|_| self.error_at(start, format!("Index {} does not fit in usize", value)))
+    }
+
+    fn indices(&mut self, n: usize) -> Result<Vec<usize>, DecodeError> {
+        (0..n).map(|_| self.index()).collect()
+    }
+
+    fn angle(&mut self) -> Result<f64, DecodeError> {
+        let end = self.pos + 8;
+        let raw = self.bytes.get(self.pos..end).ok_or_else(|| self.error("Truncated angle".into()))?;
+        self.pos = end;
+        Ok(f64::from_le_bytes(raw.try_into().unwrap()))
+    }
+
+    fn gate(&mut self, depth: usize) -> Result<Gate, DecodeError> {
+        let start = self.pos;
+        let tag = self.byte()?;
+        let kind = *TAGS.get(tag as usize).ok_or_else(|| self.error_at(start, format!("Unknown gate tag {}", tag)))?;
+
+        let gate = match kind {
+            GateKind::MCX | GateKind::MCZ | GateKind::MCP => {
+                let n = self.index()?;
+                let controls = self.indices(n)?;
+                let target = self.index()?;
+                match kind {
+                    GateKind::MCX => Gate::MCX(controls, target),
+                    GateKind::MCZ => Gate::MCZ(controls, target),
+                    _ => Gate::MCP(controls, target, self.angle()?),
+                }
+            }
+            GateKind::Barrier => {
+                let n = self.index()?;
+                Gate::Barrier(self.indices(n)?)
+            }
+            GateKind::Measure => Gate::Measure(self.index()?, self.index()?),
+            GateKind::Reset => Gate::Reset(self.index()?),
+            GateKind::ClassicalControl => {
+                if depth == MAX_CONTROL_NESTING {
+                    return Err(self.error_at(start, format!("ClassicalControl nested deeper than {}", MAX_CONTROL_NESTING)));
+                }
+                Gate::ClassicalControl(self.index()?, Box::new(self.gate(depth + 1)?))
+            }
+            GateKind::GlobalPhase => Gate::GlobalPhase(self.angle()?),
+            _ => {
+                let qubits = self.indices(kind.arity().unwrap())?;
+                let params = (0..kind.num_params()).map(|_| self.angle()).collect::<Result<Vec<_>, _>>()?;
+                build_gate(kind, &qubits, &params)
+            }
+        };
+
+        // Repeated qubits would only fail later, in the simulator
+        match validate(std::slice::from_ref(&gate)) {
+            Err(ValidationError::DuplicateQubit { gate, qubit, .. } | ValidationError::ControlIsTarget { gate, qubit, .. }) => {
+                Err(self.error_at(start, format!("Gate {} uses qubit {} twice", gate, qubit)))
+            }
+            _ => Ok(gate),
+        }
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_every_kind() {
+        let circuit = vec![
+            Gate::X(0), Gate::Y(1), Gate::Z(2), Gate::H(3), Gate::S(0), Gate::Sdg(1), Gate::T(2),
+            Gate::Tdg(3), Gate::SX(200), Gate::SXdg(7), Gate::ISWAPdg(5, 2), Gate::SqrtISWAPdg(0, 6),
+            Gate::RX(0, 0.1), Gate::RY(1, -0.2), Gate::RZ(2, 1e-300),
+            Gate::P(3, f64::MAX), Gate::U3(4, 0.1, -0.2, 0.3), Gate::CX(0, 1), Gate::CY(1, 2),
+            Gate::CZ(2, 3), Gate::SWAP(3, 4), Gate::ISWAP(4, 0), Gate::SqrtISWAP(1, 3), Gate::CRX(0, 2, 0.4),
+            Gate::CRY(1, 3, 0.5), Gate::CRZ(2, 4, 0.6), Gate::CP(3, 0, 0.7), Gate::RXX(0, 1, 0.8),
+            Gate::RYY(1, 2, 0.9), Gate::RZZ(2, 3, 1.0), Gate::CCX(0, 1, 2), Gate::CCZ(1, 2, 3),
+            Gate::CSWAP(2, 3, 4), Gate::MCX(vec![0, 1, 2], 3), Gate::MCZ(vec![], 4),
+            Gate::MCP(vec![1, 70000], 0, -1.1), Gate::GlobalPhase(0.25),
+            Gate::Barrier(vec![0, 4]), Gate::Measure(3, 1), Gate::Reset(2),
+            Gate::ClassicalControl(1, Box::new(Gate::ClassicalControl(0, Box::new(Gate::RZ(0, 0.3))))),
+        ];
+        let bytes = to_bytes(&circuit);
+        assert_eq!(bytes[0], FORMAT_VERSION);
+        assert_eq!(format!("{:?}", from_bytes(&bytes).unwrap()), format!("{:?}", circuit));
+
+        // Small indices take one byte each
+        assert_eq!(to_bytes(&[Gate::CX(0, 1)]), vec![FORMAT_VERSION, 1, 14, 0, 1]);
+    }
+
+    #[test]
+    fn test_malformed_input_is_rejected() {
+        let bytes = to_bytes(&[Gate::H(0), Gate::RX(1, 0.5)]);
+
+        let err = from_bytes(&bytes[..bytes.len() - 3]).unwrap_err();
+        assert!(err.message.contains("Truncated"), "{}", err);
+
+        let mut newer = bytes.clone();
+        newer[0] = FORMAT_VERSION + 1;
+        assert_eq!(from_bytes(&newer).unwrap_err().offset, 0);
+
+        let mut bad_tag = bytes.clone();
+        bad_tag[2] = 255;
+        assert_eq!(from_bytes(&bad_tag).unwrap_err(), DecodeError { offset: 2, message: "Unknown gate tag 255".into() });
+
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert!(from_bytes(&trailing).is_err());
+        assert!(from_bytes(&[]).is_err());
+
+        // A long run of ClassicalControl tags is rejected instead of recursing
+        let control = TAGS.iter().position(|k| *k == GateKind::ClassicalControl).unwrap() as u8;
+        let mut deep = vec![FORMAT_VERSION, 1];
+        for _ in 0..1_000_000 {
+            deep.extend([control, 0]);
+        }
+        let err = from_bytes(&deep).unwrap_err();
+        assert_eq!(err.offset, 2 + 2 * MAX_CONTROL_NESTING);
+
+        let mut nested = Gate::H(0);
+        for _ in 0..MAX_CONTROL_NESTING {
+            nested = Gate::ClassicalControl(0, Box::new(nested));
+        }
+        assert_eq!(from_bytes(&to_bytes(&[nested.clone()])).unwrap(), vec![nested]);
+
+        // Records that repeat a qubit are rejected at their own offset
+        for gate in [Gate::CX(0, 0), Gate::CCX(1, 1, 2), Gate::MCX(vec![0, 3], 3), Gate::Barrier(vec![2, 2])] {
+            let bytes = to_bytes(&[Gate::H(0), gate.clone()]);
+            let err = from_bytes(&bytes).unwrap_err();
+            assert_eq!(err.offset, 4, "{:?}: {}", gate, err);
+        }
+        let inner = to_bytes(&[Gate::ClassicalControl(0, Box::new(Gate::SWAP(1, 1)))]);
+        assert_eq!(from_bytes(&inner).unwrap_err().offset, 4);
+    }
+}
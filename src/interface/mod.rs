@@ -13,6 +13,12 @@
 //!   symbolic gate representations and Python's dynamic data structures.
 //! - **Wrappers**: Provides simplified, high-level entry points for complex 
 //!   tasks like Pauli network synthesis and Clifford isometry mapping.
+//! - **QASM Interchange**: The `qasm` submodule reads and writes OpenQASM 3
+//!   programs for exchanging `Gate` circuits with other toolchains.
+//! - **Binary Storage**: The `binary` submodule stores circuits in a compact
+//!   versioned byte format for fast loading.
+//! - **Streaming Output**: The `writer` submodule validates and encodes
+//!   circuits one gate at a time into any `std::io::Write`.
 //!
 //! ## 📍 Where to apply this?
 //! Use this when you want to leverage Quantic-Rust from Python. It is 
@@ -24,6 +30,14 @@
 //! - **Safety**: Ensures that Python-side memory management (GC) is correctly 
 //!   synchronized with Rust's ownership model.
 //!
+pub mod binary;
+pub mod qasm;
+pub mod writer;
+
+pub use binary::*;
+pub use qasm::*;
+pub use writer::*;
+
 use pyo3::prelude::*;
 use pyo3::wrap_pyfunction;
 use rustiq_core::routines::rotation_extraction::extract_rotations as extract_rot;
@@ -40,6 +54,9 @@ use rustiq_core::synthesis::clifford::graph_state::{
 use rustiq_core::synthesis::clifford::isometry::isometry_synthesis as iso_synth;
 use rustiq_core::synthesis::pauli_network::{check_circuit, greedy_pauli_network};
 
+/// Rotations as (axis, angle) strings paired with the final Clifford's logicals
+type RotationsWithClifford = (Vec<(String, String)>, Vec<(bool, String)>);
+
 #[pyclass]
 #[derive(Clone)]
 pub struct Metric {
@@ -64,7 +81,7 @@ impl Metric {
 }
 impl Metric {
     pub fn unwrap(self) -> RsMetric {
-        return self.data;
+        self.data
     }
 }
 
@@ -80,9 +97,9 @@ pub fn pauli_network_synthesis(
     skip_sort: bool,
     fix_clifford: bool,
 ) -> Vec<(String, Vec<usize>)> {
-    let mut bucket = PauliSet::from_slice(&operator_sequence);
+    let bucket = PauliSet::from_slice(&operator_sequence);
     let circuit = greedy_pauli_network(
-        &mut bucket,
+        &bucket,
         &metric.unwrap(),
         preserve_order,
         nshuffles,
@@ -92,7 +109,7 @@ pub fn pauli_network_synthesis(
     if check {
         check_circuit(&operator_sequence, &circuit);
     }
-    return circuit.gates.iter().map(|gate| gate.to_vec()).collect();
+    circuit.gates.iter().map(|gate| gate.to_vec()).collect()
 }
 
 #[pyfunction]
@@ -134,7 +151,7 @@ pub fn codiagonalization(
 ) -> Vec<(String, Vec<usize>)> {
     let mut pset = PauliSet::from_slice(&paulis);
     let circuit = codiag(&mut pset, &metric.unwrap(), niter);
-    return circuit.gates.iter().map(|gate| gate.to_vec()).collect();
+    circuit.gates.iter().map(|gate| gate.to_vec()).collect()
 }
 
 #[pyfunction]
@@ -143,7 +160,7 @@ pub fn codiagonalization(
 pub fn codiagonalization_sswise(paulis: Vec<String>, k: usize) -> Vec<(String, Vec<usize>)> {
     let pset = PauliSet::from_slice(&paulis);
     let circuit = codiagonalize_subsetwise(&pset, k);
-    return circuit.gates.iter().map(|gate| gate.to_vec()).collect();
+    circuit.gates.iter().map(|gate| gate.to_vec()).collect()
 }
 
 #[pyfunction]
@@ -164,7 +181,7 @@ pub fn isometry_synthesis(
         stabilizers,
     };
     let circuit = iso_synth(&isometry, &metric.unwrap(), niter);
-    return circuit.gates.iter().map(|gate| gate.to_vec()).collect();
+    circuit.gates.iter().map(|gate| gate.to_vec()).collect()
 }
 
 #[pyfunction]
@@ -173,9 +190,9 @@ pub fn extract_rotations(
     angles: Vec<String>,
     nqubits: usize,
     optimize: bool,
-) -> (Vec<(String, String)>, Vec<(bool, String)>) {
+) -> RotationsWithClifford {
     let (rotations, mut clifford) = extract_rot(&circuit, nqubits);
-    let angles = angles.into_iter().map(|s| Parameter::from_string(s));
+    let angles = angles.into_iter().map(Parameter::from_string);
     let mut rotations: Vec<_> = angles
         .zip(rotations)
         .map(|(mut angle, (phase, axis))| {
@@ -194,17 +211,17 @@ pub fn extract_rotations(
         .into_iter()
         .map(|(x, y)| (x, y.to_string()))
         .collect();
-    return (
+    (
         rotations,
         (0..2 * nqubits).map(|i| clifford.logicals.get(i)).collect(),
-    );
+    )
 }
 
 #[pyfunction]
 pub fn zhang_rotation_optimization(
     rotations: Vec<(String, String)>,
     nqubits: usize,
-) -> (Vec<(String, String)>, Vec<(bool, String)>) {
+) -> RotationsWithClifford {
     let rotations = rotations
         .into_iter()
         .map(|(a, b)| (a, Parameter::from_string(b)))
@@ -215,16 +232,16 @@ pub fn zhang_rotation_optimization(
         .map(|(x, y)| (x, y.to_string()))
         .collect();
     let clifford = inverse_final_clifford.adjoint();
-    return (
+    (
         rotations,
         (0..2 * nqubits).map(|i| clifford.logicals.get(i)).collect(),
-    );
+    )
 }
 
 #[pyfunction]
 pub fn initial_state_propagation(
     rotations: Vec<(String, String)>,
-) -> (Vec<(String, String)>, Vec<(bool, String)>) {
+) -> RotationsWithClifford {
     let rotations: Vec<_> = rotations
         .into_iter()
         .map(|(a, b)| (a, Parameter::from_string(b)))
@@ -234,12 +251,12 @@ pub fn initial_state_propagation(
         .into_iter()
         .map(|(x, y)| (x, y.to_string()))
         .collect();
-    return (
+    (
         rotations,
         (0..2 * final_clifford.logicals.n)
             .map(|i| final_clifford.logicals.get(i))
             .collect(),
-    );
+    )
 }
 
 #[pyfunction]
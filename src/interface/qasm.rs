@@ -0,0 +1,1166 @@
+//! OpenQASM 3 Import and Export
+//!
+//! This module converts between `Gate` circuits and OpenQASM 3 source:
+//! - `to_qasm3`: Serialization with `stdgates.inc` names, modifiers and
+//!   on-demand `gate` definitions for non-standard gates
+//! - `to_qasm2`: Serialization for legacy OpenQASM 2.0 consumers, lowering
+//!   gates `qelib1.inc` lacks to CX + single-qubit decompositions
+//! - `from_qasm3`: Parsing of registers, custom `gate` definitions, `barrier`,
+//!   mid-circuit `measure` and `reset`, and single-bit `if` feed-forward
+//!
+//! ## 🎯 Why is this used?
+//! Most quantum toolchains (Qiskit, Braket, tket, hardware vendors) speak
+//! OpenQASM 3. Reading and writing it lets circuits built or optimized here
+//! run elsewhere, and lets externally authored programs be analyzed here,
+//! including dynamic circuits whose later gates depend on measurement results.
+//!
+//! ## ⚙️ How it works?
+//! - **Statements**: Comments are stripped, then the source is split on `;`
+//!   outside braces; `gate` bodies and `if` blocks are kept whole.
+//! - **Registers**: Every `qubit`/`qreg` register is laid out contiguously in
+//!   declaration order, and likewise every `bit`/`creg` register.
+//! - **Gate Calls**: Built-in names map to `Gate` variants. `ctrl(n) @` on
+//!   `x`, `z` or `p` gives MCX/MCZ/MCP, on anything else an exact controlled
+//!   expansion; `inv @` inverts the expansion. Whole-register operands broadcast.
+//! - **Custom Gates**: `gate` definitions are expanded inline with their
+//!   parameters and qubit arguments bound. Built-in names take precedence, so
+//!   the definitions `to_qasm3` emits for `rxx`, `iswap`, ... read back natively.
+//! - **Expressions**: Angles accept `+ - * / **`, parentheses, `pi`/`tau`/`euler`,
+//!   `sin cos tan exp ln sqrt arcsin arccos arctan` and declared constants.
+//! - **Feed-Forward**: `if (c[i]) stmt;` (or `== 1`) wraps each gate of the
+//!   statement in `Gate::ClassicalControl(i, ..)`.
+//!
+//! ## 📍 Where to apply this?
+//! - **Interchange**: Exporting synthesized circuits to other SDKs or hardware.
+//! - **Regression Tests**: Storing reference circuits as readable text.
+//! - **Dynamic Circuits**: Teleportation, error-correction rounds and other
+//!   measure-and-correct protocols.
+//!
+//! ## 📊 Code Behavior
+//! - **Complexity**: Linear in the source length, plus the size of expanded
+//!   custom gates.
+//! - **Round Trip**: `from_qasm3(&to_qasm3(c))` reproduces `c` gate for gate;
+//!   angles are written with Rust's shortest round-trip float formatting.
+//! - **Scope**: Classical arithmetic, loops, `else` branches and multi-bit
+//!   conditions are rejected with a `QasmError` naming the line.
+
+use std::collections::HashMap;
+use std::fmt;
+use crate::gates::circuit::inverse_circuit;
+use crate::gates::core::Gate;
+use crate::gates::decomposition::{
+    control_gate, decompose_crx, decompose_cry, decompose_mcx, decompose_mcz, mcp_no_ancilla,
+};
+
+/// Parse failure with the 1-based source line of the offending statement
+#[derive(Clone, Debug, PartialEq)]
+pub struct QasmError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for QasmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for QasmError {}
+
+/// Definitions emitted for gates that `stdgates.inc` does not provide
+pub(crate) const EXTRA_GATE_DEFINITIONS: [(&str, &str); 9] = [
+    ("sxdg", "gate sxdg a { inv @ sx a; }"),
+    ("iswap", "gate iswap a, b { s a; s b; h a; cx a, b; cx b, a; h b; }"),
+    ("iswapdg", "gate iswapdg a, b { h b; cx b, a; cx a, b; h a; sdg b; sdg a; }"),
+    (
+        "sqrtiswap",
+        "gate sqrtiswap a, b { h a; h b; cx a, b; rz(-pi/4) b; cx a, b; h a; h b; \
+         rx(pi/2) a; rx(pi/2) b; cx a, b; rz(-pi/4) b; cx a, b; rx(-pi/2) a; rx(-pi/2) b; }",
+    ),
+    (
+        "sqrtiswapdg",
+        "gate sqrtiswapdg a, b { h a; h b; cx a, b; rz(pi/4) b; cx a, b; h a; h b; \
+         rx(pi/2) a; rx(pi/2) b; cx a, b; rz(pi/4) b; cx a, b; rx(-pi/2) a; rx(-pi/2) b; }",
+    ),
+    ("rxx", "gate rxx(theta) a, b { h a; h b; cx a, b; rz(theta) b; cx a, b; h a; h b; }"),
+    (
+        "ryy",
+        "gate ryy(theta) a, b { rx(pi/2) a; rx(pi/2) b; cx a, b; rz(theta) b; cx a, b; \
+         rx(-pi/2) a; rx(-pi/2) b; }",
+    ),
+    ("rzz", "gate rzz(theta) a, b { cx a, b; rz(theta) b; cx a, b; }"),
+    ("ccz", "gate ccz a, b, c { h c; ccx a, b, c; h c; }"),
+];
+
+/// Gate names handled natively by the parser (they shadow custom definitions)
+const BUILTIN_GATES: [&str; 46] = [
+    "id", "x", "y", "z", "h", "s", "sdg", "t", "tdg", "sx", "sxdg", "rx", "ry", "rz", "p", "phase",
+    "u1", "u3", "u", "U", "u2", "gphase", "cx", "CX", "cnot", "cy", "cz", "ch", "swap",
+    "iswap", "iswapdg", "sqrtiswap", "siswap", "sqrtiswapdg", "crx", "cry", "crz", "cp", "cphase", "cu1", "rxx", "ryy", "rzz", "ccx", "ccz",
+    "cswap",
+];
+
+/// Nesting limit for custom gate expansion
+const MAX_GATE_NESTING: usize = 64;
+
+// ============================================================================
+// EXPORT
+// ============================================================================
+
+/// Serialize a circuit as an OpenQASM 3 program on registers `q` and `c`
+///
+/// Register sizes are the highest qubit and classical bit used, plus one.
+pub fn to_qasm3(circuit: &[Gate]) -> String {
+    let num_qubits = circuit.iter().flat_map(|g| g.qubits()).max().map_or(0, |q| q + 1);
+    let num_bits = circuit.iter().filter_map(max_bit).max().map_or(0, |b| b + 1);
+
+    let mut body = String::new();
+    let mut used_extras: Vec<&str> = Vec::new();
+    for gate in circuit {
+        let stmt = qasm3_statement(gate, &mut used_extras);
+        if !stmt.is_empty() {
+            body.push_str(&stmt);
+            body.push('\n');
+        }
+    }
+
+    let mut out = String::from("OPENQASM 3.0;\ninclude \"stdgates.inc\";\n");
+    for (name, definition) in EXTRA_GATE_DEFINITIONS {
+        if used_extras.contains(&name) {
+            out.push_str(definition);
+            out.push('\n');
+        }
+    }
+    out.push_str(&format!("qubit[{}] q;\n", num_qubits));
+    if num_bits > 0 {
+        out.push_str(&format!("bit[{}] c;\n", num_bits));
+    }
+    out.push_str(&body);
+    out
+}
+
+fn max_bit(gate: &Gate) -> Option<usize> {
+    match gate {
+        Gate::Measure(_, c) => Some(*c),
+        Gate::ClassicalControl(c, inner) => Some(max_bit(inner).map_or(*c, |b| b.max(*c))),
+        _ => None,
+    }
+}
+
+pub(crate) fn qasm3_statement(gate: &Gate, used_extras: &mut Vec<&'static str>) -> String {
+    let q = |i: &usize| format!("q[{}]", i);
+    let list = |qs: &[usize]| qs.iter().map(q).collect::<Vec<_>>().join(", ");
+
+    let (name, qubits): (String, Vec<usize>) = match gate {
+        Gate::Measure(qubit, bit) => return format!("c[{}] = measure q[{}];", bit, qubit),
+        Gate::Reset(qubit) => return format!("reset q[{}];", qubit),
+        Gate::ClassicalControl(bit, inner) => {
+            return format!("if (c[{}]) {}", bit, qasm3_statement(inner, used_extras));
+        }
+        Gate::Barrier(qs) if qs.is_empty() => return String::new(),
+        Gate::Barrier(qs) => return format!("barrier {};", list(qs)),
+        Gate::GlobalPhase(theta) => return format!("gphase({});", theta),
+
+        Gate::X(a) => ("x".into(), vec![*a]),
+        Gate::Y(a) => ("y".into(), vec![*a]),
+        Gate::Z(a) => ("z".into(), vec![*a]),
+        Gate::H(a) => ("h".into(), vec![*a]),
+        Gate::S(a) => ("s".into(), vec![*a]),
+        Gate::Sdg(a) => ("sdg".into(), vec![*a]),
+        Gate::T(a) => ("t".into(), vec![*a]),
+        Gate::Tdg(a) => ("tdg".into(), vec![*a]),
+        Gate::SX(a) => ("sx".into(), vec![*a]),
+        Gate::SXdg(a) => ("sxdg".into(), vec![*a]),
+        Gate::RX(a, t) => (format!("rx({})", t), vec![*a]),
+        Gate::RY(a, t) => (format!("ry({})", t), vec![*a]),
+        Gate::RZ(a, t) => (format!("rz({})", t), vec![*a]),
+        Gate::P(a, t) => (format!("p({})", t), vec![*a]),
+        Gate::U3(a, theta, phi, lambda) => (format!("u3({}, {}, {})", theta, phi, lambda), vec![*a]),
+
+        Gate::CX(a, b) => ("cx".into(), vec![*a, *b]),
+        Gate::CY(a, b) => ("cy".into(), vec![*a, *b]),
+        Gate::CZ(a, b) => ("cz".into(), vec![*a, *b]),
+        Gate::SWAP(a, b) => ("swap".into(), vec![*a, *b]),
+        Gate::ISWAP(a, b) => ("iswap".into(), vec![*a, *b]),
+        Gate::ISWAPdg(a, b) => ("iswapdg".into(), vec![*a, *b]),
+        Gate::SqrtISWAP(a, b) => ("sqrtiswap".into(), vec![*a, *b]),
+        Gate::SqrtISWAPdg(a, b) => ("sqrtiswapdg".into(), vec![*a, *b]),
+        Gate::CRX(a, b, t) => (format!("crx({})", t), vec![*a, *b]),
+        Gate::CRY(a, b, t) => (format!("cry({})", t), vec![*a, *b]),
+        Gate::CRZ(a, b, t) => (format!("crz({})", t), vec![*a, *b]),
+        Gate::CP(a, b, t) => (format!("cp({})", t), vec![*a, *b]),
+        Gate::RXX(a, b, t) => (format!("rxx({})", t), vec![*a, *b]),
+        Gate::RYY(a, b, t) => (format!("ryy({})", t), vec![*a, *b]),
+        Gate::RZZ(a, b, t) => (format!("rzz({})", t), vec![*a, *b]),
+
+        Gate::CCX(a, b, c) => ("ccx".into(), vec![*a, *b, *c]),
+        Gate::CCZ(a, b, c) => ("ccz".into(), vec![*a, *b, *c]),
+        Gate::CSWAP(a, b, c) => ("cswap".into(), vec![*a, *b, *c]),
+        Gate::MCX(controls, _) => (format!("ctrl({}) @ x", controls.len()), gate.qubits()),
+        Gate::MCZ(controls, _) => (format!("ctrl({}) @ z", controls.len()), gate.qubits()),
+        Gate::MCP(controls, _, phi) => (format!("ctrl({}) @ p({})", controls.len(), phi), gate.qubits()),
+    };
+
+    let bare = name.split('(').next().unwrap_or("");
+    if let Some((extra, _)) = EXTRA_GATE_DEFINITIONS.iter().find(|(n, _)| *n == bare) {
+        if !used_extras.contains(extra) {
+            used_extras.push(extra);
+        }
+    }
+    format!("{} {};", name, list(&qubits))
+}
+
+/// Serialize a circuit as an OpenQASM 2.0 program over `qelib1.inc`
+///
+/// Gates outside the original `qelib1.inc` set (CRY, CRX, SWAP, iSWAP,
+/// √iSWAP and their adjoints, RXX, RYY, RZZ, CCZ, CSWAP, SX, SX† and multi-controlled gates) are
+/// written as their CX + single-qubit decompositions. Global phases are dropped. Each classical
+/// bit b becomes its own one-bit register `c{b}`, so feed-forward on a single
+/// bit can be written as `if(c{b}==1)`.
+pub fn to_qasm2(circuit: &[Gate]) -> String {
+    let num_qubits = circuit.iter().flat_map(|g| g.qubits()).max().map_or(0, |q| q + 1);
+    let num_bits = circuit.iter().filter_map(max_bit).max().map_or(0, |b| b + 1);
+
+    let mut out = String::from("OPENQASM 2.0;\ninclude \"qelib1.inc\";\n");
+    out.push_str(&format!("qreg q[{}];\n", num_qubits));
+    for b in 0..num_bits {
+        out.push_str(&format!("creg c{}[1];\n", b));
+    }
+    for gate in circuit {
+        qasm2_statements(gate, "", &mut out);
+    }
+    out
+}
+
+/// Append the QASM 2 lines for `gate`, each prefixed with `condition`
+fn qasm2_statements(gate: &Gate, condition: &str, out: &mut String) {
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    let q = |i: &usize| format!("q[{}]", i);
+    let list = |qs: &[usize]| qs.iter().map(q).collect::<Vec<_>>().join(",");
+
+    let native = |out: &mut String, name: String| {
+        out.push_str(&format!("{}{} {};\n", condition, name, list(&gate.qubits())));
+    };
+
+    let lowered = match gate {
+        Gate::Measure(qubit, bit) => {
+            out.push_str(&format!("{}measure q[{}] -> c{}[0];\n", condition, qubit, bit));
+            return;
+        }
+        Gate::Reset(qubit) => {
+            out.push_str(&format!("{}reset q[{}];\n", condition, qubit));
+            return;
+        }
+        Gate::ClassicalControl(bit, inner) => {
+            return qasm2_statements(inner, &format!("{}if(c{}==1) ", condition, bit), out);
+        }
+        Gate::Barrier(qs) if qs.is_empty() => return,
+        Gate::Barrier(qs) => {
+            out.push_str(&format!("{}barrier {};\n", condition, list(qs)));
+            return;
+        }
+        Gate::GlobalPhase(_) => return,
+
+        Gate::SX(a) => vec![Gate::RX(*a, FRAC_PI_2)],
+        Gate::SXdg(a) => vec![Gate::RX(*a, -FRAC_PI_2)],
+        Gate::SWAP(a, b) => vec![Gate::CX(*a, *b), Gate::CX(*b, *a), Gate::CX(*a, *b)],
+        Gate::ISWAP(a, b) => vec![
+            Gate::S(*a), Gate::S(*b), Gate::H(*a), Gate::CX(*a, *b), Gate::CX(*b, *a), Gate::H(*b),
+        ],
+        Gate::ISWAPdg(a, b) => vec![
+            Gate::H(*b), Gate::CX(*b, *a), Gate::CX(*a, *b), Gate::H(*a), Gate::Sdg(*b), Gate::Sdg(*a),
+        ],
+        Gate::SqrtISWAP(a, b) => vec![Gate::RXX(*a, *b, -FRAC_PI_4), Gate::RYY(*a, *b, -FRAC_PI_4)],
+        Gate::SqrtISWAPdg(a, b) => vec![Gate::RXX(*a, *b, FRAC_PI_4), Gate::RYY(*a, *b, FRAC_PI_4)],
+        Gate::CRX(c, t, theta) => decompose_crx(*c, *t, *theta),
+        Gate::CRY(c, t, theta) => decompose_cry(*c, *t, *theta),
+        Gate::RZZ(a, b, theta) => vec![Gate::CX(*a, *b), Gate::RZ(*b, *theta), Gate::CX(*a, *b)],
+        Gate::RXX(a, b, theta) => {
+            let mut gates = vec![Gate::H(*a), Gate::H(*b), Gate::RZZ(*a, *b, *theta)];
+            gates.extend([Gate::H(*a), Gate::H(*b)]);
+            gates
+        }
+        Gate::RYY(a, b, theta) => {
+            let mut gates = vec![Gate::RX(*a, FRAC_PI_2), Gate::RX(*b, FRAC_PI_2), Gate::RZZ(*a, *b, *theta)];
+            gates.extend([Gate::RX(*a, -FRAC_PI_2), Gate::RX(*b, -FRAC_PI_2)]);
+            gates
+        }
+        Gate::CCZ(a, b, c) => vec![Gate::H(*c), Gate::CCX(*a, *b, *c), Gate::H(*c)],
+        Gate::CSWAP(c, a, b) => vec![Gate::CX(*b, *a), Gate::CCX(*c, *a, *b), Gate::CX(*b, *a)],
+        Gate::MCX(controls, t) => match controls.as_slice() {
+            [] => vec![Gate::X(*t)],
+            [c] => vec![Gate::CX(*c, *t)],
+            [c1, c2] => vec![Gate::CCX(*c1, *c2, *t)],
+            _ => decompose_mcx(controls, *t),
+        },
+        Gate::MCZ(controls, t) => decompose_mcz(controls, *t),
+        Gate::MCP(controls, t, phi) => mcp_no_ancilla(controls, *t, *phi),
+
+        // Gates qelib1.inc provides
+        Gate::X(_) => return native(out, "x".into()),
+        Gate::Y(_) => return native(out, "y".into()),
+        Gate::Z(_) => return native(out, "z".into()),
+        Gate::H(_) => return native(out, "h".into()),
+        Gate::S(_) => return native(out, "s".into()),
+        Gate::Sdg(_) => return native(out, "sdg".into()),
+        Gate::T(_) => return native(out, "t".into()),
+        Gate::Tdg(_) => return native(out, "tdg".into()),
+        Gate::RX(_, t) => return native(out, format!("rx({})", t)),
+        Gate::RY(_, t) => return native(out, format!("ry({})", t)),
+        Gate::RZ(_, t) => return native(out, format!("rz({})", t)),
+        Gate::P(_, t) => return native(out, format!("u1({})", t)),
+        Gate::U3(_, theta, phi, lambda) => return native(out, format!("u3({},{},{})", theta, phi, lambda)),
+        Gate::CX(_, _) => return native(out, "cx".into()),
+        Gate::CY(_, _) => return native(out, "cy".into()),
+        Gate::CZ(_, _) => return native(out, "cz".into()),
+        Gate::CRZ(_, _, t) => return native(out, format!("crz({})", t)),
+        Gate::CP(_, _, t) => return native(out, format!("cu1({})", t)),
+        Gate::CCX(_, _, _) => return native(out, "ccx".into()),
+    };
+    for g in &lowered {
+        qasm2_statements(g, condition, out);
+    }
+}
+
+// ============================================================================
+// IMPORT
+// ============================================================================
+
+/// Parse an OpenQASM 3 program (OpenQASM 2 `qreg`/`creg` are also accepted)
+pub fn from_qasm3(source: &str) -> Result<Vec<Gate>, QasmError> {
+    Parser::default().parse(source)
+}
+
+struct GateDefinition {
+    params: Vec<String>,
+    args: Vec<String>,
+    body: String,
+}
+
+#[derive(Default)]
+struct Parser {
+    qregs: HashMap<String, Vec<usize>>,
+    cregs: HashMap<String, Vec<usize>>,
+    num_qubits: usize,
+    num_bits: usize,
+    constants: HashMap<String, f64>,
+    definitions: HashMap<String, GateDefinition>,
+    line: usize,
+}
+
+impl Parser {
+    fn parse(mut self, source: &str) -> Result<Vec<Gate>, QasmError> {
+        let mut gates = Vec::new();
+        for (line, stmt) in split_statements(&strip_comments(source)) {
+            self.line = line;
+            self.statement(&stmt, &mut gates).map_err(|message| QasmError { line, message })?;
+        }
+        Ok(gates)
+    }
+
+    fn statement(&mut self, stmt: &str, out: &mut Vec<Gate>) -> Result<(), String> {
+        let keyword = stmt.split(|c: char| !c.is_alphanumeric() && c != '_').next().unwrap_or("");
+        match keyword {
+            "OPENQASM" | "include" => Ok(()),
+            "qubit" | "qreg" => self.declare(stmt, keyword, true),
+            "bit" | "creg" => self.declare(stmt, keyword, false),
+            "const" | "float" | "angle" => self.constant(stmt),
+            "gate" => self.define_gate(stmt),
+            "barrier" => {
+                let operands = stmt["barrier".len()..].trim();
+                let qubits = if operands.is_empty() {
+                    (0..self.num_qubits).collect()
+                } else {
+                    let mut qubits = Vec::new();
+                    for operand in split_top_level(operands, ',') {
+                        qubits.extend(self.resolve(&self.qregs, &operand)?);
+                    }
+                    qubits
+                };
+                out.push(Gate::Barrier(qubits));
+                Ok(())
+            }
+            "reset" => {
+                for operand in split_top_level(stmt["reset".len()..].trim(), ',') {
+                    out.extend(self.resolve(&self.qregs, &operand)?.into_iter().map(Gate::Reset));
+                }
+                Ok(())
+            }
+            "measure" => {
+                let (qubits, bits) = stmt["measure".len()..]
+                    .split_once("->")
+                    .ok_or("Measurement result must be stored with '->'")?;
+                self.measure(qubits, bits, out)
+            }
+            "if" => self.conditional(stmt, out),
+            "else" => Err("'else' branches are not supported".into()),
+            _ if stmt.contains("= measure") || stmt.contains("=measure") => {
+                let (bits, qubits) = stmt.split_once('=').unwrap();
+                let qubits = qubits.trim().strip_prefix("measure").unwrap();
+                self.measure(qubits, bits, out)
+            }
+            _ => {
+                let scope = self.qregs.clone();
+                let env = self.constants.clone();
+                self.gate_call(stmt, &scope, &env, 0, out)
+            }
+        }
+    }
+
+    fn declare(&mut self, stmt: &str, keyword: &str, quantum: bool) -> Result<(), String> {
+        let rest = stmt[keyword.len()..].trim();
+        // qubit[n] name | qubit name | qreg name[n]
+        let (name, size) = if let Some(rest) = rest.strip_prefix('[') {
+            let (size, name) = rest.split_once(']').ok_or("Unclosed register size")?;
+            (name.trim(), parse_size(size)?)
+        } else if let Some((name, size)) = rest.split_once('[') {
+            (name.trim(), parse_size(size.trim_end_matches(']'))?)
+        } else {
+            (rest, 1)
+        };
+        if name.is_empty() || name.contains('=') {
+            return Err(format!("Unsupported declaration '{}'", stmt));
+        }
+
+        let (regs, counter) = if quantum {
+            (&mut self.qregs, &mut self.num_qubits)
+        } else {
+            (&mut self.cregs, &mut self.num_bits)
+        };
+        if regs.contains_key(name) {
+            return Err(format!("Register '{}' declared twice", name));
+        }
+        regs.insert(name.to_string(), (*counter..*counter + size).collect());
+        *counter += size;
+        Ok(())
+    }
+
+    fn constant(&mut self, stmt: &str) -> Result<(), String> {
+        let (lhs, rhs) = stmt.split_once('=').ok_or_else(|| format!("Unsupported declaration '{}'", stmt))?;
+        let name = lhs.split_whitespace().last().ok_or("Missing constant name")?;
+        let value = eval_expr(rhs, &self.constants)?;
+        self.constants.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    fn define_gate(&mut self, stmt: &str) -> Result<(), String> {
+        let open = stmt.find('{').ok_or("Gate definition has no body")?;
+        let close = stmt.rfind('}').ok_or("Gate definition body is not closed")?;
+        let header = stmt["gate".len()..open].trim();
+        let body = stmt[open + 1..close].to_string();
+
+        let name_end = header.find(|c: char| c == '(' || c.is_whitespace()).unwrap_or(header.len());
+        let name = header[..name_end].to_string();
+        let mut rest = header[name_end..].trim();
+        let mut params = Vec::new();
+        if rest.starts_with('(') {
+            let end = matching_paren(rest, 0).ok_or("Unclosed parameter list")?;
+            params = split_top_level(&rest[1..end], ',');
+            rest = rest[end + 1..].trim();
+        }
+        let args = split_top_level(rest, ',');
+        if name.is_empty() || args.is_empty() {
+            return Err(format!("Malformed gate definition '{}'", header));
+        }
+        self.definitions.insert(name, GateDefinition { params, args, body });
+        Ok(())
+    }
+
+    fn measure(&self, qubits: &str, bits: &str, out: &mut Vec<Gate>) -> Result<(), String> {
+        let qubits = self.resolve(&self.qregs, qubits)?;
+        let bits = self.resolve(&self.cregs, bits)?;
+        if qubits.len() != bits.len() {
+            return Err(format!("Measuring {} qubits into {} bits", qubits.len(), bits.len()));
+        }
+        out.extend(qubits.into_iter().zip(bits).map(|(q, c)| Gate::Measure(q, c)));
+        Ok(())
+    }
+
+    fn conditional(&mut self, stmt: &str, out: &mut Vec<Gate>) -> Result<(), String> {
+        let open = stmt.find('(').ok_or("Missing condition")?;
+        let close = matching_paren(stmt, open).ok_or("Unclosed condition")?;
+        let condition = &stmt[open + 1..close];
+
+        let bit_expr = match condition.split_once("==") {
+            Some((lhs, rhs)) => {
+                if !matches!(rhs.trim(), "1" | "true") {
+                    return Err("Only conditions on a bit being 1 are supported".into());
+                }
+                lhs
+            }
+            None => condition,
+        };
+        let bits = self.resolve(&self.cregs, bit_expr)?;
+        if bits.len() != 1 {
+            return Err(format!("Condition '{}' is not a single bit", bit_expr.trim()));
+        }
+
+        let body = stmt[close + 1..].trim();
+        let body = body.strip_prefix('{').and_then(|b| b.strip_suffix('}')).unwrap_or(body);
+        let mut inner = Vec::new();
+        for part in split_top_level(body, ';') {
+            self.statement(&part, &mut inner)?;
+        }
+        out.extend(inner.into_iter().map(|g| Gate::ClassicalControl(bits[0], Box::new(g))));
+        Ok(())
+    }
+
+    /// Resolve `name` or `name[i]` against a register map
+    fn resolve(&self, regs: &HashMap<String, Vec<usize>>, operand: &str) -> Result<Vec<usize>, String> {
+        let operand = operand.trim();
+        let (name, index) = match operand.split_once('[') {
+            Some((name, index)) => (name.trim(), Some(index.trim_end_matches(']'))),
+            None => (operand, None),
+        };
+        let reg = regs.get(name).ok_or_else(|| format!("Unknown register '{}'", name))?;
+        match index {
+            None => Ok(reg.clone()),
+            Some(index) => {
+                let i = parse_size(index)?;
+                reg.get(i).map(|&q| vec![q])
+                    .ok_or_else(|| format!("Index {} out of range for '{}'", i, name))
+            }
+        }
+    }
+
+    fn gate_call(
+        &self,
+        stmt: &str,
+        scope: &HashMap<String, Vec<usize>>,
+        env: &HashMap<String, f64>,
+        depth: usize,
+        out: &mut Vec<Gate>,
+    ) -> Result<(), String> {
+        if depth > MAX_GATE_NESTING {
+            return Err("Gate definitions nest too deeply (recursive definition?)".into());
+        }
+
+        // Peel `modifier @` prefixes
+        let mut rest = stmt.trim();
+        let mut controls = 0;
+        let mut invert = false;
+        while let Some(at) = find_top_level(rest, '@') {
+            let modifier = rest[..at].trim();
+            if modifier == "inv" {
+                invert = !invert;
+            } else if modifier == "ctrl" {
+                controls += 1;
+            } else if let Some(n) = modifier.strip_prefix("ctrl(").and_then(|m| m.strip_suffix(')')) {
+                controls += parse_size(n)?;
+            } else {
+                return Err(format!("Unsupported gate modifier '{}'", modifier));
+            }
+            rest = rest[at + 1..].trim();
+        }
+
+        let name_end = rest.find(|c: char| c == '(' || c.is_whitespace()).unwrap_or(rest.len());
+        let name = &rest[..name_end];
+        let mut operands = rest[name_end..].trim();
+        let mut params = Vec::new();
+        if operands.starts_with('(') {
+            let end = matching_paren(operands, 0).ok_or("Unclosed parameter list")?;
+            for p in split_top_level(&operands[1..end], ',') {
+                params.push(eval_expr(&p, env)?);
+            }
+            operands = operands[end + 1..].trim();
+        }
+
+        // Resolve operands and broadcast whole registers elementwise
+        let resolved = split_top_level(operands, ',')
+            .iter()
+            .map(|o| self.resolve(scope, o))
+            .collect::<Result<Vec<_>, _>>()?;
+        let width = resolved.iter().map(|r| r.len()).max().unwrap_or(1);
+        if resolved.iter().any(|r| r.len() != 1 && r.len() != width) {
+            return Err("Broadcast operands must have equal register sizes".into());
+        }
+
+        for k in 0..width {
+            let qubits: Vec<usize> = resolved.iter().map(|r| r[if r.len() == 1 { 0 } else { k }]).collect();
+            if let Some(i) = (1..qubits.len()).find(|&i| qubits[..i].contains(&qubits[i])) {
+                return Err(format!("'{}' lists qubit {} more than once", name, qubits[i]));
+            }
+            let mut gates = self.apply(name, controls, &params, &qubits, depth)?;
+            if invert {
+                gates = inverse_circuit(&gates);
+            }
+            out.extend(gates);
+        }
+        Ok(())
+    }
+
+    fn apply(
+        &self,
+        name: &str,
+        controls: usize,
+        params: &[f64],
+        qubits: &[usize],
+        depth: usize,
+    ) -> Result<Vec<Gate>, String> {
+        if qubits.len() < controls {
+            return Err(format!("'{}' has fewer operands than controls", name));
+        }
+        let (ctrl, targets) = qubits.split_at(controls);
+
+        // Multi-controlled gates with a native variant
+        if controls > 0 {
+            if let ([], [t]) = (params, targets) {
+                match name {
+                    "x" => return Ok(vec![Gate::MCX(ctrl.to_vec(), *t)]),
+                    "z" => return Ok(vec![Gate::MCZ(ctrl.to_vec(), *t)]),
+                    _ => {}
+                }
+            }
+            if let ([phi], [t], "p" | "phase" | "u1") = (params, targets, name) {
+                return Ok(vec![Gate::MCP(ctrl.to_vec(), *t, *phi)]);
+            }
+        }
+
+        let mut gates = match builtin_gate(name, params, targets) {
+            Some(result) => result?,
+            None => {
+                let def = self.definitions.get(name).ok_or_else(|| format!("Unknown gate '{}'", name))?;
+                if def.params.len() != params.len() || def.args.len() != targets.len() {
+                    return Err(format!(
+                        "'{}' expects {} parameters and {} qubits, got {} and {}",
+                        name, def.params.len(), def.args.len(), params.len(), targets.len()
+                    ));
+                }
+                let mut env = self.constants.clone();
+                env.extend(def.params.iter().cloned().zip(params.iter().copied()));
+                let scope: HashMap<String, Vec<usize>> =
+                    def.args.iter().cloned().zip(targets.iter().map(|&q| vec![q])).collect();
+
+                let mut gates = Vec::new();
+                for stmt in split_top_level(&def.body, ';') {
+                    self.gate_call(&stmt, &scope, &env, depth + 1, &mut gates)?;
+                }
+                gates
+            }
+        };
+
+        // Remaining controls: exact expansion, innermost control last
+        for &c in ctrl.iter().rev() {
+            gates = gates.iter().flat_map(|g| control_gate(c, g)).collect();
+        }
+        Ok(gates)
+    }
+}
+
+/// Built-in gate by name, `None` if the name is not built in
+fn builtin_gate(name: &str, params: &[f64], q: &[usize]) -> Option<Result<Vec<Gate>, String>> {
+    use std::f64::consts::FRAC_PI_2;
+
+    let gate = match (name, params, q) {
+        ("id", [], [_]) => return Some(Ok(vec![])),
+        ("x", [], [a]) => Gate::X(*a),
+        ("y", [], [a]) => Gate::Y(*a),
+        ("z", [], [a]) => Gate::Z(*a),
+        ("h", [], [a]) => Gate::H(*a),
+        ("s", [], [a]) => Gate::S(*a),
+        ("sdg", [], [a]) => Gate::Sdg(*a),
+        ("t", [], [a]) => Gate::T(*a),
+        ("tdg", [], [a]) => Gate::Tdg(*a),
+        ("sx", [], [a]) => Gate::SX(*a),
+        ("sxdg", [], [a]) => Gate::SXdg(*a),
+        ("rx", [t], [a]) => Gate::RX(*a, *t),
+        ("ry", [t], [a]) => Gate::RY(*a, *t),
+        ("rz", [t], [a]) => Gate::RZ(*a, *t),
+        ("p" | "phase" | "u1", [t], [a]) => Gate::P(*a, *t),
+        ("u3" | "u" | "U", [theta, phi, lambda], [a]) => Gate::U3(*a, *theta, *phi, *lambda),
+        ("u2", [phi, lambda], [a]) => Gate::U3(*a, FRAC_PI_2, *phi, *lambda),
+        ("gphase", [t], []) => Gate::GlobalPhase(*t),
+
+        ("cx" | "CX" | "cnot", [], [a, b]) => Gate::CX(*a, *b),
+        ("cy", [], [a, b]) => Gate::CY(*a, *b),
+        ("cz", [], [a, b]) => Gate::CZ(*a, *b),
+        ("ch", [], [a, b]) => return Some(Ok(control_gate(*a, &Gate::H(*b)))),
+        ("swap", [], [a, b]) => Gate::SWAP(*a, *b),
+        ("iswap", [], [a, b]) => Gate::ISWAP(*a, *b),
+        ("iswapdg", [], [a, b]) => Gate::ISWAPdg(*a, *b),
+        ("sqrtiswap" | "siswap", [], [a, b]) => Gate::SqrtISWAP(*a, *b),
+        ("sqrtiswapdg", [], [a, b]) => Gate::SqrtISWAPdg(*a, *b),
+        ("crx", [t], [a, b]) => Gate::CRX(*a, *b, *t),
+        ("cry", [t], [a, b]) => Gate::CRY(*a, *b, *t),
+        ("crz", [t], [a, b]) => Gate::CRZ(*a, *b, *t),
+        ("cp" | "cphase" | "cu1", [t], [a, b]) => Gate::CP(*a, *b, *t),
+        ("rxx", [t], [a, b]) => Gate::RXX(*a, *b, *t),
+        ("ryy", [t], [a, b]) => Gate::RYY(*a, *b, *t),
+        ("rzz", [t], [a, b]) => Gate::RZZ(*a, *b, *t),
+
+        ("ccx", [], [a, b, c]) => Gate::CCX(*a, *b, *c),
+        ("ccz", [], [a, b, c]) => Gate::CCZ(*a, *b, *c),
+        ("cswap", [], [a, b, c]) => Gate::CSWAP(*a, *b, *c),
+
+        _ if BUILTIN_GATES.contains(&name) => {
+            return Some(Err(format!(
+                "Wrong number of parameters ({}) or qubits ({}) for '{}'",
+                params.len(), q.len(), name
+            )));
+        }
+        _ => return None,
+    };
+    Some(Ok(vec![gate]))
+}
+
+// ============================================================================
+// LEXICAL HELPERS
+// ============================================================================
+
+/// Remove `//` and `/* */` comments, keeping newlines for line numbers
+fn strip_comments(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                    }
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Split into (line, statement) pairs on `;` outside braces
+///
+/// A `}` closing the outermost brace also ends a `gate` or `if` statement.
+fn split_statements(source: &str) -> Vec<(usize, String)> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut line = 1;
+    let mut start_line = 1;
+    let mut depth = 0usize;
+
+    for c in source.chars() {
+        if current.trim().is_empty() {
+            start_line = line;
+        }
+        if c == '\n' {
+            line += 1;
+        }
+        match c {
+            '{' => {
+                depth += 1;
+                current.push(c);
+            }
+            '}' => {
+                depth = depth.saturating_sub(1);
+                current.push(c);
+                let head = current.trim_start();
+                if depth == 0 && (head.starts_with("gate") || head.starts_with("if")) {
+                    statements.push((start_line, current.trim().to_string()));
+                    current.clear();
+                }
+            }
+            ';' if depth == 0 => {
+                if !current.trim().is_empty() {
+                    statements.push((start_line, current.trim().to_string()));
+                }
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.trim().is_empty() {
+        statements.push((start_line, current.trim().to_string()));
+    }
+    statements
+}
+
+/// Split on `sep` outside parentheses and braces, dropping empty pieces
+fn split_top_level(s: &str, sep: char) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut current = String::new();
+    for c in s.chars() {
+        match c {
+            '(' | '{' | '[' => depth += 1,
+            ')' | '}' | ']' => depth -= 1,
+            _ => {}
+        }
+        if c == sep && depth == 0 {
+            parts.push(current.trim().to_string());
+            current.clear();
+        } else {
+            current.push(c);
+        }
+    }
+    parts.push(current.trim().to_string());
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+fn find_top_level(s: &str, target: char) -> Option<usize> {
+    let mut depth = 0i32;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ if c == target && depth == 0 => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Byte index of the `)` matching the `(` at `open`
+fn matching_paren(s: &str, open: usize) -> Option<usize> {
+    let mut depth = 0i32;
+    for (i, c) in s[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_size(s: &str) -> Result<usize, String> {
+    s.trim().parse().map_err(|_| format!("Expected a non-negative integer, got '{}'", s.trim()))
+}
+
+// ============================================================================
+// EXPRESSIONS
+// ============================================================================
+
+/// Evaluate an angle expression with `env` supplying named values
+fn eval_expr(expr: &str, env: &HashMap<String, f64>) -> Result<f64, String> {
+    let chars: Vec<char> = expr.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut eval = ExprEval { chars: &chars, pos: 0, env };
+    let value = eval.sum()?;
+    if eval.pos != chars.len() {
+        return Err(format!("Unexpected '{}' in expression '{}'", chars[eval.pos], expr.trim()));
+    }
+    Ok(value)
+}
+
+struct ExprEval<'a> {
+    chars: &'a [char],
+    pos: usize,
+    env: &'a HashMap<String, f64>,
+}
+
+impl ExprEval<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    // sum := product (('+' | '-') product)*
+    fn sum(&mut self) -> Result<f64, String> {
+        let mut value = self.product()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.pos += 1;
+            let rhs = self.product()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    // product := unary (('*' | '/') unary)*
+    fn product(&mut self) -> Result<f64, String> {
+        let mut value = self.unary()?;
+        loop {
+            match (self.peek(), self.chars.get(self.pos + 1)) {
+                (Some('*'), Some('*')) => break,
+                (Some('*'), _) => {
+                    self.pos += 1;
+                    value *= self.unary()?;
+                }
+                (Some('/'), _) => {
+                    self.pos += 1;
+                    value /= self.unary()?;
+                }
+                _ => break,
+            }
+        }
+        Ok(value)
+    }
+
+    // unary := ('-' | '+') unary | power
+    fn unary(&mut self) -> Result<f64, String> {
+        match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                Ok(-self.unary()?)
+            }
+            Some('+') => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    // power := atom ('**' unary)?
+    fn power(&mut self) -> Result<f64, String> {
+        let base = self.atom()?;
+        if self.peek() == Some('*') && self.chars.get(self.pos + 1) == Some(&'*') {
+            self.pos += 2;
+            return Ok(base.powf(self.unary()?));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<f64, String> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let value = self.sum()?;
+                if self.peek() != Some(')') {
+                    return Err("Unclosed parenthesis in expression".into());
+                }
+                self.pos += 1;
+                Ok(value)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(),
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let start = self.pos;
+                while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                    self.pos += 1;
+                }
+                let name: String = self.chars[start..self.pos].iter().collect();
+                if self.peek() == Some('(') {
+                    let arg = self.atom()?;
+                    return apply_function(&name, arg);
+                }
+                match name.as_str() {
+                    "pi" | "π" => Ok(std::f64::consts::PI),
+                    "tau" | "τ" => Ok(std::f64::consts::TAU),
+                    "euler" | "ℇ" => Ok(std::f64::consts::E),
+                    _ => self.env.get(&name).copied().ok_or_else(|| format!("Unknown identifier '{}'", name)),
+                }
+            }
+            Some(c) => Err(format!("Unexpected '{}' in expression", c)),
+            None => Err("Unexpected end of expression".into()),
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, String> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+            self.pos += 1;
+        }
+        // Exponent, e.g. 1e-3
+        if matches!(self.peek(), Some('e' | 'E')) {
+            let mark = self.pos;
+            self.pos += 1;
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.pos += 1;
+            }
+            if self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.pos += 1;
+                }
+            } else {
+                self.pos = mark;
+            }
+        }
+        let literal: String = self.chars[start..self.pos].iter().collect();
+        literal.parse().map_err(|_| format!("Invalid number '{}'", literal))
+    }
+}
+
+fn apply_function(name: &str, x: f64) -> Result<f64, String> {
+    Ok(match name {
+        "sin" => x.sin(),
+        "cos" => x.cos(),
+        "tan" => x.tan(),
+        "exp" => x.exp(),
+        "ln" => x.ln(),
+        "sqrt" => x.sqrt(),
+        "arcsin" => x.asin(),
+        "arccos" => x.acos(),
+        "arctan" => x.atan(),
+        _ => return Err(format!("Unknown function '{}'", name)),
+    })
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::{simulate_from, states_close};
+    use crate::gates::core::Complex;
+
+    fn teleportation() -> Vec<Gate> {
+        vec![
+            Gate::RY(0, 0.7), Gate::RZ(0, -1.25),
+            Gate::H(1), Gate::CX(1, 2),
+            Gate::Barrier(vec![0, 1, 2]),
+            Gate::CX(0, 1), Gate::H(0),
+            Gate::Measure(0, 0), Gate::Measure(1, 1),
+            Gate::ClassicalControl(1, Box::new(Gate::X(2))),
+            Gate::ClassicalControl(0, Box::new(Gate::Z(2))),
+        ]
+    }
+
+    #[test]
+    fn test_teleportation_round_trip() {
+        let circuit = teleportation();
+        let qasm = to_qasm3(&circuit);
+
+        assert!(qasm.contains("bit[2] c;"));
+        assert!(qasm.contains("c[0] = measure q[0];"));
+        assert!(qasm.contains("if (c[1]) x q[2];"));
+
+        let parsed = from_qasm3(&qasm).unwrap();
+        assert_eq!(format!("{:?}", parsed), format!("{:?}", circuit));
+    }
+
+    #[test]
+    fn test_every_gate_round_trips() {
+        let circuit = vec![
+            Gate::SX(0), Gate::SXdg(1), Gate::U3(1, 0.1, -0.2, 0.3), Gate::P(2, 1e-7),
+            Gate::ISWAP(0, 1), Gate::ISWAPdg(1, 0), Gate::RXX(1, 2, 0.4), Gate::RYY(0, 2, -0.5), Gate::RZZ(0, 1, 0.6),
+            Gate::SqrtISWAP(2, 1), Gate::SqrtISWAPdg(0, 2), Gate::CRY(2, 0, 0.7), Gate::CCZ(0, 1, 2), Gate::CSWAP(2, 0, 1),
+            Gate::MCX(vec![0, 1, 2], 3), Gate::MCZ(vec![3], 0), Gate::MCP(vec![0, 1], 3, 0.8),
+            Gate::GlobalPhase(0.9), Gate::Reset(2),
+        ];
+        let parsed = from_qasm3(&to_qasm3(&circuit)).unwrap();
+        assert_eq!(format!("{:?}", parsed), format!("{:?}", circuit));
+    }
+
+    #[test]
+    fn test_extra_definitions_match_gates() {
+        let cases = [
+            ("sxdg", Gate::SXdg(0), ""),
+            ("iswap", Gate::ISWAP(0, 1), ""),
+            ("iswapdg", Gate::ISWAPdg(0, 1), ""),
+            ("sqrtiswap", Gate::SqrtISWAP(0, 1), ""),
+            ("sqrtiswapdg", Gate::SqrtISWAPdg(0, 1), ""),
+            ("rxx", Gate::RXX(0, 1, 0.3), "(0.3)"),
+            ("ryy", Gate::RYY(0, 1, 0.3), "(0.3)"),
+            ("rzz", Gate::RZZ(0, 1, 0.3), "(0.3)"),
+            ("ccz", Gate::CCZ(0, 1, 2), ""),
+        ];
+        let input: Vec<Complex> = (0..8).map(|i| Complex::new(0.1 * i as f64, 0.3 - 0.05 * i as f64)).collect();
+
+        for (name, gate, args) in cases {
+            // Rename so the definition body is expanded rather than read natively
+            let definition = EXTRA_GATE_DEFINITIONS.iter().find(|(n, _)| *n == name).unwrap().1;
+            let renamed = definition.replacen(name, "custom", 1);
+            let operands = ["q[0]", "q[0], q[1]", "q[0], q[1], q[2]"][gate.qubits().len() - 1];
+            let source = format!("qubit[3] q;\n{}\ncustom{} {};", renamed, args, operands);
+
+            let expanded = from_qasm3(&source).unwrap();
+            let a = simulate_from(&expanded, &input).unwrap();
+            let b = simulate_from(&[gate], &input).unwrap();
+            assert!(states_close(&a, &b, 1e-10), "Definition of {} is wrong", name);
+        }
+    }
+
+    #[test]
+    fn test_parse_features() {
+        let source = r#"
+            OPENQASM 3.0;
+            include "stdgates.inc";
+            const float half = pi / 2;
+            /* two registers laid out back to back */
+            qubit[2] a;
+            qubit b;
+            bit[2] m;
+            gate rot(theta, phi) x, y { rz(theta * 2) x; cx x, y; ry(-phi + half) y; }
+            h a;                                 // broadcast over a[0], a[1]
+            rot(pi / 4, 2 ** -1) a[1], b;
+            inv @ s b;
+            ctrl @ rx(0.5) a[0], b;
+            barrier a, b;
+            measure a -> m;
+            if (m[1] == 1) { x b; z a[0]; }
+        "#;
+        let gates = from_qasm3(source).unwrap();
+
+        assert!(matches!(gates[0], Gate::H(0)));
+        assert!(matches!(gates[1], Gate::H(1)));
+        assert!(matches!(gates[2], Gate::RZ(1, t) if (t - std::f64::consts::FRAC_PI_2).abs() < 1e-12));
+        assert!(matches!(gates[3], Gate::CX(1, 2)));
+        assert!(matches!(gates[4], Gate::RY(2, t) if (t - (std::f64::consts::FRAC_PI_2 - 0.5)).abs() < 1e-12));
+        assert!(matches!(gates[5], Gate::Sdg(2)));
+        assert!(matches!(gates[6], Gate::CRX(0, 2, t) if (t - 0.5).abs() < 1e-12));
+        assert_eq!(gates[7].qubits(), vec![0, 1, 2]);
+        assert!(matches!(gates[8], Gate::Measure(0, 0)));
+        assert!(matches!(gates[9], Gate::Measure(1, 1)));
+        assert!(matches!(&gates[10], Gate::ClassicalControl(1, g) if matches!(**g, Gate::X(2))));
+        assert!(matches!(&gates[11], Gate::ClassicalControl(1, g) if matches!(**g, Gate::Z(0))));
+        assert_eq!(gates.len(), 12);
+    }
+
+    #[test]
+    fn test_qasm2_export_lowers_unsupported_gates() {
+        use crate::analysis::circuit_analysis::unitary_equivalent;
+
+        let circuit = vec![
+            Gate::H(0), Gate::SX(1), Gate::CRY(0, 1, 0.7), Gate::CRX(2, 0, -0.4), Gate::CRZ(1, 2, 0.3),
+            Gate::CP(0, 2, 1.1), Gate::SWAP(0, 3), Gate::ISWAP(1, 2), Gate::RXX(0, 1, 0.4),
+            Gate::RYY(2, 3, -0.5), Gate::RZZ(0, 3, 0.6), Gate::CCZ(0, 1, 2), Gate::CSWAP(3, 0, 1),
+            Gate::U3(2, 0.1, -0.2, 0.3), Gate::MCX(vec![0, 1, 2], 3), Gate::MCZ(vec![1, 3], 0),
+            Gate::MCP(vec![0, 1, 3], 2, 0.8), Gate::GlobalPhase(0.9),
+        ];
+        let qasm = to_qasm2(&circuit);
+        assert!(qasm.starts_with("OPENQASM 2.0;\ninclude \"qelib1.inc\";\nqreg q[4];\n"));
+        for missing in ["cry", "crx", "swap", "iswap", "rxx", "ryy", "rzz", "ccz", "sx ", "cp(", "gphase"] {
+            assert!(!qasm.contains(missing), "QASM 2 output uses '{}':\n{}", missing, qasm);
+        }
+
+        let parsed = from_qasm3(&qasm).unwrap();
+        assert!(unitary_equivalent(&parsed, &circuit, 4));
+    }
+
+    #[test]
+    fn test_qasm2_teleportation() {
+        let qasm = to_qasm2(&teleportation());
+        assert!(qasm.contains("creg c1[1];"));
+        assert!(qasm.contains("measure q[0] -> c0[0];"));
+        assert!(qasm.contains("if(c1==1) x q[2];"));
+
+        let parsed = from_qasm3(&qasm).unwrap();
+        assert_eq!(format!("{:?}", parsed), format!("{:?}", teleportation()));
+    }
+
+    #[test]
+    fn test_errors_report_line() {
+        let err = from_qasm3("qubit[2] q;\nh q[0];\nfoo q[1];").unwrap_err();
+        assert_eq!(err.line, 3);
+        assert!(err.message.contains("foo"));
+
+        let err = from_qasm3("qubit q;\nbit[2] c;\nif (c == 3) x q;").unwrap_err();
+        assert_eq!(err.line, 3);
+
+        // Repeated operands are rejected instead of building invalid gates
+        for stmt in ["ctrl @ h q[0], q[0];", "ch q[0], q[0];", "cx q[0], q[0];"] {
+            let err = from_qasm3(&format!("qubit[2] q;\n{}", stmt)).unwrap_err();
+            assert_eq!(err.line, 2, "{}", stmt);
+            assert!(err.message.contains("more than once"), "{}: {}", stmt, err.message);
+        }
+        assert!(from_qasm3("qubit[2] q;\ncx q, q;").is_err());
+    }
+}
@@ -12,7 +12,11 @@
 //! - [`synthesis`] - Advanced synthesis (amplitude encoding, state preparation)
 //! - [`analysis`] - Circuit analysis (depth, resource estimation)
 //! - [`error_mitigation`] - Error mitigation techniques (ZNE, PEC, CDR)
+//! - [`simulator`] - Exact statevector simulation for verification
 //! - [`cutting`] - Circuit cutting and distribution
+//! - [`routing`] - Qubit routing on restricted connectivity (SWAP networks, placement)
+//! - [`util`] - Shared helpers (the seedable `Rng` taken by the `*_with_rng` variants)
+//! - [`error`] - `RustiqError`, returned instead of panicking on bad inputs
 //!
 //! ## 🎯 Why is this used?
 //! Quantic-Rust is designed to be a "瑞士军刀" (Swiss Army Knife) for quantum 
@@ -22,7 +26,7 @@
 //!
 //! ## ⚙️ How it works?
 //! - **Hierarchical Composition**: Low-level `gates` form the basis for `algorithms`, 
-//!    which are then `optimized` and further protected by `error_correction` or `mitigation`.
+//!   which are then `optimized` and further protected by `error_correction` or `mitigation`.
 //! - **Unified Representation**: Uses a consistent internal format for circuits, 
 //!   enabling seamless interaction between synthesis, analysis, and optimization tools.
 //! - **Extensibility**: Each module is built to be independent yet interoperable, 
@@ -53,6 +57,12 @@ pub mod synthesis;
 pub mod analysis;
 pub mod error_mitigation;
 pub mod cutting;
+pub mod simulator;
+pub mod routing;
+pub mod util;
+pub mod error;
+
+pub use error::RustiqError;
 
 // Post-Quantum Cryptography (NIST FIPS 203/204/205)
 pub mod cryptography;
@@ -10,7 +10,8 @@
 //! - **Pipeline approach**: Combines rule-based peephole optimization with 
 //!   topological rewrite rules (ZX-calculus) to achieve deep circuit reduction.
 //! - **Submodules**: `optimize` handles standard gate-set transformations, while 
-//!   `zx_calculus` provides advanced graph-theoretic optimization.
+//!   `zx_calculus` provides advanced graph-theoretic optimization, and
+//!   `phase_polynomial` rewrites CNOT + phase blocks in sum-over-parities form.
 //!
 //! ## 📍 Where to apply this?
 //! Mandatory step before running any high-depth circuit on physical QPUs. 
@@ -21,6 +22,8 @@
 
 pub mod optimize;
 pub mod zx_calculus;
+pub mod phase_polynomial;
 
 pub use optimize::*;
 pub use zx_calculus::*;
+pub use phase_polynomial::*;
@@ -3,10 +3,14 @@
 //! This module provides quantum circuit optimization techniques:
 //! - Gate cancellation (removing consecutive inverse gates)
 //! - Gate merging (combining compatible rotations)
+//! - Angle normalization modulo 2π, keeping the sign of RZ(2π) = −I
 //! - Commutation analysis
 //! - T-count optimization
 //! - CNOT minimization
 //! - Template matching
+//! - Single-qubit run fusion
+//! - SWAP recognition and lowering
+//! - Per-pass gate, T and depth reports
 //!
 //! ## 🎯 Why is this used?
 //! Synthesis algorithms often produce redundant or non-local gate sequences. This 
@@ -21,6 +25,8 @@
 //!   to enable distant gate cancellations (e.g., $Z$ moving past $CNOT$ control).
 //! - **Rotation Merging**: Combines multiple rotations around the same axis 
 //!   (e.g., $RZ(\theta_1) \cdot RZ(\theta_2) \rightarrow RZ(\theta_1 + \theta_2)$).
+//! - **Run Fusion**: Multiplies maximal runs of 1-qubit gates on a wire into one
+//!   2x2 unitary and re-synthesizes it as at most three ZYZ rotations.
 //! - **Template Matching**: Replaces specific sub-circuits with more efficient 
 //!   equivalent versions from a pre-computed library.
 //!
@@ -38,7 +44,10 @@
 //!   relative precision error across merges.
 
 use std::f64::consts::PI;
-use crate::gates::core::Gate;
+use crate::gates::circuit::build_gate;
+use crate::gates::core::{Gate, GateMatrix2x2, DEFAULT_TOL};
+use crate::analysis::circuit_analysis::circuit_depth;
+use crate::gates::decomposition::{euler_decompose_zyz, euler_to_gates_zyz, matrix_multiply_2x2};
 
 // ============================================================================
 // GATE CANCELLATION
@@ -89,6 +98,15 @@ fn are_inverse_gates(g1: &Gate, g2: &Gate) -> bool {
         // T and Tdg
         (Gate::T(q1), Gate::Tdg(q2)) => q1 == q2,
         (Gate::Tdg(q1), Gate::T(q2)) => q1 == q2,
+
+        // SX and SXdg
+        (Gate::SX(q1), Gate::SXdg(q2)) => q1 == q2,
+        (Gate::SXdg(q1), Gate::SX(q2)) => q1 == q2,
+
+        // iSWAP and √iSWAP against their adjoints (both are symmetric)
+        (Gate::ISWAP(a1, b1), Gate::ISWAPdg(a2, b2)) | (Gate::ISWAPdg(a1, b1), Gate::ISWAP(a2, b2)) |
+        (Gate::SqrtISWAP(a1, b1), Gate::SqrtISWAPdg(a2, b2)) | (Gate::SqrtISWAPdg(a1, b1), Gate::SqrtISWAP(a2, b2)) =>
+            (a1 == a2 && b1 == b2) || (a1 == b2 && b1 == a2),
         
         // Rotation gates with opposite angles
         (Gate::RX(q1, theta1), Gate::RX(q2, theta2)) => 
@@ -152,12 +170,8 @@ fn try_merge_rotation(gates: &[Gate]) -> (usize, Gate) {
                 }
             }
             
-            // Remove if angle is multiple of 2π
-            if (total_angle % (2.0 * PI)).abs() < 1e-10 {
-                (count, Gate::RZ(*q, 0.0)) // Will be removed in cleanup
-            } else {
-                (count, Gate::RZ(*q, total_angle))
-            }
+            // RZ(2π) = −I, so the sum is left for `normalize_angles`
+            (count, Gate::RZ(*q, total_angle))
         }
         Gate::RX(q, theta) => {
             let mut total_angle = *theta;
@@ -211,6 +225,112 @@ fn try_merge_rotation(gates: &[Gate]) -> (usize, Gate) {
     }
 }
 
+// ============================================================================
+// ANGLE NORMALIZATION
+// ============================================================================
+
+/// Reduce every angle into (−π, π] and drop the rotations that become identity
+///
+/// Phase-type angles (P, CP, MCP, the φ and λ of U3, `GlobalPhase`) have
+/// period 2π. Rotations e^(−iθP/2) (RX, RY, RZ, RXX, RYY, RZZ, the θ of U3)
+/// have period 4π: shifting θ by 2π multiplies them by −1, which is kept as a
+/// phase of π. For CRX, CRY and CRZ that −1 is conditional, so it becomes a Z
+/// on the control. All global phase is summed into one trailing
+/// `GlobalPhase`, omitted when it vanishes; the unitary is unchanged exactly.
+pub fn normalize_angles(circuit: &[Gate]) -> Vec<Gate> {
+    normalize_angles_with_tol(circuit, DEFAULT_TOL)
+}
+
+/// [`normalize_angles`] treating reduced angles with |θ| ≤ `tol` as zero
+pub fn normalize_angles_with_tol(circuit: &[Gate], tol: f64) -> Vec<Gate> {
+    let mut result = Vec::new();
+    let mut phase = 0.0;
+    for gate in circuit {
+        normalize_gate(gate, tol, &mut result, &mut phase);
+    }
+    push_phase(&mut result, phase, tol);
+    result
+}
+
+fn normalize_gate(gate: &Gate, tol: f64, out: &mut Vec<Gate>, phase: &mut f64) {
+    let kind = gate.kind();
+    let qubits = gate.qubits();
+    match gate {
+        Gate::ClassicalControl(bit, inner) => {
+            // The branch's phase is only global within the branch
+            let mut branch = Vec::new();
+            let mut branch_phase = 0.0;
+            normalize_gate(inner, tol, &mut branch, &mut branch_phase);
+            push_phase(&mut branch, branch_phase, tol);
+            out.extend(branch.into_iter().map(|g| Gate::ClassicalControl(*bit, Box::new(g))));
+        }
+        Gate::GlobalPhase(phi) => *phase += phi,
+        Gate::RX(_, theta) | Gate::RY(_, theta) | Gate::RZ(_, theta)
+        | Gate::RXX(_, _, theta) | Gate::RYY(_, _, theta) | Gate::RZZ(_, _, theta) => {
+            let (theta, negated) = reduce_half_angle(*theta);
+            if negated {
+                *phase += PI;
+            }
+            if theta.abs() > tol {
+                out.push(build_gate(kind, &qubits, &[theta]));
+            }
+        }
+        Gate::CRX(control, _, theta) | Gate::CRY(control, _, theta) | Gate::CRZ(control, _, theta) => {
+            let (theta, negated) = reduce_half_angle(*theta);
+            if negated {
+                out.push(Gate::Z(*control));
+            }
+            if theta.abs() > tol {
+                out.push(build_gate(kind, &qubits, &[theta]));
+            }
+        }
+        Gate::P(_, phi) | Gate::CP(_, _, phi) => {
+            let phi = wrap_angle(*phi);
+            if phi.abs() > tol {
+                out.push(build_gate(kind, &qubits, &[phi]));
+            }
+        }
+        Gate::MCP(controls, target, phi) => {
+            let phi = wrap_angle(*phi);
+            if phi.abs() > tol {
+                out.push(Gate::MCP(controls.clone(), *target, phi));
+            }
+        }
+        Gate::U3(q, theta, phi, lambda) => {
+            let (theta, negated) = reduce_half_angle(*theta);
+            if negated {
+                *phase += PI;
+            }
+            let (phi, lambda) = (wrap_angle(*phi), wrap_angle(*lambda));
+            // U3(0, φ, λ) = P(φ + λ)
+            if theta.abs() > tol || wrap_angle(phi + lambda).abs() > tol {
+                out.push(Gate::U3(*q, theta, phi, lambda));
+            }
+        }
+        _ => out.push(gate.clone()),
+    }
+}
+
+fn push_phase(out: &mut Vec<Gate>, phase: f64, tol: f64) {
+    let phase = wrap_angle(phase);
+    if phase.abs() > tol {
+        out.push(Gate::GlobalPhase(phase));
+    }
+}
+
+/// θ mod 2π in (−π, π]
+fn wrap_angle(theta: f64) -> f64 {
+    let r = theta.rem_euclid(2.0 * PI);
+    if r > PI { r - 2.0 * PI } else { r }
+}
+
+/// θ mod 2π in (−π, π], and whether an odd multiple of 2π was removed
+fn reduce_half_angle(theta: f64) -> (f64, bool) {
+    let reduced = wrap_angle(theta);
+    let turns = ((theta - reduced) / (2.0 * PI)).round() as i64;
+    (reduced, turns % 2 != 0)
+}
+
 // ============================================================================
 // COMMUTATION ANALYSIS
 // ============================================================================
@@ -402,7 +522,7 @@ fn flush_all_t_gates(
     }
 }
 
-fn add_t_gates(result: &mut Vec<Gate>, q: usize, count: i32) {
+pub(crate) fn add_t_gates(result: &mut Vec<Gate>, q: usize, count: i32) {
     let count = count % 8;
     match count {
         0 => {}
@@ -643,6 +763,176 @@ fn optimize_window(window: &[Gate]) -> Option<Vec<Gate>> {
     None
 }
 
+// ============================================================================
+// SINGLE-QUBIT RUN FUSION
+// ============================================================================
+
+/// Fuse consecutive 1-qubit gates on each wire into a single ZYZ triple
+///
+/// A run ends when any multi-qubit gate touches the wire. Runs of two or more
+/// gates are replaced by RZ·RY·RZ (identity angles dropped) plus the
+/// `GlobalPhase` that makes the replacement exact; lone gates are kept as-is.
+pub fn fuse_single_qubit_runs(circuit: &[Gate]) -> Vec<Gate> {
+    let mut result = Vec::new();
+    // Per-qubit pending run: accumulated product and the original gates
+    let mut runs: Vec<Option<(GateMatrix2x2, Vec<Gate>)>> = Vec::new();
+
+    for gate in circuit {
+        if let Some(m) = gate.matrix_2x2() {
+            let q = gate.qubits()[0];
+            if runs.len() <= q {
+                runs.resize(q + 1, None);
+            }
+            runs[q] = Some(match runs[q].take() {
+                // Later gates multiply from the left
+                Some((acc, mut gates)) => {
+                    gates.push(gate.clone());
+                    (matrix_multiply_2x2(&m, &acc), gates)
+                }
+                None => (m, vec![gate.clone()]),
+            });
+        } else {
+            for q in gate.qubits() {
+                if let Some(run) = runs.get_mut(q).and_then(Option::take) {
+                    flush_run(&mut result, q, run);
+                }
+            }
+            result.push(gate.clone());
+        }
+    }
+
+    for (q, run) in runs.into_iter().enumerate() {
+        if let Some(run) = run {
+            flush_run(&mut result, q, run);
+        }
+    }
+
+    result
+}
+
+fn flush_run(result: &mut Vec<Gate>, qubit: usize, (matrix, gates): (GateMatrix2x2, Vec<Gate>)) {
+    if gates.len() == 1 {
+        result.extend(gates);
+    } else {
+        result.extend(euler_to_gates_zyz(&euler_decompose_zyz(&matrix), qubit));
+    }
+}
+
+// ============================================================================
+// SWAP RECOGNITION
+// ============================================================================
+
+/// Fuse CX(a,b)·CX(b,a)·CX(a,b) into SWAP(a,b)
+///
+/// The three CNOTs need not be adjacent in the list, only consecutive on
+/// wires a and b: gates on other qubits may sit between them. The SWAP takes
+/// the place of the first CNOT.
+pub fn recognize_swaps(circuit: &[Gate]) -> Vec<Gate> {
+    let mut removed = vec![false; circuit.len()];
+    let mut result = Vec::new();
+
+    for i in 0..circuit.len() {
+        if removed[i] {
+            continue;
+        }
+        if let Gate::CX(a, b) = circuit[i] {
+            // Next two live gates touching a or b
+            let mut next = (i + 1..circuit.len())
+                .filter(|&j| !removed[j])
+                .filter(|&j| circuit[j].qubits().iter().any(|&q| q == a || q == b));
+            if let (Some(j), Some(k)) = (next.next(), next.next()) {
+                if matches!(circuit[j], Gate::CX(c, t) if (c, t) == (b, a))
+                    && matches!(circuit[k], Gate::CX(c, t) if (c, t) == (a, b))
+                {
+                    removed[j] = true;
+                    removed[k] = true;
+                    result.push(Gate::SWAP(a, b));
+                    continue;
+                }
+            }
+        }
+        result.push(circuit[i].clone());
+    }
+    result
+}
+
+/// Lower every SWAP(a,b) to CX(a,b)·CX(b,a)·CX(a,b)
+pub fn expand_swaps(circuit: &[Gate]) -> Vec<Gate> {
+    circuit
+        .iter()
+        .flat_map(|gate| match gate {
+            Gate::SWAP(a, b) => vec![Gate::CX(*a, *b), Gate::CX(*b, *a), Gate::CX(*a, *b)],
+            other => vec![other.clone()],
+        })
+        .collect()
+}
+
+// ============================================================================
+// PASS REPORTS
+// ============================================================================
+
+/// Before/after metrics of one optimization pass
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PassReport {
+    pub gates_before: usize,
+    pub gates_after: usize,
+    pub t_before: usize,
+    pub t_after: usize,
+    pub depth_before: usize,
+    pub depth_after: usize,
+}
+
+impl PassReport {
+    /// Metrics of `before` and `after`
+    pub fn new(before: &[Gate], after: &[Gate]) -> Self {
+        PassReport {
+            gates_before: before.len(),
+            gates_after: after.len(),
+            t_before: t_count(before),
+            t_after: t_count(after),
+            depth_before: circuit_depth(before),
+            depth_after: circuit_depth(after),
+        }
+    }
+
+    /// Gates removed by the pass (negative if it added gates)
+    pub fn gate_delta(&self) -> isize {
+        self.gates_before as isize - self.gates_after as isize
+    }
+
+    /// True when neither the gate count nor the T-count went up
+    pub fn no_regression(&self) -> bool {
+        self.gates_after <= self.gates_before && self.t_after <= self.t_before
+    }
+}
+
+/// Run any pass and report its effect
+pub fn run_with_report(circuit: &[Gate], pass: impl Fn(&[Gate]) -> Vec<Gate>) -> (Vec<Gate>, PassReport) {
+    let result = pass(circuit);
+    let report = PassReport::new(circuit, &result);
+    (result, report)
+}
+
+/// [`cancel_inverse_gates`] with a [`PassReport`]
+pub fn cancel_inverse_gates_with_report(circuit: &[Gate]) -> (Vec<Gate>, PassReport) {
+    run_with_report(circuit, cancel_inverse_gates)
+}
+
+/// [`optimize_t_gates`] with a [`PassReport`]
+pub fn optimize_t_gates_with_report(circuit: &[Gate]) -> (Vec<Gate>, PassReport) {
+    run_with_report(circuit, optimize_t_gates)
+}
+
+/// [`optimize_cnot_gates`] with a [`PassReport`]
+pub fn optimize_cnot_gates_with_report(circuit: &[Gate]) -> (Vec<Gate>, PassReport) {
+    run_with_report(circuit, optimize_cnot_gates)
+}
+
+/// [`fuse_single_qubit_runs`] with a [`PassReport`]
+pub fn fuse_single_qubit_runs_with_report(circuit: &[Gate]) -> (Vec<Gate>, PassReport) {
+    run_with_report(circuit, fuse_single_qubit_runs)
+}
+
 // ============================================================================
 // FULL OPTIMIZATION PIPELINE
 // ============================================================================
@@ -672,14 +962,8 @@ pub fn full_optimize(circuit: &[Gate]) -> Vec<Gate> {
     // Final cleanup
     result = cancel_inverse_gates(&result);
     
-    // Remove identity gates
-    result.retain(|g| match g {
-        Gate::RZ(_, theta) | Gate::RX(_, theta) | Gate::RY(_, theta) => theta.abs() > 1e-10,
-        Gate::P(_, phi) => phi.abs() > 1e-10,
-        _ => true,
-    });
-    
-    result
+    // Reduce angles and remove identity rotations
+    normalize_angles(&result)
 }
 
 // ============================================================================
@@ -711,6 +995,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_normalize_angles() {
+        use crate::analysis::circuit_analysis::circuit_unitary;
+
+        let exact = |a: &[Gate], b: &[Gate], n: usize| {
+            let (u, v) = (circuit_unitary(a, n), circuit_unitary(b, n));
+            u.iter().flatten().zip(v.iter().flatten()).all(|(x, y)| (*x - *y).norm() < 1e-10)
+        };
+
+        // Four quarter turns: RZ(2π) = −I survives as a phase, not as nothing
+        let chain = vec![Gate::RZ(0, PI / 2.0); 4];
+        let merged = merge_rotations(&chain);
+        let normalized = normalize_angles(&merged);
+        assert_eq!(normalized.len(), 1);
+        assert!(matches!(normalized[0], Gate::GlobalPhase(phi) if (phi - PI).abs() < 1e-12), "{:?}", normalized);
+        assert!(exact(&normalized, &chain, 1));
+
+        // A full 4π turn is the identity
+        assert!(normalize_angles(&merge_rotations(&vec![Gate::RZ(0, PI / 2.0); 8])).is_empty());
+
+        // Out-of-range angles land in (−π, π], with the conditional sign on the control
+        let circuit = vec![
+            Gate::RX(0, 3.0 * PI), Gate::CRY(0, 1, 2.0 * PI + 0.3), Gate::P(1, -4.0 * PI + 0.2),
+            Gate::RZZ(0, 1, -2.5 * PI), Gate::U3(1, 5.0, 7.0, -7.0), Gate::CP(1, 0, 2.0 * PI),
+            Gate::GlobalPhase(3.0 * PI), Gate::H(0),
+        ];
+        let normalized = normalize_angles(&circuit);
+        assert!(exact(&normalized, &circuit, 2));
+        assert!(normalized.iter().flat_map(|g| g.params()).all(|a| a > -PI && a <= PI));
+        assert!(normalized.contains(&Gate::Z(0)));
+        assert!(!normalized.iter().any(|g| matches!(g, Gate::CP(..))));
+    }
+
     #[test]
     fn test_gates_commute() {
         assert!(gates_commute(&Gate::RZ(0, 1.0), &Gate::RZ(0, 2.0)));
@@ -734,4 +1051,119 @@ mod tests {
         let result = optimize_cnot_gates(&circuit);
         assert!(result.is_empty());
     }
+
+    #[test]
+    fn test_fuse_single_qubit_runs() {
+        use crate::simulator::{simulate, states_close};
+
+        let circuit = vec![Gate::H(0), Gate::S(0), Gate::H(0), Gate::T(0)];
+        let fused = fuse_single_qubit_runs(&circuit);
+        let rotations = fused.iter().filter(|g| !matches!(g, Gate::GlobalPhase(_))).count();
+        assert_eq!(rotations, 3);
+        assert!(states_close(&simulate(&circuit, 1).unwrap(), &simulate(&fused, 1).unwrap(), 1e-10));
+    }
+
+    #[test]
+    fn test_fuse_stops_at_two_qubit_gate() {
+        use crate::simulator::{simulate, states_close};
+
+        let circuit = vec![
+            Gate::H(0), Gate::T(0), Gate::X(1),
+            Gate::CX(0, 1),
+            Gate::S(0), Gate::H(0), Gate::RY(1, 0.3), Gate::RX(1, 0.7),
+        ];
+        let fused = fuse_single_qubit_runs(&circuit);
+
+        // H·T fuses before the CX, X(1) is kept, S·H and RY·RX fuse after it
+        let cx = fused.iter().position(|g| matches!(g, Gate::CX(0, 1))).unwrap();
+        assert!(matches!(fused[..cx].last(), Some(Gate::X(1))));
+        assert!(fused[..cx].iter().all(|g| !matches!(g, Gate::H(_) | Gate::T(_))));
+        assert!(fused[cx + 1..].iter().all(|g| !matches!(g, Gate::S(_) | Gate::H(_) | Gate::RX(..))));
+        assert!(states_close(&simulate(&circuit, 2).unwrap(), &simulate(&fused, 2).unwrap(), 1e-10));
+    }
+
+    #[test]
+    fn test_pass_reports() {
+        let circuit = vec![
+            Gate::H(0), Gate::H(0), Gate::T(1), Gate::T(1),
+            Gate::CX(0, 1), Gate::CX(0, 1), Gate::X(2),
+        ];
+
+        let (result, report) = cancel_inverse_gates_with_report(&circuit);
+        assert_eq!(report.gates_before, 7);
+        assert_eq!(report.gates_after, result.len());
+        assert!(report.gate_delta() >= 4);
+
+        let (_, report) = optimize_t_gates_with_report(&circuit);
+        assert_eq!((report.t_before, report.t_after), (2, 0));
+
+        for pass in [
+            cancel_inverse_gates_with_report,
+            optimize_t_gates_with_report,
+            optimize_cnot_gates_with_report,
+            fuse_single_qubit_runs_with_report,
+        ] {
+            let (_, report) = pass(&circuit);
+            assert!(report.no_regression(), "{:?}", report);
+        }
+    }
+
+    #[test]
+    fn test_passes_preserve_random_circuits() {
+        use crate::gates::circuit::random_circuit;
+        use crate::gates::core::GateKind;
+        use crate::simulator::{simulate, states_close};
+
+        let set = [
+            GateKind::H, GateKind::X, GateKind::T, GateKind::Tdg, GateKind::S,
+            GateKind::RZ, GateKind::RY, GateKind::CX, GateKind::CZ,
+        ];
+        for seed in 0..20 {
+            let circuit = random_circuit(3, 15, seed, &set);
+            let reference = simulate(&circuit, 3).unwrap();
+            for optimized in [cancel_inverse_gates(&circuit), fuse_single_qubit_runs(&circuit)] {
+                assert!(states_close(&reference, &simulate(&optimized, 3).unwrap(), 1e-9), "Seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_recognize_swaps_across_interleaved_gates() {
+        use crate::analysis::circuit_analysis::unitary_equivalent;
+
+        let circuit = vec![
+            Gate::CX(0, 1), Gate::H(2), Gate::CX(1, 0), Gate::CX(2, 3), Gate::CX(0, 1),
+            Gate::CX(2, 1), Gate::CX(1, 2),
+        ];
+        let fused = recognize_swaps(&circuit);
+        assert_eq!(
+            format!("{:?}", fused),
+            format!("{:?}", [Gate::SWAP(0, 1), Gate::H(2), Gate::CX(2, 3), Gate::CX(2, 1), Gate::CX(1, 2)])
+        );
+        assert!(unitary_equivalent(&fused, &circuit, 4));
+
+        // A gate on a or b between the CNOTs blocks the fusion
+        let blocked = [Gate::CX(0, 1), Gate::T(1), Gate::CX(1, 0), Gate::CX(0, 1)];
+        assert_eq!(recognize_swaps(&blocked).len(), 4);
+    }
+
+    #[test]
+    fn test_swap_passes_preserve_unitary() {
+        use crate::analysis::circuit_analysis::unitary_equivalent;
+        use crate::gates::circuit::random_circuit;
+        use crate::gates::core::GateKind;
+
+        let set = [GateKind::H, GateKind::T, GateKind::CX, GateKind::SWAP];
+        for seed in 0..10 {
+            let circuit = random_circuit(3, 8, seed, &set);
+            let swaps = circuit.iter().filter(|g| matches!(g, Gate::SWAP(..))).count();
+            let expanded = expand_swaps(&circuit);
+            assert!(expanded.iter().all(|g| !matches!(g, Gate::SWAP(..))));
+            assert!(unitary_equivalent(&expanded, &circuit, 3), "Seed {}", seed);
+
+            let fused = recognize_swaps(&expanded);
+            assert!(fused.iter().filter(|g| matches!(g, Gate::SWAP(..))).count() >= swaps);
+            assert!(unitary_equivalent(&fused, &circuit, 3), "Seed {}", seed);
+        }
+    }
 }
@@ -35,6 +35,7 @@
 //!   different gate sequence than the original.
 
 use crate::gates::core::Gate;
+use super::optimize::{run_with_report, PassReport};
 
 /// Apply spider fusion rules
 /// 
@@ -66,8 +67,33 @@ pub fn spider_fusion(circuit: &[Gate]) -> Vec<Gate> {
     result
 }
 
+/// [`spider_fusion`] with a [`PassReport`]
+pub fn spider_fusion_with_report(circuit: &[Gate]) -> (Vec<Gate>, PassReport) {
+    run_with_report(circuit, spider_fusion)
+}
+
 /// Pivot rule (conceptual) - used in graph-like ZX simplification
 pub fn apply_pivot_rule(_graph: &mut Vec<Gate>) {
     // This would involve identifying a pair of internal hubs and 
     // applying the pivot transform to simplify connectivity.
 }
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spider_fusion_report() {
+        let circuit = vec![Gate::RZ(0, 0.25), Gate::RZ(0, 0.5), Gate::RX(1, 0.1), Gate::RX(1, 0.2)];
+        let (result, report) = spider_fusion_with_report(&circuit);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!((report.gates_before, report.gates_after), (4, 2));
+        assert_eq!((report.depth_before, report.depth_after), (2, 1));
+        assert!(report.no_regression());
+    }
+}
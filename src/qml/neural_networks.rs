@@ -264,9 +264,9 @@ impl VariationalQuantumCircuit {
         // Sum probabilities where first qubit is 1
         let size = 1 << self.num_qubits;
         let mut p_one = 0.0;
-        for i in 0..size {
+        for (i, &p) in probs.iter().enumerate().take(size) {
             if i & 1 != 0 {
-                p_one += probs[i];
+                p_one += p;
             }
         }
         p_one
@@ -279,7 +279,7 @@ impl VariationalQuantumCircuit {
         let shift = PI / 2.0;
         let mut gradients = vec![0.0; self.num_params];
         
-        for i in 0..self.num_params {
+        for (i, grad) in gradients.iter_mut().enumerate() {
             // Shift up
             self.parameters[i] += shift;
             let pred_plus = self.predict_binary(features);
@@ -294,7 +294,7 @@ impl VariationalQuantumCircuit {
             // Parameter shift gradient
             // For MSE loss: d/dθ (pred - target)² = 2(pred - target) * d(pred)/dθ
             let pred = self.predict_binary(features);
-            gradients[i] = 2.0 * (pred - target) * (pred_plus - pred_minus) / 2.0;
+            *grad = 2.0 * (pred - target) * (pred_plus - pred_minus) / 2.0;
         }
         
         gradients
@@ -563,7 +563,7 @@ mod tests {
         
         let pred = vqc.predict_binary(&[0.5, 0.5]);
         
-        assert!(pred >= 0.0 && pred <= 1.0);
+        assert!((0.0..=1.0).contains(&pred));
     }
 
     #[test]
@@ -577,7 +577,7 @@ mod tests {
 
     #[test]
     fn test_quantum_reservoir() {
-        let mut reservoir = QuantumReservoir::new(3, 2, 12345);
+        let reservoir = QuantumReservoir::new(3, 2, 12345);
         
         let input = vec![0.5, 0.3, 0.1];
         let features = reservoir.reservoir_transform(&input);
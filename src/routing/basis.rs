@@ -0,0 +1,579 @@
+//! Rewriting Circuits into a Native Gate Set
+//!
+//! This module lowers arbitrary circuits onto the gates a backend executes:
+//! - `transpile_to_basis`: Rewrite every gate into the kinds of a given basis,
+//!   or report the first gate that cannot be expressed (`TranspileError`)
+//! - `native_gates` / `transpile_to_target`: Presets for common hardware
+//!   families (`HardwareTarget`)
+//! - `transpile_to_basis_with_cost`: Pick among several native entanglers
+//!   by `circuit_cost`, e.g. the least noisy one under `CostWeights::noise_aware`
+//!
+//! ## 🎯 Why is this used?
+//! Synthesis routines emit whatever gates are most natural for them: Toffolis,
+//! controlled rotations, SWAPs, multi-controlled phases. A device accepts a
+//! handful of kinds, and which ones differs from vendor to vendor. This pass
+//! is the last step before routing and execution.
+//!
+//! ## ⚙️ How it works?
+//! - **Lowering**: Gates of a kind outside the basis are expanded with the
+//!   exact decompositions (`decompose_toffoli`, `decompose_cry`, `mcp_no_ancilla`,
+//!   SWAP → 3 CX, CZ → H·CX·H, …) until only native gates, CX and single-qubit
+//!   gates remain.
+//! - **Entangler**: CX itself is rebuilt from the first native two-qubit gate
+//!   among CX, CZ, CY, RZZ, RXX, RYY, iSWAP and √iSWAP, conjugated by
+//!   single-qubit gates; the iSWAP family takes two applications.
+//! - **Single-Qubit Runs**: Consecutive single-qubit gates on a wire are kept
+//!   as written when they are all native; otherwise their product is
+//!   re-synthesized from its ZYZ Euler angles as U3, RZ·RY·RZ, RZ·RX·RZ,
+//!   RZ·SX·RZ·SX·RZ, RZ·H·RZ·H·RZ or RX·RY·RX, whichever the basis allows
+//!   (P stands in for RZ).
+//! - **Phase**: The global phase of every rewrite is tracked and emitted as one
+//!   trailing `GlobalPhase` when that kind is in the basis; otherwise it is
+//!   dropped and the result is equal up to global phase.
+//!
+//! ## 📍 Where to apply this?
+//! - **Hardware Compilation**: After synthesis and optimization, before
+//!   `route_circuit`.
+//! - **Backend Portability**: Targeting several devices from one circuit.
+//!
+//! ## 📊 Code Behavior
+//! - **Complexity**: $O(G)$ for $G$ gates after lowering; multi-controlled
+//!   gates expand to $O(3^n)$ gates for $n$ controls.
+//! - **Non-Unitary Operations**: `Barrier`, `Measure` and `Reset` pass through
+//!   unchanged; `ClassicalControl` is kept around each lowered inner gate.
+//! - **Rotation Angles**: Re-synthesized angles are wrapped into (−π, π] and
+//!   rotations by a multiple of 2π are omitted.
+
+use std::f64::consts::PI;
+use crate::analysis::circuit_analysis::{circuit_cost, CostWeights};
+use crate::gates::core::{decompose_fredkin, decompose_toffoli, Complex, Gate, GateKind, GateMatrix2x2};
+use crate::gates::decomposition::{
+    decompose_cp, decompose_crx, decompose_cry, decompose_crz, decompose_mcx, euler_decompose_zyz,
+    matrix_multiply_2x2, mcp_no_ancilla,
+};
+
+/// Native two-qubit gates a missing CX is rebuilt from, in order of preference
+const ENTANGLERS: [GateKind; 7] = [
+    GateKind::CZ, GateKind::CY, GateKind::RZZ, GateKind::RXX, GateKind::RYY, GateKind::ISWAP, GateKind::SqrtISWAP,
+];
+
+/// Angles closer than this to a multiple of 2π are treated as zero
+const ANGLE_TOL: f64 = 1e-12;
+
+/// Why [`transpile_to_basis`] could not express a circuit
+#[derive(Clone, Debug, PartialEq)]
+pub enum TranspileError {
+    /// `gate` needs an entangling gate and the basis has none of CX, CZ, CY,
+    /// RZZ, RXX, RYY, iSWAP or √iSWAP
+    MissingEntangler { gate: GateKind },
+    /// The single-qubit gates of the basis cannot build the unitary that a
+    /// run containing `gate` on `qubit` reduces to
+    MissingRotations { gate: GateKind, qubit: usize },
+}
+
+impl std::fmt::Display for TranspileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TranspileError::MissingEntangler { gate } => {
+                write!(f, "Cannot express {}: the basis has no two-qubit gate to build CX from", gate.name())
+            }
+            TranspileError::MissingRotations { gate, qubit } => write!(
+                f, "Cannot express {} on qubit {} with the single-qubit gates of the basis", gate.name(), qubit
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TranspileError {}
+
+/// Rewrite `circuit` so that every unitary gate has a kind listed in `basis`
+///
+/// Gates already in the basis are kept; see the module docs for the
+/// rewrites. The result implements the same unitary, exactly when
+/// `GlobalPhase` is in the basis and up to global phase otherwise.
+pub fn transpile_to_basis(circuit: &[Gate], basis: &[GateKind]) -> Result<Vec<Gate>, TranspileError> {
+    let mut lowered = Vec::with_capacity(circuit.len());
+    for gate in circuit {
+        // Report the gate the user wrote, not the CX it was lowered through
+        lower(gate, basis, &mut lowered).map_err(|err| match err {
+            TranspileError::MissingEntangler { .. } => TranspileError::MissingEntangler { gate: gate.kind() },
+            other => other,
+        })?;
+    }
+    resynthesize(&lowered, basis)
+}
+
+/// [`transpile_to_basis`], choosing among several native entanglers by cost
+///
+/// When the basis offers more than one of CX and the `ENTANGLERS`, the
+/// circuit is transpiled once per entangler with the others removed, and the
+/// result with the lowest `circuit_cost` under `weights` wins (ties go to the
+/// usual preference order). Gate-count weights favour CZ, which needs one
+/// application per CX where the iSWAP family needs two; noise-aware weights
+/// can favour the less noisy gate even when it costs more applications.
+/// An entangler that cannot lower the circuit on its own is skipped; the
+/// first error is returned only if every entangler fails.
+pub fn transpile_to_basis_with_cost(
+    circuit: &[Gate],
+    basis: &[GateKind],
+    weights: &CostWeights,
+) -> Result<Vec<Gate>, TranspileError> {
+    let entanglers: Vec<GateKind> = std::iter::once(GateKind::CX).chain(ENTANGLERS)
+        .filter(|k| basis.contains(k))
+        .collect();
+    if entanglers.len() < 2 {
+        return transpile_to_basis(circuit, basis);
+    }
+
+    let mut best: Option<(f64, Vec<Gate>)> = None;
+    let mut first_error = None;
+    for &entangler in &entanglers {
+        let restricted: Vec<GateKind> = basis.iter().copied()
+            .filter(|k| *k == entangler || !entanglers.contains(k))
+            .collect();
+        let gates = match transpile_to_basis(circuit, &restricted) {
+            Ok(gates) => gates,
+            Err(err) => {
+                first_error.get_or_insert(err);
+                continue;
+            }
+        };
+        let cost = circuit_cost(&gates, weights);
+        if best.as_ref().is_none_or(|(lowest, _)| cost < *lowest) {
+            best = Some((cost, gates));
+        }
+    }
+    match best {
+        Some((_, gates)) => Ok(gates),
+        None => Err(first_error.expect("At least two entanglers were tried")),
+    }
+}
+
+// ============================================================================
+// HARDWARE PRESETS
+// ============================================================================
+
+/// Vendor families with a well-known native gate set
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HardwareTarget {
+    /// IBM Eagle-class superconducting devices: RZ, SX, X and CX
+    IbmEagle,
+    /// IonQ trapped ions: RX, RY, RZ and the Mølmer–Sørensen RXX
+    IonQ,
+    /// Rigetti superconducting devices: RZ, RX and CZ
+    Rigetti,
+    /// Google superconducting devices, CZ-based: RZ, RX, RY and CZ
+    Google,
+    /// iSWAP-coupled superconducting devices: RZ, RX, RY, iSWAP and √iSWAP
+    ISwap,
+}
+
+/// Typical native gate kinds of `target`
+///
+/// These are the kinds a vendor compiler accepts without further lowering.
+/// Calibration restrictions (e.g. Rigetti's RX only at ±π/2 and π) are not
+/// modelled: any angle of a listed rotation is treated as native.
+pub fn native_gates(target: HardwareTarget) -> Vec<GateKind> {
+    use GateKind::*;
+    match target {
+        HardwareTarget::IbmEagle => vec![RZ, SX, X, CX],
+        HardwareTarget::IonQ => vec![RX, RY, RZ, RXX],
+        HardwareTarget::Rigetti => vec![RZ, RX, CZ],
+        HardwareTarget::Google => vec![RZ, RX, RY, CZ],
+        HardwareTarget::ISwap => vec![RZ, RX, RY, ISWAP, SqrtISWAP],
+    }
+}
+
+/// [`transpile_to_basis`] with the basis `native_gates(target)`
+pub fn transpile_to_target(circuit: &[Gate], target: HardwareTarget) -> Result<Vec<Gate>, TranspileError> {
+    transpile_to_basis(circuit, &native_gates(target))
+}
+
+// ============================================================================
+// LOWERING
+// ============================================================================
+
+/// Expand `gate` until only native, CX and single-qubit gates are left
+fn lower(gate: &Gate, basis: &[GateKind], out: &mut Vec<Gate>) -> Result<(), TranspileError> {
+    let kind = gate.kind();
+    let single_qubit = kind.arity() == Some(1) && !matches!(gate, Gate::Measure(..) | Gate::Reset(_));
+    if basis.contains(&kind) || single_qubit {
+        out.push(gate.clone());
+        return Ok(());
+    }
+
+    let expansion = match *gate {
+        Gate::GlobalPhase(_) | Gate::Barrier(_) | Gate::Measure(..) | Gate::Reset(_) => {
+            out.push(gate.clone());
+            return Ok(());
+        }
+        Gate::ClassicalControl(bit, ref inner) => {
+            let body = transpile_to_basis(std::slice::from_ref(inner.as_ref()), basis)?;
+            out.extend(body.into_iter().map(|g| Gate::ClassicalControl(bit, Box::new(g))));
+            return Ok(());
+        }
+        Gate::CX(c, t) => return cx_from_entangler(c, t, basis, out),
+        Gate::CZ(c, t) => vec![Gate::H(t), Gate::CX(c, t), Gate::H(t)],
+        Gate::CY(c, t) => vec![Gate::Sdg(t), Gate::CX(c, t), Gate::S(t)],
+        Gate::SWAP(a, b) => vec![Gate::CX(a, b), Gate::CX(b, a), Gate::CX(a, b)],
+        // iSWAP = exp(iπ/4 (XX + YY)), and the two terms commute
+        Gate::ISWAP(a, b) => vec![Gate::RXX(a, b, -PI / 2.0), Gate::RYY(a, b, -PI / 2.0)],
+        Gate::ISWAPdg(a, b) => vec![Gate::RXX(a, b, PI / 2.0), Gate::RYY(a, b, PI / 2.0)],
+        Gate::SqrtISWAP(a, b) => vec![Gate::RXX(a, b, -PI / 4.0), Gate::RYY(a, b, -PI / 4.0)],
+        Gate::SqrtISWAPdg(a, b) => vec![Gate::RXX(a, b, PI / 4.0), Gate::RYY(a, b, PI / 4.0)],
+        Gate::CRX(c, t, theta) => decompose_crx(c, t, theta),
+        Gate::CRY(c, t, theta) => decompose_cry(c, t, theta),
+        Gate::CRZ(c, t, theta) => decompose_crz(c, t, theta),
+        Gate::CP(c, t, phi) => decompose_cp(c, t, phi),
+        Gate::RZZ(a, b, theta) => vec![Gate::CX(a, b), Gate::RZ(b, theta), Gate::CX(a, b)],
+        Gate::RXX(a, b, theta) => vec![
+            Gate::H(a), Gate::H(b), Gate::RZZ(a, b, theta), Gate::H(a), Gate::H(b),
+        ],
+        // RX(π/2) maps Y to Z under conjugation
+        Gate::RYY(a, b, theta) => vec![
+            Gate::RX(a, PI / 2.0), Gate::RX(b, PI / 2.0), Gate::RZZ(a, b, theta),
+            Gate::RX(a, -PI / 2.0), Gate::RX(b, -PI / 2.0),
+        ],
+        Gate::CCX(c1, c2, t) => decompose_toffoli(c1, c2, t),
+        Gate::CCZ(c1, c2, t) => vec![Gate::H(t), Gate::CCX(c1, c2, t), Gate::H(t)],
+        Gate::CSWAP(c, a, b) => decompose_fredkin(c, a, b),
+        Gate::MCX(ref controls, t) => decompose_mcx(controls, t),
+        Gate::MCZ(ref controls, t) => {
+            let mut gates = vec![Gate::H(t)];
+            gates.extend(decompose_mcx(controls, t));
+            gates.push(Gate::H(t));
+            gates
+        }
+        Gate::MCP(ref controls, t, phi) => mcp_no_ancilla(controls, t, phi),
+        _ => unreachable!("Single-qubit gates are always lowered as they are"),
+    };
+    for g in &expansion {
+        lower(g, basis, out)?;
+    }
+    Ok(())
+}
+
+/// CX(c, t) = H(t) · CZ(c, t) · H(t), with the CZ built from the preferred native entangler
+fn cx_from_entangler(c: usize, t: usize, basis: &[GateKind], out: &mut Vec<Gate>) -> Result<(), TranspileError> {
+    let entangler = ENTANGLERS.iter().find(|k| basis.contains(k))
+        .ok_or(TranspileError::MissingEntangler { gate: GateKind::CX })?;
+
+    // CZ = e^{iπ/4} · RZ(c, π/2) · RZ(t, π/2) · RZZ(−π/2), all commuting
+    let rzz_cz = |core: Vec<Gate>| {
+        let mut gates = vec![Gate::GlobalPhase(PI / 4.0), Gate::RZ(c, PI / 2.0), Gate::RZ(t, PI / 2.0)];
+        gates.extend(core);
+        gates
+    };
+    let cz = match entangler {
+        GateKind::CZ => vec![Gate::CZ(c, t)],
+        GateKind::CY => vec![Gate::H(t), Gate::S(t), Gate::CY(c, t), Gate::Sdg(t), Gate::H(t)],
+        GateKind::RZZ => rzz_cz(vec![Gate::RZZ(c, t, -PI / 2.0)]),
+        GateKind::RXX => rzz_cz(vec![
+            Gate::H(c), Gate::H(t), Gate::RXX(c, t, -PI / 2.0), Gate::H(c), Gate::H(t),
+        ]),
+        GateKind::RYY => rzz_cz(vec![
+            Gate::RX(c, PI / 2.0), Gate::RX(t, PI / 2.0), Gate::RYY(c, t, -PI / 2.0),
+            Gate::RX(c, -PI / 2.0), Gate::RX(t, -PI / 2.0),
+        ]),
+        // iSWAP·RX(c, π/2)·iSWAP = exp(−iπ/4 Z_c Y_t)·Z_c Z_t, and RX(t, π/2)
+        // turns Y_t into Z_t; RZZ(−π/2) = i·Z_c Z_t·RZZ(π/2)
+        GateKind::ISWAP => rzz_cz(vec![
+            Gate::RX(t, -PI / 2.0), Gate::Z(c), Gate::Z(t),
+            Gate::ISWAP(c, t), Gate::RX(c, PI / 2.0), Gate::ISWAP(c, t),
+            Gate::RX(t, PI / 2.0), Gate::Z(c), Gate::Z(t), Gate::GlobalPhase(PI / 2.0),
+        ]),
+        // H⊗H turns XX + YY into ZZ + YY and Z_c flips the YY, so the two
+        // commuting halves exp(iπ/8 (ZZ ± YY)) multiply to RZZ(−π/2)
+        _ => rzz_cz(vec![
+            Gate::H(c), Gate::H(t), Gate::SqrtISWAP(c, t), Gate::H(c), Gate::H(t),
+            Gate::Z(c), Gate::H(c), Gate::H(t), Gate::SqrtISWAP(c, t), Gate::H(c), Gate::H(t), Gate::Z(c),
+        ]),
+    };
+    out.push(Gate::H(t));
+    out.extend(cz);
+    out.push(Gate::H(t));
+    Ok(())
+}
+
+// ============================================================================
+// SINGLE-QUBIT RE-SYNTHESIS
+// ============================================================================
+
+/// Merge single-qubit runs into native gates and fold the global phase
+fn resynthesize(lowered: &[Gate], basis: &[GateKind]) -> Result<Vec<Gate>, TranspileError> {
+    let num_qubits = lowered.iter().flat_map(|g| g.qubits()).max().map_or(0, |q| q + 1);
+    let mut runs: Vec<Vec<Gate>> = vec![Vec::new(); num_qubits];
+    let mut phase = 0.0;
+    let mut out = Vec::with_capacity(lowered.len());
+
+    for gate in lowered {
+        match gate {
+            Gate::GlobalPhase(theta) if !basis.contains(&GateKind::GlobalPhase) => phase += theta,
+            _ if gate.matrix_2x2().is_some() => runs[gate.qubits()[0]].push(gate.clone()),
+            _ => {
+                for q in gate.qubits() {
+                    phase += flush(&mut runs[q], q, basis, &mut out)?;
+                }
+                out.push(gate.clone());
+            }
+        }
+    }
+    for (q, run) in runs.iter_mut().enumerate() {
+        phase += flush(run, q, basis, &mut out)?;
+    }
+
+    if basis.contains(&GateKind::GlobalPhase) && wrap(phase).abs() > ANGLE_TOL {
+        out.push(Gate::GlobalPhase(wrap(phase)));
+    }
+    Ok(out)
+}
+
+/// Emit the pending run on `q`; returns the global phase the rewrite dropped
+fn flush(run: &mut Vec<Gate>, q: usize, basis: &[GateKind], out: &mut Vec<Gate>) -> Result<f64, TranspileError> {
+    if run.is_empty() {
+        return Ok(0.0);
+    }
+    let Some(foreign) = run.iter().map(|g| g.kind()).find(|k| !basis.contains(k)) else {
+        out.append(run);
+        return Ok(0.0);
+    };
+
+    let target = run.iter().fold(crate::gates::core::identity(), |acc, g| {
+        matrix_multiply_2x2(&g.matrix_2x2().unwrap(), &acc)
+    });
+    run.clear();
+    let gates = synthesize_1q(&target, q, basis)
+        .ok_or(TranspileError::MissingRotations { gate: foreign, qubit: q })?;
+
+    // target = e^{iα} · product, with α read off Tr(product† · target)
+    let product = gates.iter().fold(crate::gates::core::identity(), |acc, g| {
+        matrix_multiply_2x2(&g.matrix_2x2().unwrap(), &acc)
+    });
+    let mut overlap = Complex::ZERO;
+    for i in 0..2 {
+        for j in 0..2 {
+            overlap = overlap + product.data[i][j].conj() * target.data[i][j];
+        }
+    }
+    out.extend(gates);
+    Ok(overlap.im.atan2(overlap.re))
+}
+
+/// Native gates implementing `u` on `q` up to global phase, if the basis allows
+fn synthesize_1q(u: &GateMatrix2x2, q: usize, basis: &[GateKind]) -> Option<Vec<Gate>> {
+    let has = |k: GateKind| basis.contains(&k);
+    let euler = euler_decompose_zyz(u);
+    let (theta, phi, lambda) = (euler.theta, euler.phi, euler.lambda);
+    let diagonal = wrap(theta).abs() < ANGLE_TOL;
+
+    if has(GateKind::U3) {
+        return Some(vec![Gate::U3(q, wrap(theta), wrap(phi), wrap(lambda))]);
+    }
+
+    let mut gates = Vec::new();
+    let push = |gates: &mut Vec<Gate>, gate: fn(usize, f64) -> Gate, angle: f64| {
+        if wrap(angle).abs() > ANGLE_TOL {
+            gates.push(gate(q, wrap(angle)));
+        }
+    };
+
+    // u ≃ RZ(φ) · RY(θ) · RZ(λ), with RY(θ) = RZ(π/2) · RX(θ) · RZ(−π/2)
+    let z: Option<fn(usize, f64) -> Gate> = if has(GateKind::RZ) {
+        Some(Gate::RZ)
+    } else if has(GateKind::P) {
+        Some(Gate::P)
+    } else {
+        None
+    };
+    if let Some(z) = z {
+        if diagonal {
+            push(&mut gates, z, phi + lambda);
+        } else if has(GateKind::RY) {
+            push(&mut gates, z, lambda);
+            push(&mut gates, Gate::RY, theta);
+            push(&mut gates, z, phi);
+        } else if has(GateKind::RX) {
+            push(&mut gates, z, lambda - PI / 2.0);
+            push(&mut gates, Gate::RX, theta);
+            push(&mut gates, z, phi + PI / 2.0);
+        } else if has(GateKind::SX) {
+            // RX(θ) ≃ RZ(π/2) · SX · RZ(θ + π) · SX · RZ(π/2)
+            push(&mut gates, z, lambda);
+            gates.push(Gate::SX(q));
+            push(&mut gates, z, theta + PI);
+            gates.push(Gate::SX(q));
+            push(&mut gates, z, phi + PI);
+        } else if has(GateKind::H) {
+            push(&mut gates, z, lambda - PI / 2.0);
+            gates.push(Gate::H(q));
+            push(&mut gates, z, theta);
+            gates.push(Gate::H(q));
+            push(&mut gates, z, phi + PI / 2.0);
+        } else {
+            return None;
+        }
+        return Some(gates);
+    }
+
+    if has(GateKind::RX) && has(GateKind::RY) {
+        // H·u·H ≃ RZ(φ')·RY(θ')·RZ(λ') gives u ≃ RX(φ')·RY(−θ')·RX(λ')
+        let h = crate::gates::core::hadamard();
+        let flipped = euler_decompose_zyz(&matrix_multiply_2x2(&matrix_multiply_2x2(&h, u), &h));
+        push(&mut gates, Gate::RX, flipped.lambda);
+        push(&mut gates, Gate::RY, -flipped.theta);
+        push(&mut gates, Gate::RX, flipped.phi);
+        return Some(gates);
+    }
+    None
+}
+
+/// Angle reduced into (−π, π]
+fn wrap(angle: f64) -> f64 {
+    let reduced = angle.rem_euclid(2.0 * PI);
+    if reduced > PI { reduced - 2.0 * PI } else { reduced }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::circuit_analysis::{circuit_unitary, unitary_equivalent};
+
+    fn assert_same_unitary(a: &[Gate], b: &[Gate], n: usize) {
+        let (ua, ub) = (circuit_unitary(a, n), circuit_unitary(b, n));
+        for (ra, rb) in ua.iter().zip(&ub) {
+            for (x, y) in ra.iter().zip(rb) {
+                assert!((*x - *y).norm() < 1e-9, "Unitaries differ: {:?} vs {:?}", x, y);
+            }
+        }
+    }
+
+    fn mixed_circuit() -> Vec<Gate> {
+        vec![
+            Gate::H(0), Gate::CZ(0, 1), Gate::SWAP(1, 2), Gate::U3(2, 0.3, -1.2, 0.7), Gate::SqrtISWAP(1, 0),
+            Gate::CRY(0, 2, 0.9), Gate::CCX(0, 1, 2), Gate::ISWAP(2, 0), Gate::T(1),
+            Gate::CP(1, 2, -0.4), Gate::RYY(0, 1, 1.3), Gate::MCZ(vec![0, 1], 2),
+            Gate::CSWAP(2, 0, 1), Gate::SX(0), Gate::CY(2, 1), Gate::MCP(vec![0, 2], 1, 0.6),
+        ]
+    }
+
+    #[test]
+    fn test_rz_rx_cx_basis() {
+        let basis = [GateKind::RZ, GateKind::RX, GateKind::CX];
+        let circuit = mixed_circuit();
+        let out = transpile_to_basis(&circuit, &basis).unwrap();
+        assert!(out.iter().all(|g| basis.contains(&g.kind())), "{:?}", out);
+        assert!(unitary_equivalent(&circuit, &out, 3));
+
+        // With GlobalPhase available the rewrite is exact
+        let exact_basis = [GateKind::RZ, GateKind::RX, GateKind::CX, GateKind::GlobalPhase];
+        assert_same_unitary(&circuit, &transpile_to_basis(&circuit, &exact_basis).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_entangler_choice_follows_error_rates() {
+        use crate::analysis::circuit_analysis::ErrorRates;
+        use GateKind::*;
+
+        let circuit = vec![Gate::H(0), Gate::CX(0, 1), Gate::CX(1, 2), Gate::CRZ(0, 2, 0.8)];
+        let basis = [RZ, RX, RY, CZ, ISWAP, GlobalPhase];
+        let uses = |gates: &[Gate], kind: GateKind| gates.iter().any(|g| g.kind() == kind);
+
+        // Counting gates, one CZ per CX beats two iSWAPs
+        let by_count = transpile_to_basis_with_cost(&circuit, &basis, &CostWeights::nisq()).unwrap();
+        assert!(uses(&by_count, CZ) && !uses(&by_count, ISWAP));
+        assert_eq!(by_count, transpile_to_basis(&circuit, &basis).unwrap());
+
+        // A noisy CZ flips the choice, and a clean one flips it back
+        let noisy_cz = ErrorRates::new(1e-4, 1e-3, 0.0).with_kind(CZ, 0.05).with_kind(ISWAP, 0.002);
+        let by_error = transpile_to_basis_with_cost(&circuit, &basis, &CostWeights::noise_aware(noisy_cz)).unwrap();
+        assert!(uses(&by_error, ISWAP) && !uses(&by_error, CZ));
+        assert_same_unitary(&circuit, &by_error, 3);
+
+        let clean_cz = ErrorRates::new(1e-4, 1e-3, 0.0).with_kind(CZ, 0.001).with_kind(ISWAP, 0.002);
+        let weights = CostWeights::noise_aware(clean_cz);
+        assert!(uses(&transpile_to_basis_with_cost(&circuit, &basis, &weights).unwrap(), CZ));
+
+        // A single entangler leaves nothing to choose
+        let rigetti = native_gates(HardwareTarget::Rigetti);
+        assert_eq!(
+            transpile_to_basis_with_cost(&circuit, &rigetti, &weights).unwrap(),
+            transpile_to_basis(&circuit, &rigetti).unwrap()
+        );
+
+        // An entangler that cannot lower the circuit is skipped, and the
+        // error surfaces only when none can
+        let diagonal_only = [RZ, CX, CZ];
+        let cz_native = transpile_to_basis_with_cost(&[Gate::CZ(0, 1)], &diagonal_only, &weights).unwrap();
+        assert_eq!(cz_native, vec![Gate::CZ(0, 1)]);
+        assert!(transpile_to_basis(&[Gate::CZ(0, 1)], &[RZ, CX]).is_err());
+        assert!(transpile_to_basis_with_cost(&[Gate::H(0), Gate::CZ(0, 1)], &diagonal_only, &weights).is_err());
+    }
+
+    #[test]
+    fn test_every_entangler_and_rotation_set() {
+        use GateKind::*;
+        let circuit = mixed_circuit();
+        let bases: [&[GateKind]; 9] = [
+            &[U3, CZ, GlobalPhase], &[RZ, RY, CY, GlobalPhase], &[RZ, SX, X, CX, GlobalPhase],
+            &[P, H, RZZ, GlobalPhase], &[RX, RY, RXX, GlobalPhase], &[RZ, RY, RYY, GlobalPhase],
+            &[RZ, RX, ISWAP, CX, GlobalPhase], &[RZ, RX, ISWAP, GlobalPhase], &[RZ, RY, SqrtISWAP, GlobalPhase],
+        ];
+        for basis in bases {
+            let out = transpile_to_basis(&circuit, basis).unwrap();
+            assert!(out.iter().all(|g| basis.contains(&g.kind())), "{:?}: {:?}", basis, out);
+            assert_same_unitary(&circuit, &out, 3);
+        }
+    }
+
+    #[test]
+    fn test_hardware_targets() {
+        let circuit = mixed_circuit();
+        let ionq = transpile_to_target(&circuit, HardwareTarget::IonQ).unwrap();
+        assert!(ionq.iter().all(|g| native_gates(HardwareTarget::IonQ).contains(&g.kind())), "{:?}", ionq);
+        assert!(ionq.iter().any(|g| matches!(g, Gate::RXX(..))));
+        assert!(unitary_equivalent(&circuit, &ionq, 3));
+
+        for target in [HardwareTarget::IbmEagle, HardwareTarget::Rigetti, HardwareTarget::Google, HardwareTarget::ISwap] {
+            let basis = native_gates(target);
+            let out = transpile_to_target(&circuit, target).unwrap();
+            assert!(out.iter().all(|g| basis.contains(&g.kind())), "{:?}: {:?}", target, out);
+            assert!(unitary_equivalent(&circuit, &out, 3), "{:?}", target);
+        }
+    }
+
+    #[test]
+    fn test_native_gates_and_non_unitary_ops_are_kept() {
+        let basis = [GateKind::H, GateKind::T, GateKind::CX];
+        let circuit = vec![
+            Gate::H(0), Gate::T(0), Gate::CX(0, 1), Gate::Barrier(vec![0, 1]), Gate::Measure(1, 0),
+            Gate::Reset(1), Gate::ClassicalControl(0, Box::new(Gate::CZ(0, 1))),
+        ];
+        let out = transpile_to_basis(&circuit, &basis).unwrap();
+        assert_eq!(out[..6], circuit[..6]);
+        assert_eq!(out[6..], [
+            Gate::ClassicalControl(0, Box::new(Gate::H(1))),
+            Gate::ClassicalControl(0, Box::new(Gate::CX(0, 1))),
+            Gate::ClassicalControl(0, Box::new(Gate::H(1))),
+        ]);
+    }
+
+    #[test]
+    fn test_inexpressible_gates_are_reported() {
+        let err = transpile_to_basis(&[Gate::H(0), Gate::CZ(0, 1)], &[GateKind::RZ, GateKind::RX]).unwrap_err();
+        assert_eq!(err, TranspileError::MissingEntangler { gate: GateKind::CZ });
+        assert!(err.to_string().starts_with("Cannot express CZ"), "{}", err);
+
+        let err = transpile_to_basis(&[Gate::CX(0, 1), Gate::RY(1, 0.3)], &[GateKind::H, GateKind::T, GateKind::CX])
+            .unwrap_err();
+        assert_eq!(err, TranspileError::MissingRotations { gate: GateKind::RY, qubit: 1 });
+        assert!(err.to_string().contains("RY on qubit 1"), "{}", err);
+
+        // Diagonal runs only need a Z rotation
+        let out = transpile_to_basis(&[Gate::T(0), Gate::S(0)], &[GateKind::RZ]).unwrap();
+        assert_eq!(out, vec![Gate::RZ(0, 3.0 * PI / 4.0)]);
+    }
+}
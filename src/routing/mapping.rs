@@ -0,0 +1,328 @@
+//! Qubit Placement and Greedy Routing
+//!
+//! This module maps logical circuits onto a restricted coupling graph:
+//! - Interaction-weighted initial placement (`initial_mapping`)
+//! - Shortest-path SWAP insertion from a given or computed placement (`route_circuit`)
+//!
+//! ## 🎯 Why is this used?
+//! Every two-qubit gate between physically distant qubits costs SWAPs. Most of
+//! that cost is decided before the first SWAP is inserted, by where each
+//! logical qubit starts. Exposing the placement separately lets one mapping be
+//! computed once and reused across many similar circuits (parameter sweeps,
+//! Trotter steps, repeated QAOA layers).
+//!
+//! ## ⚙️ How it works?
+//! - **Interaction Weights**: w(a, b) counts the two-qubit gates between logical
+//!   qubits a and b. A placement π costs Σ w(a, b)·(d(π(a), π(b)) − 1), where d is
+//!   the shortest-path distance in the coupling graph.
+//! - **Greedy Placement**: The busiest logical qubit goes to the best-connected
+//!   physical qubit; each following qubit (most weight to already placed ones)
+//!   takes the free physical qubit minimizing its weighted distance.
+//! - **Local Search**: Pairwise exchanges of placed qubits are applied while they
+//!   strictly lower the cost. The identity placement is kept on ties, so circuits
+//!   that already fit the device are left untouched.
+//! - **Routing**: Gates are replayed in order; for a distant pair, the first
+//!   qubit is swapped along a shortest path until it neighbours the second.
+//!
+//! ## 📍 Where to apply this?
+//! - **Hardware Compilation**: After decomposition to one- and two-qubit gates.
+//! - **Mapping Reuse**: Compute `initial_mapping` on a representative circuit and
+//!   pass it to `route_circuit` for the rest of the family.
+//!
+//! ## 📊 Code Behavior
+//! - **Placement**: $O(N^2 \cdot P)$ per local-search sweep for $N$ qubits and $P$
+//!   interacting pairs.
+//! - **Routing**: At most d − 1 SWAPs per distant gate; not globally optimal.
+//! - **Conventions**: Mappings are logical → physical, `mapping[l] = p`.
+
+use std::collections::{BTreeMap, VecDeque};
+use crate::gates::core::Gate;
+
+// ============================================================================
+// COUPLING DISTANCES
+// ============================================================================
+
+/// All-pairs shortest-path distances (BFS); `usize::MAX` when disconnected
+fn coupling_distances(coupling: &[(usize, usize)], num_qubits: usize) -> Vec<Vec<usize>> {
+    let adjacency = adjacency(coupling, num_qubits);
+    (0..num_qubits)
+        .map(|source| {
+            let mut dist = vec![usize::MAX; num_qubits];
+            dist[source] = 0;
+            let mut queue = VecDeque::from([source]);
+            while let Some(u) = queue.pop_front() {
+                for &v in &adjacency[u] {
+                    if dist[v] == usize::MAX {
+                        dist[v] = dist[u] + 1;
+                        queue.push_back(v);
+                    }
+                }
+            }
+            dist
+        })
+        .collect()
+}
+
+fn adjacency(coupling: &[(usize, usize)], num_qubits: usize) -> Vec<Vec<usize>> {
+    let mut adjacency = vec![Vec::new(); num_qubits];
+    for &(a, b) in coupling {
+        assert!(a < num_qubits && b < num_qubits, "Coupling edge ({}, {}) out of range", a, b);
+        adjacency[a].push(b);
+        adjacency[b].push(a);
+    }
+    adjacency
+}
+
+// ============================================================================
+// INITIAL MAPPING
+// ============================================================================
+
+/// Interaction-weighted logical → physical placement
+///
+/// Returns a permutation `mapping` of `0..num_qubits` with `mapping[l]` the
+/// physical qubit for logical qubit `l`, chosen to minimize the weighted
+/// number of two-qubit gates on non-adjacent physical qubits. Returns the
+/// identity when the circuit already fits the coupling graph.
+pub fn initial_mapping(circuit: &[Gate], coupling: &[(usize, usize)], num_qubits: usize) -> Vec<usize> {
+    let dist = coupling_distances(coupling, num_qubits);
+    let weights = interaction_weights(circuit, num_qubits);
+
+    let identity: Vec<usize> = (0..num_qubits).collect();
+    let greedy = greedy_placement(&weights, &dist, coupling, num_qubits);
+
+    let mut best = identity;
+    let mut best_cost = placement_cost(&best, &weights, &dist);
+    let greedy_cost = placement_cost(&greedy, &weights, &dist);
+    if greedy_cost < best_cost {
+        best = greedy;
+        best_cost = greedy_cost;
+    }
+
+    // Pairwise exchanges while they strictly improve
+    let mut improved = best_cost > 0;
+    while improved {
+        improved = false;
+        for a in 0..num_qubits {
+            for b in a + 1..num_qubits {
+                best.swap(a, b);
+                let cost = placement_cost(&best, &weights, &dist);
+                if cost < best_cost {
+                    best_cost = cost;
+                    improved = true;
+                } else {
+                    best.swap(a, b);
+                }
+            }
+        }
+    }
+    best
+}
+
+/// w(a, b) for every interacting logical pair a < b
+fn interaction_weights(circuit: &[Gate], num_qubits: usize) -> BTreeMap<(usize, usize), usize> {
+    let mut weights = BTreeMap::new();
+    for gate in circuit {
+        let qs = gate.qubits();
+        for q in &qs {
+            assert!(*q < num_qubits, "Gate {:?} uses qubit {} beyond the device", gate, q);
+        }
+        if let [a, b] = qs[..] {
+            *weights.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+        }
+    }
+    weights
+}
+
+/// Σ w(a, b)·(d(π(a), π(b)) − 1)
+fn placement_cost(mapping: &[usize], weights: &BTreeMap<(usize, usize), usize>, dist: &[Vec<usize>]) -> usize {
+    weights
+        .iter()
+        .map(|(&(a, b), &w)| {
+            let d = dist[mapping[a]][mapping[b]];
+            assert!(d != usize::MAX, "Logical qubits {} and {} cannot be connected", a, b);
+            w * (d - 1)
+        })
+        .sum()
+}
+
+fn greedy_placement(
+    weights: &BTreeMap<(usize, usize), usize>,
+    dist: &[Vec<usize>],
+    coupling: &[(usize, usize)],
+    num_qubits: usize,
+) -> Vec<usize> {
+    let mut total = vec![0usize; num_qubits];
+    for (&(a, b), &w) in weights {
+        total[a] += w;
+        total[b] += w;
+    }
+    let degree: Vec<usize> = adjacency(coupling, num_qubits).iter().map(Vec::len).collect();
+    let weight = |a: usize, b: usize| weights.get(&(a.min(b), a.max(b))).copied().unwrap_or(0);
+
+    let mut mapping = vec![usize::MAX; num_qubits];
+    let mut used = vec![false; num_qubits];
+    for _ in 0..num_qubits {
+        // Next logical qubit: most weight to placed qubits, then most total weight
+        let placed: Vec<usize> = (0..num_qubits).filter(|&l| mapping[l] != usize::MAX).collect();
+        let logical = (0..num_qubits)
+            .filter(|&l| mapping[l] == usize::MAX)
+            .max_by_key(|&l| {
+                let attached: usize = placed.iter().map(|&p| weight(l, p)).sum();
+                (attached, total[l], std::cmp::Reverse(l))
+            })
+            .unwrap();
+
+        // Free physical qubit minimizing weighted distance, then highest degree
+        let physical = (0..num_qubits)
+            .filter(|&p| !used[p])
+            .min_by_key(|&p| {
+                let cost: usize = placed
+                    .iter()
+                    .map(|&q| weight(logical, q).saturating_mul(dist[p][mapping[q]]))
+                    .fold(0, usize::saturating_add);
+                (cost, std::cmp::Reverse(degree[p]), p)
+            })
+            .unwrap();
+
+        mapping[logical] = physical;
+        used[physical] = true;
+    }
+    mapping
+}
+
+// ============================================================================
+// ROUTING
+// ============================================================================
+
+/// Route `circuit` onto `coupling` by inserting SWAPs along shortest paths
+///
+/// Starts from `mapping` when given (logical → physical, e.g. a cached
+/// `initial_mapping`), otherwise computes `initial_mapping` first. Returns the
+/// physical circuit and the final logical → physical mapping. Gates must act
+/// on at most two qubits; decompose larger gates first.
+pub fn route_circuit(
+    circuit: &[Gate],
+    coupling: &[(usize, usize)],
+    num_qubits: usize,
+    mapping: Option<&[usize]>,
+) -> (Vec<Gate>, Vec<usize>) {
+    let mut layout = match mapping {
+        Some(m) => {
+            assert_eq!(m.len(), num_qubits, "Mapping must cover all {} qubits", num_qubits);
+            let mut seen = vec![false; num_qubits];
+            for &p in m {
+                assert!(p < num_qubits && !seen[p], "Mapping is not a permutation: {:?}", m);
+                seen[p] = true;
+            }
+            m.to_vec()
+        }
+        None => initial_mapping(circuit, coupling, num_qubits),
+    };
+    let dist = coupling_distances(coupling, num_qubits);
+    let adjacency = adjacency(coupling, num_qubits);
+
+    // Inverse layout: physical → logical
+    let mut logical = vec![0; num_qubits];
+    for (l, &p) in layout.iter().enumerate() {
+        logical[p] = l;
+    }
+
+    let mut routed = Vec::new();
+    for gate in circuit {
+        let qs = gate.qubits();
+        assert!(
+            qs.len() <= 2 || matches!(gate, Gate::Barrier(_)),
+            "route_circuit needs gates on at most two qubits, got {:?}", gate
+        );
+        if let [a, b] = qs[..] {
+            if !matches!(gate, Gate::Barrier(_)) {
+                let target = layout[b];
+                assert!(dist[layout[a]][target] != usize::MAX, "Qubits {} and {} cannot be connected", a, b);
+                // Walk a toward b one neighbour at a time
+                while dist[layout[a]][target] > 1 {
+                    let here = layout[a];
+                    let next = *adjacency[here]
+                        .iter()
+                        .filter(|&&n| dist[n][target] + 1 == dist[here][target])
+                        .min()
+                        .unwrap();
+                    routed.push(Gate::SWAP(here, next));
+                    let (la, ln) = (logical[here], logical[next]);
+                    layout.swap(la, ln);
+                    logical.swap(here, next);
+                }
+            }
+        }
+        routed.push(gate.map_qubits(|q| layout[q]));
+    }
+    (routed, layout)
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::{basis_state, simulate_from, states_close};
+
+    fn line(n: usize) -> Vec<(usize, usize)> {
+        (0..n - 1).map(|i| (i, i + 1)).collect()
+    }
+
+    #[test]
+    fn test_pre_adjacent_circuit_keeps_identity() {
+        let circuit = vec![Gate::H(0), Gate::CX(0, 1), Gate::CX(1, 2), Gate::CZ(3, 2), Gate::RZZ(1, 0, 0.3)];
+        assert_eq!(initial_mapping(&circuit, &line(4), 4), vec![0, 1, 2, 3]);
+
+        let (routed, layout) = route_circuit(&circuit, &line(4), 4, None);
+        assert!(routed.iter().all(|g| !matches!(g, Gate::SWAP(_, _))));
+        assert_eq!(layout, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_star_interaction_centers_hub() {
+        // Logical 0 talks to everyone; on a line it belongs in the middle
+        let circuit: Vec<Gate> = (1..5).flat_map(|t| vec![Gate::CX(0, t); 3]).collect();
+        let mapping = initial_mapping(&circuit, &line(5), 5);
+        assert!(mapping[0] == 1 || mapping[0] == 2 || mapping[0] == 3, "Hub at {}", mapping[0]);
+
+        let dist = coupling_distances(&line(5), 5);
+        let weights = interaction_weights(&circuit, 5);
+        let identity: Vec<usize> = (0..5).collect();
+        assert!(placement_cost(&mapping, &weights, &dist) < placement_cost(&identity, &weights, &dist));
+    }
+
+    #[test]
+    fn test_routed_circuit_respects_coupling_and_semantics() {
+        let coupling = line(4);
+        let circuit = vec![Gate::CX(0, 3), Gate::CX(2, 0), Gate::CX(1, 3), Gate::CX(3, 0)];
+        let mapping = vec![2, 0, 3, 1];
+        let (routed, layout) = route_circuit(&circuit, &coupling, 4, Some(&mapping));
+
+        for gate in &routed {
+            let qs = gate.qubits();
+            assert!(coupling.contains(&(qs[0].min(qs[1]), qs[0].max(qs[1]))), "{:?} not on coupling", gate);
+        }
+
+        // Logical bits placed by the initial mapping, read back through the final one
+        let place = |bits: usize, m: &[usize]| (0..4).filter(|&l| bits >> l & 1 == 1).map(|l| 1 << m[l]).sum();
+        for input in 0..16usize {
+            let expected = simulate_from(&circuit, &basis_state(4, input)).unwrap();
+            let output_bits = (0..16).find(|&i| expected[i].norm() > 0.5).unwrap();
+            let state = simulate_from(&routed, &basis_state(4, place(input, &mapping))).unwrap();
+            assert!(states_close(&state, &basis_state(4, place(output_bits, &layout)), 1e-10), "Input {}", input);
+        }
+    }
+
+    #[test]
+    fn test_precomputed_mapping_matches_default() {
+        let circuit = vec![Gate::CX(0, 2), Gate::CX(0, 3), Gate::CX(0, 2), Gate::CX(1, 3)];
+        let mapping = initial_mapping(&circuit, &line(4), 4);
+        let (a, la) = route_circuit(&circuit, &line(4), 4, None);
+        let (b, lb) = route_circuit(&circuit, &line(4), 4, Some(&mapping));
+        assert_eq!(format!("{:?}", a), format!("{:?}", b));
+        assert_eq!(la, lb);
+    }
+}
@@ -0,0 +1,28 @@
+//! Routing Module
+//!
+//! ## 🎯 Why is this used?
+//! Physical devices only couple a few qubit pairs directly. This module
+//! provides the constructions that make logical interactions fit a restricted
+//! connectivity graph by moving qubits around with SWAP gates.
+//!
+//! ## ⚙️ How it works?
+//! - **SWAP Networks**: Fixed, input-independent SWAP schedules that make every
+//!   pair of qubits on a line adjacent exactly once.
+//! - **Placement and Routing**: Interaction-weighted initial mappings and greedy
+//!   shortest-path SWAP insertion on arbitrary coupling graphs.
+//! - **Basis Translation**: Rewriting circuits into a device's native gate set.
+//!
+//! ## 📍 Where to apply this?
+//! Use this between synthesis and execution when targeting linear or other
+//! sparsely connected hardware.
+//!
+//! ## 📊 Code Behavior
+//! - Structural wrapper with zero overhead.
+
+pub mod swap_network;
+pub mod mapping;
+pub mod basis;
+
+pub use swap_network::*;
+pub use mapping::*;
+pub use basis::*;
@@ -0,0 +1,126 @@
+//! SWAP Networks for Linear Connectivity
+//!
+//! This module provides fixed SWAP schedules for all-to-all interactions:
+//! - The odd-even transposition network on a line
+//! - Per-pair interaction callbacks placed while the pair is adjacent
+//!
+//! ## 🎯 Why is this used?
+//! Fully connected Hamiltonians (SK models, molecular Coulomb terms, QAOA on
+//! dense graphs) need an interaction between every pair of qubits. On a linear
+//! chip, routing each term independently costs O(N) SWAPs per term. A SWAP
+//! network instead moves every qubit past every other exactly once, giving all
+//! N(N-1)/2 interactions in N layers of nearest-neighbour gates.
+//!
+//! ## ⚙️ How it works?
+//! - **Odd-Even Transposition**: Layer ℓ acts on physical pairs (i, i+1) with
+//!   i ≡ ℓ (mod 2). After N layers the line is reversed and every logical pair
+//!   has shared one such slot.
+//! - **Interaction Slot**: When two logical qubits sit on a pair, the callback
+//!   emits their interaction on the logical labels; the gates are relabelled onto
+//!   the current physical wires and followed by the SWAP.
+//!
+//! ## 📍 Where to apply this?
+//! - **Trotterized All-to-All Models**: One network per Trotter step, alternating
+//!   direction so the permutation cancels.
+//! - **Dense QAOA**: Cost layers of complete-graph MaxCut instances.
+//!
+//! ## 📊 Code Behavior
+//! - **Depth**: N SWAP layers, each interaction fused with its SWAP.
+//! - **Gate Count**: N(N-1)/2 SWAPs plus the interaction gates.
+
+use crate::gates::core::Gate;
+
+// ============================================================================
+// ODD-EVEN SWAP NETWORK
+// ============================================================================
+
+/// Odd-even SWAP network making every logical pair adjacent exactly once
+///
+/// `interaction(a, b)` is called once per logical pair with `a < b` and returns
+/// gates written on qubits `a` and `b`; they are relabelled onto the physical
+/// wires currently holding those logical qubits. Returns the circuit and the
+/// final permutation, where `perm[p]` is the logical qubit on physical wire `p`.
+pub fn swap_network(
+    num_qubits: usize,
+    interaction: impl Fn(usize, usize) -> Vec<Gate>,
+) -> (Vec<Gate>, Vec<usize>) {
+    let mut perm: Vec<usize> = (0..num_qubits).collect();
+    let mut gates = Vec::new();
+
+    for layer in 0..num_qubits {
+        for p in (layer % 2..num_qubits.saturating_sub(1)).step_by(2) {
+            let (a, b) = (perm[p], perm[p + 1]);
+            for gate in interaction(a.min(b), a.max(b)) {
+                assert!(
+                    gate.qubits().iter().all(|&q| q == a || q == b),
+                    "Interaction for pair ({}, {}) touches other qubits", a, b
+                );
+                gates.push(gate.map_qubits(|q| if q == a { p } else { p + 1 }));
+            }
+            gates.push(Gate::SWAP(p, p + 1));
+            perm.swap(p, p + 1);
+        }
+    }
+
+    (gates, perm)
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_every_pair_meets_once() {
+        let n = 6;
+        let seen = RefCell::new(Vec::new());
+        let (gates, perm) = swap_network(n, |a, b| {
+            seen.borrow_mut().push((a, b));
+            vec![Gate::RZZ(a, b, 0.1)]
+        });
+
+        let mut seen = seen.into_inner();
+        seen.sort();
+        let expected: Vec<(usize, usize)> =
+            (0..n).flat_map(|a| (a + 1..n).map(move |b| (a, b))).collect();
+        assert_eq!(seen, expected);
+
+        // Every gate is nearest-neighbour and the line ends reversed
+        for gate in &gates {
+            let qs = gate.qubits();
+            assert_eq!(qs.len(), 2);
+            assert_eq!(qs[0].abs_diff(qs[1]), 1);
+        }
+        assert_eq!(perm, (0..n).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_interaction_lands_on_logical_pair() {
+        use crate::simulator::{basis_state, simulate_from, states_close};
+
+        // Logical CX(a, b) slots, replayed classically in network order
+        let order = RefCell::new(Vec::new());
+        let (gates, perm) = swap_network(4, |a, b| {
+            order.borrow_mut().push((a, b));
+            vec![Gate::CX(a, b)]
+        });
+        let order = order.into_inner();
+
+        for input in 0..16usize {
+            let mut bits: Vec<bool> = (0..4).map(|q| input >> q & 1 == 1).collect();
+            for &(a, b) in &order {
+                if bits[a] {
+                    bits[b] = !bits[b];
+                }
+            }
+            // Physical wire w holds logical qubit perm[w]
+            let output: usize = (0..4).filter(|&w| bits[perm[w]]).map(|w| 1 << w).sum();
+            let state = simulate_from(&gates, &basis_state(4, input)).unwrap();
+            assert!(states_close(&state, &basis_state(4, output), 1e-10), "Input {}", input);
+        }
+    }
+}
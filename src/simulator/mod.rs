@@ -0,0 +1,25 @@
+//! Simulator Module
+//!
+//! ## 🎯 Why is this used?
+//! This module provides exact classical simulation of `Gate` circuits. It is
+//! the reference oracle used to verify that synthesized, optimized or
+//! decomposed circuits implement the intended transformation.
+//!
+//! ## ⚙️ How it works?
+//! - **Statevector Backend**: Re-exports the dense statevector simulator and
+//!   its state comparison helpers.
+//! - **Noise Backend**: Depolarizing / amplitude-damping `NoiseModel`s with
+//!   Monte Carlo trajectories and an exact density-matrix reference.
+//!
+//! ## 📍 Where to apply this?
+//! Use this in tests and small-scale experiments where the full $2^N$ state
+//! (or $4^N$ density matrix) fits in memory.
+//!
+//! ## 📊 Code Behavior
+//! - Structural wrapper with zero overhead.
+
+pub mod statevector;
+pub mod noise;
+
+pub use statevector::*;
+pub use noise::*;
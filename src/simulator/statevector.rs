@@ -0,0 +1,589 @@
+//! Dense Statevector Simulator
+//!
+//! This module provides an exact, dense simulator for `Gate` circuits:
+//! - Statevector evolution from |0…0⟩ or an arbitrary input state
+//! - Single-gate application in place
+//! - `simulate_postselected`: Measurements forced to chosen outcomes, with
+//!   classically controlled gates following them
+//! - Overlap / fidelity helpers for verifying circuit identities
+//! - Input checks up front: bad states and circuits are `RustiqError`s
+//! - `ancillas_restored`: Whether scratch qubits end back in |0⟩
+//! - `embed_gate`: A local 2ᵏ×2ᵏ matrix lifted to the full 2ⁿ×2ⁿ space
+//! - `apply_pauli_string`: A Pauli product applied in place without a matrix
+//!
+//! ## 🎯 Why is this used?
+//! Most constructions in the library (decompositions, arithmetic, oracles,
+//! uncomputation) are only trustworthy once they have been checked against the
+//! unitary they claim to implement. A small exact simulator turns those claims
+//! into tests: apply the circuit, then compare amplitudes.
+//!
+//! ## ⚙️ How it works?
+//! - **Encoding**: Little-endian — qubit `q` is bit `q` of the basis index, so
+//!   |q₁q₀⟩ = |10⟩ is index 2 when q₁ = 1.
+//! - **1-Qubit Gates**: The 2x2 matrix from `Gate::matrix_2x2` is applied to every
+//!   amplitude pair differing in bit `q`.
+//! - **2-Qubit Gates**: The 4x4 matrix is applied with local index
+//!   (bit(q₀) << 1) | bit(q₁), i.e. the first-listed qubit is the most
+//!   significant, matching the `cnot()`/`crz()` matrix layouts.
+//! - **Embedding**: `embed_gate` uses the same two rules for any k: the full
+//!   index is little-endian over all n qubits, and the local index reads
+//!   `acting_on[0]` as its most significant bit. Both it and the 2-qubit
+//!   kernel go through one offset table, so they cannot drift apart.
+//! - **Multi-Qubit Gates**: CCX/MCX/CSWAP act as permutations and CCZ/MCZ/MCP
+//!   as diagonal phases, so they are applied directly without a matrix.
+//! - **Pauli Strings**: X swaps the amplitude pairs differing in bit q, Z
+//!   negates those with bit q set, and Y does both with a factor ±i, so a
+//!   k-qubit string costs $O(k \cdot 2^N)$ instead of a $4^N$ matrix product.
+//! - **Global Phase**: `GlobalPhase(θ)` multiplies every amplitude by e^(iθ).
+//! - **Non-Unitary Gates**: `Barrier` is a no-op; `Measure` and
+//!   `ClassicalControl` are rejected, since a single statevector cannot
+//!   branch, except by `simulate_postselected`, which follows one branch.
+//!
+//! ## 📍 Where to apply this?
+//! - **Unit Tests**: Verifying decompositions and identities on small registers.
+//! - **Debugging**: Inspecting amplitudes of intermediate states.
+//!
+//! ## 📊 Code Behavior
+//! - **Complexity**: $O(G \cdot 2^N)$ time, $O(2^N)$ memory.
+//! - **Limits**: Intended for $N \le 20$; dense storage grows exponentially.
+
+use crate::gates::core::{Complex, Gate, GateMatrix2x2, GateMatrix4x4, PauliOp, DEFAULT_TOL};
+use crate::analysis::circuit_analysis::validate_width;
+use crate::error::{check_dimension, state_qubits, RustiqError};
+
+// ============================================================================
+// STATE CONSTRUCTION
+// ============================================================================
+
+/// The computational basis state |0…0⟩ on `num_qubits` qubits
+pub fn zero_state(num_qubits: usize) -> Vec<Complex> {
+    basis_state(num_qubits, 0)
+}
+
+/// The computational basis state |index⟩ on `num_qubits` qubits
+pub fn basis_state(num_qubits: usize, index: usize) -> Vec<Complex> {
+    let dim = 1usize << num_qubits;
+    assert!(index < dim, "Basis index {} out of range for {} qubits", index, num_qubits);
+    let mut state = vec![Complex::ZERO; dim];
+    state[index] = Complex::ONE;
+    state
+}
+
+// ============================================================================
+// SIMULATION
+// ============================================================================
+
+/// Simulate `circuit` on |0…0⟩ and return the final statevector
+///
+/// Fails on the same circuits as [`simulate_from`].
+pub fn simulate(circuit: &[Gate], num_qubits: usize) -> Result<Vec<Complex>, RustiqError> {
+    simulate_from(circuit, &zero_state(num_qubits))
+}
+
+/// Simulate `circuit` starting from an arbitrary input state
+///
+/// The state must have length 2ⁿ and the circuit must pass `validate_width`
+/// for n qubits; `Measure`, `Reset` and `ClassicalControl` are
+/// `UnsupportedGate`. Every check runs before the first gate is applied.
+/// The input need not be normalized, since evolution is linear.
+pub fn simulate_from(circuit: &[Gate], state: &[Complex]) -> Result<Vec<Complex>, RustiqError> {
+    let num_qubits = state_qubits(state)?;
+    validate_width(circuit, num_qubits)?;
+    if let Some(gate) = circuit.iter().find(|g| !is_unitary(g)) {
+        return Err(RustiqError::UnsupportedGate { gate: gate.kind().name().to_string() });
+    }
+    Ok(evolve(circuit, state))
+}
+
+/// Simulate `circuit` with every `Measure` forced to a chosen outcome
+///
+/// The k-th measurement projects its qubit onto |`outcomes[k]`⟩ and writes
+/// the outcome to its classical bit; a `ClassicalControl` applies its gate
+/// when that bit holds 1 (bits start at 0). Returns the normalized final
+/// state and the probability of the whole outcome record, which is 0 (with
+/// a zero state) for an impossible record. `Reset` is `UnsupportedGate`,
+/// and `outcomes` must have one entry per measurement.
+pub fn simulate_postselected(
+    circuit: &[Gate],
+    state: &[Complex],
+    outcomes: &[bool],
+) -> Result<(Vec<Complex>, f64), RustiqError> {
+    fn supported(gate: &Gate) -> bool {
+        match gate {
+            Gate::Reset(_) => false,
+            Gate::ClassicalControl(_, inner) => !matches!(**inner, Gate::Measure(..)) && supported(inner),
+            _ => true,
+        }
+    }
+    // The gate a (possibly nested) classical control runs, if its bits are set
+    fn fired<'a>(gate: &'a Gate, bits: &[usize]) -> Option<&'a Gate> {
+        match gate {
+            Gate::ClassicalControl(bit, inner) if bits.contains(bit) => fired(inner, bits),
+            Gate::ClassicalControl(..) => None,
+            g => Some(g),
+        }
+    }
+    let num_qubits = state_qubits(state)?;
+    validate_width(circuit, num_qubits)?;
+    if let Some(gate) = circuit.iter().find(|g| !supported(g)) {
+        return Err(RustiqError::UnsupportedGate { gate: gate.kind().name().to_string() });
+    }
+    check_dimension(circuit.iter().filter(|g| matches!(g, Gate::Measure(..))).count(), outcomes.len())?;
+
+    let norm_sq = |s: &[Complex]| s.iter().map(|a| a.norm_sq()).sum::<f64>();
+    let input_norm = norm_sq(state);
+    let mut state = state.to_vec();
+    let mut bits: Vec<usize> = Vec::new();
+    let mut outcomes = outcomes.iter();
+    for gate in circuit {
+        match gate {
+            Gate::Measure(q, bit) => {
+                let outcome = *outcomes.next().expect("One outcome per measurement was checked");
+                for (i, amp) in state.iter_mut().enumerate() {
+                    if (i >> q & 1 == 1) != outcome {
+                        *amp = Complex::ZERO;
+                    }
+                }
+                bits.retain(|b| b != bit);
+                if outcome {
+                    bits.push(*bit);
+                }
+            }
+            g => {
+                if let Some(g) = fired(g, &bits) {
+                    apply_gate_unchecked(&mut state, g);
+                }
+            }
+        }
+    }
+
+    let final_norm = norm_sq(&state);
+    if final_norm > 0.0 {
+        let scale = 1.0 / final_norm.sqrt();
+        state.iter_mut().for_each(|a| *a = *a * scale);
+    }
+    Ok((state, if input_norm > 0.0 { final_norm / input_norm } else { 0.0 }))
+}
+
+/// [`simulate_from`] without the input checks, for circuits the crate built
+pub(crate) fn evolve(circuit: &[Gate], state: &[Complex]) -> Vec<Complex> {
+    let mut state = state.to_vec();
+    for gate in circuit {
+        apply_gate_unchecked(&mut state, gate);
+    }
+    state
+}
+
+/// Apply a single gate to a statevector in place
+///
+/// Fails, leaving the state untouched, on the inputs [`simulate_from`]
+/// rejects.
+pub fn apply_gate(state: &mut [Complex], gate: &Gate) -> Result<(), RustiqError> {
+    let num_qubits = state_qubits(state)?;
+    validate_width(std::slice::from_ref(gate), num_qubits)?;
+    if !is_unitary(gate) {
+        return Err(RustiqError::UnsupportedGate { gate: gate.kind().name().to_string() });
+    }
+    apply_gate_unchecked(state, gate);
+    Ok(())
+}
+
+fn is_unitary(gate: &Gate) -> bool {
+    !matches!(gate, Gate::Measure(..) | Gate::Reset(_) | Gate::ClassicalControl(..))
+}
+
+/// [`apply_gate`] panicking instead of returning an error
+pub(crate) fn apply_gate_unchecked(state: &mut [Complex], gate: &Gate) {
+    assert!(state.len().is_power_of_two(), "Statevector length must be a power of two");
+    let num_qubits = state.len().trailing_zeros() as usize;
+    for q in gate.qubits() {
+        assert!(q < num_qubits, "Gate acts on qubit {} but state has {} qubits", q, num_qubits);
+    }
+
+    if let Some(m) = gate.matrix_2x2() {
+        apply_1q(state, gate.qubits()[0], &m);
+        return;
+    }
+    if let Some(m) = gate.matrix_4x4() {
+        let qs = gate.qubits();
+        apply_2q(state, qs[0], qs[1], &m);
+        return;
+    }
+
+    match gate {
+        Gate::CCX(c1, c2, t) => apply_mcx(state, &[*c1, *c2], *t),
+        Gate::MCX(controls, t) => apply_mcx(state, controls, *t),
+        Gate::CCZ(c1, c2, t) => apply_mcp(state, &[*c1, *c2], *t, std::f64::consts::PI),
+        Gate::MCZ(controls, t) => apply_mcp(state, controls, *t, std::f64::consts::PI),
+        Gate::MCP(controls, t, phi) => apply_mcp(state, controls, *t, *phi),
+        Gate::GlobalPhase(theta) => {
+            let phase = Complex::from_polar(1.0, *theta);
+            for amp in state.iter_mut() {
+                *amp = *amp * phase;
+            }
+        }
+        Gate::CSWAP(c, a, b) => {
+            let (c, a, b) = (1usize << c, 1usize << a, 1usize << b);
+            for i in 0..state.len() {
+                // Visit each swapped pair once, from the |a=1, b=0⟩ side
+                if i & c != 0 && i & a != 0 && i & b == 0 {
+                    state.swap(i, (i & !a) | b);
+                }
+            }
+        }
+        Gate::Barrier(_) => {}
+        Gate::Measure(_, _) | Gate::ClassicalControl(_, _) | Gate::Reset(_) => {
+            panic!("Measurement, reset and feed-forward need a sampling simulator, got {:?}", gate)
+        }
+        _ => unreachable!("Gate {:?} has no simulator rule", gate),
+    }
+}
+
+/// Lift a local unitary on `acting_on` to the full 2ⁿ×2ⁿ matrix (row-major)
+///
+/// Full indices are little-endian (qubit q is bit q); local indices put
+/// `acting_on[0]` in the most significant bit, as in `Gate::matrix_4x4`. The
+/// qubits may be non-adjacent and in any order. So X on qubit 1 of 3 is
+/// I⊗X⊗I with qubit 2 leftmost.
+pub fn embed_gate(local: &[Vec<Complex>], acting_on: &[usize], num_qubits: usize) -> Result<Vec<Vec<Complex>>, RustiqError> {
+    let k = acting_on.len();
+    check_dimension(1 << k, local.len())?;
+    for row in local {
+        check_dimension(1 << k, row.len())?;
+    }
+    for (i, &q) in acting_on.iter().enumerate() {
+        if q >= num_qubits {
+            return Err(RustiqError::QubitOutOfRange { qubit: q, num_qubits });
+        }
+        if acting_on[..i].contains(&q) {
+            return Err(RustiqError::DuplicateQubit { qubit: q });
+        }
+    }
+
+    let offsets = local_offsets(acting_on);
+    let mask = offsets[offsets.len() - 1];
+    let dim = 1usize << num_qubits;
+    let mut full = vec![vec![Complex::ZERO; dim]; dim];
+    for base in (0..dim).filter(|i| i & mask == 0) {
+        for (r, &ro) in offsets.iter().enumerate() {
+            for (c, &co) in offsets.iter().enumerate() {
+                full[base | ro][base | co] = local[r][c];
+            }
+        }
+    }
+    Ok(full)
+}
+
+/// Full-index offset of every local index, `acting_on[0]` most significant
+///
+/// The last entry has every bit set, so it doubles as the qubit mask.
+fn local_offsets(acting_on: &[usize]) -> Vec<usize> {
+    let k = acting_on.len();
+    (0..1usize << k)
+        .map(|local| {
+            acting_on.iter().enumerate()
+                .filter(|(i, _)| local >> (k - 1 - i) & 1 == 1)
+                .fold(0, |acc, (_, &q)| acc | 1 << q)
+        })
+        .collect()
+}
+
+/// Apply the Pauli product ∏ P_q to a statevector in place
+///
+/// Each `(q, P)` acts on qubit q and the factors are applied in list order,
+/// so a repeated qubit gets the product of its factors, last one leftmost.
+/// On error the state is left untouched.
+pub fn apply_pauli_string(state: &mut [Complex], pauli: &[(usize, PauliOp)]) -> Result<(), RustiqError> {
+    let num_qubits = state_qubits(state)?;
+    if let Some(&(qubit, _)) = pauli.iter().find(|(q, _)| *q >= num_qubits) {
+        return Err(RustiqError::QubitOutOfRange { qubit, num_qubits });
+    }
+    for &(q, op) in pauli {
+        let bit = 1usize << q;
+        match op {
+            PauliOp::I => {}
+            PauliOp::X => {
+                for i in (0..state.len()).filter(|i| i & bit == 0) {
+                    state.swap(i, i | bit);
+                }
+            }
+            PauliOp::Y => {
+                // Y|0⟩ = i|1⟩, Y|1⟩ = −i|0⟩
+                for i in (0..state.len()).filter(|i| i & bit == 0) {
+                    let (a0, a1) = (state[i], state[i | bit]);
+                    state[i] = Complex::NEG_I * a1;
+                    state[i | bit] = Complex::I * a0;
+                }
+            }
+            PauliOp::Z => {
+                for amp in state.iter_mut().enumerate().filter(|(i, _)| i & bit != 0).map(|(_, a)| a) {
+                    *amp = -*amp;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// STATE COMPARISON
+// ============================================================================
+
+/// Inner product ⟨a|b⟩
+pub fn inner_product(a: &[Complex], b: &[Complex]) -> Complex {
+    assert_eq!(a.len(), b.len(), "States must have equal dimension");
+    a.iter().zip(b.iter()).fold(Complex::ZERO, |acc, (x, y)| acc + x.conj() * *y)
+}
+
+/// State fidelity |⟨a|b⟩|² for pure states
+pub fn state_fidelity(a: &[Complex], b: &[Complex]) -> f64 {
+    inner_product(a, b).norm_sq()
+}
+
+/// Measurement probabilities |ψᵢ|² in the computational basis
+pub fn probabilities(state: &[Complex]) -> Vec<f64> {
+    state.iter().map(|a| a.norm_sq()).collect()
+}
+
+/// Amplitude-wise comparison within `tol` (global phase is significant)
+pub fn states_close(a: &[Complex], b: &[Complex], tol: f64) -> bool {
+    a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| (*x - *y).norm() < tol)
+}
+
+/// Whether `circuit`, run on |0…0⟩, leaves every ancilla back in |0⟩
+///
+/// Checks that the ancilla bits read all-zero with probability 1, which also
+/// means they are disentangled from the rest of the register. Only the
+/// all-zero input is tried: an uncomputation bug that shows up for other
+/// data inputs passes unnoticed, so prepare those inputs in the circuit.
+pub fn ancillas_restored(circuit: &[Gate], ancilla_qubits: &[usize], num_qubits: usize) -> bool {
+    ancillas_restored_with_tol(circuit, ancilla_qubits, num_qubits, DEFAULT_TOL)
+}
+
+/// [`ancillas_restored`] allowing probability `tol` on any non-zero ancilla pattern
+pub fn ancillas_restored_with_tol(circuit: &[Gate], ancilla_qubits: &[usize], num_qubits: usize, tol: f64) -> bool {
+    assert!(ancilla_qubits.iter().all(|&q| q < num_qubits), "Ancilla outside the {}-qubit register", num_qubits);
+    let mask = ancilla_qubits.iter().fold(0usize, |m, &q| m | (1 << q));
+    let state = evolve(circuit, &zero_state(num_qubits));
+    let dirty: f64 = state.iter().enumerate()
+        .filter(|(i, _)| i & mask != 0)
+        .map(|(_, a)| a.norm_sq())
+        .sum();
+    dirty <= tol
+}
+
+// ============================================================================
+// GATE KERNELS
+// ============================================================================
+
+pub(crate) fn apply_1q(state: &mut [Complex], q: usize, m: &GateMatrix2x2) {
+    let bit = 1usize << q;
+    for i in 0..state.len() {
+        if i & bit == 0 {
+            let (a0, a1) = (state[i], state[i | bit]);
+            state[i] = m.data[0][0] * a0 + m.data[0][1] * a1;
+            state[i | bit] = m.data[1][0] * a0 + m.data[1][1] * a1;
+        }
+    }
+}
+
+fn apply_2q(state: &mut [Complex], q0: usize, q1: usize, m: &GateMatrix4x4) {
+    let offsets = local_offsets(&[q0, q1]);
+    for i in 0..state.len() {
+        if i & offsets[3] == 0 {
+            // Local index k = (bit(q0) << 1) | bit(q1)
+            let idx = [0, 1, 2, 3].map(|k| i | offsets[k]);
+            let amps = idx.map(|j| state[j]);
+            for (row, &j) in idx.iter().enumerate() {
+                state[j] = (0..4).fold(Complex::ZERO, |acc, col| acc + m.data[row][col] * amps[col]);
+            }
+        }
+    }
+}
+
+fn apply_mcx(state: &mut [Complex], controls: &[usize], target: usize) {
+    let mask = controls.iter().fold(0usize, |m, &c| m | (1 << c));
+    let t = 1usize << target;
+    for i in 0..state.len() {
+        if i & mask == mask && i & t == 0 {
+            state.swap(i, i | t);
+        }
+    }
+}
+
+fn apply_mcp(state: &mut [Complex], controls: &[usize], target: usize, phi: f64) {
+    let mask = controls.iter().fold(1usize << target, |m, &c| m | (1 << c));
+    let phase = Complex::from_polar(1.0, phi);
+    for (i, amp) in state.iter_mut().enumerate() {
+        if i & mask == mask {
+            *amp = *amp * phase;
+        }
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gates::core::decompose_toffoli;
+
+    #[test]
+    fn test_apply_pauli_string_matches_matrix() {
+        use crate::gates::core::{pauli_x, pauli_y, pauli_z};
+        let state: Vec<Complex> = (0..8).map(|i| Complex::new(0.1 * i as f64 + 0.2, 0.3 - 0.05 * i as f64)).collect();
+        let kron = |a: &GateMatrix2x2, b: &GateMatrix2x2| -> Vec<Vec<Complex>> {
+            (0..4).map(|r| (0..4).map(|c| a.data[r >> 1][c >> 1] * b.data[r & 1][c & 1]).collect()).collect()
+        };
+        let apply_matrix = |m: &[Vec<Complex>], v: &[Complex]| -> Vec<Complex> {
+            m.iter().map(|row| row.iter().zip(v).fold(Complex::ZERO, |acc, (a, b)| acc + *a * *b)).collect()
+        };
+
+        // X on qubit 2, Z on qubit 0
+        let mut fast = state.clone();
+        apply_pauli_string(&mut fast, &[(2, PauliOp::X), (0, PauliOp::Z)]).unwrap();
+        let full = embed_gate(&kron(&pauli_x(), &pauli_z()), &[2, 0], 3).unwrap();
+        assert!(states_close(&fast, &apply_matrix(&full, &state), 1e-12));
+
+        // Identities are skipped; a repeated qubit applies Z₁·Y₁
+        let mut fast = state.clone();
+        apply_pauli_string(&mut fast, &[(1, PauliOp::Y), (0, PauliOp::I), (1, PauliOp::Z)]).unwrap();
+        let y = embed_gate(&kron(&pauli_y(), &crate::gates::core::identity()), &[1, 0], 3).unwrap();
+        let z = embed_gate(&kron(&pauli_z(), &crate::gates::core::identity()), &[1, 0], 3).unwrap();
+        let expected = apply_matrix(&z, &apply_matrix(&y, &state));
+        assert!(states_close(&fast, &expected, 1e-12));
+    }
+
+    #[test]
+    fn test_bell_state() {
+        let state = simulate(&[Gate::H(0), Gate::CX(0, 1)], 2).unwrap();
+        let p = probabilities(&state);
+
+        assert!((p[0] - 0.5).abs() < 1e-10);
+        assert!((p[3] - 0.5).abs() < 1e-10);
+        assert!(p[1].abs() < 1e-10 && p[2].abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_little_endian_cx() {
+        // X on qubit 1 then CX(1, 0) → |11⟩
+        let state = simulate(&[Gate::X(1), Gate::CX(1, 0)], 2).unwrap();
+        assert!((state[3].re - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_toffoli_decomposition_matches_ccx() {
+        for input in 0..8 {
+            let direct = simulate_from(&[Gate::CCX(0, 1, 2)], &basis_state(3, input)).unwrap();
+            let decomposed = simulate_from(&decompose_toffoli(0, 1, 2), &basis_state(3, input)).unwrap();
+            assert!(states_close(&direct, &decomposed, 1e-10), "Mismatch on input {}", input);
+        }
+    }
+
+    #[test]
+    fn test_embed_gate_conventions() {
+        let kron = |a: &[Vec<Complex>], b: &[Vec<Complex>]| -> Vec<Vec<Complex>> {
+            let n = b.len();
+            (0..a.len() * n)
+                .map(|r| (0..a.len() * n).map(|c| a[r / n][c / n] * b[r % n][c % n]).collect())
+                .collect()
+        };
+        let id = vec![vec![Complex::ONE, Complex::ZERO], vec![Complex::ZERO, Complex::ONE]];
+        let x = vec![vec![Complex::ZERO, Complex::ONE], vec![Complex::ONE, Complex::ZERO]];
+        let full = embed_gate(&x, &[1], 3).unwrap();
+        let expected = kron(&kron(&id, &x), &id);
+        assert!(full.iter().zip(&expected).all(|(a, b)| states_close(a, b, 1e-12)));
+
+        // Non-adjacent and reversed qubits agree with the simulator, column by column
+        let gates = [Gate::CX(2, 0), Gate::CRY(0, 2, 0.7), Gate::ISWAP(3, 1), Gate::CCX(3, 0, 2), Gate::CSWAP(1, 3, 0)];
+        for gate in gates {
+            let local: Vec<Vec<Complex>> = match gate.matrix_4x4() {
+                Some(m) => m.data.iter().map(|r| r.to_vec()).collect(),
+                None => gate.matrix_8x8().unwrap().data.iter().map(|r| r.to_vec()).collect(),
+            };
+            let full = embed_gate(&local, &gate.qubits(), 4).unwrap();
+            for j in 0..16 {
+                let column: Vec<Complex> = full.iter().map(|row| row[j]).collect();
+                let simulated = simulate_from(std::slice::from_ref(&gate), &basis_state(4, j)).unwrap();
+                assert!(states_close(&column, &simulated, 1e-12), "{:?} column {}", gate, j);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cswap() {
+        // Control set, |a=1, b=0⟩ → |a=0, b=1⟩
+        let state = simulate_from(&[Gate::CSWAP(0, 1, 2)], &basis_state(3, 0b011)).unwrap();
+        assert!((state[0b101].re - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_ancillas_restored() {
+        use crate::gates::decomposition::decompose_mcx_v_chain;
+        // Controls 0..4 set, target 4, ancillas 5 and 6 computed and uncomputed
+        let mut circuit: Vec<Gate> = (0..4).map(Gate::X).collect();
+        circuit.extend(decompose_mcx_v_chain(&[0, 1, 2, 3], 4, &[5, 6]));
+        assert!(ancillas_restored(&circuit, &[5, 6], 7));
+        assert!(!ancillas_restored(&circuit[..6], &[5, 6], 7));
+
+        // Entangled with the data: ancilla reads 0 only half the time
+        let entangled = [Gate::H(0), Gate::CX(0, 1)];
+        assert!(!ancillas_restored(&entangled, &[1], 2));
+        assert!(ancillas_restored(&entangled, &[], 2));
+    }
+
+    #[test]
+    fn test_simulate_postselected_follows_one_branch() {
+        // Teleport-style feed-forward: measure qubit 0 of a Bell pair and undo
+        // the flip on qubit 1, so qubit 1 ends in |0⟩ on either outcome
+        let bell = simulate(&[Gate::H(0), Gate::CX(0, 1)], 2).unwrap();
+        let circuit = [Gate::Measure(0, 0), Gate::ClassicalControl(0, Box::new(Gate::X(1)))];
+        for outcome in [false, true] {
+            let (state, probability) = simulate_postselected(&circuit, &bell, &[outcome]).unwrap();
+            assert!((probability - 0.5).abs() < 1e-12);
+            assert!(states_close(&state, &basis_state(2, outcome as usize), 1e-12), "Outcome {}", outcome);
+        }
+
+        // An impossible record has probability 0
+        let (state, probability) = simulate_postselected(&[Gate::Measure(0, 0)], &zero_state(1), &[true]).unwrap();
+        assert_eq!(probability, 0.0);
+        assert!(state.iter().all(|a| a.norm_sq() == 0.0));
+
+        assert_eq!(
+            simulate_postselected(&circuit, &bell, &[]),
+            Err(RustiqError::DimensionMismatch { expected: 1, found: 0 })
+        );
+        assert!(matches!(simulate_postselected(&[Gate::Reset(0)], &bell, &[]), Err(RustiqError::UnsupportedGate { .. })));
+    }
+
+    #[test]
+    fn test_bad_inputs_are_errors() {
+        let plus = simulate(&[Gate::H(0)], 2).unwrap();
+
+        // Rejected Pauli strings leave the state as it was
+        let mut state = plus.clone();
+        let err = apply_pauli_string(&mut state, &[(0, PauliOp::X), (2, PauliOp::Z)]);
+        assert_eq!(err, Err(RustiqError::QubitOutOfRange { qubit: 2, num_qubits: 2 }));
+        assert_eq!(state, plus);
+        assert!(apply_pauli_string(&mut state[..3], &[]).is_err());
+
+        let x: Vec<Vec<Complex>> = crate::gates::core::pauli_x().data.iter().map(|r| r.to_vec()).collect();
+        assert_eq!(embed_gate(&x, &[0, 1], 2), Err(RustiqError::DimensionMismatch { expected: 4, found: 2 }));
+        assert_eq!(embed_gate(&x, &[3], 2), Err(RustiqError::QubitOutOfRange { qubit: 3, num_qubits: 2 }));
+
+        assert_eq!(simulate(&[Gate::CX(0, 2)], 2), Err(RustiqError::QubitOutOfRange { qubit: 2, num_qubits: 2 }));
+        assert!(matches!(simulate(&[Gate::CX(1, 1)], 2), Err(RustiqError::InvalidCircuit(_))));
+        assert_eq!(
+            simulate(&[Gate::H(0), Gate::Measure(0, 0)], 1),
+            Err(RustiqError::UnsupportedGate { gate: Gate::Measure(0, 0).kind().name().to_string() })
+        );
+        assert!(matches!(simulate_from(&[], &plus[..3]), Err(RustiqError::DimensionMismatch { found: 3, .. })));
+
+        // A rejected gate leaves the state as it was
+        assert_eq!(apply_gate(&mut state, &Gate::CZ(0, 5)), Err(RustiqError::QubitOutOfRange { qubit: 5, num_qubits: 2 }));
+        assert!(apply_gate(&mut state, &Gate::Reset(0)).is_err());
+        assert_eq!(state, plus);
+    }
+}
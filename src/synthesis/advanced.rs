@@ -1,8 +1,14 @@
 //! Advanced Quantum Synthesis Methods
 //!
 //! This module provides advanced synthesis algorithms:
-//! - Quantum Shannon Decomposition
-//! - Amplitude encoding
+//! - Quantum Shannon Decomposition (`quantum_shannon_decompose`) of any unitary
+//! - Amplitude encoding and `prepare_state` for any input length
+//! - Uniformly controlled RY (`uniformly_controlled_ry`) and diagonal phase
+//!   functions (`diagonal_unitary`)
+//! - `_with_budget` variants that fail with `BudgetExceeded` instead of
+//!   emitting more two-qubit gates than the hardware allows
+//! - `quantum_shannon_decompose_with_stats`, a recursion-depth cap that
+//!   reports depth, gate count and achieved error
 //! - State preparation (Grover-Rudolph, Dicke states)
 //! - Linear reversible synthesis
 //! - Boolean function synthesis
@@ -20,6 +26,16 @@
 //!   the number of CNOT gates.
 //! - **Amplitude Encoding**: Implements an efficient tree-based synthesis to 
 //!   encode $2^n$ classical data points into the amplitudes of an $n$-qubit state.
+//! - **State Preparation**: `prepare_state` zero-pads the data to the next
+//!   power of two, normalizes it and loads one qubit per level of the RY
+//!   tree with a uniformly controlled rotation; signs are folded into the
+//!   last level of the tree.
+//! - **Multiplexed Rotations**: `uniformly_controlled_ry` applies a different
+//!   RY angle for every control pattern with 2ᵏ CX along a Gray code.
+//! - **Diagonal Unitaries**: `diagonal_unitary` peels one qubit at a time off
+//!   diag(e^{iφᵢ}) with RZ multiplexers, 2ⁿ − 1 rotations in total.
+//! - **Budgets**: QSD emits exactly (3/4)·4ⁿ − (3/2)·2ⁿ CX, so its budget is
+//!   checked before any matrix is factored.
 //! - **Dicke/W-State Preparation**: Uses specialized gate patterns to prepare 
 //!   high-entanglement states with specific Hamming weights in their basis states.
 //! - **Boolean Synthesis**: Converts classical reversible circuits (ESOP/EXOR forms) 
@@ -38,68 +54,392 @@
 //! - **Numerical Precision**: Highly sensitive to the precision of input matrices; 
 //!   orthonormality is checked before decomposition begins.
 
-use crate::gates::core::{Gate, Complex, GateMatrix2x2};
-use crate::gates::decomposition::euler_decompose_zyz;
+use crate::error::{check_dimension, RustiqError};
+use crate::gates::core::{Gate, Complex, GateMatrix2x2, DEFAULT_TOL};
+use crate::gates::decomposition::{
+    check_budget, control_gate, euler_decompose_zyz, euler_to_gates_zyz, BudgetExceeded,
+    SynthesisError, SynthesisStats,
+};
 
 // ============================================================================
 // QUANTUM SHANNON DECOMPOSITION
 // ============================================================================
 
-/// Quantum Shannon Decomposition for arbitrary n-qubit unitary
-/// 
-/// Recursively decomposes a 2^n × 2^n unitary into single-qubit and CNOT gates.
-/// Uses the formula:
-/// U = (V₀ ⊕ V₁) · (I ⊗ D) · (W₀ ⊕ W₁)
-/// 
-/// where V₀, V₁, W₀, W₁ are (n-1)-qubit unitaries and D is diagonal.
+/// Quantum Shannon Decomposition of `u` acting on `qubits`
+///
+/// Bit j of a row or column index of `u` is `qubits[j]`, so `qubits = [0, 1, …]`
+/// reproduces `circuit_unitary` exactly, global phase included. Each level
+/// splits on the top qubit with a cosine-sine decomposition
+///
+/// U = (L₀ ⊕ L₁) · [[C, −S], [S, C]] · (R₀ ⊕ R₁)
+///
+/// where the middle factor is a multiplexed RY, and each block-diagonal factor
+/// becomes (I ⊗ V)(D ⊕ D†)(I ⊗ W): two (n−1)-qubit unitaries around a
+/// multiplexed RZ. Recursion stops at single qubits (ZYZ). Output is RY, RZ,
+/// CX and `GlobalPhase`, with O(4ⁿ) gates.
+pub fn quantum_shannon_decompose(u: &[Vec<Complex>], qubits: &[usize]) -> Vec<Gate> {
+    let dim = 1usize << qubits.len();
+    assert!(u.len() == dim && u.iter().all(|row| row.len() == dim),
+        "Unitary on {} qubits must be {}×{}", qubits.len(), dim, dim);
+    let product = mat_mul(&adjoint(u), u);
+    let unitary = (0..dim).all(|i| (0..dim).all(|j| {
+        let expected = if i == j { 1.0 } else { 0.0 };
+        (product[i][j] - Complex::new(expected, 0.0)).norm() < 1e-8
+    }));
+    assert!(unitary, "Matrix is not unitary");
+
+    let mut gates = Vec::new();
+    shannon_recursive(u, qubits, &mut gates);
+    gates
+}
+
+/// [`quantum_shannon_decompose`] refusing to emit more than `max_two_qubit` CX
+///
+/// The CX count depends only on the number of qubits, so an over-budget
+/// request returns before any decomposition work is done.
+pub fn quantum_shannon_decompose_with_budget(
+    u: &[Vec<Complex>],
+    qubits: &[usize],
+    max_two_qubit: Option<usize>,
+) -> Result<Vec<Gate>, BudgetExceeded> {
+    check_budget(shannon_cx_count(qubits.len()), max_two_qubit)?;
+    Ok(quantum_shannon_decompose(u, qubits))
+}
+
+/// [`quantum_shannon_decompose`] with its recursion capped at `max_depth` levels
+///
+/// Recursion on n qubits goes n − 1 levels deep, so a cap below that fails
+/// with `DepthExceeded` before any work. The reported error is the largest
+/// entry of |U − circuit_unitary(gates)|.
+pub fn quantum_shannon_decompose_with_stats(
+    u: &[Vec<Complex>],
+    qubits: &[usize],
+    max_depth: usize,
+) -> Result<(Vec<Gate>, SynthesisStats), SynthesisError> {
+    let required = qubits.len().saturating_sub(1);
+    if required > max_depth {
+        return Err(SynthesisError::DepthExceeded { required, max_depth });
+    }
+    let gates = quantum_shannon_decompose(u, qubits);
+
+    let mut local = vec![0; qubits.iter().max().map_or(0, |&q| q + 1)];
+    for (j, &q) in qubits.iter().enumerate() {
+        local[q] = j;
+    }
+    let relabelled: Vec<Gate> = gates.iter().map(|g| g.map_qubits(|q| local[q])).collect();
+    let achieved = crate::analysis::circuit_analysis::circuit_unitary(&relabelled, qubits.len());
+    let error = u
+        .iter()
+        .zip(&achieved)
+        .flat_map(|(a, b)| a.iter().zip(b).map(|(x, y)| (*x - *y).norm()))
+        .fold(0.0, f64::max);
+    let stats = SynthesisStats { depth: required, gates: gates.len(), error };
+    Ok((gates, stats))
+}
+
+/// CX emitted by `quantum_shannon_decompose` on n qubits
+///
+/// T(n) = 4·T(n−1) + 3·2ⁿ⁻¹ with T(1) = 0: two demultiplexers of two
+/// recursive calls each, plus three multiplexed rotations with n−1 controls.
+fn shannon_cx_count(n: usize) -> usize {
+    if n < 2 { 0 } else { 3 * (1usize << (2 * n)) / 4 - 3 * (1usize << n) / 2 }
+}
+
+/// [`quantum_shannon_decompose`] on qubits 0..num_qubits
 pub fn shannon_decompose(num_qubits: usize, matrix: &[Vec<Complex>]) -> Vec<Gate> {
-    if num_qubits == 1 {
-        // Base case: single-qubit decomposition
-        let gate_matrix = GateMatrix2x2 {
-            data: [
-                [matrix[0][0], matrix[0][1]],
-                [matrix[1][0], matrix[1][1]],
-            ],
-        };
-        let euler = euler_decompose_zyz(&gate_matrix);
-        return crate::gates::decomposition::euler_to_gates_zyz(&euler, 0);
+    let qubits: Vec<usize> = (0..num_qubits).collect();
+    quantum_shannon_decompose(matrix, &qubits)
+}
+
+fn shannon_recursive(u: &[Vec<Complex>], qubits: &[usize], gates: &mut Vec<Gate>) {
+    let n = qubits.len();
+    if n == 0 {
+        // A 1×1 unitary is a bare phase
+        let phase = u[0][0].im.atan2(u[0][0].re);
+        if phase.abs() > 1e-12 {
+            gates.push(Gate::GlobalPhase(phase));
+        }
+        return;
     }
-    
-    let dim = 1usize << num_qubits;
-    let half = dim / 2;
-    
+    if n == 1 {
+        let matrix = GateMatrix2x2 { data: [[u[0][0], u[0][1]], [u[1][0], u[1][1]]] };
+        gates.extend(euler_to_gates_zyz(&euler_decompose_zyz(&matrix), qubits[0]));
+        return;
+    }
+
+    let (top, rest) = (qubits[n - 1], &qubits[..n - 1]);
+    let (l0, l1, angles, r0, r1) = cosine_sine(u);
+    demultiplex(&r0, &r1, top, rest, gates);
+    multiplexed_rotation(Axis::Y, &angles, top, rest, gates);
+    demultiplex(&l0, &l1, top, rest, gates);
+}
+
+type CsParts = (Matrix, Matrix, Vec<f64>, Matrix, Matrix);
+
+/// Cosine-sine decomposition U = (L₀ ⊕ L₁) · CS(θ) · (R₀ ⊕ R₁)
+///
+/// CS(θ) has blocks cos(θᵢ/2) and ±sin(θᵢ/2), i.e. RY(θᵢ) on the top qubit
+/// when the lower qubits are in state i. Columns of L₀ or L₁ that the data
+/// leaves undetermined (a vanishing cosine or sine) are completed to an
+/// orthonormal basis.
+fn cosine_sine(u: &[Vec<Complex>]) -> CsParts {
+    let half = u.len() / 2;
+    let block = |r: usize, c: usize| -> Matrix {
+        (0..half).map(|i| u[r + i][c..c + half].to_vec()).collect()
+    };
+    let (u00, u01, u10, u11) = (block(0, 0), block(0, half), block(half, 0), block(half, half));
+
+    // U₀₀†U₀₀ = R₀† C² R₀
+    let (_, r0_dag) = hermitian_eigen(&mat_mul(&adjoint(&u00), &u00));
+    let a = mat_mul(&u00, &r0_dag);
+    let b = mat_mul(&u10, &r0_dag);
+    let column = |m: &Matrix, j: usize| -> Vec<Complex> { m.iter().map(|row| row[j]).collect() };
+    let cos: Vec<f64> = (0..half).map(|j| vector_norm(&column(&a, j))).collect();
+    let sin: Vec<f64> = (0..half).map(|j| vector_norm(&column(&b, j))).collect();
+    let l0 = orthonormal_columns((0..half).map(|j| column(&a, j)).collect(), &cos);
+    let l1 = orthonormal_columns((0..half).map(|j| column(&b, j)).collect(), &sin);
+
+    // Row i of R₁ from U₁₁ = L₁ C R₁ or U₀₁ = −L₀ S R₁, whichever divides by more
+    let l0_u01 = mat_mul(&adjoint(&l0), &u01);
+    let l1_u11 = mat_mul(&adjoint(&l1), &u11);
+    let r1: Matrix = (0..half).map(|i| {
+        if cos[i] >= sin[i] {
+            l1_u11[i].iter().map(|&z| z * (1.0 / cos[i])).collect()
+        } else {
+            l0_u01[i].iter().map(|&z| z * (-1.0 / sin[i])).collect()
+        }
+    }).collect();
+
+    let angles = (0..half).map(|i| 2.0 * sin[i].atan2(cos[i])).collect();
+    (l0, l1, angles, adjoint(&r0_dag), r1)
+}
+
+/// A₀ ⊕ A₁ = (I ⊗ V)(D ⊕ D†)(I ⊗ W) with A₀A₁† = V D² V†, emitted W first
+fn demultiplex(a0: &[Vec<Complex>], a1: &[Vec<Complex>], top: usize, rest: &[usize], gates: &mut Vec<Gate>) {
+    let (eigenvalues, v) = unitary_eigen(&mat_mul(a0, &adjoint(a1)));
+    let d: Vec<Complex> = eigenvalues.iter().map(|z| Complex::from_polar(1.0, z.im.atan2(z.re) / 2.0)).collect();
+    let v_dag_a1 = mat_mul(&adjoint(&v), a1);
+    let w: Matrix = v_dag_a1.iter().zip(&d).map(|(row, &di)| row.iter().map(|&z| di * z).collect()).collect();
+
+    shannon_recursive(&w, rest, gates);
+    // diag(dᵢ, d̄ᵢ) = RZ(−2 arg dᵢ)
+    let angles: Vec<f64> = d.iter().map(|z| -2.0 * z.im.atan2(z.re)).collect();
+    multiplexed_rotation(Axis::Z, &angles, top, rest, gates);
+    shannon_recursive(&v, rest, gates);
+}
+
+/// RY(`angles[i]`) on `target` when `controls` hold i (bit j is `controls[j]`)
+///
+/// A uniformly controlled rotation, or RY multiplexer: the 2ᵏ controlled
+/// rotations of a naive construction collapse into 2ᵏ RY and 2ᵏ CX for k
+/// controls, with no ancilla. Rotations whose angle vanishes are omitted.
+/// Any number of angles other than 2ᵏ is a `DimensionMismatch`.
+pub fn uniformly_controlled_ry(controls: &[usize], target: usize, angles: &[f64]) -> Result<Vec<Gate>, RustiqError> {
+    check_dimension(1 << controls.len(), angles.len())?;
     let mut gates = Vec::new();
-    
-    // Extract block structure
-    let _u00 = extract_block(matrix, 0, 0, half);
-    let _u01 = extract_block(matrix, 0, half, half);
-    let _u10 = extract_block(matrix, half, 0, half);
-    let _u11 = extract_block(matrix, half, half, half);
-    
-    // Compute CS decomposition: U = (L₀ ⊕ L₁) · Σ · (R₀ ⊕ R₁)
-    // where Σ contains the cosine-sine structure
-    
-    // Simplified: use uniform controlled rotation approach
-    // Apply multiplexed rotations on the first qubit
-    
-    // This is a simplified version - full implementation needs
-    // proper cosine-sine decomposition
-    
-    for q in 0..num_qubits {
-        gates.push(Gate::H(q));
+    multiplexed_rotation(Axis::Y, angles, target, controls, &mut gates);
+    Ok(gates)
+}
+
+/// diag(e^{iφ₀}, …, e^{iφ_{2ⁿ−1}}) on `qubits`, with bit j of the index on `qubits[j]`
+///
+/// Each pair of phases differing in bit 0 is diag(e^{iφ₂ₘ}, e^{iφ₂ₘ₊₁}) =
+/// e^{i(φ₂ₘ + φ₂ₘ₊₁)/2} · RZ(φ₂ₘ₊₁ − φ₂ₘ), so an RZ multiplexer on `qubits[0]`
+/// leaves a diagonal of the pair means on the remaining qubits. Recursing
+/// down to a `GlobalPhase` gives 2ⁿ − 1 RZ and 2ⁿ − 2 CX, exact including
+/// the global phase. Any phase function f(i) of a QAOA cost or an oracle
+/// is `diagonal_unitary(&f, qubits)`. Any number of phases other than 2ⁿ
+/// is a `DimensionMismatch`.
+pub fn diagonal_unitary(phases: &[f64], qubits: &[usize]) -> Result<Vec<Gate>, RustiqError> {
+    check_dimension(1 << qubits.len(), phases.len())?;
+    let mut gates = Vec::new();
+    let mut phases = phases.to_vec();
+    for (j, &target) in qubits.iter().enumerate() {
+        let angles: Vec<f64> = phases.chunks(2).map(|pair| pair[1] - pair[0]).collect();
+        if angles.iter().any(|theta| theta.abs() > 1e-12) {
+            multiplexed_rotation(Axis::Z, &angles, target, &qubits[j + 1..], &mut gates);
+        }
+        phases = phases.chunks(2).map(|pair| (pair[0] + pair[1]) / 2.0).collect();
     }
-    
-    gates
+    if phases[0].abs() > 1e-12 {
+        gates.push(Gate::GlobalPhase(phases[0]));
+    }
+    Ok(gates)
 }
 
-fn extract_block(matrix: &[Vec<Complex>], row_start: usize, col_start: usize, size: usize) -> Vec<Vec<Complex>> {
-    let mut block = vec![vec![Complex::ZERO; size]; size];
+enum Axis {
+    Y,
+    Z,
+}
+
+/// RY or RZ by `angles[i]` on `target` when `controls` hold i (bit j is `controls[j]`)
+///
+/// Möttönen et al.: 2ᵏ rotations interleaved with 2ᵏ CX along a Gray code,
+/// with angles from the Walsh–Hadamard transform of `angles`.
+fn multiplexed_rotation(axis: Axis, angles: &[f64], target: usize, controls: &[usize], gates: &mut Vec<Gate>) {
+    let k = controls.len();
+    let size = 1usize << k;
+    let gray = |i: usize| i ^ (i >> 1);
     for i in 0..size {
-        for j in 0..size {
-            block[i][j] = matrix[row_start + i][col_start + j];
+        let theta = (0..size)
+            .map(|j| if (j & gray(i)).count_ones().is_multiple_of(2) { angles[j] } else { -angles[j] })
+            .sum::<f64>() / size as f64;
+        if theta.abs() > 1e-12 {
+            gates.push(match axis {
+                Axis::Y => Gate::RY(target, theta),
+                Axis::Z => Gate::RZ(target, theta),
+            });
+        }
+        if k > 0 {
+            let flip = (gray(i) ^ gray((i + 1) % size)).trailing_zeros() as usize;
+            gates.push(Gate::CX(controls[flip], target));
         }
     }
-    block
+}
+
+// ============================================================================
+// DENSE COMPLEX LINEAR ALGEBRA
+// ============================================================================
+
+type Matrix = Vec<Vec<Complex>>;
+
+fn mat_mul(a: &[Vec<Complex>], b: &[Vec<Complex>]) -> Matrix {
+    let (n, m) = (b.len(), b[0].len());
+    a.iter().map(|row| {
+        (0..m).map(|j| (0..n).fold(Complex::ZERO, |acc, k| acc + row[k] * b[k][j])).collect()
+    }).collect()
+}
+
+fn adjoint(a: &[Vec<Complex>]) -> Matrix {
+    (0..a[0].len()).map(|j| a.iter().map(|row| row[j].conj()).collect()).collect()
+}
+
+fn vector_norm(v: &[Complex]) -> f64 {
+    v.iter().map(|z| z.norm_sq()).sum::<f64>().sqrt()
+}
+
+/// Matrix whose columns orthonormalize `columns`, strongest `weights` first
+///
+/// Columns of weight ≤ 1e-9 carry no usable direction and are replaced by
+/// the basis vector with the largest component outside the span so far.
+fn orthonormal_columns(columns: Vec<Vec<Complex>>, weights: &[f64]) -> Matrix {
+    let dim = columns.len();
+    let mut order: Vec<usize> = (0..dim).collect();
+    order.sort_by(|&i, &j| weights[j].total_cmp(&weights[i]));
+
+    let mut basis: Vec<Vec<Complex>> = Vec::with_capacity(dim);
+    let mut result = vec![Vec::new(); dim];
+    let project_out = |v: &mut Vec<Complex>, basis: &[Vec<Complex>]| {
+        for e in basis {
+            let overlap = e.iter().zip(v.iter()).fold(Complex::ZERO, |acc, (x, y)| acc + x.conj() * *y);
+            for (vi, ei) in v.iter_mut().zip(e) {
+                *vi = *vi - overlap * *ei;
+            }
+        }
+    };
+    for &j in &order {
+        let mut v = if weights[j] > 1e-9 {
+            columns[j].clone()
+        } else {
+            (0..dim).map(|k| {
+                let mut e = vec![Complex::ZERO; dim];
+                e[k] = Complex::ONE;
+                project_out(&mut e, &basis);
+                e
+            }).max_by(|x, y| vector_norm(x).total_cmp(&vector_norm(y))).unwrap()
+        };
+        // Twice is enough for numerical orthogonality
+        project_out(&mut v, &basis);
+        project_out(&mut v, &basis);
+        let norm = vector_norm(&v);
+        let v: Vec<Complex> = v.iter().map(|&z| z * (1.0 / norm)).collect();
+        basis.push(v.clone());
+        result[j] = v;
+    }
+    (0..dim).map(|i| (0..dim).map(|j| result[j][i]).collect()).collect()
+}
+
+/// Eigenvalues and eigenvector columns of a Hermitian matrix (cyclic Jacobi)
+pub(crate) fn hermitian_eigen(h: &[Vec<Complex>]) -> (Vec<f64>, Matrix) {
+    let n = h.len();
+    let mut a = h.to_vec();
+    let mut v: Matrix = (0..n).map(|i| (0..n).map(|j| if i == j { Complex::ONE } else { Complex::ZERO }).collect()).collect();
+    let scale = a.iter().flatten().map(|z| z.norm_sq()).sum::<f64>().sqrt().max(1e-300);
+
+    for _sweep in 0..100 {
+        let off = (0..n).flat_map(|p| (0..n).filter(move |&q| q != p).map(move |q| (p, q)))
+            .map(|(p, q)| a[p][q].norm_sq()).sum::<f64>().sqrt();
+        if off <= 1e-15 * scale {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                let magnitude = a[p][q].norm();
+                if magnitude <= 1e-300 {
+                    continue;
+                }
+                // Phase a_pq to a real entry, then a real Jacobi rotation
+                let w = a[p][q].conj() * (1.0 / magnitude);
+                let tau = (a[q][q].re - a[p][p].re) / (2.0 * magnitude);
+                let t = if tau >= 0.0 { 1.0 } else { -1.0 } / (tau.abs() + (tau * tau + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                let g = [[Complex::new(c, 0.0), Complex::new(s, 0.0)], [w * -s, w * c]];
+
+                for row in a.iter_mut().chain(v.iter_mut()) {
+                    let (x, y) = (row[p], row[q]);
+                    row[p] = x * g[0][0] + y * g[1][0];
+                    row[q] = x * g[0][1] + y * g[1][1];
+                }
+                #[allow(clippy::needless_range_loop)]
+                for k in 0..n {
+                    let (x, y) = (a[p][k], a[q][k]);
+                    a[p][k] = g[0][0].conj() * x + g[1][0].conj() * y;
+                    a[q][k] = g[0][1].conj() * x + g[1][1].conj() * y;
+                }
+            }
+        }
+    }
+    ((0..n).map(|i| a[i][i].re).collect(), v)
+}
+
+/// Eigenvalues and eigenvector columns of a unitary (hence normal) matrix
+///
+/// The Hermitian and anti-Hermitian parts commute, so eigenvectors of a
+/// generic real combination of them diagonalize the matrix; clusters of equal
+/// eigenvalues are then split by the anti-Hermitian part alone.
+fn unitary_eigen(m: &[Vec<Complex>]) -> (Vec<Complex>, Matrix) {
+    let n = m.len();
+    let m_dag = adjoint(m);
+    let re_part: Matrix = (0..n).map(|i| (0..n).map(|j| (m[i][j] + m_dag[i][j]) * 0.5).collect()).collect();
+    let im_part: Matrix = (0..n).map(|i| (0..n).map(|j| (m[i][j] - m_dag[i][j]) * Complex::new(0.0, -0.5)).collect()).collect();
+    let mixed: Matrix = (0..n).map(|i| (0..n).map(|j| re_part[i][j] + im_part[i][j] * 0.618_033_988_749_895).collect()).collect();
+    let (values, mut v) = hermitian_eigen(&mixed);
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| values[i].total_cmp(&values[j]));
+    let mut start = 0;
+    while start < n {
+        let mut end = start + 1;
+        while end < n && values[order[end]] - values[order[end - 1]] < 1e-6 {
+            end += 1;
+        }
+        if end - start > 1 {
+            let cluster = &order[start..end];
+            let basis: Matrix = (0..n).map(|i| cluster.iter().map(|&j| v[i][j]).collect()).collect();
+            let (_, rotation) = hermitian_eigen(&mat_mul(&mat_mul(&adjoint(&basis), &im_part), &basis));
+            let rotated = mat_mul(&basis, &rotation);
+            for (c, &j) in cluster.iter().enumerate() {
+                for i in 0..n {
+                    v[i][j] = rotated[i][c];
+                }
+            }
+        }
+        start = end;
+    }
+
+    let diagonal = mat_mul(&mat_mul(&adjoint(&v), m), &v);
+    ((0..n).map(|i| diagonal[i][i]).collect(), v)
 }
 
 /// Two-level unitary decomposition
@@ -132,7 +472,7 @@ pub fn two_level_decompose(
     
     // Apply the rotation on target qubit
     let euler = euler_decompose_zyz(rotation);
-    gates.extend(crate::gates::decomposition::euler_to_gates_zyz(&euler, target));
+    gates.extend(euler_to_gates_zyz(&euler, target));
     
     // Undo CNOT cascade
     for k in (0..gray_path.len() - 1).rev() {
@@ -172,6 +512,14 @@ fn construct_gray_path(from: usize, to: usize, _n: usize) -> Vec<usize> {
 /// 
 /// Uses the method of Shende, Bullock, Markov for efficient state preparation.
 pub fn amplitude_encoding(data: &[f64]) -> Vec<Gate> {
+    amplitude_encoding_with_tol(data, DEFAULT_TOL)
+}
+
+/// [`amplitude_encoding`] treating sub-blocks of norm ≤ `tol` as empty
+///
+/// `tol` is relative to the normalized data vector: a half of the remaining
+/// amplitudes whose norm is at or below it gets no rotations at all.
+pub fn amplitude_encoding_with_tol(data: &[f64], tol: f64) -> Vec<Gate> {
     let n = data.len();
     assert!(n.is_power_of_two(), "Data length must be power of 2");
     
@@ -182,10 +530,10 @@ pub fn amplitude_encoding(data: &[f64]) -> Vec<Gate> {
     let normalized: Vec<f64> = data.iter().map(|x| x / norm).collect();
     
     // Use recursive Schmidt decomposition approach
-    amplitude_encoding_recursive(&normalized, 0, num_qubits)
+    amplitude_encoding_recursive(&normalized, 0, num_qubits, tol)
 }
 
-fn amplitude_encoding_recursive(amplitudes: &[f64], start_qubit: usize, num_qubits: usize) -> Vec<Gate> {
+fn amplitude_encoding_recursive(amplitudes: &[f64], start_qubit: usize, num_qubits: usize, tol: f64) -> Vec<Gate> {
     if num_qubits == 0 || amplitudes.len() <= 1 {
         return Vec::new();
     }
@@ -198,7 +546,7 @@ fn amplitude_encoding_recursive(amplitudes: &[f64], start_qubit: usize, num_qubi
     let right_norm: f64 = amplitudes[half..].iter().map(|x| x * x).sum::<f64>().sqrt();
     let total_norm = (left_norm * left_norm + right_norm * right_norm).sqrt();
     
-    if total_norm > 1e-10 {
+    if total_norm > tol {
         // RY rotation to create the split
         let theta = 2.0 * (right_norm / total_norm).acos();
         gates.push(Gate::RY(start_qubit, theta));
@@ -207,22 +555,22 @@ fn amplitude_encoding_recursive(amplitudes: &[f64], start_qubit: usize, num_qubi
     // Recursively encode each half
     if half > 1 {
         // Left subtree (when MSB is 0)
-        let left_amplitudes: Vec<f64> = if left_norm > 1e-10 {
+        let left_amplitudes: Vec<f64> = if left_norm > tol {
             amplitudes[..half].iter().map(|x| x / left_norm).collect()
         } else {
             vec![0.0; half]
         };
         
         // Right subtree (when MSB is 1)
-        let right_amplitudes: Vec<f64> = if right_norm > 1e-10 {
+        let right_amplitudes: Vec<f64> = if right_norm > tol {
             amplitudes[half..].iter().map(|x| x / right_norm).collect()
         } else {
             vec![0.0; half]
         };
         
         // Apply controlled rotations for subtrees
-        let left_gates = amplitude_encoding_recursive(&left_amplitudes, start_qubit + 1, num_qubits - 1);
-        let right_gates = amplitude_encoding_recursive(&right_amplitudes, start_qubit + 1, num_qubits - 1);
+        let left_gates = amplitude_encoding_recursive(&left_amplitudes, start_qubit + 1, num_qubits - 1, tol);
+        let right_gates = amplitude_encoding_recursive(&right_amplitudes, start_qubit + 1, num_qubits - 1, tol);
         
         // Left: controlled on start_qubit being 0
         gates.push(Gate::X(start_qubit));
@@ -240,15 +588,102 @@ fn amplitude_encoding_recursive(amplitudes: &[f64], start_qubit: usize, num_qubi
     gates
 }
 
-fn control_gate(control: usize, gate: &Gate) -> Vec<Gate> {
-    match gate {
-        Gate::RY(t, theta) => vec![Gate::CRY(control, *t, *theta)],
-        Gate::RZ(t, theta) => vec![Gate::CRZ(control, *t, *theta)],
-        Gate::RX(t, theta) => vec![Gate::CRX(control, *t, *theta)],
-        _ => vec![gate.clone()], // Simplified for other gates
+/// Why [`prepare_state`] rejected its input
+#[derive(Clone, Debug, PartialEq)]
+pub enum StatePrepError {
+    /// No amplitudes were given
+    Empty,
+    /// Every amplitude is zero, so there is no direction to normalize
+    ZeroNorm,
+    /// The preparation circuit needs more multi-qubit gates than allowed
+    BudgetExceeded(BudgetExceeded),
+}
+
+impl std::fmt::Display for StatePrepError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatePrepError::Empty => write!(f, "Cannot prepare a state from no amplitudes"),
+            StatePrepError::ZeroNorm => write!(f, "All amplitudes are zero"),
+            StatePrepError::BudgetExceeded(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for StatePrepError {}
+
+impl From<BudgetExceeded> for StatePrepError {
+    fn from(err: BudgetExceeded) -> Self {
+        StatePrepError::BudgetExceeded(err)
     }
 }
 
+/// Circuit mapping |0…0⟩ to (1/∥x∥) Σᵢ xᵢ |i⟩ on qubits 0..⌈log₂ len⌉
+///
+/// Any length is accepted: the data is zero-padded to the next power of two,
+/// so the padding amplitudes are exactly zero. Index i is little-endian
+/// (qubit q is bit q). Real signs are exact, including the global sign.
+///
+/// Qubit q is rotated by one `uniformly_controlled_ry` on the qubits above
+/// it, splitting the weight of each block of 2^(q+1) amplitudes between its
+/// halves; the q = 0 level uses the signed pair 2·atan2(a₁, a₀). That is
+/// 2ⁿ − 2 CX in total, and levels whose angles all vanish are skipped.
+pub fn prepare_state(amplitudes: &[f64]) -> Result<Vec<Gate>, StatePrepError> {
+    if amplitudes.is_empty() {
+        return Err(StatePrepError::Empty);
+    }
+    let norm = amplitudes.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm == 0.0 {
+        return Err(StatePrepError::ZeroNorm);
+    }
+
+    let dim = amplitudes.len().next_power_of_two();
+    let mut padded: Vec<f64> = amplitudes.iter().map(|x| x / norm).collect();
+    padded.resize(dim, 0.0);
+
+    let n = dim.trailing_zeros() as usize;
+    let mut gates = Vec::new();
+    if n == 0 && padded[0] < 0.0 {
+        gates.push(Gate::GlobalPhase(std::f64::consts::PI));
+    }
+    let weight = |a: &[f64]| a.iter().map(|x| x * x).sum::<f64>().sqrt();
+    for q in (0..n).rev() {
+        let block = 2usize << q;
+        let angles: Vec<f64> = padded.chunks(block)
+            .map(|chunk| match chunk {
+                [a0, a1] => 2.0 * a1.atan2(*a0),
+                _ => 2.0 * weight(&chunk[block / 2..]).atan2(weight(&chunk[..block / 2])),
+            })
+            .collect();
+        if angles.iter().any(|theta| theta.abs() > 1e-12) {
+            let controls: Vec<usize> = (q + 1..n).collect();
+            multiplexed_rotation(Axis::Y, &angles, q, &controls, &mut gates);
+        }
+    }
+    Ok(gates)
+}
+
+/// [`prepare_state`] failing when the tree needs more than `max_two_qubit`
+/// multi-qubit gates
+///
+/// Each level with k controls costs 2ᵏ CX, so the n-qubit tree needs 2ⁿ − 2.
+/// That count depends only on the length, so an over-budget request returns
+/// before any synthesis; levels that `prepare_state` skips for a sparse
+/// state are not credited.
+pub fn prepare_state_with_budget(amplitudes: &[f64], max_two_qubit: Option<usize>) -> Result<Vec<Gate>, StatePrepError> {
+    if !amplitudes.is_empty() {
+        let n = amplitudes.len().next_power_of_two().trailing_zeros() as usize;
+        check_budget(state_prep_cx_count(n), max_two_qubit)?;
+    }
+    prepare_state(amplitudes)
+}
+
+/// CX emitted by the `prepare_state` tree on n qubits when no level is skipped
+///
+/// Σₖ 2ᵏ over k = 1..n−1 controls, i.e. 2ⁿ − 2 (0 for a single qubit).
+fn state_prep_cx_count(n: usize) -> usize {
+    (1usize << n).saturating_sub(2)
+}
+
 /// Angle encoding for quantum machine learning
 /// 
 /// Encodes classical data as rotation angles:
@@ -304,7 +739,7 @@ pub fn dicke_state(n: usize, k: usize) -> Vec<Gate> {
         return Vec::new(); // Already in |00...0⟩
     }
     if k == n {
-        return (0..n).map(|q| Gate::X(q)).collect(); // |11...1⟩
+        return (0..n).map(Gate::X).collect(); // |11...1⟩
     }
     
     let mut gates = Vec::new();
@@ -368,13 +803,7 @@ pub fn linear_reversible_synthesis(matrix: &[Vec<bool>]) -> Vec<Gate> {
     // Forward elimination (lower triangular)
     for col in 0..n {
         // Find pivot
-        let mut pivot = None;
-        for row in col..n {
-            if work[row][col] {
-                pivot = Some(row);
-                break;
-            }
-        }
+        let pivot = (col..n).find(|&row| work[row][col]);
         
         if let Some(pivot_row) = pivot {
             // Swap rows if needed
@@ -387,6 +816,7 @@ pub fn linear_reversible_synthesis(matrix: &[Vec<bool>]) -> Vec<Gate> {
             for row in col + 1..n {
                 if work[row][col] {
                     // Add row 'col' to row 'row' (XOR)
+                    #[allow(clippy::needless_range_loop)]
                     for c in 0..n {
                         work[row][c] ^= work[col][c];
                     }
@@ -400,6 +830,7 @@ pub fn linear_reversible_synthesis(matrix: &[Vec<bool>]) -> Vec<Gate> {
     for col in (1..n).rev() {
         for row in 0..col {
             if work[row][col] {
+                #[allow(clippy::needless_range_loop)]
                 for c in 0..n {
                     work[row][c] ^= work[col][c];
                 }
@@ -485,6 +916,91 @@ pub fn permutation_synthesis(permutation: &[usize]) -> Vec<Gate> {
 mod tests {
     use super::*;
 
+    /// Haar-like random unitary: orthonormalized uniform-noise columns
+    fn random_unitary(seed: u64, dim: usize) -> Matrix {
+        let mut rng = crate::util::rng::Rng::new(seed);
+        let columns: Vec<Vec<Complex>> = (0..dim)
+            .map(|_| (0..dim).map(|_| Complex::new(rng.uniform() - 0.5, rng.uniform() - 0.5)).collect())
+            .collect();
+        orthonormal_columns(columns, &vec![1.0; dim])
+    }
+
+    #[test]
+    fn test_quantum_shannon_decompose() {
+        use crate::analysis::circuit_analysis::circuit_unitary;
+        use crate::simulator::embed_gate;
+
+        let close = |a: &[Vec<Complex>], b: &[Vec<Complex>]| {
+            a.iter().flatten().zip(b.iter().flatten()).all(|(x, y)| (*x - *y).norm() < 1e-8)
+        };
+
+        for dim in [2, 4, 8] {
+            let u = random_unitary(7 + dim as u64, dim);
+            let qubits: Vec<usize> = (0..dim.trailing_zeros() as usize).collect();
+            let circuit = quantum_shannon_decompose(&u, &qubits);
+            assert!(close(&circuit_unitary(&circuit, qubits.len()), &u), "dim {}", dim);
+            assert!(circuit.iter().all(|g| matches!(g, Gate::RY(..) | Gate::RZ(..) | Gate::CX(..) | Gate::GlobalPhase(_))));
+        }
+
+        // Scattered qubits inside a wider register
+        let u = random_unitary(7, 8);
+        let circuit = quantum_shannon_decompose(&u, &[3, 0, 2]);
+        // embed_gate puts its first qubit in the most significant bit
+        assert!(close(&circuit_unitary(&circuit, 4), &embed_gate(&u, &[2, 0, 3], 4).unwrap()));
+
+        // Block-diagonal input leaves the cosine-sine factors degenerate
+        let toffoli: Matrix = crate::gates::core::toffoli().data.iter().map(|r| r.to_vec()).collect();
+        let circuit = shannon_decompose(3, &toffoli);
+        assert!(close(&circuit_unitary(&circuit, 3), &toffoli));
+    }
+
+    #[test]
+    fn test_two_qubit_budgets() {
+        use crate::gates::decomposition::{kak_decompose, kak_to_circuit_with_budget};
+
+        let u = random_unitary(11, 8);
+
+        // A random 3-qubit unitary needs 36 CX
+        let err = quantum_shannon_decompose_with_budget(&u, &[0, 1, 2], Some(5)).unwrap_err();
+        assert_eq!(err, BudgetExceeded { produced: 36, budget: 5 });
+        let circuit = quantum_shannon_decompose_with_budget(&u, &[0, 1, 2], Some(36)).unwrap();
+        assert_eq!(circuit.iter().filter(|g| matches!(g, Gate::CX(..))).count(), 36);
+        assert!(quantum_shannon_decompose_with_budget(&u, &[0, 1, 2], None).is_ok());
+        assert_eq!(shannon_cx_count(2), 6);
+
+        // A dense 3-qubit state needs 2³ − 2 = 6 CX, checked from the length alone
+        let data = [0.3, -0.1, -0.5, 0.2, 0.0, -0.7, 0.1, 0.4];
+        let cx = prepare_state(&data).unwrap().iter().filter(|g| matches!(g, Gate::CX(..))).count();
+        assert_eq!(cx, state_prep_cx_count(3));
+        assert_eq!(
+            prepare_state_with_budget(&data, Some(5)),
+            Err(StatePrepError::BudgetExceeded(BudgetExceeded { produced: 6, budget: 5 }))
+        );
+        assert!(prepare_state_with_budget(&data, Some(6)).is_ok());
+        assert_eq!(prepare_state_with_budget(&[], Some(0)), Err(StatePrepError::Empty));
+        assert!(prepare_state_with_budget(&[-1.0], Some(0)).is_ok());
+
+        // The CX count of a KAK circuit comes from its canonical coordinates
+        let mut decomp = kak_decompose(&crate::gates::core::swap());
+        (decomp.alpha, decomp.beta, decomp.gamma) = (0.4, 0.3, 0.2);
+        assert_eq!(kak_to_circuit_with_budget(&decomp, 0, 1, Some(2)), Err(BudgetExceeded { produced: 3, budget: 2 }));
+        assert!(kak_to_circuit_with_budget(&decomp, 0, 1, Some(3)).is_ok());
+    }
+
+    #[test]
+    fn test_shannon_depth_cap() {
+        let u = random_unitary(5, 8);
+
+        let (circuit, stats) = quantum_shannon_decompose_with_stats(&u, &[3, 0, 2], 2).unwrap();
+        assert_eq!(circuit, quantum_shannon_decompose(&u, &[3, 0, 2]));
+        assert_eq!((stats.depth, stats.gates), (2, circuit.len()));
+        assert!(stats.error < 1e-8);
+        assert_eq!(
+            quantum_shannon_decompose_with_stats(&u, &[0, 1, 2], 1),
+            Err(SynthesisError::DepthExceeded { required: 2, max_depth: 1 })
+        );
+    }
+
     #[test]
     fn test_amplitude_encoding() {
         let data = vec![0.5, 0.5, 0.5, 0.5]; // Uniform
@@ -492,6 +1008,90 @@ mod tests {
         assert!(!circuit.is_empty());
     }
 
+    #[test]
+    fn test_uniformly_controlled_ry_matches_naive_multiplexer() {
+        use crate::analysis::circuit_analysis::circuit_unitary;
+
+        // Controls 2 and 0 (bit 0 of the pattern is qubit 2), target 1
+        let (controls, target) = ([2, 0], 1);
+        let angles = [0.4, -1.1, 2.3, 0.7];
+        let fast = uniformly_controlled_ry(&controls, target, &angles).unwrap();
+        assert_eq!(fast.iter().filter(|g| matches!(g, Gate::CX(..))).count(), 4);
+        assert_eq!(fast.iter().filter(|g| matches!(g, Gate::RY(..))).count(), 4);
+
+        // Naive: for each pattern, flip the 0 controls and apply a doubly controlled RY
+        let mut naive = Vec::new();
+        for (i, &theta) in angles.iter().enumerate() {
+            let flips: Vec<Gate> = (0..2).filter(|j| i >> j & 1 == 0).map(|j| Gate::X(controls[j])).collect();
+            naive.extend(flips.iter().cloned());
+            naive.extend([
+                Gate::RY(target, theta / 2.0), Gate::CCX(controls[0], controls[1], target),
+                Gate::RY(target, -theta / 2.0), Gate::CCX(controls[0], controls[1], target),
+            ]);
+            naive.extend(flips);
+        }
+        let (a, b) = (circuit_unitary(&fast, 3), circuit_unitary(&naive, 3));
+        for (ra, rb) in a.iter().zip(&b) {
+            for (x, y) in ra.iter().zip(rb) {
+                assert!((*x - *y).norm() < 1e-12, "{:?} vs {:?}", x, y);
+            }
+        }
+
+        // prepare_state spends 2ⁿ − 2 CX on a dense state
+        let data = [0.3, -0.1, -0.5, 0.2, 0.1, -0.7, 0.1, 0.4];
+        let cx = prepare_state(&data).unwrap().iter().filter(|g| matches!(g, Gate::CX(..))).count();
+        assert_eq!(cx, 6);
+    }
+
+    #[test]
+    fn test_diagonal_unitary_reproduces_phases() {
+        use crate::analysis::circuit_analysis::circuit_unitary;
+
+        // Index bit 0 on qubit 1 and bit 1 on qubit 0
+        let phases = [0.3, -1.2, 2.0, 0.9];
+        let circuit = diagonal_unitary(&phases, &[1, 0]).unwrap();
+        assert_eq!(circuit.iter().filter(|g| matches!(g, Gate::RZ(..))).count(), 3);
+        assert_eq!(circuit.iter().filter(|g| matches!(g, Gate::CX(..))).count(), 2);
+
+        let u = circuit_unitary(&circuit, 2);
+        for (i, row) in u.iter().enumerate() {
+            // Bit 0 of the phase index is qubit 1, i.e. bit 1 of the basis index
+            let index = (i & 1) << 1 | i >> 1;
+            for (j, &z) in row.iter().enumerate() {
+                let want = if i == j { Complex::from_polar(1.0, phases[index]) } else { Complex::ZERO };
+                assert!((z - want).norm() < 1e-12, "[{}][{}]: {:?}", i, j, z);
+            }
+        }
+
+        // Constant phases are a single global phase
+        assert_eq!(diagonal_unitary(&[0.5; 8], &[0, 1, 2]).unwrap(), vec![Gate::GlobalPhase(0.5)]);
+        assert!(diagonal_unitary(&[0.0; 2], &[3]).unwrap().is_empty());
+        assert_eq!(diagonal_unitary(&[0.0; 3], &[0, 1]), Err(RustiqError::DimensionMismatch { expected: 4, found: 3 }));
+        assert!(uniformly_controlled_ry(&[1], 0, &[0.1]).is_err());
+    }
+
+    #[test]
+    fn test_prepare_state_pads_to_power_of_two() {
+        use crate::simulator::{simulate, states_close};
+
+        let state = simulate(&prepare_state(&[1.0, 2.0, 2.0]).unwrap(), 2).unwrap();
+        let expected = [1.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0, 0.0].map(|a| Complex::new(a, 0.0));
+        assert!(states_close(&state, &expected, 1e-12));
+
+        // Signs survive, in every position of a padded 3-qubit register
+        let data = [0.3, -0.1, -0.5, 0.2, 0.0, -0.7];
+        let norm = data.iter().map(|x| x * x).sum::<f64>().sqrt();
+        let state = simulate(&prepare_state(&data).unwrap(), 3).unwrap();
+        for (i, amp) in state.iter().enumerate() {
+            let want = data.get(i).map_or(0.0, |x| x / norm);
+            assert!((*amp - Complex::new(want, 0.0)).norm() < 1e-12, "Index {}", i);
+        }
+
+        assert_eq!(prepare_state(&[-2.0]).unwrap().len(), 1);
+        assert_eq!(prepare_state(&[]), Err(StatePrepError::Empty));
+        assert_eq!(prepare_state(&[0.0, 0.0, 0.0]), Err(StatePrepError::ZeroNorm));
+    }
+
     #[test]
     fn test_angle_encoding() {
         let data = vec![0.1, 0.2, 0.3];
@@ -0,0 +1,211 @@
+//! Subcircuit Cache
+//!
+//! This module memoizes synthesis results by the content of their inputs:
+//! - `CacheKey`: A builder name plus its canonicalized inputs
+//! - `SubcircuitCache`: Map from keys to the gate lists built for them
+//!
+//! ## 🎯 Why is this used?
+//! Large programs ask for the same expensive subcircuit over and over: the
+//! same amplitude vector loaded in every shot of a kernel estimate, the same
+//! unitary decomposed in every Trotter step. Synthesizing each copy again
+//! wastes the bulk of compile time on work that has already been done.
+//!
+//! ## ⚙️ How it works?
+//! - **Keys**: A key stores its input by value, not by identity. Floats are
+//!   kept as bit patterns with −0.0 folded onto 0.0, and circuits in their
+//!   `canonicalize` form, so commuting reorderings share an entry. The
+//!   builder name is part of the key, so `prepare_state` and
+//!   `grover_rudolph_prep` of one vector never share an entry.
+//! - **Lookup**: `get_or_build(key, build)` runs `build` only on a miss and
+//!   stores its result; every later hit returns the stored gates untouched.
+//!   The map hashes keys to find a bucket and then compares the stored
+//!   inputs in full, so distinct inputs never share an entry.
+//!
+//! ## 📍 Where to apply this?
+//! - **Repeated State Loading**: Wrap `prepare_state` calls inside loops.
+//! - **Block Reuse**: Share one cache across the passes of a compiler run.
+//!
+//! ## 📊 Code Behavior
+//! - **Complexity**: O(input) to build, hash and compare a key, O(1)
+//!   expected lookups per call.
+//! - **Memory**: Each entry keeps a copy of its input next to its gates.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use crate::gates::core::Gate;
+use crate::gates::circuit::canonicalize;
+
+// ============================================================================
+// CACHE KEYS
+// ============================================================================
+
+/// Builder name and canonical input identifying one builder call
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    builder: String,
+    input: CacheInput,
+}
+
+/// The input of a builder call in comparable form
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum CacheInput {
+    /// Bit patterns of a real vector, with −0.0 stored as 0.0
+    Values(Vec<u64>),
+    /// A circuit in `canonicalize` form
+    Circuit(Vec<Gate>),
+}
+
+impl CacheKey {
+    /// Key for a builder taking a real vector, e.g. `prepare_state(values)`
+    pub fn from_values(builder: &str, values: &[f64]) -> Self {
+        // −0.0 == 0.0 must compare alike
+        let bits = values.iter().map(|&v| if v == 0.0 { 0.0f64 } else { v }.to_bits()).collect();
+        CacheKey { builder: builder.to_string(), input: CacheInput::Values(bits) }
+    }
+
+    /// Key for a builder taking a circuit, stored in canonical form
+    pub fn from_circuit(builder: &str, circuit: &[Gate]) -> Self {
+        CacheKey { builder: builder.to_string(), input: CacheInput::Circuit(canonicalize(circuit)) }
+    }
+}
+
+// ============================================================================
+// CACHE
+// ============================================================================
+
+/// Memo table from builder inputs to synthesized gate lists
+#[derive(Clone, Debug, Default)]
+pub struct SubcircuitCache {
+    entries: HashMap<CacheKey, Vec<Gate>>,
+    hits: usize,
+    misses: usize,
+}
+
+impl SubcircuitCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stored gates for `key`, running `build` only if there are none yet
+    pub fn get_or_build<F: FnOnce() -> Vec<Gate>>(&mut self, key: CacheKey, build: F) -> &[Gate] {
+        match self.try_get_or_build(key, || Ok::<_, std::convert::Infallible>(build())) {
+            Ok(gates) => gates,
+            Err(never) => match never {},
+        }
+    }
+
+    /// `get_or_build` for fallible builders; an error is returned and not cached
+    pub fn try_get_or_build<E, F: FnOnce() -> Result<Vec<Gate>, E>>(
+        &mut self,
+        key: CacheKey,
+        build: F,
+    ) -> Result<&[Gate], E> {
+        match self.entries.entry(key) {
+            Entry::Occupied(entry) => {
+                self.hits += 1;
+                Ok(entry.into_mut())
+            }
+            Entry::Vacant(entry) => {
+                self.misses += 1;
+                Ok(entry.insert(build()?))
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Lookups answered from the cache
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Lookups that had to run the builder
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Drops every entry and resets the counters
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthesis::advanced::prepare_state;
+
+    #[test]
+    fn test_cache_hit_skips_rebuild() {
+        let amps = [0.5, 0.5, -0.5, 0.5];
+        let mut builds = 0;
+        let mut cache = SubcircuitCache::new();
+
+        let key = CacheKey::from_values("prepare_state", &amps);
+        let first = cache
+            .try_get_or_build(key.clone(), || {
+                builds += 1;
+                prepare_state(&amps)
+            })
+            .unwrap()
+            .to_vec();
+        let copy = vec![0.5, 0.5, -0.5, 0.5];
+        let again = CacheKey::from_values("prepare_state", &copy);
+        let second = cache
+            .try_get_or_build(again, || {
+                builds += 1;
+                prepare_state(&amps)
+            })
+            .unwrap()
+            .to_vec();
+
+        assert_eq!(builds, 1);
+        assert_eq!(first, second);
+        assert_eq!(first, prepare_state(&amps).unwrap());
+        assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 1, 1));
+
+        // A different builder or input is a different entry
+        let other = CacheKey::from_values("grover_rudolph_prep", &amps);
+        assert_ne!(other, key);
+        assert_ne!(CacheKey::from_values("prepare_state", &[0.5, 0.5, 0.5, 0.5]), key);
+        assert_eq!(CacheKey::from_values("x", &[-0.0]), CacheKey::from_values("x", &[0.0]));
+
+        // Inputs are compared in full, length and kind included
+        assert_ne!(CacheKey::from_values("x", &[1.0]), CacheKey::from_values("x", &[1.0, 0.0]));
+        assert_ne!(CacheKey::from_values("x", &[]), CacheKey::from_circuit("x", &[]));
+
+        // Failed builds are not cached
+        let bad = CacheKey::from_values("prepare_state", &[]);
+        assert!(cache.try_get_or_build(bad, || prepare_state(&[])).is_err());
+        assert_eq!(cache.len(), 1);
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.hits(), 0);
+    }
+
+    #[test]
+    fn test_circuit_keys_use_canonical_form() {
+        let a = vec![Gate::H(0), Gate::X(1), Gate::CX(0, 1)];
+        let b = vec![Gate::X(1), Gate::H(0), Gate::CX(0, 1)];
+        assert_eq!(CacheKey::from_circuit("inverse", &a), CacheKey::from_circuit("inverse", &b));
+
+        let mut cache = SubcircuitCache::new();
+        let mut builds = 0;
+        for circuit in [&a, &b] {
+            let gates = cache.get_or_build(CacheKey::from_circuit("inverse", circuit), || {
+                builds += 1;
+                crate::gates::circuit::inverse_circuit(circuit)
+            });
+            assert_eq!(gates.len(), 3);
+        }
+        assert_eq!(builds, 1);
+        assert_eq!(cache.hits(), 1);
+    }
+}
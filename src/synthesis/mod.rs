@@ -10,6 +10,8 @@
 //! - **Specialized Layout**: Organizes synthesis methods by their target: 
 //!   classical data (`qram`), general states (`state_preparation`), and 
 //!   complex unitaries (`advanced`).
+//! - **Memoization**: `cache` stores synthesized gate lists by the content
+//!   hash of their inputs so recurring subcircuits are built once.
 //!
 //! ## 📍 Where to apply this?
 //! Use this at the start of your quantum program to load data or define the 
@@ -19,9 +21,11 @@
 //! - Structural organization module with zero runtime overhead.
 
 pub mod advanced;
+pub mod cache;
 pub mod qram;
 pub mod state_preparation;
 
 pub use advanced::*;
+pub use cache::*;
 pub use qram::*;
 pub use state_preparation::*;
@@ -16,9 +16,9 @@
 //!   The address bits navigate the tree to activate a unique path to a 
 //!   memory cell. This architecture is efficient as it only activates $O(\log N)$ 
 //!   routing nodes per access.
-//! - **Fan-out QRAM**: Uses a parallel "fan-out" of the address bits to 
-//!   access all memory cells simultaneously and then filters for the 
-//!   target address.
+//! - **Fan-out QRAM**: Decodes the address into a one-hot select register
+//!   with a tree of Toffolis, copies the selected memory word onto the data
+//!   register with CX gates, then uncomputes the select register.
 //!
 //! ## 📍 Where to apply this?
 //! - **Database Search**: Oracles that need to retrieve values based on 
@@ -31,12 +31,15 @@
 //! - **Complexity**: 
 //!     - Bucket-Brigade: $O(N)$ physical qubits for $N$ memory cells, but 
 //!       only $O(\log N)$ gates are active per memory request.
+//!     - Address fan-out uses a balanced CX tree, $O(\log N)$ depth per bit.
+//!     - Fan-out QRAM: $N$ select qubits, $2(N-1)$ Toffolis per query.
 //! - **Ancilla Management**: Requires a significant number of routing 
 //!   qubits that must be properly uncomputed to avoid decoherence.
 //! - **Connectivity**: Performance is highly dependent on the ability 
 //!   to map a tree structure onto the physical QPU topology.
 
 use crate::gates::core::Gate;
+use crate::gates::circuit::cnot_fanout;
 
 // ============================================================================
 // BUCKET-BRIGADE QRAM
@@ -57,12 +60,9 @@ pub fn bucket_brigade_qram(
     
     // 1. Layer-by-layer activation of routing tree
     for (i, &aq) in address_qubits.iter().enumerate() {
-        let _layer = &routing_qubits[i];
         // For each node in the layer, apply routing based on address bit
         // Simplified: Controlled activation of child nodes
-        for &rq in &routing_qubits[i] {
-            gates.push(Gate::CX(aq, rq));
-        }
+        gates.extend(cnot_fanout(aq, &routing_qubits[i]));
     }
     
     // 2. Data readout
@@ -78,9 +78,7 @@ pub fn bucket_brigade_qram(
     
     // 3. Uncompute routing tree
     for (i, &aq) in address_qubits.iter().enumerate().rev() {
-        for &rq in &routing_qubits[i] {
-            gates.push(Gate::CX(aq, rq));
-        }
+        gates.extend(cnot_fanout(aq, &routing_qubits[i]));
     }
     
     gates
@@ -90,22 +88,92 @@ pub fn bucket_brigade_qram(
 // FAN-OUT QRAM
 // ============================================================================
 
-/// Fan-out QRAM architecture
+/// Fan-out QRAM read |i⟩|0…0⟩|d⟩ → |i⟩|0…0⟩|d ⊕ memory[i]⟩
+///
+/// The address is decoded into the one-hot `select_qubits`, whose qubit i is
+/// then the control of a CX onto every data qubit set in `memory[i]`, and the
+/// decoding is undone. Decoding sets select qubit 0, then each address bit k
+/// splits the 2ᵏ nodes so far with one Toffoli apiece: 2ⁿ − 1 Toffolis in all.
+///
+/// # Arguments
+/// * `address_qubits` - Address, least significant bit first (n qubits)
+/// * `select_qubits` - 2ⁿ clean qubits, returned to |0⟩
+/// * `data_qubits` - Output word, bit b of `memory[i]` onto `data_qubits[b]`
+/// * `memory` - At most 2ⁿ words; missing addresses read as 0
 pub fn fan_out_qram(
     address_qubits: &[usize],
+    select_qubits: &[usize],
     data_qubits: &[usize],
+    memory: &[u64],
 ) -> Vec<Gate> {
-    let mut gates = Vec::new();
-    
-    // Fan-out involves unary encoding of the address
-    // and then controlled-data transfer.
-    
-    // Simplified representation of address decoding
-    for &aq in address_qubits {
-        for &dq in data_qubits {
-            gates.push(Gate::CX(aq, dq));
+    let n = address_qubits.len();
+    assert_eq!(select_qubits.len(), 1 << n, "Fan-out QRAM needs 2^{} select qubits", n);
+    assert!(memory.len() <= 1 << n, "{} words do not fit {} address bits", memory.len(), n);
+    assert!(memory.iter().all(|&w| data_qubits.len() >= 64 || w >> data_qubits.len() == 0),
+        "Memory word wider than the {} data qubits", data_qubits.len());
+
+    // Unary decoding: after bit k, select[j] = 1 iff the low k+1 address bits equal j
+    let mut decode = vec![Gate::X(select_qubits[0])];
+    for (k, &a) in address_qubits.iter().enumerate() {
+        for j in 0..1 << k {
+            decode.push(Gate::CCX(a, select_qubits[j], select_qubits[j + (1 << k)]));
+            decode.push(Gate::CX(select_qubits[j + (1 << k)], select_qubits[j]));
         }
     }
-    
+
+    let mut gates = decode.clone();
+    for (&word, &select) in memory.iter().zip(select_qubits) {
+        for (b, &dq) in data_qubits.iter().enumerate() {
+            if (word >> b) & 1 == 1 {
+                gates.push(Gate::CX(select, dq));
+            }
+        }
+    }
+    gates.extend(decode.into_iter().rev());
     gates
 }
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::{basis_state, simulate, simulate_from, states_close};
+    use crate::gates::core::Complex;
+
+    #[test]
+    fn test_fan_out_qram_reads_memory() {
+        // Address on qubits 0-2, select on 3-10, data on 11-14
+        let address = [0, 1, 2];
+        let select: Vec<usize> = (3..11).collect();
+        let data = [11, 12, 13, 14];
+        let memory = [0b1010, 0b0001, 0b1111, 0b0000, 0b0110, 0b1001, 0b0011];
+        let circuit = fan_out_qram(&address, &select, &data, &memory);
+        assert_eq!(circuit.iter().filter(|g| matches!(g, Gate::CCX(..))).count(), 2 * 7);
+
+        let word = |w: u64| (0..4).map(|b| ((w as usize >> b) & 1) << data[b]).sum::<usize>();
+        for i in 0..8 {
+            // Address 7 lies past the end of memory and reads 0
+            let expected = memory.get(i).copied().unwrap_or(0);
+            let state = simulate_from(&circuit, &basis_state(15, i)).unwrap();
+            assert!(states_close(&state, &basis_state(15, i | word(expected)), 1e-12), "Address {}", i);
+
+            // A second read XORs the same word back out
+            let stored = i | word(0b0101);
+            let state = simulate_from(&circuit, &basis_state(15, stored)).unwrap();
+            assert!(states_close(&state, &basis_state(15, stored ^ word(expected)), 1e-12));
+        }
+
+        // Addresses in superposition each fetch their own word
+        let mut query = vec![Gate::H(0), Gate::H(1)];
+        query.extend(circuit);
+        let state = simulate(&query, 15).unwrap();
+        let mut expected = vec![Complex::ZERO; 1 << 15];
+        for i in 0..4 {
+            expected[i | word(memory[i])] = Complex::new(0.5, 0.0);
+        }
+        assert!(states_close(&state, &expected, 1e-12));
+    }
+}
@@ -0,0 +1,23 @@
+//! Utility Module
+//!
+//! ## 🎯 Why is this used?
+//! Small building blocks shared by otherwise unrelated parts of the library,
+//! kept here so no feature module has to depend on another just for them.
+//!
+//! ## ⚙️ How it works?
+//! - **Randomness**: `rng` provides a seedable generator. The `*_with_rng`
+//!   variants of stochastic passes (random circuits, trajectories, twirling,
+//!   sampling) take a `&mut Rng`, so one generator can drive a pipeline
+//!   reproducibly. Some seeded helpers still step their own LCG; see the
+//!   Scope note in `rng`.
+//!
+//! ## 📍 Where to apply this?
+//! Pass one `&mut Rng` through the `*_with_rng` variants of stochastic
+//! functions instead of inventing a seed for each call.
+//!
+//! ## 📊 Code Behavior
+//! - Structural wrapper with zero overhead.
+
+pub mod rng;
+
+pub use rng::*;
@@ -0,0 +1,127 @@
+//! Seedable Pseudo-Random Number Generator
+//!
+//! This module provides the generator shared by the library's seeded passes:
+//! - `Rng`: A 64-bit linear congruential generator with uniform float and
+//!   bounded integer draws
+//!
+//! ## 🎯 Why is this used?
+//! Stochastic passes used to take a `u64` seed each, which makes a pipeline
+//! of several such passes hard to keep deterministic: every stage needs its
+//! own seed and reordering stages silently changes the results. Threading
+//! one `&mut Rng` through them makes the whole experiment a pure function
+//! of a single seed.
+//!
+//! ## ⚙️ How it works?
+//! - **Recurrence**: sₖ₊₁ = 6364136223846793005 · sₖ + 1442695040888963407
+//!   (mod 2⁶⁴), Knuth's MMIX constants, starting from s₀ = seed.
+//! - **Floats**: `uniform` keeps the top 53 bits, (s >> 11) / 2⁵³ ∈ [0, 1).
+//! - **Integers**: `below(n)` is (s >> 33) mod n, using the better-mixed
+//!   high bits; the modulo bias is negligible for the small n used here.
+//!
+//! ## 📍 Where to apply this?
+//! - **Reproducible Experiments**: Seed once, then pass the generator to
+//!   `random_circuit_with_rng`, `simulate_trajectory_with_rng`,
+//!   `pauli_twirl_with_rng`, `expectation_with_error_with_rng` and
+//!   `gradient_variance_with_rng`.
+//!
+//! ## 📊 Code Behavior
+//! - **Stability**: The recurrence and the draw formulas are part of the
+//!   API. The same seed produces the same stream on every platform and
+//!   release, and the seed-taking functions are exactly their `_with_rng`
+//!   twins called on `Rng::new(seed)`.
+//! - **Quality**: Fine for Monte Carlo sampling; not cryptographically secure.
+//! - **Scope**: Not every seeded function draws from `Rng` yet. The VQE,
+//!   QAOA and QML parameter initializers and placeholder pool gradients, the
+//!   lattice-crypto samplers and `create_balanced_oracle` step their own LCG
+//!   with increment 1, so their streams differ from `Rng::new` on the same
+//!   seed.
+
+/// 64-bit LCG with Knuth's MMIX multiplier and increment
+#[derive(Clone, Debug)]
+pub struct Rng {
+    pub state: u64,
+}
+
+impl Rng {
+    pub const MULTIPLIER: u64 = 6364136223846793005;
+    pub const INCREMENT: u64 = 1442695040888963407;
+
+    /// Generator whose first draw advances from `seed`
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    /// Advance the state and return it
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_mul(Self::MULTIPLIER).wrapping_add(Self::INCREMENT);
+        self.state
+    }
+
+    /// Uniform f64 in [0, 1)
+    pub fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Integer in 0..n
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "Empty range");
+        ((self.next_u64() >> 33) as usize) % n
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_is_the_documented_recurrence() {
+        let mut rng = Rng::new(7);
+        let s1 = 7u64.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let s2 = s1.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        assert_eq!(rng.next_u64(), s1);
+        assert_eq!(rng.uniform(), (s2 >> 11) as f64 / (1u64 << 53) as f64);
+
+        // Pinned values guard cross-release stability
+        let mut rng = Rng::new(0);
+        assert_eq!(rng.next_u64(), 1442695040888963407);
+        assert_eq!(rng.below(10), ((1442695040888963407u64.wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407) >> 33) % 10) as usize);
+    }
+
+    #[test]
+    fn test_draws_stay_in_range() {
+        let mut rng = Rng::new(123);
+        for _ in 0..1000 {
+            let u = rng.uniform();
+            assert!((0.0..1.0).contains(&u));
+            assert!(rng.below(3) < 3);
+        }
+        let (mut a, mut b) = (Rng::new(5), Rng::new(5));
+        assert!((0..10).all(|_| a.next_u64() == b.next_u64()));
+    }
+
+    #[test]
+    fn test_one_stream_drives_a_pipeline() {
+        use crate::error_mitigation::{pauli_twirl, pauli_twirl_with_rng};
+        use crate::gates::circuit::{random_circuit, random_circuit_with_rng};
+        use crate::gates::core::GateKind;
+
+        let kinds = [GateKind::H, GateKind::CX, GateKind::RZ];
+        let pipeline = |seed| {
+            let mut rng = Rng::new(seed);
+            let circuit = random_circuit_with_rng(4, 5, &mut rng, &kinds);
+            format!("{:?}", pauli_twirl_with_rng(&circuit, &mut rng))
+        };
+        assert_eq!(pipeline(9), pipeline(9));
+        assert_ne!(pipeline(9), pipeline(10));
+
+        // Seed-taking functions are their twins on a fresh generator
+        let circuit = random_circuit(4, 5, 3, &kinds);
+        assert_eq!(format!("{:?}", circuit), format!("{:?}", random_circuit_with_rng(4, 5, &mut Rng::new(3), &kinds)));
+        assert_eq!(format!("{:?}", pauli_twirl(&circuit, 8)), format!("{:?}", pauli_twirl_with_rng(&circuit, &mut Rng::new(8))));
+    }
+}
@@ -5,6 +5,8 @@
 //! - UCCSD Ansatz
 //! - QAOA circuits
 //! - Parameter shift gradient computation
+//! - Reusable parametric circuits with late angle binding
+//! - Gradient-variance sampling for barren-plateau detection
 //!
 //! ## 🎯 Why is this used?
 //! Variational algorithms (like VQE and QAOA) are the primary candidates for 
@@ -16,6 +18,7 @@
 //! ## ⚙️ How it works?
 //! - **HEA**: Uses a repeating pattern of single-qubit rotations and entangling 
 //!   layers (like CZ or CNOT) that are easy to execute on specific hardware.
+//!   A `Custom` pattern lists the exact CX pairs, e.g. a device coupling map.
 //! - **UCCSD**: A chemically-inspired ansatz that uses Unitary Coupled Cluster 
 //!   theory to model electron correlations in molecules.
 //! - **QAOA**: Interleaves a problem-specific Hamiltonian (cost) with a 
@@ -33,12 +36,15 @@
 //! - **Performance**: Circuit generation is $O(L \times N)$ where $L$ is 
 //!   the number of layers and $N$ is the number of qubits.
 //! - **Gradient Cost**: Parameter shift requires $2P$ circuit executions 
-//!   to find the gradient for $P$ parameters.
+//!   to find the gradient for $P$ parameters; controlled rotations take 4.
 //! - **Barren Plateaus**: Users should be aware that high-depth HEA ansätze 
 //!   can suffer from vanishing gradients.
 
 use std::f64::consts::PI;
 use crate::gates::core::Gate;
+use crate::gates::decomposition::control_gate;
+use crate::variational::observable::{expectation_value, Observable};
+use crate::util::Rng;
 
 // ============================================================================
 // HARDWARE EFFICIENT ANSATZ (HEA)
@@ -68,6 +74,8 @@ pub enum EntanglingPattern {
     Circular,
     /// Alternating layers
     Alternating,
+    /// Exactly these (control, target) CX pairs, in order, every layer
+    Custom(Vec<(usize, usize)>),
 }
 
 /// Configuration for Hardware Efficient Ansatz
@@ -113,6 +121,9 @@ pub fn hardware_efficient_ansatz(config: &HEAConfig, parameters: &[f64]) -> Vec<
     
     assert_eq!(parameters.len(), expected_params, 
         "Expected {} parameters, got {}", expected_params, parameters.len());
+    if let EntanglingPattern::Custom(pairs) = &config.entangling_pattern {
+        validate_entangler_pairs(pairs, n);
+    }
     
     let mut gates = Vec::new();
     let mut param_idx = 0;
@@ -165,6 +176,18 @@ fn rotation_layer(n: usize, rotation_type: &HEALayerType, params: &[f64]) -> Vec
     gates
 }
 
+/// Panic unless every pair is in range, joins two distinct qubits and appears once
+fn validate_entangler_pairs(pairs: &[(usize, usize)], n: usize) {
+    for (i, &(a, b)) in pairs.iter().enumerate() {
+        assert!(a < n && b < n, "Entangler pair ({}, {}) is outside {} qubits", a, b, n);
+        assert_ne!(a, b, "Entangler pair ({}, {}) uses one qubit twice", a, b);
+        assert!(
+            !pairs[..i].iter().any(|&(c, d)| (c, d) == (a, b) || (c, d) == (b, a)),
+            "Entangler pair ({}, {}) is listed twice", a, b
+        );
+    }
+}
+
 fn entangling_layer(n: usize, pattern: &EntanglingPattern) -> Vec<Gate> {
     let mut gates = Vec::new();
     
@@ -199,6 +222,9 @@ fn entangling_layer(n: usize, pattern: &EntanglingPattern) -> Vec<Gate> {
                 gates.push(Gate::CX(i, i + 1));
             }
         }
+        EntanglingPattern::Custom(pairs) => {
+            gates.extend(pairs.iter().map(|&(a, b)| Gate::CX(a, b)));
+        }
     }
     
     gates
@@ -482,35 +508,285 @@ pub fn qaoa_ising(
 // PARAMETER SHIFT RULE
 // ============================================================================
 
+/// Two-term parameter-shift rule (s, c) for a gate's angle
+///
+/// For a gate e^{-iθG} whose generator G has exactly two eigenvalues, ±½
+/// apart up to a constant, df/dθ = c·(f(θ + s) − f(θ − s)) exactly, with
+/// s = π/2 and c = ½. That covers RX, RY, RZ, RXX, RYY, RZZ and the phase
+/// gates P, CP, MCP, whose projector generators differ from a ±½ one by a
+/// global phase. Controlled rotations CRX, CRY, CRZ have generator
+/// eigenvalues {0, ±½} and return `None`: they need the four-term rule that
+/// `parameter_shift_gradient` applies. Gates without an angle return `None`.
+pub fn shift_rule(gate: &Gate) -> Option<(f64, f64)> {
+    match gate {
+        Gate::RX(..) | Gate::RY(..) | Gate::RZ(..) |
+        Gate::RXX(..) | Gate::RYY(..) | Gate::RZZ(..) |
+        Gate::P(..) | Gate::CP(..) | Gate::MCP(..) => Some((PI / 2.0, 0.5)),
+        _ => None,
+    }
+}
+
+/// Exact gradient of `expectation(circuit)` with respect to every gate angle
+///
+/// Entry i is ∂f/∂θᵢ for the angle of `circuit[i]`, and 0 for gates without
+/// one (including `GlobalPhase`, which no expectation depends on). Gates
+/// with a `shift_rule` use two evaluations; CRX, CRY and CRZ use the
+/// four-term rule
+/// ∂f = d₁[f(θ+π/2) − f(θ−π/2)] − d₂[f(θ+3π/2) − f(θ−3π/2)],
+/// d₁,₂ = (√2 ± 1)/(4√2). U3 has three angles and panics; split it first.
+pub fn parameter_shift_gradient(circuit: &[Gate], expectation: impl Fn(&[Gate]) -> f64) -> Vec<f64> {
+    (0..circuit.len())
+        .map(|i| match &circuit[i] {
+            Gate::U3(..) => panic!("U3 has three angles; decompose it before differentiating"),
+            gate => shift_terms(gate)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(c, s)| {
+                    let mut shifted = circuit.to_vec();
+                    shifted[i] = shift_angle(gate, s);
+                    c * expectation(&shifted)
+                })
+                .sum(),
+        })
+        .collect()
+}
+
+/// Coefficient–shift pairs (cₖ, sₖ) with ∂f/∂θ = Σₖ cₖ·f(θ + sₖ)
+///
+/// Two terms for gates with a `shift_rule`, four for CRX, CRY and CRZ, and
+/// `None` for gates without a single angle.
+fn shift_terms(gate: &Gate) -> Option<Vec<(f64, f64)>> {
+    match gate {
+        gate if shift_rule(gate).is_some() => {
+            let (s, c) = shift_rule(gate).unwrap();
+            Some(vec![(c, s), (-c, -s)])
+        }
+        Gate::CRX(..) | Gate::CRY(..) | Gate::CRZ(..) => {
+            let r2 = 2f64.sqrt();
+            let (d1, d2) = ((r2 + 1.0) / (4.0 * r2), (r2 - 1.0) / (4.0 * r2));
+            Some(vec![
+                (d1, PI / 2.0), (-d1, -PI / 2.0),
+                (-d2, 3.0 * PI / 2.0), (d2, -3.0 * PI / 2.0),
+            ])
+        }
+        _ => None,
+    }
+}
+
+/// The same single-angle gate with its angle moved by `delta`
+fn shift_angle(gate: &Gate, delta: f64) -> Gate {
+    map_angle(gate, |t| t + delta)
+}
+
+/// The same single-angle gate with its angle θ replaced by f(θ)
+fn map_angle(gate: &Gate, f: impl Fn(f64) -> f64) -> Gate {
+    match gate {
+        Gate::RX(q, t) => Gate::RX(*q, f(*t)),
+        Gate::RY(q, t) => Gate::RY(*q, f(*t)),
+        Gate::RZ(q, t) => Gate::RZ(*q, f(*t)),
+        Gate::P(q, t) => Gate::P(*q, f(*t)),
+        Gate::RXX(a, b, t) => Gate::RXX(*a, *b, f(*t)),
+        Gate::RYY(a, b, t) => Gate::RYY(*a, *b, f(*t)),
+        Gate::RZZ(a, b, t) => Gate::RZZ(*a, *b, f(*t)),
+        Gate::CRX(c, q, t) => Gate::CRX(*c, *q, f(*t)),
+        Gate::CRY(c, q, t) => Gate::CRY(*c, *q, f(*t)),
+        Gate::CRZ(c, q, t) => Gate::CRZ(*c, *q, f(*t)),
+        Gate::CP(c, q, t) => Gate::CP(*c, *q, f(*t)),
+        Gate::MCP(cs, q, t) => Gate::MCP(cs.clone(), *q, f(*t)),
+        other => panic!("{:?} has no single angle", other),
+    }
+}
+
 /// Generate circuits for parameter shift gradient computation
 /// 
-/// For f(θ) = ⟨ψ(θ)|H|ψ(θ)⟩, the gradient is:
-/// df/dθ = (f(θ + π/2) - f(θ - π/2)) / 2
-/// 
-/// Returns two circuits: one with θ + π/2, one with θ - π/2
+/// For f(θ) = ⟨ψ(θ)|H|ψ(θ)⟩, the gradient is df/dθ = Σₖ cₖ·f(circuitₖ), one
+/// weighted circuit per shift of the rule for `gate`, the gate that
+/// `parameters[param_index]` feeds: two (θ ± π/2, c = ±½) for gates with a
+/// `shift_rule`, four for CRX, CRY and CRZ. Panics if `gate` has no single angle.
 pub fn parameter_shift_circuits(
     circuit_generator: impl Fn(&[f64]) -> Vec<Gate>,
     parameters: &[f64],
     param_index: usize,
-) -> (Vec<Gate>, Vec<Gate>) {
-    let shift = PI / 2.0;
-    
-    let mut params_plus = parameters.to_vec();
-    params_plus[param_index] += shift;
-    
-    let mut params_minus = parameters.to_vec();
-    params_minus[param_index] -= shift;
-    
-    (circuit_generator(&params_plus), circuit_generator(&params_minus))
+    gate: &Gate,
+) -> Vec<(f64, Vec<Gate>)> {
+    let terms = shift_terms(gate).unwrap_or_else(|| panic!("{:?} has no single angle to shift", gate));
+    terms
+        .into_iter()
+        .map(|(c, shift)| {
+            let mut params = parameters.to_vec();
+            params[param_index] += shift;
+            (c, circuit_generator(&params))
+        })
+        .collect()
 }
 
 /// Generate all parameter shift circuits for gradient computation
+///
+/// `gates[i]` is the gate that `parameters[i]` feeds.
 pub fn all_parameter_shift_circuits(
     circuit_generator: impl Fn(&[f64]) -> Vec<Gate>,
     parameters: &[f64],
-) -> Vec<(Vec<Gate>, Vec<Gate>)> {
+    gates: &[Gate],
+) -> Vec<Vec<(f64, Vec<Gate>)>> {
+    assert_eq!(gates.len(), parameters.len(), "Need one gate per parameter");
     (0..parameters.len())
-        .map(|i| parameter_shift_circuits(&circuit_generator, parameters, i))
+        .map(|i| parameter_shift_circuits(&circuit_generator, parameters, i, &gates[i]))
+        .collect()
+}
+
+// ============================================================================
+// PARAMETRIC CIRCUITS
+// ============================================================================
+
+/// One gate of a `ParametricCircuit`
+#[derive(Clone, Debug)]
+pub enum ParamGate {
+    /// A gate whose angles, if any, are constants
+    Fixed(Gate),
+    /// A single-angle gate whose angle is `params[index]` at bind time
+    ///
+    /// The angle stored in the gate is a placeholder and is ignored.
+    Param(Gate, usize),
+    /// A single-angle gate whose angle is `scale · params[index]`
+    ///
+    /// UCCSD excitations drive several Pauli rotations from one amplitude
+    /// with opposite signs or halved angles; the chain rule scales each
+    /// contribution to the gradient by `scale`.
+    Scaled(Gate, usize, f64),
+}
+
+/// A circuit template built once and rebound to new parameters cheaply
+///
+/// Several gates may share one parameter index, even gates with different
+/// generators; `gradient` sums their contributions, each with its own shift
+/// rule and scale.
+#[derive(Clone, Debug, Default)]
+pub struct ParametricCircuit {
+    pub gates: Vec<ParamGate>,
+}
+
+impl ParametricCircuit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a gate with constant angles
+    pub fn push(&mut self, gate: Gate) {
+        self.gates.push(ParamGate::Fixed(gate));
+    }
+
+    /// Append a single-angle gate driven by parameter `index`
+    pub fn push_param(&mut self, gate: Gate, index: usize) {
+        assert_parametrizable(&gate);
+        self.gates.push(ParamGate::Param(gate, index));
+    }
+
+    /// Append a single-angle gate whose angle is `scale · params[index]`
+    pub fn push_param_scaled(&mut self, gate: Gate, index: usize, scale: f64) {
+        assert_parametrizable(&gate);
+        self.gates.push(ParamGate::Scaled(gate, index, scale));
+    }
+
+    /// Number of parameters `bind` expects: the highest index used, plus one
+    pub fn num_parameters(&self) -> usize {
+        self.gates.iter()
+            .filter_map(|g| match g {
+                ParamGate::Param(_, i) | ParamGate::Scaled(_, i, _) => Some(i + 1),
+                ParamGate::Fixed(_) => None,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The concrete circuit for angles `params`
+    pub fn bind(&self, params: &[f64]) -> Vec<Gate> {
+        assert!(params.len() >= self.num_parameters(),
+            "Circuit uses {} parameters, got {}", self.num_parameters(), params.len());
+        self.gates.iter()
+            .map(|g| match g {
+                ParamGate::Fixed(gate) => gate.clone(),
+                ParamGate::Param(gate, i) => map_angle(gate, |_| params[*i]),
+                ParamGate::Scaled(gate, i, scale) => map_angle(gate, |_| scale * params[*i]),
+            })
+            .collect()
+    }
+
+    /// ∂f/∂params of `expectation(bind(params))` by the parameter-shift rule
+    ///
+    /// Only `Param` and `Scaled` gates are shifted, with the rule of
+    /// `parameter_shift_gradient`; `Fixed` gates (U3 included) are never
+    /// evaluated shifted.
+    pub fn gradient(&self, params: &[f64], expectation: impl Fn(&[Gate]) -> f64) -> Vec<f64> {
+        let bound = self.bind(params);
+        let mut gradient = vec![0.0; params.len()];
+        for (k, g) in self.gates.iter().enumerate() {
+            let (i, scale) = match g {
+                ParamGate::Param(_, i) => (*i, 1.0),
+                ParamGate::Scaled(_, i, scale) => (*i, *scale),
+                ParamGate::Fixed(_) => continue,
+            };
+            let terms = shift_terms(&bound[k]).expect("Parametrized gates have a shift rule");
+            let d: f64 = terms
+                .into_iter()
+                .map(|(c, s)| {
+                    let mut shifted = bound.clone();
+                    shifted[k] = shift_angle(&bound[k], s);
+                    c * expectation(&shifted)
+                })
+                .sum();
+            gradient[i] += scale * d;
+        }
+        gradient
+    }
+}
+
+fn assert_parametrizable(gate: &Gate) {
+    assert!(shift_rule(gate).is_some() || matches!(gate, Gate::CRX(..) | Gate::CRY(..) | Gate::CRZ(..)),
+        "{:?} has no single angle to parametrize", gate);
+}
+
+/// Per-parameter variance of ∂⟨obs⟩/∂θₖ over uniformly random θ ∈ [0, 2π)ᴾ
+///
+/// Barren-plateau diagnostic: for deep random ansätze Var[∂ₖ⟨O⟩] decays
+/// exponentially in `num_qubits`, so gradients are too small to estimate with
+/// a feasible number of shots. A variance that halves with every added qubit
+/// signals a plateau; one that decays polynomially or not at all means the
+/// landscape is trainable. Uses `samples` ≥ 2 points seeded by `seed`, each
+/// with an exact parameter-shift gradient, and the unbiased sample variance.
+pub fn gradient_variance(
+    ansatz: &ParametricCircuit,
+    obs: &Observable,
+    num_qubits: usize,
+    samples: usize,
+    seed: u64,
+) -> Vec<f64> {
+    gradient_variance_with_rng(ansatz, obs, num_qubits, samples, &mut Rng::new(seed))
+}
+
+/// `gradient_variance` drawing from a shared generator
+pub fn gradient_variance_with_rng(
+    ansatz: &ParametricCircuit,
+    obs: &Observable,
+    num_qubits: usize,
+    samples: usize,
+    rng: &mut Rng,
+) -> Vec<f64> {
+    assert!(samples >= 2, "Need at least two samples for a variance");
+    let mut uniform = || rng.uniform();
+
+    let p = ansatz.num_parameters();
+    let gradients: Vec<Vec<f64>> = (0..samples)
+        .map(|_| {
+            let params: Vec<f64> = (0..p).map(|_| 2.0 * PI * uniform()).collect();
+            ansatz.gradient(&params, |c| expectation_value(c, obs, num_qubits))
+        })
+        .collect();
+
+    (0..p)
+        .map(|k| {
+            let mean = gradients.iter().map(|g| g[k]).sum::<f64>() / samples as f64;
+            gradients.iter().map(|g| (g[k] - mean).powi(2)).sum::<f64>() / (samples - 1) as f64
+        })
         .collect()
 }
 
@@ -552,47 +828,33 @@ pub fn swap_test(
 }
 
 /// Hadamard test for computing ⟨ψ|U|ψ⟩
-/// 
-/// Uses a control qubit to measure real or imaginary part of expectation.
+///
+/// Prepares |ψ⟩ = `state_prep`|0⟩, puts `ancilla` in |+⟩ (then S† when
+/// `imaginary`), applies controlled-U and a final Hadamard. The ancilla's
+/// ⟨Z⟩ — equivalently its ⟨X⟩ (or ⟨Y⟩) before the last H — equals
+/// Re⟨ψ|U|ψ⟩ (or Im⟨ψ|U|ψ⟩).
 pub fn hadamard_test(
-    control: usize,
-    _target_qubits: &[usize],
+    state_prep: &[Gate],
     unitary: &[Gate],
-    measure_imaginary: bool,
+    ancilla: usize,
+    imaginary: bool,
 ) -> Vec<Gate> {
-    let mut gates = Vec::new();
-    
-    // Hadamard on control
-    gates.push(Gate::H(control));
-    
-    // S gate if measuring imaginary part
-    if measure_imaginary {
-        gates.push(Gate::Sdg(control));
+    assert!(
+        state_prep.iter().all(|g| !g.qubits().contains(&ancilla)),
+        "State preparation must not touch the ancilla"
+    );
+
+    let mut gates = state_prep.to_vec();
+    gates.push(Gate::H(ancilla));
+    if imaginary {
+        gates.push(Gate::Sdg(ancilla));
     }
-    
-    // Controlled-U
-    // Wrap each gate in controlled version
     for gate in unitary {
-        gates.extend(make_controlled(control, gate));
+        gates.extend(control_gate(ancilla, gate));
     }
-    
-    // Final Hadamard
-    gates.push(Gate::H(control));
-    
-    gates
-}
+    gates.push(Gate::H(ancilla));
 
-fn make_controlled(control: usize, gate: &Gate) -> Vec<Gate> {
-    match gate {
-        Gate::X(t) => vec![Gate::CX(control, *t)],
-        Gate::Y(t) => vec![Gate::CY(control, *t)],
-        Gate::Z(t) => vec![Gate::CZ(control, *t)],
-        Gate::RZ(t, theta) => vec![Gate::CRZ(control, *t, *theta)],
-        Gate::RX(t, theta) => vec![Gate::CRX(control, *t, *theta)],
-        Gate::RY(t, theta) => vec![Gate::CRY(control, *t, *theta)],
-        // For more complex gates, decompose first
-        _ => vec![gate.clone()], // Simplified
-    }
+    gates
 }
 
 // ============================================================================
@@ -603,6 +865,209 @@ fn make_controlled(control: usize, gate: &Gate) -> Vec<Gate> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parameter_shift_gradient_matches_finite_difference() {
+        use crate::variational::observable::expectation_value;
+        use crate::variational::vqe_variants::{Hamiltonian, HamiltonianTerm, PauliOp};
+
+        let circuit = vec![
+            Gate::H(0), Gate::RX(1, 0.4), Gate::CRY(0, 1, 0.9), Gate::RZZ(1, 2, -0.6),
+            Gate::CRZ(1, 2, 1.3), Gate::H(2), Gate::CP(0, 2, 0.7), Gate::CRX(2, 0, -0.5),
+            Gate::H(2), Gate::RY(2, 0.2),
+        ];
+        let mut obs = Hamiltonian::new(3);
+        obs.add_term(HamiltonianTerm::new(1.0, vec![PauliOp::X, PauliOp::Z, PauliOp::I]));
+        obs.add_term(HamiltonianTerm::new(0.5, vec![PauliOp::I, PauliOp::Y, PauliOp::X]));
+        obs.add_term(HamiltonianTerm::z_field(2, 3, 0.8));
+        let f = |c: &[Gate]| expectation_value(c, &obs, 3);
+
+        let gradient = parameter_shift_gradient(&circuit, f);
+        let h = 1e-5;
+        for (i, &g) in gradient.iter().enumerate() {
+            if matches!(circuit[i], Gate::H(_)) {
+                assert_eq!(g, 0.0);
+                continue;
+            }
+            let numeric = (f(&[&circuit[..i], &[shift_angle(&circuit[i], h)], &circuit[i + 1..]].concat())
+                - f(&[&circuit[..i], &[shift_angle(&circuit[i], -h)], &circuit[i + 1..]].concat())) / (2.0 * h);
+            assert!((g - numeric).abs() < 1e-6, "Gate {:?}: {} vs {}", circuit[i], g, numeric);
+        }
+    }
+
+    #[test]
+    fn test_parameter_shift_circuits_controlled_rotation() {
+        use crate::variational::observable::expectation_value;
+        use crate::variational::vqe_variants::{Hamiltonian, HamiltonianTerm};
+
+        let generator = |p: &[f64]| vec![Gate::H(0), Gate::RY(1, p[0]), Gate::CRY(0, 1, p[1])];
+        let obs = {
+            let mut h = Hamiltonian::new(2);
+            h.add_term(HamiltonianTerm::z_field(1, 2, 1.0));
+            h
+        };
+        let f = |c: &[Gate]| expectation_value(c, &obs, 2);
+        let params = [0.3, 0.8];
+        let gates = generator(&params)[1..].to_vec();
+
+        let circuits = all_parameter_shift_circuits(generator, &params, &gates);
+        assert_eq!(circuits[0].len(), 2);
+        assert_eq!(circuits[1].len(), 4);
+        let expected = parameter_shift_gradient(&generator(&params), f);
+        for (k, terms) in circuits.iter().enumerate() {
+            let g: f64 = terms.iter().map(|(c, circuit)| c * f(circuit)).sum();
+            assert!((g - expected[k + 1]).abs() < 1e-12, "Parameter {}: {} vs {}", k, g, expected[k + 1]);
+        }
+    }
+
+    #[test]
+    fn test_parametric_gradient_skips_fixed_gates() {
+        use crate::variational::observable::expectation_value;
+        use crate::variational::vqe_variants::{Hamiltonian, HamiltonianTerm};
+        use std::cell::Cell;
+
+        let mut obs = Hamiltonian::new(2);
+        obs.add_term(HamiltonianTerm::z_field(1, 2, 1.0));
+        let calls = Cell::new(0);
+        let f = |c: &[Gate]| {
+            calls.set(calls.get() + 1);
+            expectation_value(c, &obs, 2)
+        };
+
+        // A fixed U3 has no parameter and must not be differentiated
+        let mut template = ParametricCircuit::new();
+        template.push(Gate::U3(0, 0.4, 0.1, -0.3));
+        template.push(Gate::RX(1, 0.2));
+        assert_eq!(template.gradient(&[], f), Vec::<f64>::new());
+        assert_eq!(calls.get(), 0);
+
+        // Two evaluations for the RY, four for the CRY, none for the fixed gates
+        template.push_param(Gate::RY(1, 0.0), 0);
+        template.push_param_scaled(Gate::CRY(0, 1, 0.0), 0, 0.5);
+        let gradient = template.gradient(&[0.7], f);
+        assert_eq!(calls.get(), 6);
+
+        let h = 1e-5;
+        let numeric = (f(&template.bind(&[0.7 + h])) - f(&template.bind(&[0.7 - h]))) / (2.0 * h);
+        assert!((gradient[0] - numeric).abs() < 1e-6, "{} vs {}", gradient[0], numeric);
+    }
+
+    #[test]
+    fn test_parametric_circuit_binding() {
+        use crate::variational::observable::expectation_value;
+        use crate::variational::vqe_variants::{Hamiltonian, HamiltonianTerm, PauliOp};
+
+        let mut template = ParametricCircuit::new();
+        template.push(Gate::H(0));
+        template.push_param(Gate::RY(1, 0.0), 0);
+        template.push(Gate::CX(0, 1));
+        template.push_param(Gate::CRZ(1, 0, 0.0), 1);
+        template.push_param(Gate::RX(0, 0.0), 0);
+        template.push(Gate::RZ(1, 0.25));
+        assert_eq!(template.num_parameters(), 2);
+
+        let params = [0.4, -1.2];
+        let first = template.bind(&params);
+        assert_eq!(format!("{:?}", first), format!("{:?}", template.bind(&params)));
+        assert!(matches!(first[1], Gate::RY(1, t) if t == 0.4));
+        assert!(matches!(first[3], Gate::CRZ(1, 0, t) if t == -1.2));
+        assert!(matches!(first[5], Gate::RZ(1, t) if t == 0.25));
+
+        // The shared parameter 0 collects the gradient of both gates that use it
+        let mut obs = Hamiltonian::new(2);
+        obs.add_term(HamiltonianTerm::new(1.0, vec![PauliOp::X, PauliOp::Z]));
+        obs.add_term(HamiltonianTerm::z_field(0, 2, 0.6));
+        let f = |c: &[Gate]| expectation_value(c, &obs, 2);
+        let gradient = template.gradient(&params, f);
+        let h = 1e-5;
+        for i in 0..2 {
+            let (mut plus, mut minus) = (params, params);
+            plus[i] += h;
+            minus[i] -= h;
+            let numeric = (f(&template.bind(&plus)) - f(&template.bind(&minus))) / (2.0 * h);
+            assert!((gradient[i] - numeric).abs() < 1e-6, "Parameter {}: {} vs {}", i, gradient[i], numeric);
+        }
+    }
+
+    #[test]
+    fn test_linked_parameters_sum_contributions() {
+        use crate::variational::observable::expectation_value;
+        use crate::variational::vqe_variants::{Hamiltonian, HamiltonianTerm, PauliOp};
+
+        let mut obs = Hamiltonian::new(2);
+        obs.add_term(HamiltonianTerm::new(1.0, vec![PauliOp::X, PauliOp::X]));
+        obs.add_term(HamiltonianTerm::new(0.5, vec![PauliOp::Y, PauliOp::X]));
+        obs.add_term(HamiltonianTerm::z_field(1, 2, -0.3));
+        let f = |c: &[Gate]| expectation_value(c, &obs, 2);
+        let check = |template: &ParametricCircuit, params: &[f64]| {
+            let gradient = template.gradient(params, f);
+            let h = 1e-5;
+            for i in 0..params.len() {
+                let (mut plus, mut minus) = (params.to_vec(), params.to_vec());
+                plus[i] += h;
+                minus[i] -= h;
+                let numeric = (f(&template.bind(&plus)) - f(&template.bind(&minus))) / (2.0 * h);
+                assert!((gradient[i] - numeric).abs() < 1e-6, "Parameter {}: {} vs {}", i, gradient[i], numeric);
+            }
+        };
+
+        // One parameter on two RZ gates
+        let mut template = ParametricCircuit::new();
+        template.push(Gate::H(0));
+        template.push(Gate::H(1));
+        template.push_param(Gate::RZ(0, 0.0), 0);
+        template.push(Gate::CX(0, 1));
+        template.push_param(Gate::RZ(1, 0.0), 0);
+        template.push(Gate::RY(0, 0.3));
+        assert_eq!(template.num_parameters(), 1);
+        check(&template, &[0.7]);
+
+        // The same parameter behind different generators and scales
+        template.push_param_scaled(Gate::RXX(0, 1, 0.0), 0, -0.5);
+        template.push_param(Gate::CRY(0, 1, 0.0), 0);
+        template.push_param_scaled(Gate::RZ(0, 0.0), 1, 2.0);
+        let bound = template.bind(&[0.7, 0.2]);
+        assert!(matches!(bound[6], Gate::RXX(0, 1, t) if t == -0.35));
+        assert!(matches!(bound[8], Gate::RZ(0, t) if t == 0.4));
+        check(&template, &[0.7, 0.2]);
+    }
+
+    #[test]
+    fn test_gradient_variance() {
+        use crate::variational::vqe_variants::{Hamiltonian, HamiltonianTerm, PauliOp};
+
+        let mut ansatz = ParametricCircuit::new();
+        for q in 0..3 {
+            ansatz.push_param(Gate::RY(q, 0.0), q);
+        }
+        ansatz.push(Gate::CX(0, 1));
+        ansatz.push(Gate::CX(1, 2));
+        for q in 0..3 {
+            ansatz.push_param(Gate::RX(q, 0.0), 3 + q);
+        }
+
+        // A multiple of the identity has a flat landscape
+        let mut flat = Hamiltonian::new(3);
+        flat.add_term(HamiltonianTerm::new(2.5, vec![PauliOp::I; 3]));
+        let variance = gradient_variance(&ansatz, &flat, 3, 20, 11);
+        assert_eq!(variance.len(), 6);
+        assert!(variance.iter().all(|v| v.abs() < 1e-20), "{:?}", variance);
+
+        // ⟨Z₀⟩ depends on the first layer's qubit-0 angles
+        let mut local = Hamiltonian::new(3);
+        local.add_term(HamiltonianTerm::z_field(0, 3, 1.0));
+        let variance = gradient_variance(&ansatz, &local, 3, 50, 11);
+        assert!(variance[0] > 0.05 && variance[3] > 0.05, "{:?}", variance);
+        assert!(variance[2].abs() < 1e-20 && variance[5].abs() < 1e-20, "{:?}", variance);
+    }
+
+    #[test]
+    fn test_shift_rule_coverage() {
+        assert_eq!(shift_rule(&Gate::RYY(0, 1, 0.3)), Some((PI / 2.0, 0.5)));
+        assert_eq!(shift_rule(&Gate::CP(0, 1, 0.3)), Some((PI / 2.0, 0.5)));
+        assert_eq!(shift_rule(&Gate::CRY(0, 1, 0.3)), None);
+        assert_eq!(shift_rule(&Gate::H(0)), None);
+    }
+
     #[test]
     fn test_hea_parameter_count() {
         let config = HEAConfig {
@@ -619,6 +1084,32 @@ mod tests {
         assert!(!circuit.is_empty());
     }
 
+    #[test]
+    fn test_hea_custom_entangler() {
+        let pairs = vec![(0, 2), (3, 1)];
+        let config = HEAConfig {
+            num_qubits: 4,
+            num_layers: 3,
+            rotation_type: HEALayerType::RyOnly,
+            entangling_pattern: EntanglingPattern::Custom(pairs),
+            include_initial_layer: false,
+        };
+        let circuit = hardware_efficient_ansatz(&config, &[0.1; 12]);
+        let two_qubit: Vec<Gate> = circuit.into_iter().filter(|g| g.qubits().len() == 2).collect();
+        let expected: Vec<Gate> = (0..3).flat_map(|_| [Gate::CX(0, 2), Gate::CX(3, 1)]).collect();
+        assert_eq!(two_qubit, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "listed twice")]
+    fn test_hea_custom_entangler_rejects_duplicates() {
+        let config = HEAConfig {
+            entangling_pattern: EntanglingPattern::Custom(vec![(0, 1), (1, 0)]),
+            ..HEAConfig::default()
+        };
+        hardware_efficient_ansatz(&config, &[0.0; 24]);
+    }
+
     #[test]
     fn test_qaoa_maxcut() {
         let edges = vec![(0, 1), (1, 2), (2, 0)]; // Triangle
@@ -638,4 +1129,23 @@ mod tests {
         // H + 2 CSWAP + H
         assert_eq!(circuit.len(), 4);
     }
+
+    #[test]
+    fn test_hadamard_test_matches_overlap() {
+        use crate::simulator::{inner_product, probabilities, simulate, simulate_from};
+
+        let prep = vec![Gate::H(0), Gate::T(0), Gate::RY(1, 0.4), Gate::CX(0, 1)];
+        let unitary = vec![Gate::RZ(0, 0.7), Gate::CX(0, 1), Gate::H(1), Gate::S(1)];
+
+        let psi = simulate(&prep, 3).unwrap();
+        let overlap = inner_product(&psi, &simulate_from(&unitary, &psi).unwrap());
+
+        for (imaginary, expected) in [(false, overlap.re), (true, overlap.im)] {
+            let state = simulate(&hadamard_test(&prep, &unitary, 2, imaginary), 3).unwrap();
+            let z: f64 = probabilities(&state).iter().enumerate()
+                .map(|(i, p)| if i & 0b100 == 0 { *p } else { -*p })
+                .sum();
+            assert!((z - expected).abs() < 1e-10, "imaginary={}: {} vs {}", imaginary, z, expected);
+        }
+    }
 }
@@ -21,7 +21,9 @@
 pub mod ansatz;
 pub mod qaoa_variants;
 pub mod vqe_variants;
+pub mod observable;
 
 pub use ansatz::*;
 pub use qaoa_variants::*;
 pub use vqe_variants::*;
+pub use observable::*;
@@ -0,0 +1,626 @@
+//! Observables and Measurement Grouping
+//!
+//! This module treats a Pauli-sum `Hamiltonian` as a measurable observable:
+//! - Qubit-wise commutation checks between Pauli terms
+//! - Greedy grouping of terms into simultaneously measurable sets
+//! - Basis-change circuits that rotate a group into the Z basis
+//! - Exact and shot-sampled ⟨H⟩ of a circuit's output state, with error bars
+//! - `estimate_observable_from_shots`: The same grouped estimate for circuits
+//!   wider than the observable (ancillas, data registers)
+//! - Model Hamiltonians (Heisenberg chain, Jordan-Wigner Fermi-Hubbard chain)
+//! - Pauli expansion of a Hermitian matrix and the dense matrix of an observable
+//!
+//! ## 🎯 Why is this used?
+//! Estimating ⟨H⟩ = Σᵢ cᵢ⟨Pᵢ⟩ term by term costs one batch of shots per Pauli
+//! string. Terms that commute qubit-wise share an eigenbasis and can be read
+//! out from the same shots, which cuts the number of distinct circuits a VQE
+//! iteration needs, often by an order of magnitude.
+//!
+//! ## ⚙️ How it works?
+//! - **Qubit-Wise Commutation (QWC)**: Two strings commute qubit-wise when, on
+//!   every qubit, their operators are equal or at least one is the identity.
+//! - **Graph Coloring**: Terms are vertices; an edge joins every pair that does
+//!   *not* commute qubit-wise. A greedy largest-degree-first coloring assigns
+//!   each term to a group, so no group contains a conflicting pair.
+//! - **Basis Change**: Per qubit, X is measured after H and Y after S†·H; Z and
+//!   I need no rotation.
+//! - **Pauli Expansion**: The 4ⁿ Pauli strings are an orthogonal basis, so
+//!   H = Σ_P Tr(P·H)/2ⁿ · P. A string with X/Y on mask x permutes |j⟩ to
+//!   |j ⊕ x⟩, so each trace is a single pass over the matrix.
+//!
+//! ## 📍 Where to apply this?
+//! - **VQE / QAOA**: Reducing measurement circuits per energy evaluation.
+//! - **Shadow-free Estimation**: Any workflow that reads Pauli expectations
+//!   from computational-basis counts.
+//!
+//! ## 📊 Code Behavior
+//! - **Complexity**: $O(T^2 N)$ for $T$ terms on $N$ qubits (pairwise checks).
+//! - **Optimality**: Greedy coloring is a heuristic; minimum clique cover is
+//!   NP-hard, but largest-first ordering performs well on chemistry Hamiltonians.
+
+use crate::gates::core::{Complex, Gate, DEFAULT_TOL};
+use crate::gates::circuit::{measure_x_basis, measure_y_basis};
+use crate::simulator::{evolve, probabilities, zero_state};
+use crate::util::Rng;
+use super::vqe_variants::{Hamiltonian, HamiltonianTerm, PauliOp};
+
+/// A measurable observable expressed as a weighted sum of Pauli strings
+pub type Observable = Hamiltonian;
+
+// ============================================================================
+// COMMUTATION
+// ============================================================================
+
+/// Check whether two Pauli terms commute qubit-wise
+pub fn qubitwise_commute(a: &HamiltonianTerm, b: &HamiltonianTerm) -> bool {
+    a.paulis.iter().zip(b.paulis.iter()).all(|(&p, &q)| {
+        p == PauliOp::I || q == PauliOp::I || p == q
+    })
+}
+
+// ============================================================================
+// GROUPING
+// ============================================================================
+
+/// Partition term indices into qubit-wise commuting groups
+///
+/// Greedy coloring of the conflict graph, visiting terms by decreasing degree.
+/// Groups are returned in color order; indices within a group are ascending.
+pub fn group_commuting_terms(obs: &Observable) -> Vec<Vec<usize>> {
+    let n = obs.terms.len();
+
+    // Conflict (non-QWC) adjacency
+    let mut conflicts = vec![Vec::new(); n];
+    for i in 0..n {
+        for j in i + 1..n {
+            if !qubitwise_commute(&obs.terms[i], &obs.terms[j]) {
+                conflicts[i].push(j);
+                conflicts[j].push(i);
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(conflicts[i].len()));
+
+    let mut color: Vec<Option<usize>> = vec![None; n];
+    let mut num_colors = 0;
+    for &i in &order {
+        let used: Vec<usize> = conflicts[i].iter().filter_map(|&j| color[j]).collect();
+        let c = (0..).find(|c| !used.contains(c)).unwrap();
+        color[i] = Some(c);
+        num_colors = num_colors.max(c + 1);
+    }
+
+    let mut groups = vec![Vec::new(); num_colors];
+    for (i, c) in color.iter().enumerate() {
+        groups[c.unwrap()].push(i);
+    }
+    groups
+}
+
+/// Basis-change circuit that diagonalizes every term of a QWC group
+///
+/// After this circuit, each term's expectation is the parity of the
+/// computational-basis bits on its non-identity qubits.
+pub fn basis_change_circuit(obs: &Observable, group: &[usize]) -> Vec<Gate> {
+    let mut basis = vec![PauliOp::I; obs.num_qubits];
+    for &idx in group {
+        for (q, &p) in obs.terms[idx].paulis.iter().enumerate() {
+            if p != PauliOp::I {
+                assert!(
+                    basis[q] == PauliOp::I || basis[q] == p,
+                    "Terms in group do not commute qubit-wise on qubit {}", q
+                );
+                basis[q] = p;
+            }
+        }
+    }
+
+    let mut gates = Vec::new();
+    for (q, p) in basis.iter().enumerate() {
+        match p {
+            PauliOp::X => gates.extend(measure_x_basis(&[q])),
+            PauliOp::Y => gates.extend(measure_y_basis(&[q])),
+            PauliOp::Z | PauliOp::I => {}
+        }
+    }
+    gates
+}
+
+/// Group the terms and pair each group with its basis-change circuit
+pub fn measurement_groups(obs: &Observable) -> Vec<(Vec<usize>, Vec<Gate>)> {
+    group_commuting_terms(obs)
+        .into_iter()
+        .map(|group| {
+            let circuit = basis_change_circuit(obs, &group);
+            (group, circuit)
+        })
+        .collect()
+}
+
+// ============================================================================
+// MODEL HAMILTONIANS
+// ============================================================================
+
+/// 1D Heisenberg chain H = Σ⟨i,j⟩ (J_x XᵢXⱼ + J_y YᵢYⱼ + J_z ZᵢZⱼ) + h Σᵢ Xᵢ
+///
+/// Bonds join neighbouring sites; `periodic` adds the wrap-around bond
+/// (N−1, 0) when N > 2. Terms with zero coefficient are left out, so J_x = J_y
+/// gives the XXZ chain with exactly the bonds that exist. Each bond emits its
+/// XX, YY, ZZ terms in that order, then the fields follow.
+pub fn heisenberg_1d(num_sites: usize, jx: f64, jy: f64, jz: f64, h: f64, periodic: bool) -> Observable {
+    assert!(num_sites >= 2, "A chain needs at least two sites");
+    let mut bonds: Vec<(usize, usize)> = (0..num_sites - 1).map(|i| (i, i + 1)).collect();
+    if periodic && num_sites > 2 {
+        bonds.push((num_sites - 1, 0));
+    }
+
+    let mut obs = Observable::new(num_sites);
+    for (i, j) in bonds {
+        for (coefficient, pauli) in [(jx, PauliOp::X), (jy, PauliOp::Y), (jz, PauliOp::Z)] {
+            if coefficient != 0.0 {
+                let mut paulis = vec![PauliOp::I; num_sites];
+                paulis[i] = pauli;
+                paulis[j] = pauli;
+                obs.add_term(HamiltonianTerm::new(coefficient, paulis));
+            }
+        }
+    }
+    if h != 0.0 {
+        for i in 0..num_sites {
+            obs.add_term(HamiltonianTerm::x_field(i, num_sites, h));
+        }
+    }
+    obs
+}
+
+/// 1D Fermi-Hubbard chain under the Jordan-Wigner mapping
+///
+/// H = −t Σᵢσ (a†ᵢσ aᵢ₊₁σ + h.c.) + U Σᵢ nᵢ↑ nᵢ↓ on an open chain. Spin
+/// orbitals are interleaved: qubit 2i is site i spin up, qubit 2i+1 spin down.
+/// A hop between modes p < q becomes ½(XₚZ…ZX_q + YₚZ…ZY_q), with Z on the one
+/// mode between them (the other spin on site i). Each n↑n↓ becomes
+/// ¼(I − Z↑ − Z↓ + Z↑Z↓), so there is one ZZ term per site. The constant is
+/// collected in a single identity term. A zero t or U adds no terms for it.
+pub fn fermi_hubbard(sites: usize, t: f64, u: f64) -> Observable {
+    assert!(sites >= 1, "Need at least one site");
+    let n = 2 * sites;
+    let mut obs = Observable::new(n);
+
+    if t != 0.0 {
+        for i in 0..sites.saturating_sub(1) {
+            for spin in 0..2 {
+                let (p, q) = (2 * i + spin, 2 * (i + 1) + spin);
+                for pauli in [PauliOp::X, PauliOp::Y] {
+                    let mut paulis = vec![PauliOp::I; n];
+                    paulis[p] = pauli;
+                    paulis[q] = pauli;
+                    for z in &mut paulis[p + 1..q] {
+                        *z = PauliOp::Z;
+                    }
+                    obs.add_term(HamiltonianTerm::new(-t / 2.0, paulis));
+                }
+            }
+        }
+    }
+
+    if u != 0.0 {
+        for i in 0..sites {
+            let (up, down) = (2 * i, 2 * i + 1);
+            obs.add_term(HamiltonianTerm::zz_interaction(up, down, n, u / 4.0));
+            obs.add_term(HamiltonianTerm::z_field(up, n, -u / 4.0));
+            obs.add_term(HamiltonianTerm::z_field(down, n, -u / 4.0));
+        }
+        obs.add_term(HamiltonianTerm::new(u * sites as f64 / 4.0, vec![PauliOp::I; n]));
+    }
+    obs
+}
+
+// ============================================================================
+// EXPECTATION VALUES
+// ============================================================================
+
+/// Exact ⟨ψ|H|ψ⟩ with |ψ⟩ = circuit·|0…0⟩
+///
+/// Each measurement group is rotated to the Z basis and its terms are read
+/// from the exact probabilities, the same path `expectation_value_sampled`
+/// takes with finite shots.
+pub fn expectation_value(circuit: &[Gate], obs: &Observable, num_qubits: usize) -> f64 {
+    assert!(obs.num_qubits <= num_qubits, "Observable on {} qubits, circuit on {}", obs.num_qubits, num_qubits);
+    let state = evolve(circuit, &zero_state(num_qubits));
+    measurement_groups(obs)
+        .iter()
+        .map(|(group, basis)| {
+            let probs = probabilities(&evolve(basis, &state));
+            group.iter().map(|&i| {
+                let mask = z_mask(&obs.terms[i]);
+                let parity: f64 = probs.iter().enumerate()
+                    .map(|(x, p)| p * parity_sign(x, mask))
+                    .sum();
+                obs.terms[i].coefficient * parity
+            }).sum::<f64>()
+        })
+        .sum()
+}
+
+/// Estimate of ⟨H⟩ from `shots` computational-basis samples per commuting group
+///
+/// Runs on `obs.num_qubits` qubits. The estimate is unbiased with standard
+/// error at most Σ|cᵢ| / √shots; it is a pure function of `seed`.
+pub fn expectation_value_sampled(circuit: &[Gate], obs: &Observable, shots: usize, seed: u64) -> f64 {
+    expectation_with_error(circuit, obs, shots, seed).0
+}
+
+/// `expectation_value_sampled` with its shot-noise standard error
+///
+/// Returns (estimate, σ). Each shot of a group yields one sample of the
+/// group's operator Σ_{i∈g} cᵢPᵢ, so terms read from the same shots keep
+/// their covariance; groups use independent shots and their variances add:
+/// σ² = Σ_g Var_g / shots. For a single Z this is √((1 − ⟨Z⟩²)/shots)
+/// with the sampled ⟨Z⟩.
+pub fn expectation_with_error(circuit: &[Gate], obs: &Observable, shots: usize, seed: u64) -> (f64, f64) {
+    expectation_with_error_with_rng(circuit, obs, shots, &mut Rng::new(seed))
+}
+
+/// `expectation_with_error` drawing from a shared generator
+pub fn expectation_with_error_with_rng(circuit: &[Gate], obs: &Observable, shots: usize, rng: &mut Rng) -> (f64, f64) {
+    grouped_shot_estimate(circuit, obs, shots, obs.num_qubits, rng)
+}
+
+/// (estimate, σ) of ⟨H⟩ from `shots_per_group` samples of every commuting group
+///
+/// The measurement loop of a VQE iteration: terms are grouped with
+/// `group_commuting_terms`, each group's basis change is appended to
+/// `circuit` on a `num_qubits` register, and the per-term parities of the
+/// sampled outcomes are weighted by their coefficients. Qubits beyond
+/// `obs.num_qubits` are simulated but not measured. σ is the shot-noise
+/// standard error of `expectation_with_error`, so it shrinks as
+/// 1/√`shots_per_group`.
+pub fn estimate_observable_from_shots(
+    circuit: &[Gate],
+    obs: &Observable,
+    shots_per_group: usize,
+    seed: u64,
+    num_qubits: usize,
+) -> (f64, f64) {
+    grouped_shot_estimate(circuit, obs, shots_per_group, num_qubits, &mut Rng::new(seed))
+}
+
+fn grouped_shot_estimate(circuit: &[Gate], obs: &Observable, shots: usize, num_qubits: usize, rng: &mut Rng) -> (f64, f64) {
+    assert!(shots > 0, "Need at least one shot");
+    assert!(obs.num_qubits <= num_qubits, "Observable on {} qubits, circuit on {}", obs.num_qubits, num_qubits);
+    let mut uniform = || rng.uniform();
+
+    let state = evolve(circuit, &zero_state(num_qubits));
+    let mut total = 0.0;
+    let mut variance = 0.0;
+    for (group, basis) in measurement_groups(obs) {
+        let probs = probabilities(&evolve(&basis, &state));
+        let masks: Vec<usize> = group.iter().map(|&i| z_mask(&obs.terms[i])).collect();
+        let mut sums = vec![0.0; group.len()];
+        let mut sum_sq = 0.0;
+        for _ in 0..shots {
+            let r = uniform();
+            let mut cumulative = 0.0;
+            let outcome = probs.iter().position(|p| {
+                cumulative += p;
+                r < cumulative
+            }).unwrap_or(probs.len() - 1);
+            let mut sample = 0.0;
+            for ((sum, mask), &i) in sums.iter_mut().zip(&masks).zip(&group) {
+                let sign = parity_sign(outcome, *mask);
+                *sum += sign;
+                sample += obs.terms[i].coefficient * sign;
+            }
+            sum_sq += sample * sample;
+        }
+        let mut mean = 0.0;
+        for (&i, sum) in group.iter().zip(sums) {
+            mean += obs.terms[i].coefficient * sum / shots as f64;
+        }
+        total += mean;
+        variance += (sum_sq / shots as f64 - mean * mean).max(0.0) / shots as f64;
+    }
+    (total, variance.sqrt())
+}
+
+/// (−1)^{popcount(outcome & mask)}: a Z-string eigenvalue on a basis state
+fn parity_sign(outcome: usize, mask: usize) -> f64 {
+    if (outcome & mask).count_ones().is_multiple_of(2) { 1.0 } else { -1.0 }
+}
+
+// ============================================================================
+// MATRIX FORM
+// ============================================================================
+
+/// Pauli expansion of a 2ⁿ×2ⁿ Hermitian matrix, dropping |cₚ| ≤ `DEFAULT_TOL`
+pub fn observable_from_matrix(matrix: &[Vec<Complex>]) -> Observable {
+    observable_from_matrix_with_tol(matrix, DEFAULT_TOL)
+}
+
+/// [`observable_from_matrix`] keeping only terms with |cₚ| > `tol`
+///
+/// Entry [i][j] is ⟨i|H|j⟩ with little-endian indices, as in
+/// `simulator::embed_gate`, so `paulis[q]` acts on qubit q. Coefficients are
+/// cₚ = Tr(P·H)/2ⁿ; terms come out ordered by their X/Y mask, then Z/Y mask.
+/// Panics unless the matrix is square, a power of two and Hermitian to `tol`.
+pub fn observable_from_matrix_with_tol(matrix: &[Vec<Complex>], tol: f64) -> Observable {
+    let dim = matrix.len();
+    assert!(dim.is_power_of_two(), "Matrix dimension {} is not a power of two", dim);
+    for (i, row) in matrix.iter().enumerate() {
+        assert_eq!(row.len(), dim, "Row {} is not of length {}", i, dim);
+        for (j, h) in row.iter().enumerate() {
+            assert!((*h - matrix[j][i].conj()).norm() <= tol, "Matrix is not Hermitian at ({}, {})", i, j);
+        }
+    }
+
+    let n = dim.trailing_zeros() as usize;
+    let mut obs = Observable::new(n);
+    for x in 0..dim {
+        for z in 0..dim {
+            // Tr(P·H) = Σⱼ ⟨j|P|j⊕x⟩ ⟨j⊕x|H|j⟩
+            let trace = (0..dim).fold(Complex::ZERO, |acc, j| acc + pauli_entry(x, z, j ^ x) * matrix[j ^ x][j]);
+            let coefficient = trace.re / dim as f64;
+            if coefficient.abs() > tol {
+                let paulis = (0..n).map(|q| match (x >> q & 1, z >> q & 1) {
+                    (0, 0) => PauliOp::I,
+                    (1, 0) => PauliOp::X,
+                    (0, 1) => PauliOp::Z,
+                    _ => PauliOp::Y,
+                }).collect();
+                obs.add_term(HamiltonianTerm::new(coefficient, paulis));
+            }
+        }
+    }
+    obs
+}
+
+/// Dense Σᵢ cᵢPᵢ, row-major with little-endian indices
+pub fn observable_matrix(obs: &Observable) -> Vec<Vec<Complex>> {
+    let dim = 1usize << obs.num_qubits;
+    let mut matrix = vec![vec![Complex::ZERO; dim]; dim];
+    for term in &obs.terms {
+        let (mut x, mut z) = (0, 0);
+        for (q, p) in term.paulis.iter().enumerate() {
+            if matches!(p, PauliOp::X | PauliOp::Y) { x |= 1 << q; }
+            if matches!(p, PauliOp::Z | PauliOp::Y) { z |= 1 << q; }
+        }
+        for j in 0..dim {
+            matrix[j ^ x][j] = matrix[j ^ x][j] + pauli_entry(x, z, j) * term.coefficient;
+        }
+    }
+    matrix
+}
+
+/// ⟨j⊕x|P|j⟩ for the string with X/Y on mask `x` and Z/Y on mask `z`
+///
+/// Z contributes (−1)^jq and Y = iXZ contributes i·(−1)^jq.
+fn pauli_entry(x: usize, z: usize, j: usize) -> Complex {
+    let phase = [Complex::ONE, Complex::I, Complex::new(-1.0, 0.0), Complex::NEG_I][(x & z).count_ones() as usize % 4];
+    phase * parity_sign(j, z)
+}
+
+/// Bitmask of the qubits a term acts on non-trivially
+fn z_mask(term: &HamiltonianTerm) -> usize {
+    term.paulis.iter().enumerate()
+        .filter(|(_, &p)| p != PauliOp::I)
+        .fold(0, |mask, (q, _)| mask | 1 << q)
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::simulate_from;
+
+    #[test]
+    fn test_observable_from_matrix_round_trip() {
+        let c = |re: f64| Complex::new(re, 0.0);
+        let z = vec![vec![c(1.0), c(0.0)], vec![c(0.0), c(-1.0)]];
+        let obs = observable_from_matrix(&z);
+        assert_eq!(obs.terms.len(), 1);
+        assert_eq!(obs.terms[0].paulis, vec![PauliOp::Z]);
+        assert!((obs.terms[0].coefficient - 1.0).abs() < 1e-12);
+
+        // Every Pauli type on two qubits, including an odd number of Ys
+        let mut h = Observable::new(2);
+        h.add_term(HamiltonianTerm::new(0.5, vec![PauliOp::I, PauliOp::I]));
+        h.add_term(HamiltonianTerm::new(-1.2, vec![PauliOp::X, PauliOp::Z]));
+        h.add_term(HamiltonianTerm::new(0.7, vec![PauliOp::Y, PauliOp::I]));
+        h.add_term(HamiltonianTerm::new(0.3, vec![PauliOp::Y, PauliOp::X]));
+        let matrix = observable_matrix(&h);
+        assert!((matrix[1][0] - Complex::new(-1.2, 0.7)).norm() < 1e-12);
+
+        let decomposed = observable_from_matrix(&matrix);
+        assert_eq!(decomposed.terms.len(), 4);
+        let rebuilt = observable_matrix(&decomposed);
+        for (a, b) in matrix.iter().flatten().zip(rebuilt.iter().flatten()) {
+            assert!((*a - *b).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_all_z_terms_single_group() {
+        let mut obs = Observable::new(3);
+        obs.add_term(HamiltonianTerm::zz_interaction(0, 1, 3, 1.0));
+        obs.add_term(HamiltonianTerm::zz_interaction(1, 2, 3, 0.5));
+        obs.add_term(HamiltonianTerm::z_field(0, 3, -0.3));
+
+        let groups = group_commuting_terms(&obs);
+        assert_eq!(groups, vec![vec![0, 1, 2]]);
+        assert!(basis_change_circuit(&obs, &groups[0]).is_empty());
+    }
+
+    #[test]
+    fn test_transverse_ising_two_groups() {
+        let obs = Observable::transverse_ising(4, 1.0, 0.5);
+        let groups = group_commuting_terms(&obs);
+
+        // ZZ bonds and X fields never mix
+        assert_eq!(groups.len(), 2);
+        for group in &groups {
+            for &i in group {
+                for &j in group {
+                    assert!(qubitwise_commute(&obs.terms[i], &obs.terms[j]));
+                }
+            }
+        }
+    }
+
+    /// H = Z₀ + 0.5 X₁ − 0.7 Z₀Z₁ + 0.2 X₀ + 0.4 Y₀ on RY(a)⊗RY(b) then CX(0, 1)
+    fn ansatz_and_observable(a: f64, b: f64) -> (Vec<Gate>, Observable, f64) {
+        use PauliOp::*;
+        let mut obs = Observable::new(2);
+        obs.add_term(HamiltonianTerm::z_field(0, 2, 1.0));
+        obs.add_term(HamiltonianTerm::x_field(1, 2, 0.5));
+        obs.add_term(HamiltonianTerm::zz_interaction(0, 1, 2, -0.7));
+        obs.add_term(HamiltonianTerm::x_field(0, 2, 0.2));
+        obs.add_term(HamiltonianTerm::new(0.4, vec![Y, I]));
+
+        let circuit = vec![Gate::RY(0, a), Gate::RY(1, b), Gate::CX(0, 1)];
+        // CX maps Z₀Z₁ → Z₁ and X₀ → X₀X₁ back onto the product state
+        let analytic = a.cos() + 0.5 * b.sin() - 0.7 * b.cos() + 0.2 * a.sin() * b.sin();
+        (circuit, obs, analytic)
+    }
+
+    #[test]
+    fn test_expectation_value_matches_analytic() {
+        for (a, b) in [(0.3, 1.1), (2.0, -0.4), (-1.3, 2.7)] {
+            let (circuit, obs, analytic) = ansatz_and_observable(a, b);
+            assert!((expectation_value(&circuit, &obs, 2) - analytic).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_sampled_expectation_within_shot_noise() {
+        let (circuit, obs, analytic) = ansatz_and_observable(0.8, 1.9);
+        let shots = 4000;
+        let bound: f64 = obs.terms.iter().map(|t| t.coefficient.abs()).sum::<f64>() / (shots as f64).sqrt();
+
+        let estimate = expectation_value_sampled(&circuit, &obs, shots, 11);
+        assert!((estimate - analytic).abs() < 5.0 * bound, "{} vs {}", estimate, analytic);
+        assert_eq!(estimate, expectation_value_sampled(&circuit, &obs, shots, 11));
+    }
+
+    #[test]
+    fn test_expectation_error_bar_scales_with_shots() {
+        // ⟨Z⟩ = cos θ after RY(θ)
+        let theta: f64 = 1.2;
+        let mut obs = Observable::new(1);
+        obs.add_term(HamiltonianTerm::z_field(0, 1, 1.0));
+        let circuit = [Gate::RY(0, theta)];
+
+        let (mean, err) = expectation_with_error(&circuit, &obs, 1000, 5);
+        assert_eq!(mean, expectation_value_sampled(&circuit, &obs, 1000, 5));
+        assert!((err - ((1.0 - mean * mean) / 1000.0).sqrt()).abs() < 1e-12);
+        assert!((mean - theta.cos()).abs() < 5.0 * err);
+
+        let (_, err_100x) = expectation_with_error(&circuit, &obs, 100_000, 5);
+        assert!((err / err_100x - 10.0).abs() < 0.5, "{} vs {}", err, err_100x);
+
+        // Deterministic outcomes carry no shot noise
+        let (exact, zero) = expectation_with_error(&[], &obs, 50, 1);
+        assert_eq!((exact, zero), (1.0, 0.0));
+    }
+
+    #[test]
+    fn test_estimate_from_shots_converges() {
+        let (mut circuit, obs, _) = ansatz_and_observable(0.8, 1.9);
+        // An unmeasured third qubit entangled with the observable's register
+        circuit.extend([Gate::H(2), Gate::CRY(2, 1, 0.6)]);
+        let exact = expectation_value(&circuit, &obs, 3);
+
+        let mut previous = f64::INFINITY;
+        for shots in [100, 10_000, 1_000_000] {
+            let (estimate, err) = estimate_observable_from_shots(&circuit, &obs, shots, 3, 3);
+            assert!((estimate - exact).abs() < 5.0 * err, "{} shots: {} ± {} vs {}", shots, estimate, err, exact);
+            assert!(err < previous / 5.0, "{} vs {}", err, previous);
+            previous = err;
+        }
+        assert!(previous < 2e-3);
+
+        // On the observable's own width it is expectation_with_error
+        let (circuit, obs, _) = ansatz_and_observable(0.3, -1.0);
+        assert_eq!(estimate_observable_from_shots(&circuit, &obs, 500, 9, 2), expectation_with_error(&circuit, &obs, 500, 9));
+    }
+
+    #[test]
+    fn test_heisenberg_bond_and_field_counts() {
+        let open = heisenberg_1d(4, 1.0, 1.0, 0.5, 0.3, false);
+        assert_eq!(open.terms.len(), 3 * 3 + 4);
+
+        let periodic = heisenberg_1d(4, 1.0, 1.0, 0.5, 0.3, true);
+        assert_eq!(periodic.terms.len(), 4 * 3 + 4);
+        let wrap = &periodic.terms[9];
+        assert_eq!(wrap.paulis, vec![PauliOp::X, PauliOp::I, PauliOp::I, PauliOp::X]);
+
+        // No field, no YY: only XX and ZZ per bond; two sites never wrap
+        assert_eq!(heisenberg_1d(5, 1.0, 0.0, 1.0, 0.0, true).terms.len(), 5 * 2);
+        assert_eq!(heisenberg_1d(2, 1.0, 1.0, 1.0, 0.0, true).terms.len(), 3);
+
+        // |0000⟩: every ZZ bond is +1, XX, YY and X fields vanish
+        assert!((expectation_value(&[], &periodic, 4) - 4.0 * 0.5).abs() < 1e-12);
+    }
+
+    /// ⟨row|H|col⟩ by applying each Pauli string to a basis state
+    fn matrix_element(obs: &Observable, row: usize, col: usize) -> crate::gates::core::Complex {
+        use crate::gates::core::Complex;
+        use crate::simulator::basis_state;
+
+        let mut total = Complex::ZERO;
+        for term in &obs.terms {
+            let paulis: Vec<Gate> = term.paulis.iter().enumerate()
+                .filter_map(|(q, p)| match p {
+                    PauliOp::X => Some(Gate::X(q)),
+                    PauliOp::Y => Some(Gate::Y(q)),
+                    PauliOp::Z => Some(Gate::Z(q)),
+                    PauliOp::I => None,
+                })
+                .collect();
+            total = total + simulate_from(&paulis, &basis_state(obs.num_qubits, col)).unwrap()[row] * term.coefficient;
+        }
+        total
+    }
+
+    #[test]
+    fn test_fermi_hubbard_terms_and_jw_signs() {
+        let (t, u) = (1.0, 4.0);
+        let obs = fermi_hubbard(3, t, u);
+        let zz = obs.terms.iter()
+            .filter(|term| term.paulis.iter().filter(|&&p| p == PauliOp::Z).count() == 2)
+            .filter(|term| term.paulis.iter().all(|&p| p == PauliOp::I || p == PauliOp::Z))
+            .count();
+        assert_eq!(zz, 3);
+        // 2 bonds × 2 spins × (XX + YY) hops
+        let hops = obs.terms.iter().filter(|term| term.paulis.iter().any(|&p| p == PauliOp::X || p == PauliOp::Y));
+        assert_eq!(hops.count(), 8);
+
+        // Site 0 doubly occupied (qubits 0, 1) costs U; split sites cost nothing
+        let h2 = fermi_hubbard(2, t, u);
+        assert!((matrix_element(&h2, 0b0011, 0b0011).re - u).abs() < 1e-12);
+        assert!(matrix_element(&h2, 0b0101, 0b0101).norm() < 1e-12);
+
+        // ↑ hops site 0 → 1: −t alone, +t when it passes the occupied ↓ mode
+        assert!((matrix_element(&h2, 0b0100, 0b0001).re + t).abs() < 1e-12);
+        assert!((matrix_element(&h2, 0b0110, 0b0011).re - t).abs() < 1e-12);
+
+        // Zero couplings leave no zero-coefficient terms behind
+        let atomic = fermi_hubbard(3, 0.0, u);
+        assert_eq!(atomic.terms.len(), 3 * 3 + 1);
+        assert!(atomic.terms.iter().all(|term| term.coefficient != 0.0));
+        assert!(fermi_hubbard(3, 0.0, 0.0).terms.is_empty());
+    }
+
+    #[test]
+    fn test_y_basis_change() {
+        let mut obs = Observable::new(2);
+        obs.add_term(HamiltonianTerm::new(1.0, vec![PauliOp::Y, PauliOp::X]));
+
+        let circuit = basis_change_circuit(&obs, &[0]);
+        assert!(matches!(circuit[..], [Gate::Sdg(0), Gate::H(0), Gate::H(1)]));
+    }
+}
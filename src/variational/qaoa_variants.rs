@@ -383,13 +383,13 @@ impl WarmStartQAOA {
         let mut amplitudes: Vec<f64> = vec![0.0; size];
         let uniform_amp = 1.0 / (size as f64).sqrt();
         
-        for z in 0..size {
+        for (z, amp) in amplitudes.iter_mut().enumerate() {
             let hamming_dist = (z ^ self.warm_start).count_ones() as f64;
             let max_dist = n as f64;
             
             // Bias towards warm-start: amplitude decreases with Hamming distance
             let bias = (1.0 - hamming_dist / max_dist).powf(1.0 / self.mixing_strength);
-            amplitudes[z] = uniform_amp * bias;
+            *amp = uniform_amp * bias;
         }
         
         // Normalize
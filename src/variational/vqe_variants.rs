@@ -28,15 +28,11 @@
 //! - Grimsley et al. (2019). "An adaptive variational algorithm for exact molecular simulations"
 
 use std::f64::consts::PI;
+use crate::error::{check_dimension, RustiqError};
+use crate::gates::core::Complex;
+use crate::simulator::{apply_pauli_string, inner_product};
 
-/// Pauli operator for Hamiltonian terms
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum PauliOp {
-    I,  // Identity
-    X,  // Pauli X
-    Y,  // Pauli Y
-    Z,  // Pauli Z
-}
+pub use crate::gates::core::PauliOp;
 
 /// A term in a Hamiltonian: coefficient * (P_0 ⊗ P_1 ⊗ ... ⊗ P_{n-1})
 #[derive(Debug, Clone)]
@@ -137,6 +133,23 @@ impl Hamiltonian {
         hamiltonian
     }
 
+    /// Exact ⟨ψ|H|ψ⟩ for a statevector on `num_qubits` qubits
+    ///
+    /// Each term is applied with `apply_pauli_string`, so the cost is
+    /// O(T · 2ⁿ) rather than a dense matrix product. A state of any length
+    /// other than 2ⁿ is a `DimensionMismatch`.
+    pub fn expectation(&self, state: &[Complex]) -> Result<f64, RustiqError> {
+        check_dimension(1 << self.num_qubits, state.len())?;
+        let mut energy = 0.0;
+        for term in &self.terms {
+            let string: Vec<(usize, PauliOp)> = term.paulis.iter().copied().enumerate().collect();
+            let mut image = state.to_vec();
+            apply_pauli_string(&mut image, &string)?;
+            energy += term.coefficient * inner_product(state, &image).re;
+        }
+        Ok(energy)
+    }
+
     /// Computes expectation value for a computational basis state
     pub fn expectation_computational(&self, state: usize) -> f64 {
         let mut energy = 0.0;
@@ -355,7 +368,7 @@ impl VQE {
         let shift = PI / 2.0;
         let mut gradients = vec![0.0; self.parameters.len()];
         
-        for i in 0..self.parameters.len() {
+        for (i, grad) in gradients.iter_mut().enumerate() {
             // Shift up
             self.parameters[i] += shift;
             let energy_plus = self.compute_energy();
@@ -367,7 +380,7 @@ impl VQE {
             // Restore
             self.parameters[i] += shift;
             
-            gradients[i] = (energy_plus - energy_minus) / 2.0;
+            *grad = (energy_plus - energy_minus) / 2.0;
         }
         
         gradients
@@ -605,6 +618,21 @@ impl SubspaceVQE {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_expectation_matches_measurement_groups() {
+        use crate::gates::core::Gate;
+        use crate::simulator::simulate;
+        use crate::variational::observable::expectation_value;
+
+        let circuit = [Gate::H(0), Gate::RY(1, 0.7), Gate::CX(0, 2), Gate::RX(2, -0.4), Gate::S(1)];
+        let mut h = Hamiltonian::heisenberg(3, 0.8);
+        h.add_term(HamiltonianTerm::new(0.3, vec![PauliOp::Y, PauliOp::X, PauliOp::Z]));
+        h.add_term(HamiltonianTerm::x_field(2, 3, -1.1));
+        let direct = h.expectation(&simulate(&circuit, 3).unwrap()).unwrap();
+        assert!((direct - expectation_value(&circuit, &h, 3)).abs() < 1e-10);
+        assert!((h.expectation(&simulate(&[], 3).unwrap()).unwrap() - h.expectation_computational(0)).abs() < 1e-12);
+    }
+
     #[test]
     fn test_hamiltonian_creation() {
         let h = Hamiltonian::transverse_ising(4, 1.0, 0.5);